@@ -78,6 +78,13 @@ impl Capturer {
         }
     }
 
+    /// Number of monitors available for capture
+    pub fn monitor_count() -> Result<usize> {
+        Monitor::all()
+            .map(|monitors| monitors.len())
+            .map_err(|e| RecallError::Capture(format!("Failed to enumerate monitors: {}", e)))
+    }
+
     /// Capture the primary monitor
     fn capture_full_screen(&self) -> Result<CaptureResult> {
         let monitors = Monitor::all().map_err(|e| {
@@ -215,6 +222,15 @@ impl Capturer {
     pub fn captures_dir(&self) -> &PathBuf {
         &self.captures_dir
     }
+
+    /// Whether `path` looks like a file written by `generate_file_path`
+    pub fn is_capture_file(path: &std::path::Path) -> bool {
+        path.is_file()
+            && path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("capture_") && n.ends_with(".png"))
+    }
 }
 
 #[cfg(test)]
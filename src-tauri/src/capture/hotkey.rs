@@ -0,0 +1,156 @@
+//! Global hotkey registration for manual screen capture
+
+use super::CaptureManager;
+use crate::error::{RecallError, Result};
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Why a hotkey can't be used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyProblem {
+    /// The string doesn't parse as a shortcut (e.g. "Ctrl+Shift+")
+    InvalidFormat,
+    /// The combination is already taken by the OS or another application
+    Conflict,
+}
+
+/// Result of checking a hotkey before saving it
+#[derive(Debug, Clone, Serialize)]
+pub struct HotkeyValidation {
+    pub hotkey: String,
+    pub valid: bool,
+    pub problem: Option<HotkeyProblem>,
+    pub message: Option<String>,
+}
+
+impl HotkeyValidation {
+    fn ok(hotkey: &str) -> Self {
+        Self {
+            hotkey: hotkey.to_string(),
+            valid: true,
+            problem: None,
+            message: None,
+        }
+    }
+
+    fn rejected(hotkey: &str, problem: HotkeyProblem, message: String) -> Self {
+        Self {
+            hotkey: hotkey.to_string(),
+            valid: false,
+            problem: Some(problem),
+            message: Some(message),
+        }
+    }
+}
+
+fn parse_hotkey(hotkey: &str) -> Result<Shortcut> {
+    hotkey
+        .parse::<Shortcut>()
+        .map_err(|e| RecallError::Capture(format!("Invalid hotkey format '{}': {}", hotkey, e)))
+}
+
+/// Check whether `hotkey` parses and can be registered, without keeping it.
+/// `current` is the hotkey already bound to capture, which is always allowed.
+pub fn validate_hotkey<R: Runtime>(app: &AppHandle<R>, hotkey: &str, current: &str) -> HotkeyValidation {
+    let shortcut = match parse_hotkey(hotkey) {
+        Ok(shortcut) => shortcut,
+        Err(e) => return HotkeyValidation::rejected(hotkey, HotkeyProblem::InvalidFormat, e.to_string()),
+    };
+
+    if current.parse::<Shortcut>().is_ok_and(|c| c == shortcut) {
+        return HotkeyValidation::ok(hotkey);
+    }
+
+    let global_shortcut = app.global_shortcut();
+    if global_shortcut.is_registered(shortcut) {
+        return HotkeyValidation::rejected(
+            hotkey,
+            HotkeyProblem::Conflict,
+            format!("'{}' is already used by RECALL.OS", hotkey),
+        );
+    }
+
+    // Test-register to find out whether the OS or another app owns the combo
+    match global_shortcut.register(shortcut) {
+        Ok(()) => {
+            if let Err(e) = global_shortcut.unregister(shortcut) {
+                tracing::warn!("Failed to release test hotkey '{}': {}", hotkey, e);
+            }
+            HotkeyValidation::ok(hotkey)
+        }
+        Err(e) => HotkeyValidation::rejected(
+            hotkey,
+            HotkeyProblem::Conflict,
+            format!("'{}' is already in use by another application: {}", hotkey, e),
+        ),
+    }
+}
+
+/// Bind `hotkey` to an immediate capture
+pub fn register_capture_hotkey<R: Runtime>(
+    app: &AppHandle<R>,
+    capture_manager: Arc<CaptureManager>,
+    hotkey: &str,
+) -> Result<()> {
+    let shortcut = parse_hotkey(hotkey)?;
+    let app_handle = app.clone();
+
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |_app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                tracing::info!("Global hotkey triggered for screen capture");
+                let capture_manager = capture_manager.clone();
+                let app_handle = app_handle.clone();
+
+                // Spawn async task for capture
+                tauri::async_runtime::spawn(async move {
+                    match capture_manager.capture_now(&app_handle).await {
+                        Ok(result) => {
+                            tracing::info!("Hotkey capture successful: {:?}", result.file_path);
+                        }
+                        Err(e) => {
+                            tracing::warn!("Hotkey capture failed: {}", e);
+                        }
+                    }
+                });
+            }
+        })
+        .map_err(|e| RecallError::Capture(format!("Failed to register hotkey '{}': {}", hotkey, e)))?;
+
+    tracing::info!("Registered global shortcut: {}", hotkey);
+    Ok(())
+}
+
+/// Move the capture binding from `old` to `new`. If `new` can't be
+/// registered, `old` is bound again and the error is returned.
+pub fn replace_capture_hotkey<R: Runtime>(
+    app: &AppHandle<R>,
+    capture_manager: Arc<CaptureManager>,
+    old: &str,
+    new: &str,
+) -> Result<()> {
+    if old == new {
+        return Ok(());
+    }
+
+    if let Ok(old_shortcut) = old.parse::<Shortcut>() {
+        let global_shortcut = app.global_shortcut();
+        if global_shortcut.is_registered(old_shortcut) {
+            if let Err(e) = global_shortcut.unregister(old_shortcut) {
+                tracing::warn!("Failed to unregister hotkey '{}': {}", old, e);
+            }
+        }
+    }
+
+    if let Err(e) = register_capture_hotkey(app, capture_manager.clone(), new) {
+        if let Err(restore_err) = register_capture_hotkey(app, capture_manager, old) {
+            tracing::warn!("Failed to restore previous hotkey: {}", restore_err);
+        }
+        return Err(e);
+    }
+
+    Ok(())
+}
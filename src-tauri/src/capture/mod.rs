@@ -6,23 +6,32 @@
 //! - Active window capture (default) or full screen
 //! - Application whitelist/blacklist filtering
 //! - Privacy protection with default blacklist
+//! - Optional redaction of sensitive text in screenshots
 
 mod capturer;
 mod filter;
+mod hotkey;
+mod redact;
 mod scheduler;
 
 pub use capturer::{CaptureMode, CaptureResult, Capturer};
 pub use filter::{AppFilter, AppFilterMode, AppInfo, get_running_apps};
-pub use scheduler::CaptureScheduler;
-
-use crate::database::{Database, Document, DocumentStatus, FileType};
+pub use hotkey::{
+    register_capture_hotkey, replace_capture_hotkey, validate_hotkey, HotkeyProblem,
+    HotkeyValidation,
+};
+pub use redact::{pixelate_regions, redact_screenshot};
+pub use scheduler::{CaptureScheduler, CaptureTriggerMode};
+
+use crate::database::{Database, Document, DocumentStatus, FileType, IngestionSource};
 use crate::error::{RecallError, Result};
-use crate::ingestion::IngestionEngine;
+use crate::ingestion::{compute_file_hash, IngestionEngine};
 use crate::llm::LlmClient;
 use chrono::Utc;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Runtime};
 use uuid::Uuid;
@@ -42,8 +51,35 @@ pub struct CaptureSettings {
     pub app_list: Vec<String>,
     /// Days to retain captures before auto-deletion
     pub retention_days: u32,
+    /// Most captures to keep regardless of age (0 = no cap)
+    pub retention_max_count: u32,
     /// Global hotkey for manual capture
     pub hotkey: String,
+    /// What triggers automatic captures
+    pub trigger_mode: CaptureTriggerMode,
+    /// Minimum seconds between window-change captures
+    pub min_interval_secs: u64,
+    /// Per-app capture intervals in seconds, keyed by process name;
+    /// other apps use `interval_secs`
+    pub app_intervals: HashMap<String, u64>,
+    /// Skip scheduled captures after this many seconds without keyboard or
+    /// mouse input (0 = always capture)
+    pub idle_threshold_secs: u64,
+    /// Pixelate card numbers, keys and passwords found in each screenshot
+    /// before it is saved, and mask them in its OCR text
+    pub redact_sensitive_regions: bool,
+}
+
+impl CaptureSettings {
+    /// The per-app interval entry matching `process_name`, if any. Matching
+    /// is a case-insensitive substring test, like the app filter list.
+    pub fn app_interval(&self, process_name: &str) -> Option<(&str, u64)> {
+        let process_lower = process_name.to_lowercase();
+        self.app_intervals
+            .iter()
+            .find(|(app, _)| process_lower.contains(&app.to_lowercase()))
+            .map(|(app, secs)| (app.as_str(), *secs))
+    }
 }
 
 impl Default for CaptureSettings {
@@ -55,11 +91,24 @@ impl Default for CaptureSettings {
             filter_mode: AppFilterMode::None,
             app_list: Vec::new(),
             retention_days: 7,
+            retention_max_count: 0,
             hotkey: "Ctrl+Shift+S".to_string(),
+            trigger_mode: CaptureTriggerMode::Interval,
+            min_interval_secs: 10,
+            app_intervals: HashMap::new(),
+            idle_threshold_secs: 0,
+            redact_sensitive_regions: false,
         }
     }
 }
 
+/// What `cleanup_old_captures` removed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaptureCleanupResult {
+    pub files_removed: u64,
+    pub documents_removed: u64,
+}
+
 /// Status of the capture system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CaptureStatus {
@@ -79,8 +128,37 @@ pub struct CaptureStatus {
     pub last_capture: Option<String>,
     /// Registered hotkey
     pub hotkey: String,
+    /// What triggers automatic captures
+    pub trigger_mode: String,
+    /// Whether scheduled captures are being skipped because the user is idle
+    pub idle: bool,
+    /// Scheduled captures skipped while idle in this session
+    pub idle_skipped_count: u64,
 }
 
+/// Outcome of `test_capture`: a capture and OCR run that saves nothing
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureTestResult {
+    pub mode: String,
+    /// Monitors the capture backend can see
+    pub monitors_detected: usize,
+    pub width: u32,
+    pub height: u32,
+    pub foreground_app: Option<AppInfo>,
+    pub source_app: Option<String>,
+    pub window_title: Option<String>,
+    pub capture_ms: u64,
+    /// Start of the OCR text, when OCR succeeded
+    pub text_preview: Option<String>,
+    pub text_length: usize,
+    /// Why OCR failed or was skipped
+    pub ocr_error: Option<String>,
+    pub ocr_ms: Option<u64>,
+}
+
+/// Characters of OCR text returned by `test_capture`
+const TEST_CAPTURE_PREVIEW_CHARS: usize = 300;
+
 /// Event emitted when a capture is completed
 #[derive(Debug, Clone, Serialize)]
 pub struct CaptureCompleteEvent {
@@ -101,8 +179,8 @@ pub struct CaptureErrorEvent {
 
 /// Main capture manager
 pub struct CaptureManager {
-    /// The capturer instance
-    capturer: Capturer,
+    /// The capturer instance (replaced when the captures directory changes)
+    capturer: RwLock<Capturer>,
     /// App filter
     filter: RwLock<AppFilter>,
     /// Current settings
@@ -111,8 +189,7 @@ pub struct CaptureManager {
     scheduler: RwLock<CaptureScheduler>,
     /// Database for storing documents
     database: Arc<Database>,
-    /// LLM client for OCR (reserved for future use)
-    #[allow(dead_code)]
+    /// LLM client for OCR and title generation
     llm_client: Arc<RwLock<Option<LlmClient>>>,
     /// Ingestion engine for processing captures
     ingestion_engine: Arc<IngestionEngine>,
@@ -120,26 +197,59 @@ pub struct CaptureManager {
     capture_count: RwLock<u64>,
     /// Last capture timestamp
     last_capture: RwLock<Option<chrono::DateTime<Utc>>>,
-    /// App data directory (reserved for future use)
-    #[allow(dead_code)]
+    /// Whether the last idle check found the user idle
+    idle: RwLock<bool>,
+    /// Scheduled captures skipped because the user was idle
+    idle_skipped_count: RwLock<u64>,
+    /// App data directory, home of the default captures folder
     app_data_dir: PathBuf,
 }
 
+/// Default captures folder inside the app data directory
+pub fn default_captures_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("captures")
+}
+
+/// Check that `dir` exists (creating it if needed) and accepts new files
+pub fn ensure_writable_dir(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir).map_err(|e| {
+        RecallError::Config(format!("Cannot create captures directory {:?}: {}", dir, e))
+    })?;
+
+    let probe = dir.join(".recall_write_test");
+    std::fs::write(&probe, b"")
+        .and_then(|_| std::fs::remove_file(&probe))
+        .map_err(|e| RecallError::Config(format!("Captures directory {:?} is not writable: {}", dir, e)))
+}
+
+/// Pick the captures directory: the configured override when it is usable,
+/// otherwise the default folder in app data
+pub fn resolve_captures_dir(app_data_dir: &Path, captures_directory: Option<&str>) -> PathBuf {
+    if let Some(custom) = captures_directory.map(str::trim).filter(|d| !d.is_empty()) {
+        let custom = PathBuf::from(custom);
+        match ensure_writable_dir(&custom) {
+            Ok(()) => return custom,
+            Err(e) => tracing::warn!("{}; falling back to the default captures directory", e),
+        }
+    }
+    default_captures_dir(app_data_dir)
+}
+
 impl CaptureManager {
     /// Create a new capture manager
     pub fn new(
         app_data_dir: PathBuf,
+        captures_dir: PathBuf,
         database: Arc<Database>,
         llm_client: Arc<RwLock<Option<LlmClient>>>,
         ingestion_engine: Arc<IngestionEngine>,
     ) -> Result<Self> {
-        let captures_dir = app_data_dir.join("captures");
         let capturer = Capturer::new(captures_dir)?;
         let settings = CaptureSettings::default();
         let filter = AppFilter::new(settings.filter_mode, settings.app_list.clone());
 
         Ok(Self {
-            capturer,
+            capturer: RwLock::new(capturer),
             filter: RwLock::new(filter),
             settings: RwLock::new(settings),
             scheduler: RwLock::new(CaptureScheduler::new()),
@@ -148,6 +258,8 @@ impl CaptureManager {
             ingestion_engine,
             capture_count: RwLock::new(0),
             last_capture: RwLock::new(None),
+            idle: RwLock::new(false),
+            idle_skipped_count: RwLock::new(0),
             app_data_dir,
         })
     }
@@ -159,6 +271,24 @@ impl CaptureManager {
         *self.settings.write() = settings;
     }
 
+    /// Directory new captures are written to
+    pub fn captures_dir(&self) -> PathBuf {
+        self.capturer.read().captures_dir().clone()
+    }
+
+    /// Send new captures to `dir`; existing captures stay where they are
+    pub fn set_captures_dir(&self, dir: PathBuf) -> Result<()> {
+        ensure_writable_dir(&dir)?;
+        *self.capturer.write() = Capturer::new(dir.clone())?;
+        tracing::info!("Captures directory set to {:?}", dir);
+        Ok(())
+    }
+
+    /// Update the hotkey shown in capture status after it has been re-bound
+    pub fn set_hotkey(&self, hotkey: &str) {
+        self.settings.write().hotkey = hotkey.to_string();
+    }
+
     /// Get current capture settings
     pub fn get_settings(&self) -> CaptureSettings {
         self.settings.read().clone()
@@ -178,7 +308,30 @@ impl CaptureManager {
             capture_count: *self.capture_count.read(),
             last_capture: self.last_capture.read().map(|t| t.to_rfc3339()),
             hotkey: settings.hotkey.clone(),
+            trigger_mode: settings.trigger_mode.to_string(),
+            idle: *self.idle.read(),
+            idle_skipped_count: *self.idle_skipped_count.read(),
+        }
+    }
+
+    /// Whether a scheduled capture should be skipped because there has been
+    /// no input for longer than the idle threshold. Counts each skip.
+    pub fn skip_if_idle(&self) -> bool {
+        let threshold = self.settings.read().idle_threshold_secs;
+        let idle = threshold > 0
+            && get_idle_duration().is_some_and(|idle| idle.as_secs() >= threshold);
+
+        let was_idle = std::mem::replace(&mut *self.idle.write(), idle);
+        if idle && !was_idle {
+            tracing::info!("No input for {}s, skipping scheduled captures until activity resumes", threshold);
+        } else if !idle && was_idle {
+            tracing::info!("Activity resumed, scheduled captures continue");
+        }
+
+        if idle {
+            *self.idle_skipped_count.write() += 1;
         }
+        idle
     }
 
     /// Capture a screenshot now (manual trigger)
@@ -186,10 +339,80 @@ impl CaptureManager {
         self.capture_and_ingest(app_handle).await
     }
 
+    /// Capture a screenshot now with the user's note on why, stored in the
+    /// document metadata and indexed ahead of the OCR text
+    pub async fn capture_with_note<R: Runtime>(
+        &self,
+        app_handle: &AppHandle<R>,
+        note: Option<&str>,
+    ) -> Result<CaptureResult> {
+        self.capture_and_ingest_with_note(app_handle, note).await
+    }
+
+    /// Take one screenshot and OCR it without creating a document, to check
+    /// permissions, monitor detection, and OCR before enabling capture.
+    /// The screenshot is written to a temp folder and deleted afterwards.
+    pub async fn test_capture(&self) -> Result<CaptureTestResult> {
+        let mode = self.settings.read().mode;
+        let monitors_detected = Capturer::monitor_count()?;
+        let foreground_app = get_foreground_app_info();
+
+        let test_dir = std::env::temp_dir().join("recall_capture_test");
+        let started = std::time::Instant::now();
+        let capture = Capturer::new(test_dir)?.capture(mode)?;
+        let capture_ms = started.elapsed().as_millis() as u64;
+
+        let llm = self.llm_client.read().clone();
+        let (ocr, ocr_ms) = match llm {
+            Some(llm) => {
+                let started = std::time::Instant::now();
+                let ocr = crate::ingestion::extract_image(&capture.file_path, &llm).await;
+                (ocr, Some(started.elapsed().as_millis() as u64))
+            }
+            None => (Err(RecallError::Config("LLM client not configured".to_string())), None),
+        };
+
+        if let Err(e) = std::fs::remove_file(&capture.file_path) {
+            tracing::warn!("Failed to remove test capture {:?}: {}", capture.file_path, e);
+        }
+
+        let (text_preview, text_length, ocr_error) = match ocr {
+            Ok(content) => {
+                let text = content.plain_text();
+                let preview = text.chars().take(TEST_CAPTURE_PREVIEW_CHARS).collect();
+                (Some(preview), text.chars().count(), None)
+            }
+            Err(e) => (None, 0, Some(e.to_string())),
+        };
+
+        Ok(CaptureTestResult {
+            mode: mode.to_string(),
+            monitors_detected,
+            width: capture.resolution.0,
+            height: capture.resolution.1,
+            foreground_app,
+            source_app: capture.source_app,
+            window_title: capture.window_title,
+            capture_ms,
+            text_preview,
+            text_length,
+            ocr_error,
+            ocr_ms,
+        })
+    }
+
     /// Internal method to capture and ingest a screenshot
     pub async fn capture_and_ingest<R: Runtime>(
         &self,
         app_handle: &AppHandle<R>,
+    ) -> Result<CaptureResult> {
+        self.capture_and_ingest_with_note(app_handle, None).await
+    }
+
+    async fn capture_and_ingest_with_note<R: Runtime>(
+        &self,
+        app_handle: &AppHandle<R>,
+        note: Option<&str>,
     ) -> Result<CaptureResult> {
         let mode = {
             let settings = self.settings.read();
@@ -212,14 +435,26 @@ impl CaptureManager {
         }
 
         // Take the screenshot
-        let result = self.capturer.capture(mode)?;
+        let mut result = self.capturer.read().capture(mode)?;
+
+        // Redact before anything else reads the file
+        let redacted_regions = if self.settings.read().redact_sensitive_regions {
+            Some(self.redact_capture(&mut result).await?)
+        } else {
+            None
+        };
 
         // Update stats
         *self.capture_count.write() += 1;
         *self.last_capture.write() = Some(result.captured_at);
 
         // Create document record for the screenshot
-        let doc = self.create_screenshot_document(&result)?;
+        let mut doc = self.create_screenshot_document(&result, note)?;
+        if let Some(count) = redacted_regions {
+            // Tells ingestion to mask sensitive text in the OCR output too
+            doc.metadata["redact_sensitive"] = true.into();
+            doc.metadata["redacted_regions"] = count.into();
+        }
         self.database.insert_document(&doc)?;
 
         // Emit capture started event
@@ -302,11 +537,40 @@ impl CaptureManager {
         Ok(result)
     }
 
+    /// Pixelate sensitive text in a fresh capture. A capture that can't be
+    /// checked is deleted rather than kept unredacted.
+    async fn redact_capture(&self, result: &mut CaptureResult) -> Result<usize> {
+        let llm = self.llm_client.read().clone();
+        let redacted = match llm {
+            Some(llm) => redact_screenshot(&result.file_path, &llm).await,
+            None => Err(RecallError::Config("LLM client not configured".to_string())),
+        };
+
+        match redacted {
+            Ok(count) => {
+                if count > 0 {
+                    tracing::info!("Redacted {} sensitive regions in {:?}", count, result.file_path);
+                    result.file_size = std::fs::metadata(&result.file_path)?.len();
+                }
+                Ok(count)
+            }
+            Err(e) => {
+                if let Err(remove_err) = std::fs::remove_file(&result.file_path) {
+                    tracing::warn!("Failed to remove unredacted capture {:?}: {}", result.file_path, remove_err);
+                }
+                Err(RecallError::Capture(format!(
+                    "Screenshot discarded, could not check it for sensitive text: {}",
+                    e
+                )))
+            }
+        }
+    }
+
     /// Create a document record for a screenshot
-    fn create_screenshot_document(&self, result: &CaptureResult) -> Result<Document> {
+    fn create_screenshot_document(&self, result: &CaptureResult, note: Option<&str>) -> Result<Document> {
         let file_path = result.file_path.to_string_lossy().to_string();
         let file_size = result.file_size as i64;
-        let file_hash = self.compute_file_hash(&result.file_path)?;
+        let file_hash = compute_file_hash(&result.file_path)?;
 
         let title = if let Some(ref app) = result.source_app {
             format!("Screenshot - {}", app)
@@ -314,7 +578,7 @@ impl CaptureManager {
             format!("Screenshot - {}", result.captured_at.format("%Y-%m-%d %H:%M:%S"))
         };
 
-        let metadata = serde_json::json!({
+        let mut metadata = serde_json::json!({
             "capture_type": "screenshot",
             "capture_mode": result.mode.to_string(),
             "source_app": result.source_app,
@@ -322,6 +586,9 @@ impl CaptureManager {
             "resolution": format!("{}x{}", result.resolution.0, result.resolution.1),
             "captured_at": result.captured_at.to_rfc3339(),
         });
+        if let Some(note) = note.map(str::trim).filter(|n| !n.is_empty()) {
+            metadata["note"] = note.into();
+        }
 
         Ok(Document {
             id: Uuid::new_v4().to_string(),
@@ -337,19 +604,14 @@ impl CaptureManager {
             status: DocumentStatus::Pending,
             error_message: None,
             metadata,
+            ingestion_source: IngestionSource::Capture,
+            chunk_count: 0,
+            total_tokens: 0,
+            last_accessed: None,
+            access_count: 0,
         })
     }
 
-    /// Compute file hash for a screenshot
-    fn compute_file_hash(&self, path: &PathBuf) -> Result<String> {
-        use sha2::{Digest, Sha256};
-
-        let data = std::fs::read(path)?;
-        let mut hasher = Sha256::new();
-        hasher.update(&data);
-        Ok(hex::encode(hasher.finalize()))
-    }
-
     /// Generate a content-aware title from the extracted text
     async fn generate_content_title(
         database: &Arc<Database>,
@@ -414,7 +676,7 @@ impl CaptureManager {
         }
     }
 
-    /// Start periodic capture scheduler
+    /// Start the capture scheduler (periodic or window-change, per settings)
     pub fn start_scheduler<R: Runtime + 'static>(self: &Arc<Self>, app_handle: AppHandle<R>) {
         let settings = self.settings.read();
         if !settings.enabled {
@@ -422,11 +684,16 @@ impl CaptureManager {
             return;
         }
 
-        let interval = settings.interval_secs;
+        let trigger_mode = settings.trigger_mode;
+        let interval = match trigger_mode {
+            CaptureTriggerMode::Interval => settings.interval_secs,
+            CaptureTriggerMode::OnWindowChange => settings.min_interval_secs,
+        };
+        let per_app = !settings.app_intervals.is_empty();
         drop(settings);
 
         let mut scheduler = self.scheduler.write();
-        scheduler.start(self.clone(), interval, app_handle);
+        scheduler.start(self.clone(), interval, trigger_mode, per_app, app_handle);
     }
 
     /// Stop periodic capture scheduler
@@ -453,38 +720,94 @@ impl CaptureManager {
         scheduler.resume();
     }
 
-    /// Clean up old captures based on retention settings
-    pub fn cleanup_old_captures(&self) -> Result<u64> {
-        let retention_days = self.settings.read().retention_days;
+    /// Clean up captures older than the retention window, then the oldest
+    /// beyond the count cap. Each capture's document (with its chunks and
+    /// embeddings) is deleted along with the file, and capture documents
+    /// whose file was already removed are swept too. Database errors are
+    /// logged rather than returned, since files may already be gone.
+    pub fn cleanup_old_captures(&self) -> Result<CaptureCleanupResult> {
+        let (retention_days, max_count) = {
+            let settings = self.settings.read();
+            (settings.retention_days, settings.retention_max_count)
+        };
         let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
-        let captures_dir = self.capturer.captures_dir();
 
-        let mut deleted_count = 0u64;
+        // Also sweep the default folder, which holds captures taken before
+        // the directory was changed
+        let mut dirs = vec![self.captures_dir()];
+        let default_dir = default_captures_dir(&self.app_data_dir);
+        if !dirs.contains(&default_dir) {
+            dirs.push(default_dir);
+        }
 
-        if let Ok(entries) = std::fs::read_dir(captures_dir) {
+        let mut captures: Vec<(PathBuf, chrono::DateTime<Utc>)> = Vec::new();
+        for entries in dirs.iter().filter_map(|dir| std::fs::read_dir(dir).ok()) {
             for entry in entries.flatten() {
                 let path = entry.path();
-                if let Ok(metadata) = entry.metadata() {
-                    if let Ok(created) = metadata.created() {
-                        let created_dt: chrono::DateTime<Utc> = created.into();
-                        if created_dt < cutoff {
-                            if let Err(e) = std::fs::remove_file(&path) {
-                                tracing::warn!("Failed to delete old capture {:?}: {}", path, e);
-                            } else {
-                                deleted_count += 1;
-                                tracing::debug!("Deleted old capture: {:?}", path);
-                            }
-                        }
-                    }
+                // A custom directory may hold the user's own files; only touch ours
+                if !Capturer::is_capture_file(&path) {
+                    continue;
+                }
+                // Not every filesystem records a creation time
+                let created = entry.metadata().and_then(|m| m.created().or_else(|_| m.modified()));
+                if let Ok(created) = created {
+                    captures.push((path, created.into()));
                 }
             }
         }
 
-        if deleted_count > 0 {
-            tracing::info!("Cleaned up {} old captures", deleted_count);
+        // Newest first, so everything past the cap is the oldest
+        captures.sort_by_key(|(_, created)| std::cmp::Reverse(*created));
+        let keep = if max_count == 0 { usize::MAX } else { max_count as usize };
+
+        let mut result = CaptureCleanupResult::default();
+        for (i, (path, created)) in captures.iter().enumerate() {
+            if *created >= cutoff && i < keep {
+                continue;
+            }
+            if let Err(e) = std::fs::remove_file(path) {
+                tracing::warn!("Failed to delete old capture {:?}: {}", path, e);
+                continue;
+            }
+            tracing::debug!("Deleted old capture: {:?}", path);
+            result.files_removed += 1;
+
+            // The file is gone either way; a record left behind here is
+            // swept below or on the next cleanup
+            match self.database.get_document_by_path(&path.to_string_lossy()) {
+                Ok(Some(doc)) => match self.database.delete_document(&doc.id) {
+                    Ok(()) => result.documents_removed += 1,
+                    Err(e) => tracing::warn!("Failed to delete document for capture {:?}: {}", path, e),
+                },
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to look up document for capture {:?}: {}", path, e),
+            }
         }
 
-        Ok(deleted_count)
+        // Records left behind by earlier cleanups, which only removed files
+        let documents = self.database.get_all_documents().unwrap_or_else(|e| {
+            tracing::warn!("Failed to list documents for capture cleanup: {}", e);
+            Vec::new()
+        });
+        for doc in documents {
+            let path = Path::new(&doc.file_path);
+            if doc.file_type == FileType::Screenshot && Capturer::is_capture_file(path) && !path.exists() {
+                match self.database.delete_document(&doc.id) {
+                    Ok(()) => result.documents_removed += 1,
+                    Err(e) => tracing::warn!("Failed to delete document for missing capture {:?}: {}", path, e),
+                }
+            }
+        }
+
+        if result.files_removed > 0 || result.documents_removed > 0 {
+            tracing::info!(
+                "Cleaned up {} old captures and {} capture documents",
+                result.files_removed,
+                result.documents_removed
+            );
+        }
+
+        Ok(result)
     }
 }
 
@@ -554,3 +877,29 @@ fn get_process_name_by_id(process_id: u32) -> Option<String> {
 fn get_foreground_app_info() -> Option<AppInfo> {
     None
 }
+
+/// Time since the last keyboard or mouse input
+#[cfg(windows)]
+fn get_idle_duration() -> Option<std::time::Duration> {
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    unsafe {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        if !GetLastInputInfo(&mut info).as_bool() {
+            return None;
+        }
+
+        // Both are tick counts that wrap every ~49.7 days
+        let idle_ms = GetTickCount().wrapping_sub(info.dwTime);
+        Some(std::time::Duration::from_millis(idle_ms as u64))
+    }
+}
+
+#[cfg(not(windows))]
+fn get_idle_duration() -> Option<std::time::Duration> {
+    None
+}
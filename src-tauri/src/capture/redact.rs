@@ -0,0 +1,114 @@
+//! Privacy pass over new screenshots: locate lines of text, and pixelate the
+//! ones holding secrets (card numbers, keys, passwords) before ingestion.
+
+use crate::error::{RecallError, Result};
+use crate::ingestion::contains_sensitive;
+use crate::llm::LlmClient;
+use image::{Rgba, RgbaImage};
+use std::path::Path;
+
+/// Extra pixels pixelated around each region, so glyph edges don't survive
+const REGION_PADDING: u32 = 4;
+/// Smallest pixelation block, for short lines of text
+const MIN_BLOCK_SIZE: u32 = 8;
+
+/// Find text regions in the PNG at `path` and pixelate those with sensitive
+/// text, rewriting the file in place. Returns how many regions were hidden.
+pub async fn redact_screenshot(path: &Path, llm: &LlmClient) -> Result<usize> {
+    let data = std::fs::read(path)?;
+    let regions = llm.ocr_text_regions(&data, "image/png").await?;
+
+    let boxes: Vec<[u32; 4]> = regions
+        .iter()
+        .filter(|region| contains_sensitive(&region.text))
+        .map(|region| region.box_2d)
+        .collect();
+    if boxes.is_empty() {
+        return Ok(0);
+    }
+
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut image = image::load_from_memory(&data)
+            .map_err(|e| RecallError::Capture(format!("Failed to decode screenshot: {}", e)))?
+            .to_rgba8();
+        let hidden = pixelate_regions(&mut image, &boxes);
+        image
+            .save(&path)
+            .map_err(|e| RecallError::Capture(format!("Failed to save redacted screenshot: {}", e)))?;
+        Ok(hidden)
+    })
+    .await
+    .map_err(|e| RecallError::Capture(format!("Redaction task failed: {}", e)))?
+}
+
+/// Pixelate each `[ymin, xmin, ymax, xmax]` box (scaled to 0-1000) with
+/// blocks about as tall as the text, so it can't be read back. Returns how
+/// many boxes covered part of the image.
+pub fn pixelate_regions(image: &mut RgbaImage, boxes: &[[u32; 4]]) -> usize {
+    let (width, height) = image.dimensions();
+    let mut hidden = 0;
+
+    for &[ymin, xmin, ymax, xmax] in boxes {
+        let scale = |v: u32, size: u32| (v.min(1000) as u64 * size as u64 / 1000) as u32;
+        let x0 = scale(xmin.min(xmax), width).saturating_sub(REGION_PADDING);
+        let y0 = scale(ymin.min(ymax), height).saturating_sub(REGION_PADDING);
+        let x1 = (scale(xmin.max(xmax), width) + REGION_PADDING).min(width);
+        let y1 = (scale(ymin.max(ymax), height) + REGION_PADDING).min(height);
+        if x0 >= x1 || y0 >= y1 {
+            continue;
+        }
+
+        let block = (y1 - y0).max(MIN_BLOCK_SIZE);
+        for by in (y0..y1).step_by(block as usize) {
+            for bx in (x0..x1).step_by(block as usize) {
+                let (bx1, by1) = ((bx + block).min(x1), (by + block).min(y1));
+                let fill = average_color(image, bx, by, bx1, by1);
+                for y in by..by1 {
+                    for x in bx..bx1 {
+                        image.put_pixel(x, y, fill);
+                    }
+                }
+            }
+        }
+        hidden += 1;
+    }
+
+    hidden
+}
+
+fn average_color(image: &RgbaImage, x0: u32, y0: u32, x1: u32, y1: u32) -> Rgba<u8> {
+    let mut sums = [0u64; 4];
+    for y in y0..y1 {
+        for x in x0..x1 {
+            for (sum, channel) in sums.iter_mut().zip(image.get_pixel(x, y).0) {
+                *sum += channel as u64;
+            }
+        }
+    }
+    let count = ((x1 - x0) as u64 * (y1 - y0) as u64).max(1);
+    Rgba(sums.map(|sum| (sum / count) as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixelate_flattens_only_the_region() {
+        // Alternating columns stand in for glyphs
+        let mut image = RgbaImage::from_fn(200, 100, |x, _| {
+            if x % 2 == 0 { Rgba([0, 0, 0, 255]) } else { Rgba([255, 255, 255, 255]) }
+        });
+
+        // Middle fifth of the image, left half
+        let hidden = pixelate_regions(&mut image, &[[400, 0, 600, 500]]);
+        assert_eq!(hidden, 1);
+
+        // Inside the region neighbouring pixels now match
+        assert_eq!(image.get_pixel(10, 50), image.get_pixel(11, 50));
+        // Outside it the pattern is untouched
+        assert_ne!(image.get_pixel(10, 5), image.get_pixel(11, 5));
+        assert_ne!(image.get_pixel(150, 50), image.get_pixel(151, 50));
+    }
+}
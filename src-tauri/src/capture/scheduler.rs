@@ -1,7 +1,9 @@
 //! Periodic capture scheduler using tokio
 //! Manages automatic screenshot capture at configurable intervals
 
-use super::CaptureManager;
+use super::{get_foreground_app_info, CaptureManager};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -9,6 +11,40 @@ use tauri::{AppHandle, Runtime};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
+/// How often the foreground window is polled in window-change mode
+const WINDOW_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What triggers automatic captures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureTriggerMode {
+    /// Capture at a fixed interval
+    #[default]
+    Interval,
+    /// Capture whenever the foreground window changes
+    OnWindowChange,
+}
+
+impl std::str::FromStr for CaptureTriggerMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "on_window_change" | "window_change" => Ok(Self::OnWindowChange),
+            _ => Ok(Self::Interval),
+        }
+    }
+}
+
+impl std::fmt::Display for CaptureTriggerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Interval => write!(f, "interval"),
+            Self::OnWindowChange => write!(f, "on_window_change"),
+        }
+    }
+}
+
 /// Message types for the scheduler
 #[allow(dead_code)]
 pub enum SchedulerMessage {
@@ -50,16 +86,22 @@ impl CaptureScheduler {
         }
     }
 
-    /// Start the periodic capture scheduler
+    /// Start the capture scheduler
     ///
     /// # Arguments
     /// * `capture_manager` - The capture manager to use for taking screenshots
-    /// * `interval_secs` - Interval between captures in seconds
+    /// * `interval_secs` - Interval between captures in seconds (interval mode),
+    ///   or the minimum gap between captures (window-change mode)
+    /// * `trigger_mode` - Whether captures fire on a timer or on window changes
+    /// * `per_app` - In interval mode, time captures per foreground app using
+    ///   the configured app intervals
     /// * `app_handle` - Tauri app handle for emitting events
     pub fn start<R: Runtime + 'static>(
         &mut self,
         capture_manager: Arc<CaptureManager>,
         interval_secs: u64,
+        trigger_mode: CaptureTriggerMode,
+        per_app: bool,
         app_handle: AppHandle<R>,
     ) {
         if self.is_running.load(Ordering::SeqCst) {
@@ -69,26 +111,58 @@ impl CaptureScheduler {
 
         let (tx, rx) = mpsc::channel(16);
         self.tx = Some(tx);
-        self.is_running.store(true, Ordering::SeqCst);
-        self.is_paused.store(false, Ordering::SeqCst);
+        // Fresh flags so a previously signalled task can't clobber this one
+        self.is_running = Arc::new(AtomicBool::new(true));
+        self.is_paused = Arc::new(AtomicBool::new(false));
 
         let is_running = self.is_running.clone();
         let is_paused = self.is_paused.clone();
 
         let handle = tokio::spawn(async move {
-            Self::run_scheduler(
-                capture_manager,
-                interval_secs,
-                app_handle,
-                rx,
-                is_running,
-                is_paused,
-            )
-            .await;
+            match trigger_mode {
+                CaptureTriggerMode::Interval if per_app => {
+                    Self::run_per_app_scheduler(
+                        capture_manager,
+                        interval_secs,
+                        app_handle,
+                        rx,
+                        is_running,
+                        is_paused,
+                    )
+                    .await;
+                }
+                CaptureTriggerMode::Interval => {
+                    Self::run_scheduler(
+                        capture_manager,
+                        interval_secs,
+                        app_handle,
+                        rx,
+                        is_running,
+                        is_paused,
+                    )
+                    .await;
+                }
+                CaptureTriggerMode::OnWindowChange => {
+                    Self::run_window_change_scheduler(
+                        capture_manager,
+                        interval_secs,
+                        app_handle,
+                        rx,
+                        is_running,
+                        is_paused,
+                    )
+                    .await;
+                }
+            }
         });
 
         self.task_handle = Some(handle);
-        tracing::info!("Capture scheduler started with {}s interval", interval_secs);
+        tracing::info!(
+            "Capture scheduler started: trigger={}, interval={}s, per_app={}",
+            trigger_mode,
+            interval_secs,
+            per_app
+        );
     }
 
     /// Stop the scheduler (async version that waits for cleanup)
@@ -181,7 +255,7 @@ impl CaptureScheduler {
                         break;
                     }
 
-                    if is_paused.load(Ordering::SeqCst) {
+                    if is_paused.load(Ordering::SeqCst) || capture_manager.skip_if_idle() {
                         continue;
                     }
 
@@ -223,6 +297,181 @@ impl CaptureScheduler {
         is_running.store(false, Ordering::SeqCst);
         tracing::info!("Capture scheduler loop ended");
     }
+
+    /// Interval loop with per-app timing. The foreground app is polled and
+    /// captured once its own interval has passed since that app was last
+    /// captured; apps without an entry share one timer on the global interval.
+    async fn run_per_app_scheduler<R: Runtime>(
+        capture_manager: Arc<CaptureManager>,
+        initial_interval_secs: u64,
+        app_handle: AppHandle<R>,
+        mut rx: mpsc::Receiver<SchedulerMessage>,
+        is_running: Arc<AtomicBool>,
+        is_paused: Arc<AtomicBool>,
+    ) {
+        let mut global_interval = Duration::from_secs(initial_interval_secs);
+        let mut poll = tokio::time::interval(WINDOW_POLL_INTERVAL);
+        poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        // Keyed by the matching app entry; None is the shared global timer,
+        // which starts now so the first global capture waits one interval
+        let mut last_capture_at: HashMap<Option<String>, tokio::time::Instant> = HashMap::new();
+        last_capture_at.insert(None, tokio::time::Instant::now());
+
+        if get_foreground_app_info().is_none() {
+            tracing::warn!("Foreground window detection unavailable; per-app intervals fall back to the global interval");
+        }
+
+        loop {
+            tokio::select! {
+                _ = poll.tick() => {
+                    if !is_running.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    if is_paused.load(Ordering::SeqCst) {
+                        continue;
+                    }
+
+                    let (key, interval) = {
+                        let settings = capture_manager.get_settings();
+                        match get_foreground_app_info()
+                            .and_then(|app| settings.app_interval(&app.process_name).map(|(k, v)| (k.to_string(), v)))
+                        {
+                            Some((app, secs)) => (Some(app), Duration::from_secs(secs)),
+                            None => (None, global_interval),
+                        }
+                    };
+
+                    if last_capture_at.get(&key).is_some_and(|t| t.elapsed() < interval)
+                        || capture_manager.skip_if_idle()
+                    {
+                        continue;
+                    }
+                    last_capture_at.insert(key.clone(), tokio::time::Instant::now());
+
+                    match capture_manager.capture_and_ingest(&app_handle).await {
+                        Ok(result) => {
+                            tracing::debug!(
+                                "Scheduled capture completed ({}): {:?}",
+                                key.as_deref().unwrap_or("global"),
+                                result.file_path
+                            );
+                        }
+                        Err(e) => {
+                            tracing::warn!("Scheduled capture failed: {}", e);
+                        }
+                    }
+                }
+                msg = rx.recv() => {
+                    match msg {
+                        Some(SchedulerMessage::Stop) | None => {
+                            break;
+                        }
+                        Some(SchedulerMessage::UpdateInterval(new_interval)) => {
+                            tracing::info!("Updating global capture interval to {}s", new_interval);
+                            global_interval = Duration::from_secs(new_interval);
+                        }
+                        Some(SchedulerMessage::Pause) => {
+                            is_paused.store(true, Ordering::SeqCst);
+                        }
+                        Some(SchedulerMessage::Resume) => {
+                            is_paused.store(false, Ordering::SeqCst);
+                        }
+                    }
+                }
+            }
+        }
+
+        is_running.store(false, Ordering::SeqCst);
+        tracing::info!("Per-app capture loop ended");
+    }
+
+    /// Scheduler loop that captures when the foreground window changes.
+    /// A window must be stable for one poll before it is captured, and
+    /// captures are at least `min_interval_secs` apart, so rapid alt-tabbing
+    /// only captures the window the user settles on.
+    async fn run_window_change_scheduler<R: Runtime>(
+        capture_manager: Arc<CaptureManager>,
+        min_interval_secs: u64,
+        app_handle: AppHandle<R>,
+        mut rx: mpsc::Receiver<SchedulerMessage>,
+        is_running: Arc<AtomicBool>,
+        is_paused: Arc<AtomicBool>,
+    ) {
+        let mut min_interval = Duration::from_secs(min_interval_secs);
+        let mut poll = tokio::time::interval(WINDOW_POLL_INTERVAL);
+        poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        let mut previous_window: Option<(String, String)> = None;
+        let mut last_captured_window: Option<(String, String)> = None;
+        let mut last_capture_at: Option<tokio::time::Instant> = None;
+
+        if get_foreground_app_info().is_none() {
+            tracing::warn!("Foreground window detection unavailable; window-change captures will not fire");
+        }
+
+        loop {
+            tokio::select! {
+                _ = poll.tick() => {
+                    if !is_running.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    let Some(app) = get_foreground_app_info() else {
+                        continue;
+                    };
+                    let current = Some((app.process_name, app.window_title));
+
+                    // Wait until the window has been stable for a full poll
+                    let stable = current == previous_window;
+                    previous_window = current.clone();
+                    if !stable || current == last_captured_window || is_paused.load(Ordering::SeqCst) {
+                        continue;
+                    }
+
+                    if last_capture_at.is_some_and(|t| t.elapsed() < min_interval)
+                        || capture_manager.skip_if_idle()
+                    {
+                        continue;
+                    }
+
+                    last_captured_window = current;
+                    last_capture_at = Some(tokio::time::Instant::now());
+
+                    // Filtered windows return an error and are simply skipped
+                    match capture_manager.capture_and_ingest(&app_handle).await {
+                        Ok(result) => {
+                            tracing::debug!("Window-change capture completed: {:?}", result.file_path);
+                        }
+                        Err(e) => {
+                            tracing::debug!("Window-change capture skipped: {}", e);
+                        }
+                    }
+                }
+                msg = rx.recv() => {
+                    match msg {
+                        Some(SchedulerMessage::Stop) | None => {
+                            break;
+                        }
+                        Some(SchedulerMessage::UpdateInterval(new_interval)) => {
+                            tracing::info!("Updating minimum window-change capture interval to {}s", new_interval);
+                            min_interval = Duration::from_secs(new_interval);
+                        }
+                        Some(SchedulerMessage::Pause) => {
+                            is_paused.store(true, Ordering::SeqCst);
+                        }
+                        Some(SchedulerMessage::Resume) => {
+                            is_paused.store(false, Ordering::SeqCst);
+                        }
+                    }
+                }
+            }
+        }
+
+        is_running.store(false, Ordering::SeqCst);
+        tracing::info!("Window-change capture loop ended");
+    }
 }
 
 impl Drop for CaptureScheduler {
@@ -1,8 +1,12 @@
 //! Tauri commands for screen capture functionality
 
-use crate::capture::{AppFilterMode, AppInfo, CaptureMode, CaptureSettings, CaptureStatus, get_running_apps};
+use crate::capture::{
+    AppFilterMode, AppInfo, CaptureCleanupResult, CaptureMode, CaptureSettings, CaptureStatus,
+    CaptureTestResult, CaptureTriggerMode, HotkeyValidation, get_running_apps,
+};
 use crate::error::Result;
 use crate::state::AppState;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 
@@ -66,6 +70,37 @@ pub async fn capture_now(
     Ok(result.file_path.to_string_lossy().to_string())
 }
 
+/// Capture a screenshot now with an optional note, which is saved in the
+/// document metadata and searchable alongside the OCR text
+#[tauri::command]
+pub async fn capture_with_note(
+    state: State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
+    note: Option<String>,
+) -> Result<String> {
+    tracing::info!("Manual capture with note triggered");
+
+    let capture_settings = get_capture_settings_from_state(&state);
+    state.capture_manager.update_settings(capture_settings);
+
+    let result = state
+        .capture_manager
+        .capture_with_note(&app_handle, note.as_deref())
+        .await?;
+
+    Ok(result.file_path.to_string_lossy().to_string())
+}
+
+/// Take a screenshot and OCR it without saving anything, to diagnose
+/// capture permissions and OCR before enabling capture
+#[tauri::command]
+pub async fn test_capture(state: State<'_, Arc<AppState>>) -> Result<CaptureTestResult> {
+    let capture_settings = get_capture_settings_from_state(&state);
+    state.capture_manager.update_settings(capture_settings);
+
+    state.capture_manager.test_capture().await
+}
+
 /// Get current capture status
 #[tauri::command]
 pub async fn get_capture_status(state: State<'_, Arc<AppState>>) -> Result<CaptureStatus> {
@@ -78,6 +113,17 @@ pub async fn get_running_applications() -> Result<Vec<AppInfo>> {
     Ok(get_running_apps())
 }
 
+/// Check that a hotkey parses and isn't taken before the user saves it
+#[tauri::command]
+pub async fn validate_hotkey(
+    state: State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
+    hotkey: String,
+) -> Result<HotkeyValidation> {
+    let current = state.settings.read().capture_hotkey.clone();
+    Ok(crate::capture::validate_hotkey(&app_handle, hotkey.trim(), &current))
+}
+
 /// Update capture settings
 #[tauri::command]
 pub async fn update_capture_settings(
@@ -89,17 +135,58 @@ pub async fn update_capture_settings(
     app_list: Vec<String>,
     retention_days: u32,
     hotkey: String,
+    trigger_mode: Option<String>,
+    min_interval_secs: Option<u64>,
+    retention_max_count: Option<u32>,
+    idle_threshold_secs: Option<u64>,
+    redact_sensitive_regions: Option<bool>,
     app_handle: AppHandle,
 ) -> Result<()> {
     tracing::info!(
-        "Updating capture settings: enabled={}, interval={}s, mode={}, filter={}",
+        "Updating capture settings: enabled={}, interval={}s, mode={}, filter={}, trigger={:?}",
         enabled,
         interval_secs,
         mode,
-        filter_mode
+        filter_mode,
+        trigger_mode
     );
 
-    let was_enabled = state.settings.read().screen_capture_enabled;
+    let (
+        was_enabled,
+        previous_trigger,
+        previous_min_interval,
+        previous_hotkey,
+        previous_max_count,
+        previous_idle_threshold,
+        previous_redact,
+    ) = {
+        let settings = state.settings.read();
+        (
+            settings.screen_capture_enabled,
+            settings.capture_trigger_mode.clone(),
+            settings.capture_min_interval_secs,
+            settings.capture_hotkey.clone(),
+            settings.capture_retention_max_count,
+            settings.capture_idle_threshold_secs,
+            settings.capture_redact_sensitive_regions,
+        )
+    };
+
+    // Re-bind the hotkey before saving; on failure the old binding is kept
+    crate::capture::replace_capture_hotkey(
+        &app_handle,
+        state.capture_manager.clone(),
+        &previous_hotkey,
+        &hotkey,
+    )?;
+    let trigger_mode = trigger_mode.unwrap_or_else(|| previous_trigger.clone());
+    let min_interval_secs = min_interval_secs.unwrap_or(previous_min_interval).clamp(2, 300);
+    let retention_max_count = retention_max_count.unwrap_or(previous_max_count);
+    let idle_threshold_secs = match idle_threshold_secs.unwrap_or(previous_idle_threshold) {
+        0 => 0,
+        secs => secs.clamp(30, 7200),
+    };
+    let redact_sensitive_regions = redact_sensitive_regions.unwrap_or(previous_redact);
 
     // Update state settings
     {
@@ -110,7 +197,12 @@ pub async fn update_capture_settings(
         settings.capture_app_filter = filter_mode.clone();
         settings.capture_app_list = app_list.clone();
         settings.capture_retention_days = retention_days.clamp(1, 90);
+        settings.capture_retention_max_count = retention_max_count;
         settings.capture_hotkey = hotkey.clone();
+        settings.capture_trigger_mode = trigger_mode.clone();
+        settings.capture_min_interval_secs = min_interval_secs;
+        settings.capture_idle_threshold_secs = idle_threshold_secs;
+        settings.capture_redact_sensitive_regions = redact_sensitive_regions;
     }
     state.save_settings()?;
 
@@ -122,7 +214,13 @@ pub async fn update_capture_settings(
         filter_mode: filter_mode.parse().unwrap_or(AppFilterMode::None),
         app_list,
         retention_days: retention_days.clamp(1, 90),
+        retention_max_count,
         hotkey,
+        trigger_mode: trigger_mode.parse().unwrap_or_default(),
+        min_interval_secs,
+        app_intervals: state.settings.read().capture_app_intervals.clone(),
+        idle_threshold_secs,
+        redact_sensitive_regions,
     };
 
     // Update capture manager
@@ -132,6 +230,12 @@ pub async fn update_capture_settings(
     if enabled && !was_enabled {
         // Start scheduler if newly enabled
         state.capture_manager.clone().start_scheduler(app_handle);
+    } else if enabled
+        && (trigger_mode != previous_trigger || min_interval_secs != previous_min_interval)
+    {
+        // Restart so the loop picks up the new trigger and floor
+        state.capture_manager.stop_scheduler();
+        state.capture_manager.clone().start_scheduler(app_handle);
     } else if !enabled && was_enabled {
         // Stop scheduler if disabled (synchronous)
         state.capture_manager.stop_scheduler();
@@ -140,6 +244,41 @@ pub async fn update_capture_settings(
     Ok(())
 }
 
+/// Set per-app capture intervals (seconds, keyed by process name).
+/// Apps without an entry keep the global interval.
+#[tauri::command]
+pub async fn set_capture_app_intervals(
+    state: State<'_, Arc<AppState>>,
+    intervals: HashMap<String, u64>,
+    app_handle: AppHandle,
+) -> Result<()> {
+    let intervals: HashMap<String, u64> = intervals
+        .into_iter()
+        .map(|(app, secs)| (app.trim().to_string(), secs.clamp(10, 3600)))
+        .filter(|(app, _)| !app.is_empty())
+        .collect();
+    tracing::info!("Updating per-app capture intervals: {:?}", intervals);
+
+    {
+        let mut settings = state.settings.write();
+        settings.capture_app_intervals = intervals;
+    }
+    state.save_settings()?;
+
+    let capture_settings = get_capture_settings_from_state(&state);
+    let restart = capture_settings.enabled && capture_settings.trigger_mode == CaptureTriggerMode::Interval;
+    state.capture_manager.update_settings(capture_settings);
+
+    // The scheduler picks its loop at start, so restart to switch between
+    // the plain timer and per-app timing
+    if restart {
+        state.capture_manager.stop_scheduler();
+        state.capture_manager.clone().start_scheduler(app_handle);
+    }
+
+    Ok(())
+}
+
 /// Pause screen capture temporarily (scheduler keeps running)
 #[tauri::command]
 pub async fn pause_screen_capture(state: State<'_, Arc<AppState>>) -> Result<()> {
@@ -156,7 +295,7 @@ pub async fn resume_screen_capture(state: State<'_, Arc<AppState>>) -> Result<()
 
 /// Clean up old captures based on retention settings
 #[tauri::command]
-pub async fn cleanup_old_captures(state: State<'_, Arc<AppState>>) -> Result<u64> {
+pub async fn cleanup_old_captures(state: State<'_, Arc<AppState>>) -> Result<CaptureCleanupResult> {
     state.capture_manager.cleanup_old_captures()
 }
 
@@ -170,6 +309,12 @@ fn get_capture_settings_from_state(state: &State<'_, Arc<AppState>>) -> CaptureS
         filter_mode: settings.capture_app_filter.parse().unwrap_or(AppFilterMode::None),
         app_list: settings.capture_app_list.clone(),
         retention_days: settings.capture_retention_days,
+        retention_max_count: settings.capture_retention_max_count,
         hotkey: settings.capture_hotkey.clone(),
+        trigger_mode: settings.capture_trigger_mode.parse().unwrap_or_default(),
+        min_interval_secs: settings.capture_min_interval_secs,
+        app_intervals: settings.capture_app_intervals.clone(),
+        idle_threshold_secs: settings.capture_idle_threshold_secs,
+        redact_sensitive_regions: settings.capture_redact_sensitive_regions,
     }
 }
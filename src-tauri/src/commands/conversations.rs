@@ -1,5 +1,6 @@
-use crate::database::{Conversation, Message};
+use crate::database::{Chunk, Conversation, ConversationScope, Message, MessageRole};
 use crate::error::RecallError;
+use crate::rag::format_citations;
 use crate::state::AppState;
 use std::sync::Arc;
 use tauri::State;
@@ -24,15 +25,67 @@ pub async fn get_conversation_messages(
     state: State<'_, Arc<AppState>>,
     conversation_id: String,
 ) -> Result<Vec<Message>, RecallError> {
-    state.database.get_conversation_messages(&conversation_id)
+    // Stored answers keep the model's [chunk_id] markers; show them in the
+    // current citation style
+    let citation_style = state.settings.read().citation_style;
+    let mut messages = state.database.get_conversation_messages(&conversation_id)?;
+    for message in messages.iter_mut().filter(|m| m.role == MessageRole::Assistant) {
+        message.content = format_citations(&message.content, &message.citations, citation_style);
+    }
+    Ok(messages)
 }
 
 #[tauri::command]
 pub async fn create_conversation(
     state: State<'_, Arc<AppState>>,
     title: Option<String>,
+    scope: Option<ConversationScope>,
 ) -> Result<Conversation, RecallError> {
-    state.database.create_conversation(title.as_deref())
+    let mut conversation = state.database.create_conversation(title.as_deref())?;
+    if let Some(scope) = scope.filter(|s| !s.is_empty()) {
+        state.database.set_conversation_scope(&conversation.id, &scope)?;
+        conversation.scope = scope;
+    }
+    Ok(conversation)
+}
+
+/// Change the documents and categories a conversation searches by default.
+/// An empty scope makes it search everything again.
+#[tauri::command]
+pub async fn set_conversation_scope(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+    scope: ConversationScope,
+) -> Result<(), RecallError> {
+    state.database.set_conversation_scope(&id, &scope)
+}
+
+/// Always include a chunk in this conversation's context, whatever the
+/// retrieval scores. Pins are dropped when the chunk's document is re-ingested.
+#[tauri::command]
+pub async fn pin_chunk(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: String,
+    chunk_id: i64,
+) -> Result<(), RecallError> {
+    state.database.pin_chunk(&conversation_id, chunk_id)
+}
+
+#[tauri::command]
+pub async fn unpin_chunk(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: String,
+    chunk_id: i64,
+) -> Result<(), RecallError> {
+    state.database.unpin_chunk(&conversation_id, chunk_id)
+}
+
+#[tauri::command]
+pub async fn get_pinned_chunks(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: String,
+) -> Result<Vec<Chunk>, RecallError> {
+    state.database.get_pinned_chunks(&conversation_id)
 }
 
 #[tauri::command]
@@ -1,38 +1,32 @@
-use crate::database::{Chunk, Document, IngestionStats};
+use crate::database::{
+    CategoryCount, Chunk, ChunkContext, CompactionResult, Document, DocumentVersion, IngestionSource, IngestionStats,
+    IngestionTimelineEntry, TimelineBucket,
+};
 use crate::error::RecallError;
-use crate::llm::{GenerateRequest, LlmProvider};
+use crate::ingestion::{
+    apply_category_labels, apply_language, build_content_sample, detect_chunks_language, request_batch_category_labels,
+    request_category_labels, DetectedLanguage, DocumentCategorization, DocumentPreview, DocumentRepairSummary,
+    DocumentValidation, CONTENT_CATEGORIES, DEFAULT_PREVIEW_LINES,
+};
 use crate::state::AppState;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tauri::{Emitter, State};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ContentCategory {
-    pub category: String,
-    pub confidence: f32,
-}
-
-const CONTENT_CATEGORIES: &[&str] = &[
-    "Science & Technology",
-    "Business & Finance",
-    "Health & Medicine",
-    "Education & Learning",
-    "Arts & Entertainment",
-    "News & Current Events",
-    "Legal & Compliance",
-    "Personal & Lifestyle",
-    "Travel & Geography",
-    "Food & Cooking",
-    "Sports & Fitness",
-    "History & Culture",
-    "Politics & Government",
-    "Environment & Nature",
-    "Other",
-];
-
 #[tauri::command]
-pub async fn get_documents(state: State<'_, Arc<AppState>>) -> Result<Vec<Document>, RecallError> {
-    state.database.get_all_documents()
+pub async fn get_documents(
+    state: State<'_, Arc<AppState>>,
+    ingestion_source: Option<IngestionSource>,
+) -> Result<Vec<Document>, RecallError> {
+    let documents = state.database.get_all_documents()?;
+    Ok(match ingestion_source {
+        Some(source) => documents
+            .into_iter()
+            .filter(|d| d.ingestion_source == source)
+            .collect(),
+        None => documents,
+    })
 }
 
 #[tauri::command]
@@ -43,6 +37,115 @@ pub async fn get_document(
     state.database.get_document(&id)
 }
 
+/// Earlier versions of a document's file, newest first. Versions are kept
+/// when a changed file is re-ingested and `document_versions_kept` is set.
+#[tauri::command]
+pub async fn get_document_history(
+    state: State<'_, Arc<AppState>>,
+    document_id: String,
+) -> Result<Vec<DocumentVersion>, RecallError> {
+    let doc = state
+        .database
+        .get_document(&document_id)?
+        .ok_or_else(|| RecallError::NotFound(format!("Document not found: {}", document_id)))?;
+    state.database.get_document_versions(&doc.file_path)
+}
+
+/// Record that a document was opened or viewed
+#[tauri::command]
+pub async fn record_document_access(
+    state: State<'_, Arc<AppState>>,
+    document_id: String,
+) -> Result<(), RecallError> {
+    state.database.record_document_access(&[document_id])
+}
+
+/// Recently opened, viewed or cited documents, newest first
+#[tauri::command]
+pub async fn get_recent_documents(
+    state: State<'_, Arc<AppState>>,
+    limit: Option<usize>,
+) -> Result<Vec<Document>, RecallError> {
+    state.database.get_recent_documents(limit.unwrap_or(10))
+}
+
+/// Metadata keys the app maintains itself: capture provenance, the missing
+/// source flag, and the ranked labels written by categorization
+const PROTECTED_METADATA_FIELDS: &[&str] = &[
+    "capture_type",
+    "capture_mode",
+    "captured_at",
+    "resolution",
+    "source_missing",
+    "content_categories",
+];
+
+/// Metadata keys that other code reads as strings
+const STRING_METADATA_FIELDS: &[&str] = &[
+    "content_category",
+    "language",
+    "language_name",
+    "source_app",
+    "window_title",
+];
+
+#[tauri::command]
+pub async fn get_document_metadata(
+    state: State<'_, Arc<AppState>>,
+    document_id: String,
+) -> Result<serde_json::Value, RecallError> {
+    let doc = state
+        .database
+        .get_document(&document_id)?
+        .ok_or_else(|| RecallError::NotFound(format!("Document not found: {}", document_id)))?;
+    Ok(doc.metadata)
+}
+
+/// Set one metadata key, leaving the others untouched. A null value removes
+/// the key. Returns the updated metadata.
+#[tauri::command]
+pub async fn set_document_metadata_field(
+    state: State<'_, Arc<AppState>>,
+    document_id: String,
+    key: String,
+    value: serde_json::Value,
+) -> Result<serde_json::Value, RecallError> {
+    let doc = state
+        .database
+        .get_document(&document_id)?
+        .ok_or_else(|| RecallError::NotFound(format!("Document not found: {}", document_id)))?;
+
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(RecallError::Other("Metadata key cannot be empty".to_string()));
+    }
+    if PROTECTED_METADATA_FIELDS.contains(&key) {
+        return Err(RecallError::Other(format!("Metadata field '{}' is managed by Recall and cannot be edited", key)));
+    }
+    if STRING_METADATA_FIELDS.contains(&key) && !(value.is_string() || value.is_null()) {
+        return Err(RecallError::Other(format!("Metadata field '{}' must be a string", key)));
+    }
+    if key == "content_category" {
+        if let Some(category) = value.as_str().filter(|c| !CONTENT_CATEGORIES.contains(c)) {
+            return Err(RecallError::Other(format!("Unknown content category: {}", category)));
+        }
+    }
+
+    let mut metadata = match doc.metadata {
+        serde_json::Value::Object(map) => map,
+        _ => serde_json::Map::new(),
+    };
+    if value.is_null() {
+        metadata.remove(key);
+    } else {
+        metadata.insert(key.to_string(), value);
+    }
+
+    let metadata = serde_json::Value::Object(metadata);
+    state.database.update_document_metadata(&document_id, metadata.clone())?;
+    Ok(metadata)
+}
+
 #[tauri::command]
 pub async fn delete_document(
     state: State<'_, Arc<AppState>>,
@@ -59,6 +162,52 @@ pub async fn get_chunks_for_document(
     state.database.get_chunks_for_document(&document_id)
 }
 
+/// Return a chunk with up to `radius` neighbours on each side (default 1),
+/// so a viewer can scroll to and highlight a cited passage
+#[tauri::command]
+pub async fn get_chunk_context(
+    state: State<'_, Arc<AppState>>,
+    chunk_id: i64,
+    radius: Option<u32>,
+) -> Result<ChunkContext, RecallError> {
+    let chunk = state
+        .database
+        .get_chunk(chunk_id)?
+        .ok_or_else(|| RecallError::NotFound(format!("Chunk not found: {}", chunk_id)))?;
+
+    let radius = radius.unwrap_or(1).min(10) as i32;
+    let (before, after): (Vec<Chunk>, Vec<Chunk>) = state
+        .database
+        .get_neighbor_chunks(&chunk.document_id, chunk.chunk_index, radius)?
+        .into_iter()
+        .filter(|c| c.id != chunk.id)
+        .partition(|c| c.chunk_index < chunk.chunk_index);
+
+    Ok(ChunkContext { chunk, before, after })
+}
+
+/// Preview a document: first page for PDFs, a thumbnail for images, or the
+/// first `max_lines` lines for text. Rendered images are cached on disk.
+#[tauri::command]
+pub async fn render_document_preview(
+    state: State<'_, Arc<AppState>>,
+    document_id: String,
+    max_lines: Option<usize>,
+) -> Result<DocumentPreview, RecallError> {
+    let document = state
+        .database
+        .get_document(&document_id)?
+        .ok_or_else(|| RecallError::NotFound(format!("Document not found: {}", document_id)))?;
+
+    let cache_dir = state.app_data_dir.join("previews");
+    crate::ingestion::render_document_preview(
+        &document,
+        &cache_dir,
+        max_lines.unwrap_or(DEFAULT_PREVIEW_LINES),
+    )
+    .await
+}
+
 #[tauri::command]
 pub async fn get_ingestion_stats(
     state: State<'_, Arc<AppState>>,
@@ -66,6 +215,20 @@ pub async fn get_ingestion_stats(
     state.database.get_ingestion_stats()
 }
 
+/// Documents added per day, week or month, broken down by file type.
+/// Dates are `YYYY-MM-DD` and inclusive.
+#[tauri::command]
+pub async fn get_ingestion_timeline(
+    state: State<'_, Arc<AppState>>,
+    start_date: Option<chrono::NaiveDate>,
+    end_date: Option<chrono::NaiveDate>,
+    bucket: Option<TimelineBucket>,
+) -> Result<Vec<IngestionTimelineEntry>, RecallError> {
+    state
+        .database
+        .get_ingestion_timeline(start_date, end_date, bucket.unwrap_or_default())
+}
+
 #[tauri::command]
 pub async fn reset_database(
     state: State<'_, Arc<AppState>>,
@@ -83,6 +246,7 @@ pub async fn reset_database(
         tx.execute("DELETE FROM messages", [])?;
         tx.execute("DELETE FROM conversations", [])?;
         tx.execute("DELETE FROM documents", [])?;
+        tx.execute("DELETE FROM document_versions", [])?;
         tx.commit()?;
         Ok(())
     });
@@ -95,6 +259,11 @@ pub async fn reset_database(
             tracing::warn!("SQL reset failed ({}), performing hard reset...", e);
             // Database is corrupted, do a hard reset (delete files and recreate)
             state.database.hard_reset()?;
+
+            // The recreated indexes use the defaults; rebuild them as configured
+            let settings = state.settings.read().clone();
+            AppState::sync_fts_tokenizer(&state.database, &settings)?;
+            AppState::sync_similarity_metric(&state.database, &settings)?;
         }
     }
 
@@ -102,6 +271,7 @@ pub async fn reset_database(
     {
         let mut settings = state.settings.write();
         settings.watched_folders.clear();
+        settings.watched_folder_excludes.clear();
         settings.auto_ingest_enabled = false;
     }
     state.save_settings()?;
@@ -116,11 +286,84 @@ pub async fn reset_database(
     Ok(())
 }
 
+/// Reclaim disk space left behind by deleted documents. Refused while
+/// ingestion is running or queued, since VACUUM would block it for the
+/// whole rebuild.
 #[tauri::command]
-pub async fn open_file_in_default_app(path: String) -> Result<(), RecallError> {
+pub async fn compact_database(
+    state: State<'_, Arc<AppState>>,
+    analyze: Option<bool>,
+) -> Result<CompactionResult, RecallError> {
+    let (queue_len, is_processing) = state.ingestion_engine.get_queue_status();
+    if is_processing || queue_len > 0 {
+        return Err(RecallError::Ingestion(
+            "Cannot compact the database while ingestion is in progress".to_string(),
+        ));
+    }
+
+    let database = state.database.clone();
+    let analyze = analyze.unwrap_or(true);
+    tokio::task::spawn_blocking(move || database.compact(analyze))
+        .await
+        .map_err(|e| RecallError::Other(format!("Compaction task failed: {}", e)))?
+}
+
+/// Check every document for drift: missing or changed source files, empty
+/// hashes, mime or file types, timestamps and chunk counts, and statuses left
+/// behind by an interrupted ingestion. Documents being ingested are skipped.
+#[tauri::command]
+pub async fn validate_documents(state: State<'_, Arc<AppState>>) -> Result<Vec<DocumentValidation>, RecallError> {
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        crate::ingestion::validate_documents(&state.database, |id| state.ingestion_engine.is_ingesting(id))
+    })
+    .await
+    .map_err(|e| RecallError::Other(format!("Validation task failed: {}", e)))?
+}
+
+/// Fix the issues `validate_documents` finds that don't need a re-ingest,
+/// returning the documents that still need attention
+#[tauri::command]
+pub async fn repair_documents(state: State<'_, Arc<AppState>>) -> Result<DocumentRepairSummary, RecallError> {
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        crate::ingestion::repair_documents(&state.database, |id| state.ingestion_engine.is_ingesting(id))
+    })
+    .await
+    .map_err(|e| RecallError::Other(format!("Repair task failed: {}", e)))?
+}
+
+/// Compress or decompress the text of existing chunks to match the
+/// `compress_chunk_text` setting. Run `compact_database` afterwards to hand
+/// the freed space back to the filesystem.
+#[tauri::command]
+pub async fn convert_chunk_storage(state: State<'_, Arc<AppState>>) -> Result<usize, RecallError> {
+    let (queue_len, is_processing) = state.ingestion_engine.get_queue_status();
+    if is_processing || queue_len > 0 {
+        return Err(RecallError::Ingestion(
+            "Cannot convert chunk storage while ingestion is in progress".to_string(),
+        ));
+    }
+
+    let database = state.database.clone();
+    let compress = state.settings.read().compress_chunk_text;
+    tokio::task::spawn_blocking(move || database.convert_chunk_storage(compress))
+        .await
+        .map_err(|e| RecallError::Other(format!("Chunk conversion task failed: {}", e)))?
+}
+
+#[tauri::command]
+pub async fn open_file_in_default_app(
+    state: State<'_, Arc<AppState>>,
+    path: String,
+) -> Result<(), RecallError> {
     // Validate path exists and is a file to prevent command injection
     let path_ref = std::path::Path::new(&path);
     if !path_ref.exists() {
+        // Badge the document so the library shows the file is gone
+        if let Some(doc) = state.database.get_document_by_path(&path)? {
+            state.database.refresh_source_missing(&doc)?;
+        }
         return Err(RecallError::NotFound(format!("File not found: {}", path)));
     }
     if !path_ref.is_file() {
@@ -132,6 +375,10 @@ pub async fn open_file_in_default_app(path: String) -> Result<(), RecallError> {
     opener::open(&path)
         .map_err(|e| RecallError::Other(format!("Failed to open file: {}", e)))?;
 
+    if let Some(doc) = state.database.get_document_by_path(&path)? {
+        state.database.record_document_access(&[doc.id])?;
+    }
+
     Ok(())
 }
 
@@ -139,7 +386,7 @@ pub async fn open_file_in_default_app(path: String) -> Result<(), RecallError> {
 pub async fn categorize_document(
     state: State<'_, Arc<AppState>>,
     document_id: String,
-) -> Result<ContentCategory, RecallError> {
+) -> Result<DocumentCategorization, RecallError> {
     // Get the document
     let doc = state
         .database
@@ -153,16 +400,6 @@ pub async fn categorize_document(
         return Err(RecallError::Other("Document has no content to categorize".to_string()));
     }
 
-    // Take first few chunks as a sample (limit to ~2000 chars)
-    let mut content_sample = String::new();
-    for chunk in chunks.iter().take(5) {
-        content_sample.push_str(&chunk.content);
-        content_sample.push_str("\n\n");
-        if content_sample.len() > 2000 {
-            break;
-        }
-    }
-
     // Get LLM client
     let llm = {
         let guard = state.llm_client.read();
@@ -172,61 +409,45 @@ pub async fn categorize_document(
             .clone()
     };
 
-    // Build categorization prompt
-    let categories_list = CONTENT_CATEGORIES.join("\n- ");
-    let prompt = format!(
-        r#"Analyze this document and categorize it into ONE of these categories:
-- {}
-
-Document title: {}
-Content sample:
-{}
-
-Respond with ONLY the category name, nothing else."#,
-        categories_list, doc.title, content_sample
-    );
-
-    let request = GenerateRequest {
-        prompt,
-        system_prompt: Some("You are a document categorization assistant. Respond with only the category name.".to_string()),
-        context: vec![],
-        history: vec![],
-        max_tokens: Some(50),
-        temperature: Some(0.1),
-    };
-
-    let response = llm.generate(request).await?;
-    let category = response.content.trim().to_string();
-
-    // Validate category
-    let valid_category = CONTENT_CATEGORIES
-        .iter()
-        .find(|&&c| category.eq_ignore_ascii_case(c))
-        .map(|&c| c.to_string())
-        .unwrap_or_else(|| "Other".to_string());
+    let labels = request_category_labels(&llm, &doc.title, &build_content_sample(&chunks)).await?;
 
     // Update document metadata
     let mut metadata = doc.metadata.clone();
-    metadata["content_category"] = serde_json::json!(valid_category);
+    let categorization = apply_category_labels(&mut metadata, labels);
     state.database.update_document_metadata(&document_id, metadata)?;
 
-    Ok(ContentCategory {
-        category: valid_category,
-        confidence: 1.0,
-    })
+    Ok(categorization)
 }
 
+/// Documents categorized per LLM request in `categorize_all_documents`
+const CATEGORIZATION_BATCH_SIZE: usize = 5;
+/// Times a rate-limited categorization request is retried
+const CATEGORIZATION_MAX_RETRIES: u32 = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategorizationProgress {
+    pub processed: usize,
+    pub total: usize,
+    pub categorized: usize,
+    pub failed: usize,
+}
+
+/// Categorize every uncategorized document, several per LLM request.
+/// Emits `categorization-progress` after each batch, waits out rate limits,
+/// and stops early after `cancel_categorization`.
 #[tauri::command]
 pub async fn categorize_all_documents(
     state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
 ) -> Result<Vec<(String, String)>, RecallError> {
-    let documents = state.database.get_all_documents()?;
-    let mut results = Vec::new();
+    state.categorization_cancelled.store(false, Ordering::SeqCst);
 
-    for doc in documents {
+    let mut results = Vec::new();
+    let mut pending = Vec::new();
+    for doc in state.database.get_all_documents()? {
         // Skip if already categorized
-        if doc.metadata.get("content_category").is_some() {
-            results.push((doc.id.clone(), doc.metadata["content_category"].as_str().unwrap_or("Other").to_string()));
+        if let Some(category) = doc.metadata.get("content_category") {
+            results.push((doc.id.clone(), category.as_str().unwrap_or("Other").to_string()));
             continue;
         }
 
@@ -235,77 +456,137 @@ pub async fn categorize_all_documents(
         if chunks.is_empty() {
             continue;
         }
+        let sample = build_content_sample(&chunks);
+        pending.push((doc, sample));
+    }
 
-        // Take first few chunks as a sample
-        let mut content_sample = String::new();
-        for chunk in chunks.iter().take(5) {
-            content_sample.push_str(&chunk.content);
-            content_sample.push_str("\n\n");
-            if content_sample.len() > 2000 {
-                break;
-            }
+    // Clone LLM client to avoid holding lock across await
+    let llm = {
+        let guard = state.llm_client.read();
+        match guard.as_ref() {
+            Some(client) => client.clone(),
+            None if pending.is_empty() => return Ok(results),
+            None => return Err(RecallError::Config("LLM client not configured".to_string())),
         }
+    };
 
-        // Get LLM client
-        let llm = {
-            let guard = state.llm_client.read();
-            match guard.as_ref() {
-                Some(client) => client.clone(),
-                None => continue,
-            }
-        };
+    let mut progress = CategorizationProgress {
+        processed: 0,
+        total: pending.len(),
+        categorized: 0,
+        failed: 0,
+    };
+
+    for batch in pending.chunks(CATEGORIZATION_BATCH_SIZE) {
+        if state.categorization_cancelled.load(Ordering::SeqCst) {
+            tracing::info!("Categorization cancelled after {} of {} documents", progress.processed, progress.total);
+            break;
+        }
 
-        // Build categorization prompt
-        let categories_list = CONTENT_CATEGORIES.join("\n- ");
-        let prompt = format!(
-            r#"Analyze this document and categorize it into ONE of these categories:
-- {}
-
-Document title: {}
-Content sample:
-{}
-
-Respond with ONLY the category name, nothing else."#,
-            categories_list, doc.title, content_sample
-        );
-
-        let request = GenerateRequest {
-            prompt,
-            system_prompt: Some("You are a document categorization assistant. Respond with only the category name.".to_string()),
-            context: vec![],
-            history: vec![],
-            max_tokens: Some(50),
-            temperature: Some(0.1),
+        let mut labels = match with_rate_limit_retry(|| request_batch_category_labels(&llm, batch)).await {
+            Ok(labels) => labels,
+            Err(e) => {
+                tracing::warn!("Batch categorization failed, trying documents one by one: {}", e);
+                vec![None; batch.len()]
+            }
         };
 
-        match llm.generate(request).await {
-            Ok(response) => {
-                let category = response.content.trim().to_string();
-                let valid_category = CONTENT_CATEGORIES
-                    .iter()
-                    .find(|&&c| category.eq_ignore_ascii_case(c))
-                    .map(|&c| c.to_string())
-                    .unwrap_or_else(|| "Other".to_string());
-
-                // Update document metadata
-                let mut metadata = doc.metadata.clone();
-                metadata["content_category"] = serde_json::json!(valid_category);
-                if let Err(e) = state.database.update_document_metadata(&doc.id, metadata) {
-                    tracing::warn!("Failed to update metadata for {}: {}", doc.id, e);
-                } else {
-                    results.push((doc.id.clone(), valid_category));
+        for ((doc, sample), labels) in batch.iter().zip(labels.iter_mut()) {
+            // Documents the batch answer left out get their own request
+            let labels = match labels.take() {
+                Some(labels) => Ok(labels),
+                None => with_rate_limit_retry(|| request_category_labels(&llm, &doc.title, sample)).await,
+            };
+
+            match labels {
+                Ok(labels) => {
+                    // Update document metadata
+                    let mut metadata = doc.metadata.clone();
+                    let categorization = apply_category_labels(&mut metadata, labels);
+                    if let Err(e) = state.database.update_document_metadata(&doc.id, metadata) {
+                        tracing::warn!("Failed to update metadata for {}: {}", doc.id, e);
+                        progress.failed += 1;
+                    } else {
+                        results.push((doc.id.clone(), categorization.category));
+                        progress.categorized += 1;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to categorize document {}: {}", doc.id, e);
+                    progress.failed += 1;
                 }
             }
-            Err(e) => {
-                tracing::warn!("Failed to categorize document {}: {}", doc.id, e);
-            }
+            progress.processed += 1;
         }
+
+        let _ = app_handle.emit("categorization-progress", &progress);
     }
 
     Ok(results)
 }
 
+/// Stop a running `categorize_all_documents` once its current batch finishes
+#[tauri::command]
+pub async fn cancel_categorization(state: State<'_, Arc<AppState>>) -> Result<(), RecallError> {
+    state.categorization_cancelled.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Run `request`, waiting and retrying when the provider rate-limits it.
+/// The client's limiter is paused by the 429 too, so the retry queues behind it.
+async fn with_rate_limit_retry<T, F, Fut>(mut request: F) -> Result<T, RecallError>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, RecallError>>,
+{
+    let mut retries = 0;
+    loop {
+        match request().await {
+            Err(RecallError::RateLimit(wait_secs)) if retries < CATEGORIZATION_MAX_RETRIES => {
+                retries += 1;
+                tracing::info!(
+                    "Categorization rate limited, waiting {}s (retry {}/{})",
+                    wait_secs,
+                    retries,
+                    CATEGORIZATION_MAX_RETRIES
+                );
+                tokio::time::sleep(std::time::Duration::from_secs(wait_secs)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn get_content_categories() -> Result<Vec<String>, RecallError> {
     Ok(CONTENT_CATEGORIES.iter().map(|&s| s.to_string()).collect())
 }
+
+#[tauri::command]
+pub async fn get_category_distribution(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<CategoryCount>, RecallError> {
+    state.database.get_category_distribution()
+}
+
+#[tauri::command]
+pub async fn detect_document_language(
+    state: State<'_, Arc<AppState>>,
+    document_id: String,
+) -> Result<Option<DetectedLanguage>, RecallError> {
+    let doc = state
+        .database
+        .get_document(&document_id)?
+        .ok_or_else(|| RecallError::NotFound(format!("Document not found: {}", document_id)))?;
+
+    let chunks = state.database.get_chunks_for_document(&document_id)?;
+    let language = detect_chunks_language(&chunks);
+
+    if let Some(ref language) = language {
+        let mut metadata = doc.metadata.clone();
+        apply_language(&mut metadata, language);
+        state.database.update_document_metadata(&document_id, metadata)?;
+    }
+
+    Ok(language)
+}
@@ -1,10 +1,18 @@
-use crate::database::{Document, IngestionProgress};
+use crate::database::{Document, DocumentStatus, FileType, IngestionProgress, IngestionSource};
 use crate::error::RecallError;
-use crate::ingestion::QueuedFile;
+use crate::ingestion::{
+    compute_file_hash, detect_file_type, estimate_file, extract_pdf, extract_subtitles, extract_text, BatchFailure, Chunker, ExtractedContent,
+    FFmpeg, FFmpegStatus, IngestionCostEstimate, PriceTable, QueueRemoval, QueuedFile,
+};
+use crate::llm::LlmProvider;
+use crate::logging::DocumentLogLine;
 use crate::state::AppState;
-use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, State};
 use walkdir::WalkDir;
 
 #[tauri::command]
@@ -22,8 +30,56 @@ pub async fn ingest_file(
         )));
     }
 
-    // Trial limit is enforced inside IngestionEngine::ingest_file()
-    state.ingestion_engine.ingest_file(&path, &app_handle).await
+    // Trial limit is enforced inside IngestionEngine::ingest_file(); the
+    // event lets the UI prompt for activation
+    let result = state.ingestion_engine.ingest_file(&path, &app_handle).await;
+    if let Err(RecallError::TrialLimitReached(msg)) = &result {
+        let _ = app_handle.emit("trial-limit-reached", msg);
+    }
+    result
+}
+
+/// Ingest a password-protected PDF. The password opens the file for this
+/// ingestion only; the document records that it was protected, not the password.
+#[tauri::command]
+pub async fn ingest_file_with_password(
+    state: State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
+    path: String,
+    password: String,
+) -> Result<Document, RecallError> {
+    let path = PathBuf::from(path);
+
+    if !path.exists() {
+        return Err(RecallError::NotFound(format!(
+            "File not found: {}",
+            path.display()
+        )));
+    }
+    if detect_file_type(&path) != FileType::Pdf {
+        return Err(RecallError::Ingestion("Passwords are only supported for PDF files".to_string()));
+    }
+
+    let result = state.ingestion_engine.ingest_file_with_password(&path, &password, &app_handle).await;
+    if let Err(RecallError::TrialLimitReached(msg)) = &result {
+        let _ = app_handle.emit("trial-limit-reached", msg);
+    }
+    result
+}
+
+/// Skip hidden files and unsupported types
+fn is_ingestible(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.starts_with('.'))
+        .unwrap_or(true);
+    if hidden {
+        return false;
+    }
+
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    !matches!(FileType::from_extension(ext), FileType::Unknown)
 }
 
 #[tauri::command]
@@ -43,64 +99,237 @@ pub async fn ingest_directory(
     }
 
     let recursive = recursive.unwrap_or(true);
-    let mut documents = Vec::new();
-    let mut errors = Vec::new();
-
     let walker = if recursive {
         WalkDir::new(&path)
     } else {
         WalkDir::new(&path).max_depth(1)
     };
 
+    let paths: Vec<PathBuf> = walker
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_ingestible(e.path()))
+        .map(|e| e.into_path())
+        .collect();
+
+    // Records are created in one batch; disabled file types are skipped and
+    // the trial limit is enforced inside
+    let (documents, errors) = state
+        .ingestion_engine
+        .ingest_files(&paths, IngestionSource::Manual, &app_handle)
+        .await;
+
+    if documents.is_empty() && !errors.is_empty() {
+        return Err(RecallError::Ingestion(format!(
+            "All files failed to ingest: {}",
+            errors.join("; ")
+        )));
+    }
+
+    Ok(documents)
+}
+
+/// Project the embedding tokens, OCR pages, vision frames and audio minutes
+/// ingesting `paths` would use, without extracting anything. Directories are
+/// expanded like `ingest_directory`; disabled file types are left out. With
+/// `prices`, the projection is also priced.
+#[tauri::command]
+pub async fn estimate_ingestion_cost(
+    state: State<'_, Arc<AppState>>,
+    paths: Vec<String>,
+    recursive: Option<bool>,
+    prices: Option<PriceTable>,
+) -> Result<IngestionCostEstimate, RecallError> {
+    let settings = state.settings.read().clone();
+    let recursive = recursive.unwrap_or(true);
+
+    let mut files = Vec::new();
+    for path in paths.iter().map(PathBuf::from) {
+        if path.is_dir() {
+            let walker = if recursive {
+                WalkDir::new(&path)
+            } else {
+                WalkDir::new(&path).max_depth(1)
+            };
+            files.extend(
+                walker
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_file() && is_ingestible(e.path()))
+                    .map(|e| e.into_path()),
+            );
+        } else {
+            files.push(path);
+        }
+    }
+
+    let ffmpeg = FFmpeg::with_override(settings.ffmpeg_path.as_deref()).await.ok();
+    let mut estimate = IngestionCostEstimate::default();
+    for file in files {
+        if state.ingestion_engine.disabled_file_type(&file).is_some() {
+            continue;
+        }
+        match estimate_file(&file, &settings, ffmpeg.as_ref()).await {
+            Ok(file_estimate) => estimate.add(file_estimate),
+            Err(e) => estimate.errors.push(format!("{}: {}", file.display(), e)),
+        }
+    }
+
+    if let Some(prices) = prices {
+        estimate.apply_prices(&prices);
+    }
+    Ok(estimate)
+}
+
+/// What `sync_directory` does with documents whose source file is gone
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingSourceAction {
+    /// Keep the document and its chunks, flagged `source_missing` in metadata
+    #[default]
+    Flag,
+    /// Delete the document from the library
+    Delete,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DirectorySyncSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub moved: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub flagged_missing: usize,
+    pub failed: usize,
+    pub errors: Vec<String>,
+}
+
+/// Bring the library in line with a directory: ingest new and changed
+/// files, follow moves, and delete or flag documents whose file is gone.
+/// A file whose hash matches a document at another path is a move, which
+/// `ingest_file` resolves by updating the path and keeping the chunks.
+#[tauri::command]
+pub async fn sync_directory(
+    state: State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
+    path: String,
+    recursive: Option<bool>,
+    on_missing: Option<MissingSourceAction>,
+) -> Result<DirectorySyncSummary, RecallError> {
+    let root = PathBuf::from(path);
+
+    if !root.is_dir() {
+        return Err(RecallError::NotFound(format!(
+            "Directory not found: {}",
+            root.display()
+        )));
+    }
+
+    let recursive = recursive.unwrap_or(true);
+    let on_missing = on_missing.unwrap_or_default();
+    let in_scope = |file: &Path| {
+        if recursive {
+            file.starts_with(&root)
+        } else {
+            file.parent() == Some(root.as_path())
+        }
+    };
+
+    let mut summary = DirectorySyncSummary::default();
+
+    let walker = if recursive {
+        WalkDir::new(&root)
+    } else {
+        WalkDir::new(&root).max_depth(1)
+    };
+
     for entry in walker.into_iter().filter_map(|e| e.ok()) {
-        if entry.file_type().is_file() {
-            let file_path = entry.path();
-
-            // Skip hidden files and unsupported types
-            if file_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .map(|n| n.starts_with('.'))
-                .unwrap_or(true)
-            {
+        let file_path = entry.path();
+        if !entry.file_type().is_file() || !is_ingestible(file_path) {
+            continue;
+        }
+        if state.ingestion_engine.disabled_file_type(file_path).is_some() {
+            continue;
+        }
+
+        let path_str = file_path.to_string_lossy().to_string();
+        let hash = match compute_file_hash(file_path) {
+            Ok(hash) => hash,
+            Err(e) => {
+                summary.failed += 1;
+                summary.errors.push(format!("{}: {}", file_path.display(), e));
                 continue;
             }
+        };
 
-            let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            let file_type = crate::database::FileType::from_extension(ext);
-
-            if matches!(file_type, crate::database::FileType::Unknown) {
+        // Classify before ingesting, since ingest_file replaces the records
+        let existing = state.database.get_document_by_path(&path_str)?;
+        let outcome = match &existing {
+            Some(doc) if doc.file_hash == hash && doc.status == DocumentStatus::Completed => {
+                summary.unchanged += 1;
                 continue;
             }
+            Some(_) => &mut summary.updated,
+            None => match state.ingestion_engine.find_moved_document(file_path, &hash)? {
+                Some(_) => &mut summary.moved,
+                None => &mut summary.added,
+            },
+        };
+        *outcome += 1;
 
-            // Trial limit is enforced inside IngestionEngine::ingest_file()
-            match state
-                .ingestion_engine
-                .ingest_file(file_path, &app_handle)
-                .await
-            {
-                Ok(doc) => documents.push(doc),
-                Err(RecallError::TrialLimitReached(msg)) => {
-                    tracing::warn!("Trial limit reached during directory ingest: {}", msg);
-                    errors.push(msg);
-                    break;
-                }
-                Err(e) => {
-                    tracing::error!("Failed to ingest {:?}: {}", file_path, e);
-                    errors.push(format!("{}: {}", file_path.display(), e));
-                }
+        match state
+            .ingestion_engine
+            .ingest_file_from(file_path, IngestionSource::Watched, &app_handle)
+            .await
+        {
+            Ok(_) => {}
+            Err(RecallError::TrialLimitReached(msg)) => {
+                tracing::warn!("Trial limit reached during directory sync: {}", msg);
+                summary.errors.push(msg);
+                break;
+            }
+            Err(e) => {
+                tracing::error!("Failed to sync {:?}: {}", file_path, e);
+                summary.failed += 1;
+                summary.errors.push(format!("{}: {}", file_path.display(), e));
             }
         }
     }
 
-    if documents.is_empty() && !errors.is_empty() {
-        return Err(RecallError::Ingestion(format!(
-            "All files failed to ingest: {}",
-            errors.join("; ")
-        )));
+    // Anything still pointing inside the folder at a file that isn't there
+    // was deleted; moves were already re-pointed above
+    for doc in state.database.get_all_documents()? {
+        let file_path = Path::new(&doc.file_path);
+        if !in_scope(file_path) {
+            continue;
+        }
+
+        match on_missing {
+            MissingSourceAction::Delete if !file_path.exists() => {
+                state.database.delete_document(&doc.id)?;
+                summary.removed += 1;
+            }
+            _ => {
+                if state.database.refresh_source_missing(&doc)? {
+                    summary.flagged_missing += 1;
+                }
+            }
+        }
     }
 
-    Ok(documents)
+    tracing::info!(
+        "Synced {}: {} added, {} updated, {} moved, {} unchanged, {} removed, {} missing, {} failed",
+        root.display(),
+        summary.added,
+        summary.updated,
+        summary.moved,
+        summary.unchanged,
+        summary.removed,
+        summary.flagged_missing,
+        summary.failed
+    );
+
+    Ok(summary)
 }
 
 #[tauri::command]
@@ -112,6 +341,52 @@ pub async fn cancel_ingestion(
     Ok(())
 }
 
+/// Remove one pending file (by path or document id) from the ingestion
+/// queue, or cancel it if it has already started processing
+#[tauri::command]
+pub async fn remove_from_queue(
+    state: State<'_, Arc<AppState>>,
+    path_or_id: String,
+) -> Result<QueueRemoval, RecallError> {
+    state.ingestion_engine.remove_from_queue(&path_or_id)
+}
+
+/// Answer to a `duplicate-content-found` prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateContentAction {
+    /// Keep both, marking the new document `duplicate_of` the original and
+    /// relating the two
+    Link,
+    /// Delete the new document
+    Skip,
+}
+
+/// Link a document to the earlier copy of its content or delete it
+#[tauri::command]
+pub async fn resolve_duplicate_content(
+    state: State<'_, Arc<AppState>>,
+    document_id: String,
+    duplicate_of_id: String,
+    action: DuplicateContentAction,
+) -> Result<(), RecallError> {
+    if state.database.get_document(&duplicate_of_id)?.is_none() {
+        return Err(RecallError::NotFound(format!("Document not found: {}", duplicate_of_id)));
+    }
+
+    match action {
+        DuplicateContentAction::Link => {
+            state
+                .database
+                .set_document_metadata_key(&document_id, "duplicate_of", &serde_json::json!(duplicate_of_id))?;
+            state
+                .database
+                .upsert_document_relations(&document_id, &[(duplicate_of_id, 1.0)])
+        }
+        DuplicateContentAction::Skip => state.database.delete_document(&document_id),
+    }
+}
+
 #[tauri::command]
 pub async fn get_ingestion_progress(
     state: State<'_, Arc<AppState>>,
@@ -128,6 +403,13 @@ pub async fn get_ingestion_progress(
     }
 }
 
+/// Stage-by-stage log lines captured while the document was last processed
+/// this session. Empty after a restart or once evicted from the buffer.
+#[tauri::command]
+pub async fn get_document_ingestion_log(document_id: String) -> Result<Vec<DocumentLogLine>, RecallError> {
+    Ok(crate::logging::document_log(&document_id))
+}
+
 /// Re-ingest a document with updated extraction logic (e.g., to apply OCR)
 #[tauri::command]
 pub async fn reingest_document(
@@ -143,7 +425,7 @@ pub async fn reingest_document(
 
     let file_path = PathBuf::from(&doc.file_path);
 
-    if !file_path.exists() {
+    if state.database.refresh_source_missing(&doc)? {
         return Err(RecallError::NotFound(format!(
             "Original file no longer exists: {}",
             doc.file_path
@@ -153,8 +435,361 @@ pub async fn reingest_document(
     // Delete the existing document (and its chunks/embeddings)
     state.database.delete_document(&id)?;
 
-    // Re-ingest with updated code
-    state.ingestion_engine.ingest_file(&file_path, &app_handle).await
+    // Re-ingest with updated code, keeping track of how it originally arrived
+    state
+        .ingestion_engine
+        .ingest_file_from(&file_path, doc.ingestion_source, &app_handle)
+        .await
+}
+
+/// Re-ingest a document forcing a file type, for files whose type was
+/// detected wrongly (e.g. a log file seen as unknown)
+#[tauri::command]
+pub async fn reingest_as(
+    state: State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
+    id: String,
+    file_type: FileType,
+) -> Result<Document, RecallError> {
+    let doc = state
+        .database
+        .get_document(&id)?
+        .ok_or_else(|| RecallError::NotFound(format!("Document not found: {}", id)))?;
+
+    if state.database.refresh_source_missing(&doc)? {
+        return Err(RecallError::NotFound(format!(
+            "Original file no longer exists: {}",
+            doc.file_path
+        )));
+    }
+
+    state
+        .ingestion_engine
+        .reingest_as(&doc, file_type, &app_handle)
+        .await
+}
+
+/// Which documents `reingest_documents` picks up; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ReingestFilter {
+    pub status: Option<DocumentStatus>,
+    pub file_type: Option<FileType>,
+    /// Content category, as assigned by categorization
+    pub category: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl ReingestFilter {
+    fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.file_type.is_none()
+            && self.category.is_none()
+            && self.created_after.is_none()
+            && self.created_before.is_none()
+    }
+
+    fn matches(&self, doc: &Document) -> bool {
+        self.status.is_none_or(|status| doc.status == status)
+            && self.file_type.is_none_or(|file_type| doc.file_type == file_type)
+            && self.created_after.is_none_or(|after| doc.created_at >= after)
+            && self.created_before.is_none_or(|before| doc.created_at < before)
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ReingestSummary {
+    pub enqueued: usize,
+    /// Matching documents whose source file no longer exists
+    pub skipped_missing: usize,
+    /// Matching documents that could not be queued, and why
+    pub failures: Vec<BatchFailure>,
+}
+
+/// Re-ingest every document matching `filter`, e.g. all failed documents or
+/// all PDFs. Documents are queued behind any running ingestion and can be
+/// cancelled individually like any other queued file. An empty filter is
+/// rejected rather than re-ingesting the whole library.
+#[tauri::command]
+pub async fn reingest_documents(
+    state: State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
+    filter: ReingestFilter,
+) -> Result<ReingestSummary, RecallError> {
+    if filter.is_empty() {
+        return Err(RecallError::Other("Choose at least one filter for re-ingestion".to_string()));
+    }
+
+    let in_category = match &filter.category {
+        Some(category) => Some(
+            state
+                .database
+                .get_document_ids_by_categories(std::slice::from_ref(category))?
+                .into_iter()
+                .collect::<HashSet<String>>(),
+        ),
+        None => None,
+    };
+
+    let mut summary = ReingestSummary::default();
+
+    for doc in state.database.get_all_documents()? {
+        if !filter.matches(&doc) || in_category.as_ref().is_some_and(|ids| !ids.contains(&doc.id)) {
+            continue;
+        }
+
+        let requeued = state.database.refresh_source_missing(&doc).and_then(|missing| {
+            if missing {
+                return Ok(None);
+            }
+            state.ingestion_engine.requeue_document(&doc).map(Some)
+        });
+        let new_doc = match requeued {
+            Ok(Some(new_doc)) => new_doc,
+            Ok(None) => {
+                summary.skipped_missing += 1;
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to queue {} for re-ingestion: {}", doc.file_path, e);
+                summary.failures.push(BatchFailure { path: doc.file_path.clone(), reason: e.to_string() });
+                continue;
+            }
+        };
+        let ingestion_engine = state.ingestion_engine.clone();
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = ingestion_engine.ingest_existing_document(&new_doc, &app_handle).await {
+                tracing::error!("Batch re-ingest failed for {}: {}", new_doc.file_path, e);
+            }
+        });
+        summary.enqueued += 1;
+    }
+
+    tracing::info!(
+        "Queued {} documents for re-ingestion ({} skipped, source missing; {} failed)",
+        summary.enqueued,
+        summary.skipped_missing,
+        summary.failures.len()
+    );
+
+    Ok(summary)
+}
+
+/// Point a document at its file's new location, keeping its chunks and
+/// embeddings. Use after moving files outside a synced folder.
+#[tauri::command]
+pub async fn relink_document(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+    new_path: String,
+) -> Result<Document, RecallError> {
+    let doc = state
+        .database
+        .get_document(&id)?
+        .ok_or_else(|| RecallError::NotFound(format!("Document not found: {}", id)))?;
+
+    let path = PathBuf::from(&new_path);
+    if !path.is_file() {
+        return Err(RecallError::NotFound(format!("File not found: {}", new_path)));
+    }
+
+    if let Some(other) = state.database.get_document_by_path(&new_path)? {
+        if other.id != doc.id {
+            return Err(RecallError::Ingestion(format!(
+                "'{}' is already in the library as '{}'",
+                new_path, other.title
+            )));
+        }
+    }
+
+    if compute_file_hash(&path)? != doc.file_hash {
+        tracing::warn!(
+            "Relinked file differs from the ingested content; reingest {} to refresh it",
+            id
+        );
+    }
+
+    let title = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    state.database.update_document_path(&doc.id, &new_path, &title)?;
+
+    state
+        .database
+        .get_document(&doc.id)?
+        .ok_or_else(|| RecallError::NotFound("Document not found after relink".to_string()))
+}
+
+/// Re-check every document's source file and update its `source_missing`
+/// flag. Returns the ids of documents whose file is missing.
+#[tauri::command]
+pub async fn check_missing_sources(state: State<'_, Arc<AppState>>) -> Result<Vec<String>, RecallError> {
+    let mut missing = Vec::new();
+    for doc in state.database.get_all_documents()? {
+        if state.database.refresh_source_missing(&doc)? {
+            missing.push(doc.id);
+        }
+    }
+
+    if !missing.is_empty() {
+        tracing::info!("{} documents have missing source files", missing.len());
+    }
+    Ok(missing)
+}
+
+/// Chunks embedded per round trip while backfilling
+const BACKFILL_PAGE_SIZE: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingBackfillProgress {
+    pub embedded: usize,
+    pub total: usize,
+}
+
+/// Embed every chunk that has no vector, e.g. documents ingested while
+/// embeddings were disabled. Emits `embedding-backfill-progress` per page and
+/// returns the number of chunks embedded.
+#[tauri::command]
+pub async fn backfill_embeddings(
+    state: State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
+) -> Result<usize, RecallError> {
+    if !state.settings.read().embeddings_enabled {
+        return Err(RecallError::Config(
+            "Enable embeddings before backfilling vectors".to_string(),
+        ));
+    }
+
+    // Clone LLM client to avoid holding lock across await
+    let llm = state
+        .llm_client
+        .read()
+        .as_ref()
+        .ok_or(RecallError::Config("LLM client not configured".to_string()))?
+        .clone();
+
+    let total = state.database.count_chunks_without_embeddings()?;
+    let mut embedded = 0;
+
+    while embedded < total {
+        let page = state.database.get_chunks_without_embeddings(BACKFILL_PAGE_SIZE)?;
+        if page.is_empty() {
+            break;
+        }
+
+        let (ids, texts): (Vec<i64>, Vec<String>) = page.into_iter().unzip();
+        let embeddings = llm.embed(&texts).await?;
+        state.database.insert_embeddings(&ids, &embeddings)?;
+        embedded += ids.len();
+
+        let _ = app_handle.emit(
+            "embedding-backfill-progress",
+            EmbeddingBackfillProgress { embedded, total },
+        );
+    }
+
+    tracing::info!("Backfilled embeddings for {} chunks", embedded);
+    Ok(embedded)
+}
+
+/// One chunk of a chunking preview
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewChunk {
+    pub chunk_index: i32,
+    pub content: String,
+    pub token_count: i32,
+    pub start_offset: Option<i32>,
+    pub end_offset: Option<i32>,
+    pub page_number: Option<i32>,
+    pub timestamp_start: Option<f64>,
+    pub timestamp_end: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkingPreview {
+    pub chunk_size: usize,
+    pub chunk_overlap: usize,
+    pub total_tokens: i64,
+    pub chunks: Vec<PreviewChunk>,
+}
+
+/// Show how `text`, or a document's source file, would be chunked with the
+/// current settings (or the given overrides). Nothing is written to the
+/// database and no API calls are made, so only documents whose text can be
+/// read locally (text, markdown, subtitles, text-based PDFs) can be previewed.
+#[tauri::command]
+pub async fn preview_chunking(
+    state: State<'_, Arc<AppState>>,
+    text: Option<String>,
+    document_id: Option<String>,
+    chunk_size: Option<usize>,
+    chunk_overlap: Option<usize>,
+) -> Result<ChunkingPreview, RecallError> {
+    let content = match (text, document_id) {
+        (Some(text), _) => ExtractedContent::Text { text, pages: None },
+        (None, Some(id)) => {
+            let doc = state
+                .database
+                .get_document(&id)?
+                .ok_or_else(|| RecallError::NotFound(format!("Document not found: {}", id)))?;
+            let path = Path::new(&doc.file_path);
+            match doc.file_type {
+                FileType::Text | FileType::Markdown => extract_text(path).await?,
+                FileType::Subtitle => extract_subtitles(path).await?,
+                FileType::Pdf => extract_pdf(path, None).await?,
+                other => {
+                    return Err(RecallError::Ingestion(format!(
+                        "Cannot preview {} documents without re-extracting them; paste sample text instead",
+                        other.as_str()
+                    )))
+                }
+            }
+        }
+        (None, None) => return Err(RecallError::Other("Provide text or a document id".to_string())),
+    };
+
+    let (chunk_size, chunk_overlap, dedupe_chunks, min_chunk_chars) = {
+        let settings = state.settings.read();
+        (
+            chunk_size.unwrap_or(settings.chunk_size).max(1),
+            chunk_overlap.unwrap_or(settings.chunk_overlap),
+            settings.dedupe_chunks,
+            settings.min_chunk_chars,
+        )
+    };
+    let chunker = Chunker::new(chunk_size, chunk_overlap)
+        .with_dedupe(dedupe_chunks)
+        .with_min_chars(min_chunk_chars);
+
+    // Tokenizing a large file is CPU-bound
+    let chunks = tokio::task::spawn_blocking(move || chunker.chunk("preview", &content))
+        .await
+        .map_err(|e| RecallError::Other(format!("Chunking preview failed: {}", e)))??;
+
+    let chunks: Vec<PreviewChunk> = chunks
+        .into_iter()
+        .map(|c| PreviewChunk {
+            chunk_index: c.chunk_index,
+            content: c.content,
+            token_count: c.token_count,
+            start_offset: c.start_offset,
+            end_offset: c.end_offset,
+            page_number: c.page_number,
+            timestamp_start: c.timestamp_start,
+            timestamp_end: c.timestamp_end,
+        })
+        .collect();
+
+    Ok(ChunkingPreview {
+        chunk_size,
+        chunk_overlap,
+        total_tokens: chunks.iter().map(|c| c.token_count as i64).sum(),
+        chunks,
+    })
 }
 
 /// Get the current ingestion queue status
@@ -179,3 +814,13 @@ pub struct IngestionQueueStatus {
     pub is_processing: bool,
     pub queued_files: Vec<QueuedFile>,
 }
+
+/// Check whether ffmpeg is runnable. `available` tells the UI whether video and
+/// audio files can be ingested.
+#[tauri::command]
+pub async fn get_ffmpeg_status(state: State<'_, Arc<AppState>>) -> Result<FFmpegStatus, RecallError> {
+    let ffmpeg_path = state.settings.read().ffmpeg_path.clone();
+    tokio::task::spawn_blocking(move || FFmpeg::check(ffmpeg_path.as_deref()))
+        .await
+        .map_err(|e| RecallError::FFmpeg(format!("ffmpeg check failed: {}", e)))
+}
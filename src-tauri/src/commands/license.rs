@@ -1,5 +1,6 @@
+use crate::database::Database;
 use crate::error::RecallError;
-use crate::state::AppState;
+use crate::state::{AppState, Settings};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tauri::State;
@@ -91,16 +92,30 @@ struct PaddleInstance {
     name: String,
 }
 
+/// A key is only stored after activation succeeds, so its presence is the tier
+pub fn license_tier(settings: &Settings) -> LicenseTier {
+    if settings.license_key.is_some() {
+        LicenseTier::Licensed
+    } else {
+        LicenseTier::Trial
+    }
+}
+
+/// Documents counted against the trial limit: every document in the library,
+/// whatever its status
+pub fn trial_documents_used(database: &Database) -> crate::error::Result<usize> {
+    Ok(database.get_ingestion_stats()?.total_documents.max(0) as usize)
+}
+
 /// Get the current license status
 #[tauri::command]
 pub async fn get_license_status(state: State<'_, Arc<AppState>>) -> Result<LicenseStatus, RecallError> {
     let settings = state.settings.read();
 
     // Get document count for trial info
-    let stats = state.database.get_ingestion_stats()?;
-    let docs_used = stats.total_documents as usize;
+    let docs_used = trial_documents_used(&state.database)?;
 
-    if let Some(ref license_key) = settings.license_key {
+    if let (LicenseTier::Licensed, Some(license_key)) = (license_tier(&settings), settings.license_key.as_ref()) {
         // We have a stored license, check if it's still valid
         // For performance, we don't re-validate on every call
         // The key was validated on activation
@@ -0,0 +1,53 @@
+use crate::error::RecallError;
+use crate::local_api::{self, LocalApiStatus};
+use crate::state::AppState;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_local_api_status(
+    state: State<'_, Arc<AppState>>,
+) -> Result<LocalApiStatus, RecallError> {
+    let settings = state.settings.read();
+
+    Ok(LocalApiStatus {
+        running: state.local_api.address().is_some(),
+        address: state.local_api.address().map(|a| a.to_string()),
+        token: settings.local_api_token.clone(),
+        allow_lan: settings.local_api_allow_lan,
+    })
+}
+
+/// Start the local HTTP API and keep it enabled across restarts
+#[tauri::command]
+pub async fn start_local_api(
+    state: State<'_, Arc<AppState>>,
+) -> Result<LocalApiStatus, RecallError> {
+    local_api::start(state.inner().clone()).await?;
+
+    state.settings.write().enable_local_api = true;
+    state.save_settings()?;
+    get_local_api_status(state).await
+}
+
+#[tauri::command]
+pub async fn stop_local_api(
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), RecallError> {
+    state.local_api.stop().await;
+
+    state.settings.write().enable_local_api = false;
+    state.save_settings()?;
+    Ok(())
+}
+
+/// Replace the bearer token; clients using the old one are rejected at once
+#[tauri::command]
+pub async fn regenerate_local_api_token(
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, RecallError> {
+    let token = local_api::generate_token();
+    state.settings.write().local_api_token = Some(token.clone());
+    state.save_settings()?;
+    Ok(token)
+}
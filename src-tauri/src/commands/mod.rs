@@ -3,6 +3,7 @@ pub mod conversations;
 pub mod database;
 pub mod ingestion;
 pub mod license;
+pub mod local_api;
 pub mod notification;
 pub mod rag;
 pub mod search;
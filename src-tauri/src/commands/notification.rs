@@ -1,7 +1,7 @@
 //! Commands for notification window management.
 
 use tauri::{command, AppHandle, Emitter, Manager, Runtime, Window};
-use crate::notifications::{show_notification, NotificationData};
+use crate::notifications::{self, show_notification, NotificationData};
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::OnceLock;
@@ -34,6 +34,29 @@ pub async fn notification_window_ready<R: Runtime>(window: Window<R>) -> Result<
     }
 }
 
+/// Suppress related-content and processing notifications for `minutes`
+/// (0 ends the snooze), at most 7 days. Returns when the snooze ends, as
+/// RFC 3339.
+#[command]
+pub async fn snooze_notifications(minutes: u64) -> Result<Option<String>, String> {
+    if minutes == 0 {
+        notifications::clear_snooze();
+        tracing::info!("Notification snooze cleared");
+        return Ok(None);
+    }
+
+    let minutes = minutes.min(notifications::MAX_SNOOZE_MINUTES) as i64;
+    let until = notifications::snooze(chrono::Duration::minutes(minutes));
+    tracing::info!("Notifications snoozed until {}", until);
+    Ok(Some(until.to_rfc3339()))
+}
+
+/// When the current notification snooze ends, if one is active
+#[command]
+pub async fn get_notification_snooze() -> Result<Option<String>, String> {
+    Ok(notifications::snoozed_until().map(|until| until.to_rfc3339()))
+}
+
 /// Test notification - shows a sample notification window
 #[command]
 pub async fn test_notification<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
@@ -16,18 +16,25 @@ pub async fn query(
         max_chunks: None,
         include_sources: false,
         document_ids: None,
+        categories: None,
+        max_tokens: None,
+        temperature: None,
     };
 
     state.rag_engine.query(request).await
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn query_with_sources(
     state: State<'_, Arc<AppState>>,
     query: String,
     conversation_id: Option<String>,
     max_chunks: Option<usize>,
     document_ids: Option<Vec<String>>,
+    categories: Option<Vec<String>>,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
 ) -> Result<RagResponse, RecallError> {
     let request = RagQuery {
         query,
@@ -35,6 +42,9 @@ pub async fn query_with_sources(
         max_chunks,
         include_sources: true,
         document_ids,
+        categories,
+        max_tokens,
+        temperature,
     };
 
     state.rag_engine.query(request).await
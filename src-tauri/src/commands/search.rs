@@ -1,16 +1,32 @@
-use crate::database::ChunkWithScore;
+use crate::database::{
+    Chunk, ChunkWithScore, DocumentGraph, DocumentRelation, DocumentStatus, FileType, GraphNode,
+    IngestionSource, SearchType,
+};
 use crate::error::RecallError;
-use crate::rag::HybridRetriever;
+use crate::ingestion::chunk_language;
+use crate::rag::{
+    cluster_library, highlight_snippet, scope_to_categories, scope_to_sources, DocumentCluster, HybridRetriever,
+    SnippetSegment, DEFAULT_CLUSTER_COUNT,
+};
 use crate::state::AppState;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchRequest {
     pub query: String,
     pub limit: Option<usize>,
     pub document_ids: Option<Vec<String>>,
+    #[serde(default)]
+    pub categories: Option<Vec<String>>,
+    /// Only search documents that arrived through any of these sources
+    #[serde(default)]
+    pub ingestion_sources: Option<Vec<IngestionSource>>,
+    /// Only keep chunks in any of these languages (ISO 639-3, e.g. "fra")
+    #[serde(default)]
+    pub languages: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -19,28 +35,229 @@ pub struct SearchResult {
     pub total: usize,
 }
 
+/// Hybrid retriever for the search commands. With embeddings disabled the
+/// search is keyword-only and works without an API key.
+fn search_retriever(state: &AppState) -> Result<HybridRetriever, RecallError> {
+    if !state.settings.read().embeddings_enabled {
+        return Ok(HybridRetriever::fts_only(state.database.clone()));
+    }
+
+    // Clone LLM client to avoid holding lock across await
+    let llm = state
+        .llm_client
+        .read()
+        .as_ref()
+        .ok_or(RecallError::Config("LLM client not configured".to_string()))?
+        .clone();
+    Ok(HybridRetriever::new(state.database.clone(), llm))
+}
+
 #[tauri::command]
 pub async fn search_documents(
     state: State<'_, Arc<AppState>>,
     request: SearchRequest,
 ) -> Result<SearchResult, RecallError> {
+    let chunks = run_search(&state, request).await?;
+    let total = chunks.len();
+
+    Ok(SearchResult { chunks, total })
+}
+
+/// Scope and run a search request, returning the ranked chunks
+async fn run_search(state: &AppState, request: SearchRequest) -> Result<Vec<ChunkWithScore>, RecallError> {
     let limit = request.limit.unwrap_or(20);
 
-    // Clone LLM client to avoid holding lock across await
-    let llm = {
-        let guard = state.llm_client.read();
-        guard
-            .as_ref()
-            .ok_or(RecallError::Config("LLM client not configured".to_string()))?
-            .clone()
-    };
+    let document_ids = scope_to_categories(
+        &state.database,
+        request.document_ids,
+        request.categories.as_deref(),
+    )?;
+    let document_ids = scope_to_sources(
+        &state.database,
+        document_ids,
+        request.ingestion_sources.as_deref(),
+    )?;
 
-    let retriever = HybridRetriever::new(state.database.clone(), llm);
-    let chunks = retriever.retrieve(&request.query, limit, request.document_ids.as_deref()).await?;
+    let retriever = search_retriever(state)?;
+    let languages = request.languages.as_deref();
+    let mut chunks = retriever
+        .retrieve(&request.query, retrieval_limit(limit, languages), document_ids.as_deref())
+        .await?;
+    retain_languages(state, &mut chunks, languages)?;
+    chunks.truncate(limit);
+    Ok(chunks)
+}
 
-    let total = chunks.len();
+/// Version of the `search_json` response shape. Bump it only when a field is
+/// removed or changes meaning; adding fields keeps the version.
+pub const SEARCH_JSON_VERSION: u32 = 1;
 
-    Ok(SearchResult { chunks, total })
+/// Stable search response for scripts and external tools.
+///
+/// Invoke with `invoke("search_json", { request })`, where `request` takes the
+/// same options as `search_documents` (`query`, `limit`, `document_ids`,
+/// `categories`, `ingestion_sources`, `languages`). The response is:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "query": "tax return",
+///   "documents": [{ "id": "...", "title": "...", "path": "...", "file_type": "pdf" }],
+///   "results": [{
+///     "citation": 1, "document_id": "...", "chunk_id": 42, "text": "...",
+///     "score": 0.83, "match": "hybrid", "page": 3, "timestamp": null,
+///     "start_offset": 120, "end_offset": 980
+///   }]
+/// }
+/// ```
+///
+/// These types are deliberately separate from `ChunkWithScore` and
+/// `SourceChunk` so internal refactors don't change what scripts see.
+#[derive(Debug, Serialize)]
+pub struct SearchJsonResponse {
+    pub version: u32,
+    pub query: String,
+    /// Each document referenced by `results`, in first-cited order
+    pub documents: Vec<SearchJsonDocument>,
+    /// Matches ranked best first
+    pub results: Vec<SearchJsonResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchJsonDocument {
+    pub id: String,
+    pub title: String,
+    pub path: String,
+    pub file_type: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchJsonResult {
+    /// 1-based rank, usable as a `[n]` citation marker
+    pub citation: usize,
+    pub document_id: String,
+    pub chunk_id: i64,
+    pub text: String,
+    pub score: f64,
+    /// "vector", "fts" or "hybrid"
+    #[serde(rename = "match")]
+    pub match_type: String,
+    pub page: Option<i32>,
+    /// Seconds into the media, for audio and video chunks
+    pub timestamp: Option<f64>,
+    pub start_offset: Option<i32>,
+    pub end_offset: Option<i32>,
+}
+
+/// Search and return results in the versioned `SearchJsonResponse` shape
+#[tauri::command]
+pub async fn search_json(
+    state: State<'_, Arc<AppState>>,
+    request: SearchRequest,
+) -> Result<SearchJsonResponse, RecallError> {
+    build_search_json(&state, request).await
+}
+
+/// Run a search and shape it as a `SearchJsonResponse`; shared with the local HTTP API
+pub(crate) async fn build_search_json(
+    state: &AppState,
+    request: SearchRequest,
+) -> Result<SearchJsonResponse, RecallError> {
+    let query = request.query.clone();
+    let chunks = run_search(state, request).await?;
+
+    let mut documents: Vec<SearchJsonDocument> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut results = Vec::with_capacity(chunks.len());
+    for (i, cws) in chunks.into_iter().enumerate() {
+        let chunk = cws.chunk;
+        if seen.insert(chunk.document_id.clone()) {
+            if let Some(doc) = state.database.get_document(&chunk.document_id)? {
+                documents.push(SearchJsonDocument {
+                    id: doc.id,
+                    title: doc.title,
+                    path: doc.file_path,
+                    file_type: doc.file_type.as_str().to_string(),
+                });
+            }
+        }
+
+        let match_type = match cws.search_type {
+            SearchType::Vector => "vector",
+            SearchType::Fts => "fts",
+            SearchType::Hybrid => "hybrid",
+            SearchType::Pinned => "pinned",
+            SearchType::Neighbor => "neighbor",
+        };
+        results.push(SearchJsonResult {
+            citation: i + 1,
+            document_id: chunk.document_id,
+            chunk_id: chunk.id,
+            text: chunk.content,
+            score: cws.score,
+            match_type: match_type.to_string(),
+            page: chunk.page_number,
+            timestamp: chunk.timestamp_start,
+            start_offset: chunk.start_offset,
+            end_offset: chunk.end_offset,
+        });
+    }
+
+    Ok(SearchJsonResponse {
+        version: SEARCH_JSON_VERSION,
+        query,
+        documents,
+        results,
+    })
+}
+
+/// Chunks retrieved per wanted result when filtering by language. The
+/// filter runs on the retrieved chunks, so without the extra ones a search
+/// ranked mostly in other languages would come back short.
+const LANGUAGE_FILTER_OVERFETCH: usize = 5;
+
+/// How many chunks to retrieve for `limit` results after `retain_languages`
+fn retrieval_limit(limit: usize, languages: Option<&[String]>) -> usize {
+    match languages {
+        Some(languages) if !languages.is_empty() => limit.saturating_mul(LANGUAGE_FILTER_OVERFETCH),
+        _ => limit,
+    }
+}
+
+/// Drop chunks not in any of `languages`. Chunks without their own language
+/// tag (ingested before per-chunk tagging) fall back to their document's.
+fn retain_languages(
+    state: &AppState,
+    chunks: &mut Vec<ChunkWithScore>,
+    languages: Option<&[String]>,
+) -> Result<(), RecallError> {
+    let Some(languages) = languages.filter(|l| !l.is_empty()) else {
+        return Ok(());
+    };
+
+    let mut document_languages: HashMap<String, Option<String>> = HashMap::new();
+    let mut keep = Vec::with_capacity(chunks.len());
+    for cws in chunks.drain(..) {
+        let language = match chunk_language(&cws.chunk) {
+            Some(language) => Some(language.to_string()),
+            None => match document_languages.get(&cws.chunk.document_id) {
+                Some(language) => language.clone(),
+                None => {
+                    let language = state
+                        .database
+                        .get_document(&cws.chunk.document_id)?
+                        .and_then(|d| d.metadata.get("language").and_then(|v| v.as_str()).map(String::from));
+                    document_languages.insert(cws.chunk.document_id.clone(), language.clone());
+                    language
+                }
+            },
+        };
+        if language.is_some_and(|language| languages.contains(&language)) {
+            keep.push(cws);
+        }
+    }
+    *chunks = keep;
+    Ok(())
 }
 
 #[tauri::command]
@@ -51,6 +268,288 @@ pub async fn hybrid_search(
 ) -> Result<Vec<ChunkWithScore>, RecallError> {
     let limit = limit.unwrap_or(20);
 
+    let retriever = search_retriever(&state)?;
+    retriever.retrieve(&query, limit, None).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimilarChunk {
+    pub chunk: Chunk,
+    pub document_title: String,
+    pub score: f64,
+}
+
+/// Find chunks whose embeddings are closest to the given chunk's
+#[tauri::command]
+pub async fn find_similar_chunks(
+    state: State<'_, Arc<AppState>>,
+    chunk_id: i64,
+    limit: Option<usize>,
+) -> Result<Vec<SimilarChunk>, RecallError> {
+    let limit = limit.unwrap_or(10);
+
+    state
+        .database
+        .get_chunk(chunk_id)?
+        .ok_or_else(|| RecallError::NotFound(format!("Chunk not found: {}", chunk_id)))?;
+
+    // One extra neighbour because the source chunk is its own nearest match
+    let neighbours = state.database.vector_search_by_chunk(chunk_id, limit + 1)?;
+    let metric = state.database.get_similarity_metric()?;
+    let ids: Vec<i64> = neighbours.iter().map(|(id, _)| *id).collect();
+    let mut chunks: HashMap<i64, Chunk> = state
+        .database
+        .get_chunks_by_ids(&ids)?
+        .into_iter()
+        .map(|c| (c.id, c))
+        .collect();
+
+    let mut titles: HashMap<String, String> = HashMap::new();
+    let mut results = Vec::new();
+    for (id, distance) in neighbours.into_iter().take(limit) {
+        let Some(chunk) = chunks.remove(&id) else {
+            continue;
+        };
+
+        let document_title = match titles.get(&chunk.document_id) {
+            Some(title) => title.clone(),
+            None => {
+                let title = state
+                    .database
+                    .get_document(&chunk.document_id)?
+                    .map(|d| d.title)
+                    .unwrap_or_else(|| "Unknown".to_string());
+                titles.insert(chunk.document_id.clone(), title.clone());
+                title
+            }
+        };
+
+        results.push(SimilarChunk {
+            chunk,
+            document_title,
+            score: metric.similarity(distance),
+        });
+    }
+
+    Ok(results)
+}
+
+/// How chunk scores are combined into a document score
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreAggregation {
+    #[default]
+    Max,
+    Sum,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupedSearchRequest {
+    pub query: String,
+    pub limit: Option<usize>,
+    pub document_ids: Option<Vec<String>>,
+    #[serde(default)]
+    pub categories: Option<Vec<String>>,
+    /// Only search documents that arrived through any of these sources
+    #[serde(default)]
+    pub ingestion_sources: Option<Vec<IngestionSource>>,
+    /// Only keep chunks in any of these languages (ISO 639-3, e.g. "fra")
+    #[serde(default)]
+    pub languages: Option<Vec<String>>,
+    #[serde(default)]
+    pub aggregation: ScoreAggregation,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocumentSearchHit {
+    pub document_id: String,
+    pub document_title: String,
+    pub file_type: String,
+    pub score: f64,
+    pub hit_count: usize,
+    pub best_chunk_id: i64,
+    pub best_snippet: String,
+    pub page_number: Option<i32>,
+    pub timestamp: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupedSearchResult {
+    pub documents: Vec<DocumentSearchHit>,
+    pub total: usize,
+}
+
+/// Chunks retrieved per requested document, so grouping has enough hits to aggregate
+const CHUNKS_PER_DOCUMENT: usize = 5;
+
+#[tauri::command]
+pub async fn search_documents_grouped(
+    state: State<'_, Arc<AppState>>,
+    request: GroupedSearchRequest,
+) -> Result<GroupedSearchResult, RecallError> {
+    let limit = request.limit.unwrap_or(20);
+
+    let document_ids = scope_to_categories(
+        &state.database,
+        request.document_ids,
+        request.categories.as_deref(),
+    )?;
+    let document_ids = scope_to_sources(
+        &state.database,
+        document_ids,
+        request.ingestion_sources.as_deref(),
+    )?;
+
+    let retriever = search_retriever(&state)?;
+    let languages = request.languages.as_deref();
+    let mut chunks = retriever
+        .retrieve(&request.query, retrieval_limit(limit * CHUNKS_PER_DOCUMENT, languages), document_ids.as_deref())
+        .await?;
+    retain_languages(&state, &mut chunks, languages)?;
+
+    // Group hits by document, keeping the highest-scoring chunk as the snippet source
+    let mut groups: Vec<(ChunkWithScore, f64, usize)> = Vec::new();
+    let mut index_by_doc: HashMap<String, usize> = HashMap::new();
+
+    for cws in chunks {
+        match index_by_doc.get(&cws.chunk.document_id) {
+            Some(&i) => {
+                let (best, score, hits) = &mut groups[i];
+                *hits += 1;
+                *score = match request.aggregation {
+                    ScoreAggregation::Max => score.max(cws.score),
+                    ScoreAggregation::Sum => *score + cws.score,
+                };
+                if cws.score > best.score {
+                    *best = cws;
+                }
+            }
+            None => {
+                index_by_doc.insert(cws.chunk.document_id.clone(), groups.len());
+                let score = cws.score;
+                groups.push((cws, score, 1));
+            }
+        }
+    }
+
+    groups.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    groups.truncate(limit);
+
+    let mut documents = Vec::with_capacity(groups.len());
+    for (best, score, hit_count) in groups {
+        // Skip hits whose document was deleted between search and lookup
+        let Some(doc) = state.database.get_document(&best.chunk.document_id)? else {
+            continue;
+        };
+
+        documents.push(DocumentSearchHit {
+            document_id: doc.id,
+            document_title: doc.title,
+            file_type: doc.file_type.to_string(),
+            score,
+            hit_count,
+            best_chunk_id: best.chunk.id,
+            best_snippet: snippet(&best.chunk.content, 200),
+            page_number: best.chunk.page_number,
+            timestamp: best.chunk.timestamp_start,
+        });
+    }
+
+    let total = documents.len();
+
+    Ok(GroupedSearchResult { documents, total })
+}
+
+fn snippet(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let truncated: String = text.chars().take(max_chars).collect();
+        format!("{}...", truncated)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentSearchRequest {
+    pub document_id: String,
+    pub query: String,
+    pub limit: Option<usize>,
+    /// Also rank chunks by meaning, not just matching words
+    #[serde(default)]
+    pub include_vector: bool,
+}
+
+/// A chunk of the searched document that matched
+#[derive(Debug, Serialize)]
+pub struct DocumentMatch {
+    pub chunk_id: i64,
+    pub chunk_index: i32,
+    pub score: f64,
+    pub search_type: SearchType,
+    pub page_number: Option<i32>,
+    pub timestamp: Option<f64>,
+    /// Text around the first match, with matching words highlighted
+    pub snippet: Vec<SnippetSegment>,
+}
+
+/// Find a term within a single document, for a viewer's in-document search
+#[tauri::command]
+pub async fn search_within_document(
+    state: State<'_, Arc<AppState>>,
+    request: DocumentSearchRequest,
+) -> Result<Vec<DocumentMatch>, RecallError> {
+    if state.database.get_document(&request.document_id)?.is_none() {
+        return Err(RecallError::NotFound(format!("Document {}", request.document_id)));
+    }
+
+    // Keyword-only find works without an API key
+    let retriever = if request.include_vector {
+        search_retriever(&state)?
+    } else {
+        HybridRetriever::fts_only(state.database.clone())
+    };
+    let chunks = retriever
+        .retrieve_in_document(&request.query, &request.document_id, request.limit.unwrap_or(20))
+        .await?;
+
+    Ok(chunks
+        .into_iter()
+        .map(|cws| DocumentMatch {
+            chunk_id: cws.chunk.id,
+            chunk_index: cws.chunk.chunk_index,
+            score: cws.score,
+            search_type: cws.search_type,
+            page_number: cws.chunk.page_number,
+            timestamp: cws.chunk.timestamp_start,
+            snippet: highlight_snippet(&cws.chunk.content, &request.query, 200),
+        })
+        .collect())
+}
+
+/// Related documents kept per document when rebuilding relations
+const RELATIONS_PER_DOCUMENT: usize = 10;
+/// Minimum similarity for a relation to be stored
+const RELATION_MIN_SIMILARITY: f64 = 0.3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelationRebuildProgress {
+    pub processed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelationRebuildSummary {
+    pub documents_processed: usize,
+    pub relations_found: usize,
+    pub failed: usize,
+}
+
+/// Recompute related documents for the whole library and persist them
+#[tauri::command]
+pub async fn rebuild_document_relations(
+    state: State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
+) -> Result<RelationRebuildSummary, RecallError> {
     // Clone LLM client to avoid holding lock across await
     let llm = {
         let guard = state.llm_client.read();
@@ -60,6 +559,178 @@ pub async fn hybrid_search(
             .clone()
     };
 
+    let documents: Vec<_> = state
+        .database
+        .get_all_documents()?
+        .into_iter()
+        .filter(|d| d.status == DocumentStatus::Completed)
+        .collect();
+    let total = documents.len();
+
+    state.database.clear_document_relations()?;
+
     let retriever = HybridRetriever::new(state.database.clone(), llm);
-    retriever.retrieve(&query, limit, None).await
+    let mut summary = RelationRebuildSummary {
+        documents_processed: 0,
+        relations_found: 0,
+        failed: 0,
+    };
+
+    for (i, doc) in documents.iter().enumerate() {
+        match retriever
+            .find_related_documents(&doc.id, RELATIONS_PER_DOCUMENT, RELATION_MIN_SIMILARITY)
+            .await
+        {
+            Ok(related) => {
+                let edges: Vec<(String, f64)> =
+                    related.into_iter().map(|r| (r.id, r.similarity)).collect();
+                state.database.upsert_document_relations(&doc.id, &edges)?;
+                summary.relations_found += edges.len();
+                summary.documents_processed += 1;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to compute relations for '{}': {}", doc.title, e);
+                summary.failed += 1;
+            }
+        }
+
+        let _ = app_handle.emit(
+            "relations-rebuild-progress",
+            RelationRebuildProgress { processed: i + 1, total },
+        );
+    }
+
+    tracing::info!(
+        "Rebuilt document relations: {} documents, {} relations, {} failed",
+        summary.documents_processed,
+        summary.relations_found,
+        summary.failed
+    );
+
+    Ok(summary)
+}
+
+/// Get the stored related documents for a document
+#[tauri::command]
+pub async fn get_related_documents(
+    state: State<'_, Arc<AppState>>,
+    document_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<DocumentRelation>, RecallError> {
+    state
+        .database
+        .get_document_relations(&document_id, limit.unwrap_or(RELATIONS_PER_DOCUMENT))
+}
+
+/// Default minimum edge weight for the relationship graph
+const GRAPH_MIN_SIMILARITY: f64 = 0.5;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DocumentGraphRequest {
+    /// Minimum similarity for an edge to be included
+    pub min_similarity: Option<f64>,
+    /// Only include documents in any of these categories
+    #[serde(default)]
+    pub categories: Option<Vec<String>>,
+    /// Only include documents of these file types
+    #[serde(default)]
+    pub file_types: Option<Vec<FileType>>,
+    /// Only include documents created at or after this time
+    #[serde(default)]
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only include documents created at or before this time
+    #[serde(default)]
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Keep documents that have no edges in the filtered graph
+    #[serde(default)]
+    pub include_isolated: bool,
+}
+
+/// Get the stored document relations as a node/edge graph for visualization
+#[tauri::command]
+pub async fn get_document_graph(
+    state: State<'_, Arc<AppState>>,
+    request: Option<DocumentGraphRequest>,
+) -> Result<DocumentGraph, RecallError> {
+    let request = request.unwrap_or_default();
+    let min_similarity = request.min_similarity.unwrap_or(GRAPH_MIN_SIMILARITY);
+
+    let category_ids: Option<HashSet<String>> = match request.categories.as_deref() {
+        Some(categories) if !categories.is_empty() => Some(
+            state
+                .database
+                .get_document_ids_by_categories(categories)?
+                .into_iter()
+                .collect(),
+        ),
+        _ => None,
+    };
+
+    let documents: HashMap<String, _> = state
+        .database
+        .get_all_documents()?
+        .into_iter()
+        .filter(|d| d.status == DocumentStatus::Completed)
+        .filter(|d| category_ids.as_ref().is_none_or(|ids| ids.contains(&d.id)))
+        .filter(|d| {
+            request
+                .file_types
+                .as_ref()
+                .is_none_or(|types| types.contains(&d.file_type))
+        })
+        .filter(|d| request.created_after.is_none_or(|after| d.created_at >= after))
+        .filter(|d| request.created_before.is_none_or(|before| d.created_at <= before))
+        .map(|d| (d.id.clone(), d))
+        .collect();
+
+    let edges: Vec<_> = state
+        .database
+        .get_relation_edges(min_similarity)?
+        .into_iter()
+        .filter(|e| documents.contains_key(&e.source) && documents.contains_key(&e.target))
+        .collect();
+
+    let mut degrees: HashMap<&str, usize> = HashMap::new();
+    for edge in &edges {
+        *degrees.entry(edge.source.as_str()).or_default() += 1;
+        *degrees.entry(edge.target.as_str()).or_default() += 1;
+    }
+
+    let mut nodes: Vec<GraphNode> = documents
+        .values()
+        .filter_map(|doc| {
+            let degree = degrees.get(doc.id.as_str()).copied().unwrap_or(0);
+            if degree == 0 && !request.include_isolated {
+                return None;
+            }
+            Some(GraphNode {
+                id: doc.id.clone(),
+                title: doc.title.clone(),
+                file_type: doc.file_type,
+                category: doc
+                    .metadata
+                    .get("content_category")
+                    .and_then(|c| c.as_str())
+                    .map(String::from),
+                created_at: doc.created_at,
+                degree,
+            })
+        })
+        .collect();
+    nodes.sort_by(|a, b| b.degree.cmp(&a.degree).then_with(|| a.title.cmp(&b.title)));
+
+    Ok(DocumentGraph { nodes, edges })
+}
+
+/// Group the library into `k` topic clusters (default 8, at most 50) by
+/// embedding similarity, each with a suggested name. Documents without
+/// embeddings are left out.
+#[tauri::command]
+pub async fn cluster_documents(
+    state: State<'_, Arc<AppState>>,
+    k: Option<usize>,
+) -> Result<Vec<DocumentCluster>, RecallError> {
+    // Clone LLM client to avoid holding lock across await
+    let llm = state.llm_client.read().clone();
+    cluster_library(&state.database, llm.as_ref(), k.unwrap_or(DEFAULT_CLUSTER_COUNT)).await
 }
@@ -1,8 +1,70 @@
+use crate::database::{FtsTokenizer, SimilarityMetric};
 use crate::error::RecallError;
-use crate::llm::validate_api_key as validate_key;
+use crate::llm::{validate_api_key as validate_key, ProviderHealth, ProviderState};
 use crate::state::{AppState, Settings};
+use serde::Serialize;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
+
+/// Payload of the `llm-client-updated` event. Never includes the API key.
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmClientInfo {
+    pub configured: bool,
+    pub provider: String,
+    pub embedding_model: String,
+    pub ingestion_model: String,
+    pub reasoning_model: String,
+}
+
+fn llm_client_info(state: &AppState) -> LlmClientInfo {
+    let settings = state.settings.read();
+    LlmClientInfo {
+        configured: state.llm_client.read().is_some(),
+        provider: "gemini".to_string(),
+        embedding_model: settings.embedding_model.clone(),
+        ingestion_model: settings.ingestion_model.clone(),
+        reasoning_model: settings.reasoning_model.clone(),
+    }
+}
+
+/// Notify the frontend that the LLM client was rebuilt or removed
+fn emit_llm_client_updated(app_handle: &AppHandle, state: &AppState) {
+    app_handle.emit("llm-client-updated", llm_client_info(state)).ok();
+}
+
+/// Provider configuration plus health derived from recent calls
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmStatus {
+    #[serde(flatten)]
+    pub client: LlmClientInfo,
+    #[serde(flatten)]
+    pub health: ProviderHealth,
+    /// Requests per minute the local limiter allows
+    pub requests_per_minute: Option<u64>,
+}
+
+/// Current provider status. Cheap to poll: it reports what recent calls saw
+/// and never contacts the provider.
+#[tauri::command]
+pub async fn get_llm_status(state: State<'_, Arc<AppState>>) -> Result<LlmStatus, RecallError> {
+    let client = llm_client_info(&state);
+    let (health, requests_per_minute) = match state.llm_client.read().as_ref() {
+        Some(llm) => (llm.health(), Some(llm.requests_per_minute())),
+        None => (
+            ProviderHealth {
+                state: ProviderState::NotConfigured,
+                ..Default::default()
+            },
+            None,
+        ),
+    };
+
+    Ok(LlmStatus {
+        client,
+        health,
+        requests_per_minute,
+    })
+}
 
 #[tauri::command]
 pub async fn get_settings(state: State<'_, Arc<AppState>>) -> Result<Settings, RecallError> {
@@ -18,49 +80,174 @@ pub async fn get_settings(state: State<'_, Arc<AppState>>) -> Result<Settings, R
 #[tauri::command]
 pub async fn update_settings(
     state: State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
     new_settings: Settings,
 ) -> Result<(), RecallError> {
     tracing::info!("update_settings called");
 
-    // If API key changed, update LLM client
-    if let Some(ref api_key) = new_settings.gemini_api_key {
-        if !api_key.starts_with("****") {
-            // It's a new key, not the masked one
-            state.update_llm_client(api_key.clone());
-        }
+    if new_settings.fts_tokenizer.parse::<FtsTokenizer>().is_err() {
+        return Err(RecallError::Config(format!(
+            "Unknown FTS tokenizer: {}",
+            new_settings.fts_tokenizer
+        )));
+    }
+
+    if new_settings.similarity_metric.parse::<SimilarityMetric>().is_err() {
+        return Err(RecallError::Config(format!(
+            "Unknown similarity metric: {}",
+            new_settings.similarity_metric
+        )));
+    }
+
+    if !matches!(new_settings.dedupe_scope.as_str(), "global" | "folder") {
+        return Err(RecallError::Config(format!(
+            "Unknown dedupe scope: {}",
+            new_settings.dedupe_scope
+        )));
+    }
+
+    crate::logging::validate_log_level(&new_settings.log_level)?;
+    let log_level = new_settings.log_level.clone();
+
+    // Validate a new captures directory before anything is saved
+    let existing_captures_dir = state.settings.read().captures_directory.clone();
+    if new_settings.captures_directory != existing_captures_dir {
+        let dir = match new_settings.captures_directory.as_deref().map(str::trim) {
+            Some(custom) if !custom.is_empty() => std::path::PathBuf::from(custom),
+            _ => crate::capture::default_captures_dir(&state.app_data_dir),
+        };
+        state.capture_manager.set_captures_dir(dir)?;
+    }
+
+    // Re-bind the capture hotkey before saving so a rejected combo changes nothing
+    let existing_hotkey = state.settings.read().capture_hotkey.clone();
+    if new_settings.capture_hotkey != existing_hotkey {
+        crate::capture::replace_capture_hotkey(
+            &app_handle,
+            state.capture_manager.clone(),
+            &existing_hotkey,
+            &new_settings.capture_hotkey,
+        )?;
+        state.capture_manager.set_hotkey(&new_settings.capture_hotkey);
     }
 
     // Preserve existing values that are managed elsewhere
-    let mut settings = state.settings.write();
-    let existing_key = settings.gemini_api_key.clone();
-    let existing_watched_folders = settings.watched_folders.clone();
-    let existing_auto_ingest = settings.auto_ingest_enabled;
-
-    *settings = new_settings;
-
-    // Preserve API key if new one is masked
-    if settings
-        .gemini_api_key
-        .as_ref()
-        .map(|k| k.starts_with("****"))
-        .unwrap_or(false)
-    {
-        settings.gemini_api_key = existing_key;
+    let (api_key, llm_client_changed, local_api_rebind, tokenizer_changed, metric_changed) = {
+        let mut settings = state.settings.write();
+        let existing_key = settings.gemini_api_key.clone();
+        let existing_llm_settings = settings.clone();
+        let existing_watched_folders = settings.watched_folders.clone();
+        let existing_watched_folder_excludes = settings.watched_folder_excludes.clone();
+        let existing_auto_ingest = settings.auto_ingest_enabled;
+        let existing_fts_tokenizer = settings.fts_tokenizer.clone();
+        let existing_similarity_metric = settings.similarity_metric.clone();
+        let existing_local_api = (
+            settings.enable_local_api,
+            settings.local_api_token.clone(),
+            settings.local_api_port,
+            settings.local_api_allow_lan,
+        );
+
+        *settings = new_settings;
+
+        // Preserve API key if new one is masked
+        if settings
+            .gemini_api_key
+            .as_ref()
+            .map(|k| k.starts_with("****"))
+            .unwrap_or(false)
+        {
+            settings.gemini_api_key = existing_key;
+        }
+
+        // Preserve watcher settings (managed by watcher commands)
+        settings.watched_folders = existing_watched_folders;
+        settings.watched_folder_excludes = existing_watched_folder_excludes;
+        settings.auto_ingest_enabled = existing_auto_ingest;
+
+        // Preserve local API state (managed by local API commands)
+        settings.enable_local_api = existing_local_api.0;
+        settings.local_api_token = existing_local_api.1;
+        let local_api_rebind = settings.local_api_port != existing_local_api.2
+            || settings.local_api_allow_lan != existing_local_api.3;
+
+        state.database.set_chunk_compression(settings.compress_chunk_text);
+
+        (
+            settings.gemini_api_key.clone(),
+            llm_client_settings_changed(&existing_llm_settings, &settings),
+            local_api_rebind,
+            settings.fts_tokenizer != existing_fts_tokenizer,
+            settings.similarity_metric != existing_similarity_metric,
+        )
+    };
+
+    // Rebuild the full-text and vector indexes when the tokenizer or metric
+    // changes. Either can rewrite the whole index, so keep it off the async thread.
+    if tokenizer_changed || metric_changed {
+        let database = state.database.clone();
+        let settings = state.settings.read().clone();
+        tokio::task::spawn_blocking(move || {
+            if tokenizer_changed {
+                AppState::sync_fts_tokenizer(&database, &settings)?;
+            }
+            if metric_changed {
+                AppState::sync_similarity_metric(&database, &settings)?;
+            }
+            Ok::<_, RecallError>(())
+        })
+        .await
+        .map_err(|e| RecallError::Other(format!("Index rebuild task failed: {}", e)))??;
     }
 
-    // Preserve watcher settings (managed by watcher commands)
-    settings.watched_folders = existing_watched_folders;
-    settings.auto_ingest_enabled = existing_auto_ingest;
+    // Results cached under the old settings may rank differently now
+    state.rag_engine.clear_retrieval_cache();
 
-    drop(settings);
+    // Rebuild the LLM client only when the key or its settings changed
+    if llm_client_changed {
+        if let Some(api_key) = api_key {
+            state.update_llm_client(api_key);
+        }
+    }
 
     state.save_settings()?;
+
+    // Applied last so a failed save leaves the running level alone
+    crate::logging::set_log_level(&log_level)?;
+
+    // Restart a running local API on its new address
+    if local_api_rebind && state.local_api.address().is_some() {
+        let state = state.inner().clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = crate::local_api::start(state).await {
+                tracing::warn!("Failed to restart local API: {}", e);
+            }
+        });
+    }
+
+    if llm_client_changed {
+        emit_llm_client_updated(&app_handle, &state);
+    }
     Ok(())
 }
 
+/// Whether any setting the LLM client is built from or reports differs
+fn llm_client_settings_changed(old: &Settings, new: &Settings) -> bool {
+    old.gemini_api_key != new.gemini_api_key
+        || old.embedding_model != new.embedding_model
+        || old.ingestion_model != new.ingestion_model
+        || old.reasoning_model != new.reasoning_model
+        || old.gemini_requests_per_minute != new.gemini_requests_per_minute
+        || old.embedding_batch_size != new.embedding_batch_size
+        || old.generation_timeout_secs != new.generation_timeout_secs
+        || old.upload_timeout_secs != new.upload_timeout_secs
+        || old.embedding_timeout_secs != new.embedding_timeout_secs
+}
+
 #[tauri::command]
 pub async fn validate_api_key(
     state: State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
     api_key: String,
 ) -> Result<bool, RecallError> {
     let is_valid = validate_key(&api_key).await?;
@@ -75,6 +262,7 @@ pub async fn validate_api_key(
         drop(settings);
 
         state.save_settings()?;
+        emit_llm_client_updated(&app_handle, &state);
     }
 
     Ok(is_valid)
@@ -91,6 +279,7 @@ pub async fn get_api_key_unmasked(
 #[tauri::command]
 pub async fn clear_api_key(
     state: State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
 ) -> Result<(), RecallError> {
     {
         let mut settings = state.settings.write();
@@ -98,6 +287,7 @@ pub async fn clear_api_key(
     }
     *state.llm_client.write() = None;
     state.save_settings()?;
+    emit_llm_client_updated(&app_handle, &state);
     Ok(())
 }
 
@@ -108,3 +298,18 @@ fn mask_api_key(key: &str) -> String {
         format!("****{}", &key[key.len() - 4..])
     }
 }
+
+/// Path of the log file currently being written
+#[tauri::command]
+pub async fn get_log_file_path() -> Result<Option<String>, RecallError> {
+    Ok(crate::logging::current_log_file()
+        .or_else(crate::logging::log_dir)
+        .map(|p| p.to_string_lossy().to_string()))
+}
+
+/// Bundle recent log files into one file for attaching to a bug report
+#[tauri::command]
+pub async fn export_logs(destination: String, days: Option<usize>) -> Result<String, RecallError> {
+    let path = crate::logging::export_logs(std::path::Path::new(&destination), days.unwrap_or(3))?;
+    Ok(path.to_string_lossy().to_string())
+}
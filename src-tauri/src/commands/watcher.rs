@@ -1,7 +1,9 @@
-use crate::database::FileType;
+use crate::database::{FileType, IngestionSource};
 use crate::error::RecallError;
+use crate::ingestion::{is_excluded, WatcherState};
 use crate::state::AppState;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Runtime, State};
 use walkdir::WalkDir;
@@ -9,7 +11,11 @@ use walkdir::WalkDir;
 #[derive(serde::Serialize)]
 pub struct WatcherStatus {
     pub is_running: bool,
+    /// Running, paused or stopped; `is_running` is also true while paused
+    pub state: WatcherState,
     pub watched_folders: Vec<String>,
+    /// Exclude patterns for each watched folder
+    pub watched_folder_excludes: HashMap<String, Vec<String>>,
     pub auto_ingest_enabled: bool,
 }
 
@@ -21,7 +27,13 @@ pub async fn get_watcher_status(
 
     Ok(WatcherStatus {
         is_running: state.watcher_manager.is_running(),
+        state: state.watcher_manager.state(),
         watched_folders: settings.watched_folders.clone(),
+        watched_folder_excludes: settings
+            .watched_folders
+            .iter()
+            .map(|f| (f.clone(), settings.exclude_patterns_for(f)))
+            .collect(),
         auto_ingest_enabled: settings.auto_ingest_enabled,
     })
 }
@@ -43,11 +55,29 @@ pub async fn stop_watcher(
     Ok(())
 }
 
+/// Stop acting on file changes without touching the watched folders or
+/// auto-ingest; changes made meanwhile are picked up by `resume_watcher`
+#[tauri::command]
+pub async fn pause_watcher(
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), RecallError> {
+    state.watcher_manager.pause()
+}
+
+#[tauri::command]
+pub async fn resume_watcher(
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), RecallError> {
+    state.watcher_manager.resume();
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn add_watched_folder<R: Runtime>(
     app_handle: AppHandle<R>,
     state: State<'_, Arc<AppState>>,
     folder_path: String,
+    exclude_patterns: Option<Vec<String>>,
 ) -> Result<(), RecallError> {
     tracing::info!("add_watched_folder called: {}", folder_path);
 
@@ -61,22 +91,26 @@ pub async fn add_watched_folder<R: Runtime>(
         return Err(RecallError::Config(format!("Not a directory: {}", folder_path)));
     }
 
-    // Add to settings
-    {
+    // Add to settings; without explicit patterns the defaults apply
+    let excludes = {
         let mut settings = state.settings.write();
+        if let Some(patterns) = exclude_patterns {
+            settings.watched_folder_excludes.insert(folder_path.clone(), clean_patterns(patterns));
+        }
         if !settings.watched_folders.contains(&folder_path) {
             settings.watched_folders.push(folder_path.clone());
             tracing::info!("Added folder to settings: {}", folder_path);
         } else {
             tracing::info!("Folder already in settings: {}", folder_path);
         }
-    }
+        settings.exclude_patterns_for(&folder_path)
+    };
     state.save_settings()?;
 
     // If watcher is running, add the folder directly
     if state.watcher_manager.is_running() {
         tracing::info!("Watcher running, adding folder directly");
-        state.watcher_manager.add_folder(path.clone())?;
+        state.watcher_manager.add_folder(path.clone(), excludes.clone())?;
     } else {
         // Start the watcher if auto-ingest is enabled
         let auto_ingest = state.settings.read().auto_ingest_enabled;
@@ -99,6 +133,8 @@ pub async fn add_watched_folder<R: Runtime>(
         for entry in WalkDir::new(&path)
             .follow_links(true)
             .into_iter()
+            // Prune excluded directories instead of walking into them
+            .filter_entry(|e| e.depth() == 0 || !is_excluded(e.path(), &path, &excludes))
             .filter_map(|e| e.ok())
         {
             let file_path = entry.path();
@@ -115,6 +151,11 @@ pub async fn add_watched_folder<R: Runtime>(
                 continue;
             }
 
+            if ingestion_engine.disabled_file_type(file_path).is_some() {
+                skipped_count += 1;
+                continue;
+            }
+
             let path_str = file_path.to_string_lossy().to_string();
 
             // Skip if already ingested
@@ -125,7 +166,10 @@ pub async fn add_watched_folder<R: Runtime>(
 
             tracing::info!("Initial scan: ingesting {:?}", file_path);
 
-            match ingestion_engine.ingest_file(file_path, &app_handle_clone).await {
+            match ingestion_engine
+                .ingest_file_from(file_path, IngestionSource::Watched, &app_handle_clone)
+                .await
+            {
                 Ok(doc) => {
                     tracing::info!("Initial scan: ingested {}", doc.title);
                     ingested_count += 1;
@@ -163,6 +207,7 @@ pub async fn remove_watched_folder(
     {
         let mut settings = state.settings.write();
         settings.watched_folders.retain(|f| f != &folder_path);
+        settings.watched_folder_excludes.remove(&folder_path);
     }
     state.save_settings()?;
 
@@ -174,6 +219,65 @@ pub async fn remove_watched_folder(
     Ok(())
 }
 
+/// Replace the exclude patterns of a watched folder. Applies to new file
+/// events right away; already ingested files are left in place.
+#[tauri::command]
+pub async fn set_watched_folder_excludes(
+    state: State<'_, Arc<AppState>>,
+    folder_path: String,
+    exclude_patterns: Vec<String>,
+) -> Result<(), RecallError> {
+    let patterns = clean_patterns(exclude_patterns);
+    {
+        let mut settings = state.settings.write();
+        if !settings.watched_folders.contains(&folder_path) {
+            return Err(RecallError::NotFound(format!("Not a watched folder: {}", folder_path)));
+        }
+        settings.watched_folder_excludes.insert(folder_path.clone(), patterns.clone());
+    }
+    state.save_settings()?;
+
+    state.watcher_manager.set_excludes(Path::new(&folder_path), patterns);
+    Ok(())
+}
+
+/// Set the order watched folders are ingested in: when files from several
+/// folders are waiting, those from earlier folders go first. `folder_paths`
+/// must list every watched folder once.
+#[tauri::command]
+pub async fn reorder_watched_folders(
+    state: State<'_, Arc<AppState>>,
+    folder_paths: Vec<String>,
+) -> Result<Vec<String>, RecallError> {
+    {
+        let mut settings = state.settings.write();
+        let mut current = settings.watched_folders.clone();
+        let mut requested = folder_paths.clone();
+        current.sort();
+        requested.sort();
+        if current != requested {
+            return Err(RecallError::Other(
+                "The new order must list each watched folder exactly once".to_string(),
+            ));
+        }
+        settings.watched_folders = folder_paths.clone();
+    }
+    state.save_settings()?;
+
+    tracing::info!("Watched folder order: {:?}", folder_paths);
+    state.ingestion_engine.refresh_folder_ranks();
+    Ok(folder_paths)
+}
+
+/// Trim patterns and drop blank ones
+fn clean_patterns(patterns: Vec<String>) -> Vec<String> {
+    patterns
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
 #[tauri::command]
 pub async fn toggle_auto_ingest<R: Runtime>(
     app_handle: AppHandle<R>,
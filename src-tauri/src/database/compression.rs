@@ -0,0 +1,89 @@
+//! Optional zstd compression of chunk text.
+//!
+//! A compressed chunk keeps its text in `chunks.content` as a BLOB instead of
+//! TEXT, so the column needs no schema change and plain rows keep working.
+//! The FTS sync triggers call `chunk_text()` to index the decompressed text.
+
+use crate::error::{RecallError, Result};
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::{FromSqlError, ToSqlOutput, Value, ValueRef};
+use rusqlite::{Connection, Row};
+
+/// zstd level: fast, and most of the gain on prose comes early
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Texts shorter than this rarely shrink once zstd's frame header is added
+const MIN_COMPRESS_BYTES: usize = 128;
+
+/// Value to store for `text`: a zstd BLOB when compression is on and it
+/// saves space, otherwise the text itself
+pub(super) fn stored_text(text: &str, compress: bool) -> Result<ToSqlOutput<'_>> {
+    if compress && text.len() >= MIN_COMPRESS_BYTES {
+        let compressed = zstd::bulk::compress(text.as_bytes(), COMPRESSION_LEVEL)
+            .map_err(|e| RecallError::Other(format!("Failed to compress chunk text: {}", e)))?;
+        if compressed.len() < text.len() {
+            return Ok(ToSqlOutput::Owned(Value::Blob(compressed)));
+        }
+    }
+    Ok(ToSqlOutput::Borrowed(ValueRef::Text(text.as_bytes())))
+}
+
+/// Text of a `content` value, decompressing BLOBs
+fn value_text(value: ValueRef<'_>) -> std::result::Result<String, FromSqlError> {
+    match value {
+        ValueRef::Text(bytes) => String::from_utf8(bytes.to_vec()).map_err(|e| FromSqlError::Other(Box::new(e))),
+        ValueRef::Blob(bytes) => {
+            let raw = zstd::stream::decode_all(bytes).map_err(|e| FromSqlError::Other(Box::new(e)))?;
+            String::from_utf8(raw).map_err(|e| FromSqlError::Other(Box::new(e)))
+        }
+        _ => Err(FromSqlError::InvalidType),
+    }
+}
+
+/// Read a possibly compressed text column
+pub(super) fn column_text(row: &Row<'_>, idx: usize) -> rusqlite::Result<String> {
+    let value = row.get_ref(idx)?;
+    let data_type = value.data_type();
+    value_text(value).map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, data_type, Box::new(e)))
+}
+
+/// Register `chunk_text(content)`, used by the FTS triggers. Must run on every
+/// connection before any chunk is written.
+pub(super) fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "chunk_text",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| match ctx.get_raw(0) {
+            ValueRef::Null => Ok(None),
+            value => value_text(value)
+                .map(Some)
+                .map_err(|e| rusqlite::Error::UserFunctionError(Box::new(e))),
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_text_round_trips() {
+        let conn = Connection::open_in_memory().unwrap();
+        register_functions(&conn).unwrap();
+
+        let text = "The quarterly report covers revenue, churn, and hiring. ".repeat(10);
+        let stored = stored_text(&text, true).unwrap();
+        assert!(matches!(stored, ToSqlOutput::Owned(Value::Blob(_))));
+
+        let round_trip: String = conn
+            .query_row("SELECT chunk_text(?)", [&stored], |row| row.get(0))
+            .unwrap();
+        assert_eq!(round_trip, text);
+
+        // Short text and disabled compression are stored as-is
+        assert!(matches!(stored_text("short", true).unwrap(), ToSqlOutput::Borrowed(ValueRef::Text(_))));
+        assert!(matches!(stored_text(&text, false).unwrap(), ToSqlOutput::Borrowed(ValueRef::Text(_))));
+    }
+}
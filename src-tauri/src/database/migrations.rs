@@ -102,6 +102,249 @@ const MIGRATIONS: &[&str] = &[
 
     CREATE INDEX IF NOT EXISTS idx_messages_conversation_id ON messages(conversation_id);
     "#,
+    // Migration 5: Record the FTS tokenizer so it can be switched and rebuilt
+    r#"
+    -- Key/value store for index configuration.
+    --
+    -- fts_tokenizer is the tokenize= argument chunks_fts was built with. Options:
+    --   'porter unicode61'  (default) English stemming; splits on whitespace and
+    --                       punctuation, so CJK text without spaces is one token.
+    --   'unicode61 remove_diacritics 2'  no stemming; better for non-English
+    --                       alphabetic languages where the Porter stemmer misfires.
+    --   'trigram'           indexes every 3-character sequence: substring and CJK
+    --                       matching works, but no stemming, queries shorter than
+    --                       3 characters match nothing, and the index is ~3x larger.
+    --
+    -- Changing the tokenizer drops and rebuilds chunks_fts from chunks.
+    CREATE TABLE IF NOT EXISTS index_config (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+
+    INSERT OR IGNORE INTO index_config (key, value) VALUES ('fts_tokenizer', 'porter unicode61');
+    "#,
+    // Migration 6: Persisted document relations
+    r#"
+    -- Directed similarity edges between documents, refreshed after ingestion
+    -- and by a full library rebuild
+    CREATE TABLE IF NOT EXISTS document_relations (
+        document_id TEXT NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+        related_id TEXT NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+        similarity REAL NOT NULL,
+        updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+        PRIMARY KEY (document_id, related_id)
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_document_relations_related ON document_relations(related_id);
+    "#,
+    // Migration 7: Running summary of conversation turns outside the history window
+    r#"
+    -- history_summary condenses the first summarized_messages messages of the
+    -- conversation; it is extended as older turns fall out of the window
+    ALTER TABLE conversations ADD COLUMN history_summary TEXT;
+    ALTER TABLE conversations ADD COLUMN summarized_messages INTEGER NOT NULL DEFAULT 0;
+    "#,
+    // Migration 8: How each document arrived (manual, watched, capture, url)
+    r#"
+    ALTER TABLE documents ADD COLUMN ingestion_source TEXT NOT NULL DEFAULT 'manual';
+
+    -- Screenshots predate the column but their origin is unambiguous
+    UPDATE documents SET ingestion_source = 'capture' WHERE file_type = 'screenshot';
+
+    CREATE INDEX IF NOT EXISTS idx_documents_ingestion_source ON documents(ingestion_source);
+    "#,
+    // Migration 9: Index decompressed text for zstd-compressed chunks
+    r#"
+    -- chunks.content holds TEXT, or a zstd BLOB when chunk compression is on.
+    -- chunk_text() (registered by the app) returns the plain text either way.
+    DROP TRIGGER IF EXISTS chunks_ai;
+    DROP TRIGGER IF EXISTS chunks_ad;
+    DROP TRIGGER IF EXISTS chunks_au;
+
+    CREATE TRIGGER chunks_ai AFTER INSERT ON chunks BEGIN
+        INSERT INTO chunks_fts(rowid, content) VALUES (new.id, chunk_text(new.content));
+    END;
+
+    CREATE TRIGGER chunks_ad AFTER DELETE ON chunks BEGIN
+        INSERT INTO chunks_fts(chunks_fts, rowid, content) VALUES('delete', old.id, chunk_text(old.content));
+    END;
+
+    CREATE TRIGGER chunks_au AFTER UPDATE ON chunks BEGIN
+        INSERT INTO chunks_fts(chunks_fts, rowid, content) VALUES('delete', old.id, chunk_text(old.content));
+        INSERT INTO chunks_fts(rowid, content) VALUES (new.id, chunk_text(new.content));
+    END;
+    "#,
+    // Migration 10: Per-document chunk totals so listings don't count chunks per row
+    r#"
+    ALTER TABLE documents ADD COLUMN chunk_count INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE documents ADD COLUMN total_tokens INTEGER NOT NULL DEFAULT 0;
+
+    UPDATE documents SET
+        chunk_count = (SELECT COUNT(*) FROM chunks WHERE chunks.document_id = documents.id),
+        total_tokens = (SELECT COALESCE(SUM(token_count), 0) FROM chunks WHERE chunks.document_id = documents.id);
+
+    -- Kept in step by triggers so every chunk write path stays covered
+    CREATE TRIGGER chunks_stats_ai AFTER INSERT ON chunks BEGIN
+        UPDATE documents SET chunk_count = chunk_count + 1, total_tokens = total_tokens + new.token_count
+        WHERE id = new.document_id;
+    END;
+
+    CREATE TRIGGER chunks_stats_ad AFTER DELETE ON chunks BEGIN
+        UPDATE documents SET chunk_count = chunk_count - 1, total_tokens = total_tokens - old.token_count
+        WHERE id = old.document_id;
+    END;
+
+    CREATE TRIGGER chunks_stats_au AFTER UPDATE OF token_count, document_id ON chunks BEGIN
+        UPDATE documents SET chunk_count = chunk_count - 1, total_tokens = total_tokens - old.token_count
+        WHERE id = old.document_id;
+        UPDATE documents SET chunk_count = chunk_count + 1, total_tokens = total_tokens + new.token_count
+        WHERE id = new.document_id;
+    END;
+    "#,
+    // Migration 11: Default retrieval scope per conversation (JSON ConversationScope)
+    r#"
+    ALTER TABLE conversations ADD COLUMN scope TEXT;
+    "#,
+    // Migration 12: Document access tracking for "recently viewed" and personal ranking
+    r#"
+    ALTER TABLE documents ADD COLUMN last_accessed TEXT;
+    ALTER TABLE documents ADD COLUMN access_count INTEGER NOT NULL DEFAULT 0;
+    CREATE INDEX IF NOT EXISTS idx_documents_last_accessed ON documents(last_accessed);
+    "#,
+    // Migration 13: Prior extracted text of re-ingested documents, keyed by
+    // path because re-ingesting creates a new document id
+    r#"
+    CREATE TABLE IF NOT EXISTS document_versions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        document_id TEXT NOT NULL,
+        file_path TEXT NOT NULL,
+        file_hash TEXT NOT NULL,
+        content TEXT NOT NULL,
+        change_summary TEXT,
+        created_at TEXT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_document_versions_path ON document_versions(file_path, id);
+    "#,
+    // Migration 14: Index document titles alongside chunk text so title
+    // matches can be weighted above body matches
+    r#"
+    -- Text indexed in chunks_fts.title for every chunk of a document
+    CREATE VIEW IF NOT EXISTS document_fts_titles AS
+        SELECT id, title || ' ' || COALESCE(json_extract(metadata, '$.content_category'), '') AS fts_title
+        FROM documents;
+
+    DROP TRIGGER IF EXISTS chunks_ai;
+    DROP TRIGGER IF EXISTS chunks_ad;
+    DROP TRIGGER IF EXISTS chunks_au;
+    DROP TABLE IF EXISTS chunks_fts;
+
+    -- Contentless: the title lives in documents, not chunks, so there is no
+    -- single external content table. contentless_delete lets rows be removed
+    -- without supplying their old text.
+    CREATE VIRTUAL TABLE chunks_fts USING fts5(
+        title,
+        content,
+        content='',
+        contentless_delete=1,
+        tokenize='porter unicode61'
+    );
+    INSERT INTO chunks_fts(rowid, title, content)
+        SELECT c.id, COALESCE(t.fts_title, ''), chunk_text(c.content)
+        FROM chunks c LEFT JOIN document_fts_titles t ON t.id = c.document_id;
+    -- Rebuilt with the default tokenizer; startup rebuilds again if the
+    -- configured one differs
+    INSERT OR REPLACE INTO index_config (key, value) VALUES ('fts_tokenizer', 'porter unicode61');
+
+    CREATE TRIGGER chunks_ai AFTER INSERT ON chunks BEGIN
+        INSERT INTO chunks_fts(rowid, title, content) VALUES (
+            new.id,
+            COALESCE((SELECT fts_title FROM document_fts_titles WHERE id = new.document_id), ''),
+            chunk_text(new.content)
+        );
+    END;
+
+    CREATE TRIGGER chunks_ad AFTER DELETE ON chunks BEGIN
+        DELETE FROM chunks_fts WHERE rowid = old.id;
+    END;
+
+    CREATE TRIGGER chunks_au AFTER UPDATE ON chunks BEGIN
+        DELETE FROM chunks_fts WHERE rowid = old.id;
+        INSERT INTO chunks_fts(rowid, title, content) VALUES (
+            new.id,
+            COALESCE((SELECT fts_title FROM document_fts_titles WHERE id = new.document_id), ''),
+            chunk_text(new.content)
+        );
+    END;
+
+    -- Titles are usually generated after the chunks are written
+    CREATE TRIGGER documents_fts_title_au AFTER UPDATE OF title, metadata ON documents
+    WHEN old.title IS NOT new.title
+        OR json_extract(old.metadata, '$.content_category') IS NOT json_extract(new.metadata, '$.content_category')
+    BEGIN
+        DELETE FROM chunks_fts WHERE rowid IN (SELECT id FROM chunks WHERE document_id = new.id);
+        INSERT INTO chunks_fts(rowid, title, content)
+            SELECT c.id, t.fts_title, chunk_text(c.content)
+            FROM chunks c JOIN document_fts_titles t ON t.id = c.document_id
+            WHERE c.document_id = new.id;
+    END;
+    "#,
+    // Migration 15: Chunks pinned into a conversation's context
+    r#"
+    -- Pins go with the chunk: re-ingesting a document replaces its chunks
+    CREATE TABLE IF NOT EXISTS conversation_pinned_chunks (
+        conversation_id TEXT NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+        chunk_id INTEGER NOT NULL REFERENCES chunks(id) ON DELETE CASCADE,
+        pinned_at TEXT NOT NULL,
+        PRIMARY KEY (conversation_id, chunk_id)
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_conversation_pinned_chunks_chunk ON conversation_pinned_chunks(chunk_id);
+    "#,
+    // Migration 16: Hash of the normalized extracted text, to spot the same
+    // content saved in different formats
+    r#"
+    ALTER TABLE documents ADD COLUMN content_hash TEXT;
+    CREATE INDEX IF NOT EXISTS idx_documents_content_hash ON documents(content_hash);
+    "#,
+    // Migration 17: Chunks flagged `unindexed` (headers, footers, captions)
+    // stay out of keyword search
+    r#"
+    DROP TRIGGER IF EXISTS chunks_ai;
+    DROP TRIGGER IF EXISTS chunks_au;
+    DROP TRIGGER IF EXISTS documents_fts_title_au;
+
+    CREATE TRIGGER chunks_ai AFTER INSERT ON chunks
+    WHEN json_extract(new.metadata, '$.unindexed') IS NOT 1
+    BEGIN
+        INSERT INTO chunks_fts(rowid, title, content) VALUES (
+            new.id,
+            COALESCE((SELECT fts_title FROM document_fts_titles WHERE id = new.document_id), ''),
+            chunk_text(new.content)
+        );
+    END;
+
+    CREATE TRIGGER chunks_au AFTER UPDATE ON chunks BEGIN
+        DELETE FROM chunks_fts WHERE rowid = old.id;
+        INSERT INTO chunks_fts(rowid, title, content)
+            SELECT
+                new.id,
+                COALESCE((SELECT fts_title FROM document_fts_titles WHERE id = new.document_id), ''),
+                chunk_text(new.content)
+            WHERE json_extract(new.metadata, '$.unindexed') IS NOT 1;
+    END;
+
+    CREATE TRIGGER documents_fts_title_au AFTER UPDATE OF title, metadata ON documents
+    WHEN old.title IS NOT new.title
+        OR json_extract(old.metadata, '$.content_category') IS NOT json_extract(new.metadata, '$.content_category')
+    BEGIN
+        DELETE FROM chunks_fts WHERE rowid IN (SELECT id FROM chunks WHERE document_id = new.id);
+        INSERT INTO chunks_fts(rowid, title, content)
+            SELECT c.id, t.fts_title, chunk_text(c.content)
+            FROM chunks c JOIN document_fts_titles t ON t.id = c.document_id
+            WHERE c.document_id = new.id AND json_extract(c.metadata, '$.unindexed') IS NOT 1;
+    END;
+    "#,
 ];
 
 pub fn run_migrations(conn: &Connection) -> Result<()> {
@@ -1,6 +1,8 @@
+mod compression;
 mod migrations;
 mod models;
 mod queries;
+mod vector;
 
 pub use models::*;
 // queries module provides internal database helpers
@@ -9,11 +11,14 @@ use crate::error::Result;
 use parking_lot::Mutex;
 use rusqlite::Connection;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 pub struct Database {
     conn: Mutex<Connection>,
     db_path: PathBuf,
     vec_extension_path: PathBuf,
+    /// Store new chunk text zstd-compressed
+    compress_chunks: AtomicBool,
 }
 
 impl Database {
@@ -33,6 +38,7 @@ impl Database {
             conn: Mutex::new(conn),
             db_path: db_path.to_path_buf(),
             vec_extension_path,
+            compress_chunks: AtomicBool::new(false),
         };
 
         db.initialize()?;
@@ -97,6 +103,10 @@ impl Database {
         // Load sqlite-vec extension
         self.load_vec_extension(&conn)?;
 
+        // The FTS triggers call chunk_text(), so register it before migrating
+        compression::register_functions(&conn)?;
+        vector::register_functions(&conn)?;
+
         // Run migrations
         migrations::run_migrations(&conn)?;
 
@@ -152,6 +162,15 @@ impl Database {
         Ok(())
     }
 
+    /// Compress the text of chunks inserted from now on
+    pub fn set_chunk_compression(&self, enabled: bool) {
+        self.compress_chunks.store(enabled, Ordering::Relaxed);
+    }
+
+    fn chunk_compression(&self) -> bool {
+        self.compress_chunks.load(Ordering::Relaxed)
+    }
+
     pub fn with_conn<F, T>(&self, f: F) -> Result<T>
     where
         F: FnOnce(&Connection) -> Result<T>,
@@ -168,6 +187,71 @@ impl Database {
         f(&mut conn)
     }
 
+    /// Size of the database file plus its WAL on disk
+    fn file_size_on_disk(&self) -> u64 {
+        let wal_path = self.db_path.with_extension("db-wal");
+        [self.db_path.as_path(), wal_path.as_path()]
+            .iter()
+            .filter_map(|p| std::fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum()
+    }
+
+    /// Rebuild the database file to release free pages, truncate the WAL, and
+    /// optionally refresh query planner statistics. Holds the connection lock
+    /// for the whole run, so every other database call waits until it's done.
+    pub fn compact(&self, analyze: bool) -> Result<CompactionResult> {
+        let conn = self.conn.lock();
+        let started = std::time::Instant::now();
+        let bytes_before = self.file_size_on_disk();
+
+        // Checkpoint first so VACUUM sees every committed page, then again
+        // afterwards because VACUUM itself writes the rebuilt file through the WAL
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        conn.execute_batch("VACUUM;")?;
+        if analyze {
+            conn.execute_batch("ANALYZE;")?;
+        }
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+
+        let bytes_after = self.file_size_on_disk();
+        let result = CompactionResult {
+            bytes_before,
+            bytes_after,
+            bytes_reclaimed: bytes_before.saturating_sub(bytes_after),
+            analyzed: analyze,
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+
+        tracing::info!(
+            "Database compacted: {} -> {} bytes ({} reclaimed) in {}ms",
+            result.bytes_before,
+            result.bytes_after,
+            result.bytes_reclaimed,
+            result.duration_ms
+        );
+        Ok(result)
+    }
+
+    /// Prepare for the first search of a session: refresh query planner
+    /// statistics (bounded, so large libraries don't stall startup) and read
+    /// the search indexes once so their pages are cached. Returns whether
+    /// sqlite-vec is available.
+    pub fn warm_up(&self) -> Result<bool> {
+        let vec_available = self.validate_vec_extension()?;
+
+        let conn = self.conn.lock();
+        conn.execute_batch("PRAGMA analysis_limit = 400; ANALYZE;")?;
+        conn.query_row("SELECT COUNT(*) FROM chunks_fts", [], |_| Ok(()))?;
+        if vec_available {
+            // Missing when the extension was added after the database was created
+            if let Err(e) = conn.query_row("SELECT COUNT(*) FROM vec_chunks", [], |_| Ok(())) {
+                tracing::warn!("Vector index not readable: {}", e);
+            }
+        }
+        Ok(vec_available)
+    }
+
     /// Validate that sqlite-vec is properly loaded and functional
     pub fn validate_vec_extension(&self) -> Result<bool> {
         let conn = self.conn.lock();
@@ -196,7 +280,45 @@ impl Database {
 mod tests {
     use super::*;
     use std::path::PathBuf;
-    use tempfile::tempdir;
+    use tempfile::{tempdir, TempDir};
+
+    /// Fresh database in a temp dir; keep the dir alive for the test
+    fn test_db() -> (TempDir, Database) {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let db = Database::new(&temp_dir.path().join("test.db"), &PathBuf::from("."))
+            .expect("Failed to create database");
+        (temp_dir, db)
+    }
+
+    /// Insert a completed text document using `id` as its title and path
+    fn insert_test_document(db: &Database, id: &str) {
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO documents (id, title, file_path, file_type, file_size, file_hash, status)
+                 VALUES (?1, ?1, ?1, 'text', 0, ?1, 'completed')",
+                rusqlite::params![id],
+            )?;
+            Ok(())
+        })
+        .expect("Failed to insert document");
+    }
+
+    fn test_chunk(document_id: &str, chunk_index: i32, content: &str, token_count: i32) -> Chunk {
+        Chunk {
+            id: 0,
+            document_id: document_id.to_string(),
+            chunk_index,
+            content: content.to_string(),
+            token_count,
+            start_offset: None,
+            end_offset: None,
+            page_number: None,
+            timestamp_start: None,
+            timestamp_end: None,
+            metadata: serde_json::json!({}),
+            created_at: chrono::Utc::now(),
+        }
+    }
 
     #[test]
     fn test_sqlite_vec_loading() {
@@ -267,4 +389,210 @@ mod tests {
             println!("⚠️ sqlite-vec not available - skipping vector tests");
         }
     }
+
+    #[test]
+    fn test_document_ids_by_categories() {
+        let (_temp_dir, db) = test_db();
+
+        db.with_conn(|conn| {
+            for (id, metadata) in [
+                ("primary", r#"{"content_category": "Health & Medicine"}"#),
+                (
+                    "labelled",
+                    r#"{"content_category": "Science & Technology", "content_categories": [{"category": "Science & Technology", "confidence": 0.9}, {"category": "Health & Medicine", "confidence": 0.6}]}"#,
+                ),
+                ("other", r#"{"content_category": "Sports & Fitness"}"#),
+                ("uncategorized", "{}"),
+            ] {
+                conn.execute(
+                    "INSERT INTO documents (id, title, file_path, file_type, file_size, file_hash, status, metadata)
+                     VALUES (?1, ?1, ?1, 'text', 0, ?1, 'completed', ?2)",
+                    rusqlite::params![id, metadata],
+                )?;
+            }
+            Ok(())
+        })
+        .expect("Failed to insert documents");
+
+        let mut ids = db
+            .get_document_ids_by_categories(&["Health & Medicine".to_string()])
+            .expect("Category query failed");
+        ids.sort();
+        assert_eq!(ids, vec!["labelled".to_string(), "primary".to_string()]);
+
+        let distribution = db.get_category_distribution().expect("Distribution query failed");
+        let uncategorized = distribution
+            .iter()
+            .find(|c| c.category == "Uncategorized")
+            .expect("Missing Uncategorized bucket");
+        assert_eq!(uncategorized.count, 1);
+    }
+
+    #[test]
+    fn test_document_relations_are_mirrored() {
+        let (_temp_dir, db) = test_db();
+        for id in ["a", "b", "c"] {
+            insert_test_document(&db, id);
+        }
+
+        db.upsert_document_relations("a", &[("b".to_string(), 0.8), ("c".to_string(), 0.4)])
+            .expect("Failed to store relations");
+        db.upsert_document_relations("b", &[("a".to_string(), 0.6)])
+            .expect("Failed to store relations");
+
+        let from_b = db.get_document_relations("b", 10).expect("Relation query failed");
+        assert_eq!(from_b.len(), 1);
+        assert_eq!(from_b[0].related_id, "a");
+        assert!((from_b[0].similarity - 0.8).abs() < f64::EPSILON);
+
+        db.delete_document_relations("a").expect("Failed to delete relations");
+        assert!(db.get_document_relations("c", 10).expect("Relation query failed").is_empty());
+    }
+
+    #[test]
+    fn test_rebuild_fts_index_with_trigram() {
+        let (_temp_dir, db) = test_db();
+        insert_test_document(&db, "doc");
+        db.insert_chunks(&[test_chunk("doc", 0, "東京都の天気予報", 8)])
+            .expect("Failed to insert chunk");
+
+        assert_eq!(db.get_fts_tokenizer().unwrap(), FtsTokenizer::Porter);
+        assert!(db.fts_search("天気", 10).unwrap().is_empty());
+
+        assert!(db.ensure_fts_tokenizer(FtsTokenizer::Trigram).unwrap());
+        assert_eq!(db.get_fts_tokenizer().unwrap(), FtsTokenizer::Trigram);
+        assert_eq!(db.fts_search("天気予", 10).unwrap().len(), 1);
+
+        // Triggers keep the rebuilt index in sync
+        db.insert_chunks(&[test_chunk("doc", 1, "大阪府の天気予報", 8)])
+            .expect("Failed to insert chunk");
+        assert_eq!(db.fts_search("天気予", 10).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_fts_weights_title_matches() {
+        let (_temp_dir, db) = test_db();
+        insert_test_document(&db, "a");
+        insert_test_document(&db, "b");
+        let a = db.insert_chunks(&[test_chunk("a", 0, "The quarterly budget was discussed at length.", 8)])
+            .expect("Failed to insert chunk")[0];
+        let b = db.insert_chunks(&[test_chunk("b", 0, "Nothing about money here.", 4)])
+            .expect("Failed to insert chunk")[0];
+
+        let results = db.fts_search("budget", 10).unwrap();
+        assert_eq!(results.iter().map(|r| r.0).collect::<Vec<_>>(), vec![a]);
+
+        // Renaming reindexes the document's chunks, and the title match wins
+        db.update_document_title("b", "Budget Plan 2026").unwrap();
+        let results = db.fts_search("budget", 10).unwrap();
+        assert_eq!(results[0].0, b);
+        assert_eq!(results.len(), 2);
+
+        db.delete_document("b").unwrap();
+        assert_eq!(db.fts_search("budget", 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_compressed_chunks_stay_searchable() {
+        let (_temp_dir, db) = test_db();
+        db.set_chunk_compression(true);
+        insert_test_document(&db, "doc");
+
+        let content = "Quarterly revenue grew while churn fell. ".repeat(8);
+        let id = db.insert_chunks(&[test_chunk("doc", 0, &content, 64)]).expect("Failed to insert chunk")[0];
+
+        let stored_type: String = db
+            .with_conn(|conn| Ok(conn.query_row("SELECT typeof(content) FROM chunks", [], |row| row.get(0))?))
+            .unwrap();
+        assert_eq!(stored_type, "blob");
+        assert_eq!(db.get_chunk(id).unwrap().unwrap().content, content);
+        assert_eq!(db.fts_search("churn", 10).unwrap().len(), 1);
+
+        assert_eq!(db.convert_chunk_storage(false).unwrap(), 1);
+        assert_eq!(db.get_chunk(id).unwrap().unwrap().content, content);
+        assert_eq!(db.fts_search("churn", 10).unwrap().len(), 1);
+
+        assert_eq!(db.convert_chunk_storage(true).unwrap(), 1);
+        db.delete_document("doc").expect("Failed to delete document");
+        assert!(db.fts_search("churn", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_document_chunk_totals_follow_chunks() {
+        let (_temp_dir, db) = test_db();
+        insert_test_document(&db, "doc");
+
+        let chunks: Vec<Chunk> = (0..3)
+            .map(|i| test_chunk("doc", i, &format!("chunk {}", i), 10))
+            .collect();
+        let ids = db.insert_chunks(&chunks).expect("Failed to insert chunks");
+
+        let doc = db.get_document("doc").unwrap().unwrap();
+        assert_eq!((doc.chunk_count, doc.total_tokens), (3, 30));
+
+        db.with_conn(|conn| {
+            conn.execute("UPDATE chunks SET token_count = 25 WHERE id = ?", [ids[0]])?;
+            conn.execute("DELETE FROM chunks WHERE id = ?", [ids[1]])?;
+            Ok(())
+        })
+        .expect("Failed to change chunks");
+
+        let doc = db.get_document("doc").unwrap().unwrap();
+        assert_eq!((doc.chunk_count, doc.total_tokens), (2, 35));
+    }
+
+    #[test]
+    fn test_document_access_tracking() {
+        let (_temp_dir, db) = test_db();
+        insert_test_document(&db, "a");
+        insert_test_document(&db, "b");
+
+        assert!(db.get_recent_documents(10).unwrap().is_empty());
+
+        // Duplicates within one call count once
+        db.record_document_access(&["a".to_string(), "a".to_string()]).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        db.record_document_access(&["b".to_string()]).unwrap();
+        db.record_document_access(&["a".to_string()]).unwrap();
+
+        let recent: Vec<String> = db.get_recent_documents(10).unwrap().into_iter().map(|d| d.id).collect();
+        assert_eq!(recent, vec!["a", "b"]);
+
+        let counts = db.get_access_counts(&["a".to_string(), "b".to_string()]).unwrap();
+        assert_eq!((counts["a"], counts["b"]), (2, 1));
+    }
+
+    #[test]
+    fn test_document_versions_are_pruned() {
+        let (_temp_dir, db) = test_db();
+        db.set_chunk_compression(true);
+        insert_test_document(&db, "/notes.md");
+        let doc = db.get_document("/notes.md").unwrap().unwrap();
+
+        let first = db.insert_document_version(&doc, "v1", 2).unwrap();
+        db.insert_document_version(&doc, "v2", 2).unwrap();
+        let third = db.insert_document_version(&doc, "v3", 2).unwrap();
+        db.set_document_version_summary(third, "- Added v3").unwrap();
+
+        let versions = db.get_document_versions("/notes.md").unwrap();
+        let contents: Vec<&str> = versions.iter().map(|v| v.content.as_str()).collect();
+        assert_eq!(contents, vec!["v3", "v2"]);
+        assert_eq!(versions[0].change_summary.as_deref(), Some("- Added v3"));
+        assert!(db.get_document_version(first).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_similarity_metric_config() {
+        let (_temp_dir, db) = test_db();
+
+        // Indexes created before the setting existed use vec0's L2 default
+        assert_eq!(db.get_similarity_metric().unwrap(), SimilarityMetric::L2);
+        assert!(db.ensure_similarity_metric(SimilarityMetric::Cosine).unwrap());
+        assert!(!db.ensure_similarity_metric(SimilarityMetric::Cosine).unwrap());
+        assert_eq!(db.get_similarity_metric().unwrap(), SimilarityMetric::Cosine);
+
+        assert!((SimilarityMetric::Cosine.similarity(0.25) - 0.75).abs() < f64::EPSILON);
+        assert!((SimilarityMetric::L2.similarity(1.0) - 0.5).abs() < f64::EPSILON);
+        assert!(SimilarityMetric::Dot.similarity(-3.0) > SimilarityMetric::Dot.similarity(-1.0));
+    }
 }
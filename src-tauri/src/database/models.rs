@@ -16,6 +16,18 @@ pub struct Document {
     pub status: DocumentStatus,
     pub error_message: Option<String>,
     pub metadata: serde_json::Value,
+    #[serde(default)]
+    pub ingestion_source: IngestionSource,
+    /// Number of chunks and their summed token counts, kept current by the database
+    #[serde(default)]
+    pub chunk_count: i64,
+    #[serde(default)]
+    pub total_tokens: i64,
+    /// When the document was last opened, viewed or cited, and how often
+    #[serde(default)]
+    pub last_accessed: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub access_count: i64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -28,10 +40,22 @@ pub enum FileType {
     Audio,
     Image,
     Screenshot,
+    Subtitle,
     Unknown,
 }
 
 impl FileType {
+    /// Types detected from files on disk; screenshots only come from capture
+    pub const INGESTIBLE: &'static [FileType] = &[
+        Self::Pdf,
+        Self::Text,
+        Self::Markdown,
+        Self::Video,
+        Self::Audio,
+        Self::Image,
+        Self::Subtitle,
+    ];
+
     pub fn from_extension(ext: &str) -> Self {
         match ext.to_lowercase().as_str() {
             "pdf" => Self::Pdf,
@@ -40,10 +64,22 @@ impl FileType {
             "mp4" | "mkv" | "avi" | "mov" | "webm" => Self::Video,
             "mp3" | "wav" | "flac" | "m4a" | "ogg" => Self::Audio,
             "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" => Self::Image,
+            "srt" | "vtt" => Self::Subtitle,
             _ => Self::Unknown,
         }
     }
 
+    /// Map a content-sniffed mime type to a file type, if it's one we ingest
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        match mime {
+            "application/pdf" => Some(Self::Pdf),
+            "image/png" | "image/jpeg" | "image/gif" | "image/webp" | "image/bmp" => Some(Self::Image),
+            m if m.starts_with("video/") => Some(Self::Video),
+            m if m.starts_with("audio/") => Some(Self::Audio),
+            _ => None,
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Pdf => "pdf",
@@ -53,6 +89,7 @@ impl FileType {
             Self::Audio => "audio",
             Self::Image => "image",
             Self::Screenshot => "screenshot",
+            Self::Subtitle => "subtitle",
             Self::Unknown => "unknown",
         }
     }
@@ -76,6 +113,7 @@ impl std::str::FromStr for FileType {
             "audio" => Ok(Self::Audio),
             "image" => Ok(Self::Image),
             "screenshot" => Ok(Self::Screenshot),
+            "subtitle" => Ok(Self::Subtitle),
             _ => Ok(Self::Unknown),
         }
     }
@@ -88,6 +126,8 @@ pub enum DocumentStatus {
     Processing,
     Completed,
     Failed,
+    /// The provider refused the content (safety filter), not a processing bug
+    Blocked,
 }
 
 impl DocumentStatus {
@@ -97,6 +137,47 @@ impl DocumentStatus {
             Self::Processing => "processing",
             Self::Completed => "completed",
             Self::Failed => "failed",
+            Self::Blocked => "blocked",
+        }
+    }
+}
+
+/// How a document arrived in the library
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IngestionSource {
+    /// Added by the user through the file or folder picker
+    #[default]
+    Manual,
+    /// Picked up from a watched folder
+    Watched,
+    /// Screen capture
+    Capture,
+    /// Fetched from a URL
+    Url,
+}
+
+impl IngestionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Watched => "watched",
+            Self::Capture => "capture",
+            Self::Url => "url",
+        }
+    }
+}
+
+impl std::str::FromStr for IngestionSource {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "manual" => Ok(Self::Manual),
+            "watched" => Ok(Self::Watched),
+            "capture" => Ok(Self::Capture),
+            "url" => Ok(Self::Url),
+            _ => Err(()),
         }
     }
 }
@@ -110,6 +191,7 @@ impl std::str::FromStr for DocumentStatus {
             "processing" => Ok(Self::Processing),
             "completed" => Ok(Self::Completed),
             "failed" => Ok(Self::Failed),
+            "blocked" => Ok(Self::Blocked),
             _ => Ok(Self::Pending),
         }
     }
@@ -144,6 +226,10 @@ pub enum SearchType {
     Vector,
     Fts,
     Hybrid,
+    /// Pinned to the conversation, included regardless of retrieval
+    Pinned,
+    /// Adjacent to a retrieved chunk in its document, added for context
+    Neighbor,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -152,6 +238,26 @@ pub struct Conversation {
     pub title: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Retrieval scope applied to queries that don't bring their own
+    #[serde(default)]
+    pub scope: ConversationScope,
+}
+
+/// Documents and categories a conversation searches by default
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConversationScope {
+    #[serde(default)]
+    pub document_ids: Option<Vec<String>>,
+    #[serde(default)]
+    pub categories: Option<Vec<String>>,
+}
+
+impl ConversationScope {
+    /// True when the scope doesn't restrict retrieval
+    pub fn is_empty(&self) -> bool {
+        self.document_ids.as_ref().is_none_or(|ids| ids.is_empty())
+            && self.categories.as_ref().is_none_or(|c| c.is_empty())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -181,6 +287,23 @@ pub struct Citation {
     pub page_number: Option<i32>,
     pub timestamp: Option<f64>,
     pub relevance_score: f64,
+    /// Image to show beside the citation, for image and screenshot sources
+    #[serde(default)]
+    pub thumbnail_path: Option<String>,
+    /// Character range of the cited chunk in the source text (page-relative for PDFs)
+    #[serde(default)]
+    pub start_offset: Option<i32>,
+    #[serde(default)]
+    pub end_offset: Option<i32>,
+}
+
+/// A chunk together with its neighbours in the same document, for
+/// highlighting a cited passage in context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkContext {
+    pub chunk: Chunk,
+    pub before: Vec<Chunk>,
+    pub after: Vec<Chunk>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -194,6 +317,219 @@ pub struct IngestionStats {
     pub total_size_bytes: i64,
 }
 
+/// Period that `get_ingestion_timeline` groups documents into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelineBucket {
+    #[default]
+    Day,
+    /// Weeks starting on Monday
+    Week,
+    Month,
+}
+
+impl TimelineBucket {
+    /// SQL expression for the first day of the bucket containing `created_at`
+    pub(crate) fn period_sql(&self) -> &'static str {
+        match self {
+            Self::Day => "DATE(created_at)",
+            Self::Week => "DATE(created_at, 'weekday 0', '-6 days')",
+            Self::Month => "strftime('%Y-%m-01', created_at)",
+        }
+    }
+}
+
+/// Documents of one type added during one timeline period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestionTimelineEntry {
+    /// First day of the period (YYYY-MM-DD)
+    pub period: String,
+    pub file_type: FileType,
+    pub document_count: i64,
+    pub total_size_bytes: i64,
+}
+
+/// Raw facts about a document record that its `Document` doesn't show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentRecordFacts {
+    /// `ingested_at` is NULL in the database
+    pub ingested_at_missing: bool,
+    /// Chunks actually stored for the document
+    pub stored_chunks: i64,
+}
+
+/// Outcome of `Database::compact`. Sizes include the WAL file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactionResult {
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub bytes_reclaimed: u64,
+    pub analyzed: bool,
+    pub duration_ms: u64,
+}
+
+/// Tokenizer used by the `chunks_fts` full-text index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FtsTokenizer {
+    /// English stemming (default)
+    Porter,
+    /// Plain Unicode word splitting without stemming
+    Unicode61,
+    /// Character trigrams for substring and CJK matching
+    Trigram,
+}
+
+impl FtsTokenizer {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Porter => "porter",
+            Self::Unicode61 => "unicode61",
+            Self::Trigram => "trigram",
+        }
+    }
+
+    /// The FTS5 `tokenize=` argument for this tokenizer
+    pub fn fts5_spec(&self) -> &'static str {
+        match self {
+            Self::Porter => "porter unicode61",
+            Self::Unicode61 => "unicode61 remove_diacritics 2",
+            Self::Trigram => "trigram",
+        }
+    }
+
+    pub fn from_fts5_spec(spec: &str) -> Option<Self> {
+        [Self::Porter, Self::Unicode61, Self::Trigram]
+            .into_iter()
+            .find(|t| t.fts5_spec() == spec)
+    }
+}
+
+impl std::str::FromStr for FtsTokenizer {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "porter" => Ok(Self::Porter),
+            "unicode61" => Ok(Self::Unicode61),
+            "trigram" => Ok(Self::Trigram),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Distance metric used to rank `vec_chunks` neighbours
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SimilarityMetric {
+    /// Angle between vectors; what Gemini embeddings are trained for (default)
+    Cosine,
+    /// Euclidean distance
+    L2,
+    /// Inner product, for models that encode relevance in vector length
+    Dot,
+}
+
+impl SimilarityMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cosine => "cosine",
+            Self::L2 => "l2",
+            Self::Dot => "dot",
+        }
+    }
+
+    /// The vec0 `distance_metric=` argument. vec0 has no inner product
+    /// metric, so dot searches scan with `dot_distance()` instead of the index.
+    pub fn vec0_metric(&self) -> &'static str {
+        match self {
+            Self::Cosine => "cosine",
+            Self::L2 | Self::Dot => "l2",
+        }
+    }
+
+    /// Convert a distance under this metric to a similarity score, higher
+    /// meaning closer
+    pub fn similarity(&self, distance: f64) -> f64 {
+        match self {
+            // Cosine distance is 1 - cosine similarity
+            Self::Cosine => 1.0 - distance,
+            Self::L2 => 1.0 / (1.0 + distance),
+            // dot_distance() is the negated inner product
+            Self::Dot => -distance,
+        }
+    }
+}
+
+impl std::str::FromStr for SimilarityMetric {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cosine" => Ok(Self::Cosine),
+            "l2" => Ok(Self::L2),
+            "dot" => Ok(Self::Dot),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryCount {
+    pub category: String,
+    pub count: i64,
+}
+
+/// A stored similarity edge from one document to another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentRelation {
+    pub document_id: String,
+    pub related_id: String,
+    pub related_title: String,
+    pub similarity: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Extracted text of a document as it was before a re-ingest replaced it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentVersion {
+    pub id: i64,
+    /// Id of the document record the text belonged to
+    pub document_id: String,
+    pub file_path: String,
+    pub file_hash: String,
+    pub content: String,
+    /// What changed in the version that replaced this one, once summarized
+    pub change_summary: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Document node in the relationship graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub title: String,
+    pub file_type: FileType,
+    pub category: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Number of edges touching this node in the returned graph
+    pub degree: usize,
+}
+
+/// Undirected similarity edge in the relationship graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IngestionProgress {
     pub document_id: String,
@@ -201,6 +537,8 @@ pub struct IngestionProgress {
     pub stage: IngestionStage,
     pub progress: f64,
     pub message: String,
+    /// When this progress entry last changed, used to detect stuck ingestions
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -1,10 +1,56 @@
+use super::compression::{column_text, stored_text};
 use super::models::*;
+use super::vector::{blob_to_floats, EMBEDDING_DIMENSIONS};
 use crate::error::{RecallError, Result};
 use chrono::Utc;
 use rusqlite::{params, OptionalExtension, Row};
+use std::collections::HashMap;
 use std::path::Path;
 use uuid::Uuid;
 
+/// bm25 weight of the title column relative to chunk text
+const FTS_TITLE_WEIGHT: f64 = 10.0;
+
+/// The `k` nearest neighbours of `embedding_blob` in `vec_chunks` as
+/// `(chunk_id, distance)`, closest first
+fn nearest_vectors(
+    conn: &rusqlite::Connection,
+    embedding_blob: &[u8],
+    k: usize,
+    metric: SimilarityMetric,
+) -> Result<Vec<(i64, f64)>> {
+    let sql = match metric {
+        // vec0 has no inner product index, so score every vector
+        SimilarityMetric::Dot => {
+            r#"
+            SELECT chunk_id, dot_distance(embedding, ?1) AS distance
+            FROM vec_chunks
+            ORDER BY distance
+            LIMIT ?2
+            "#
+        }
+        // Note: sqlite-vec requires k=? constraint for KNN queries
+        SimilarityMetric::Cosine | SimilarityMetric::L2 => {
+            r#"
+            SELECT chunk_id, distance
+            FROM vec_chunks
+            WHERE embedding MATCH ?1 AND k = ?2
+            ORDER BY distance
+            "#
+        }
+    };
+
+    let mut stmt = conn.prepare(sql)?;
+    let results = stmt
+        .query_map(params![embedding_blob, k as i64], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(results)
+}
+
 /// Normalize file path for consistent database lookups
 /// Converts to absolute path and normalizes separators
 fn normalize_path(path: &str) -> String {
@@ -28,29 +74,44 @@ fn normalize_path(path: &str) -> String {
 impl super::Database {
     // Document queries
     pub fn insert_document(&self, doc: &Document) -> Result<()> {
+        self.insert_documents(std::slice::from_ref(doc))?;
+        tracing::debug!("Document inserted and committed: {}", doc.id);
+        Ok(())
+    }
+
+    /// Insert document records in a single transaction, so queueing a large
+    /// directory pays for one sync instead of one per file
+    pub fn insert_documents(&self, docs: &[Document]) -> Result<()> {
+        if docs.is_empty() {
+            return Ok(());
+        }
+
         self.with_conn_mut(|conn| {
             // Use explicit transaction for reliable insertion
             let tx = conn.transaction()?;
-            tx.execute(
-                r#"
-                INSERT INTO documents (id, title, file_path, file_type, file_size, file_hash, mime_type, status, metadata)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-                "#,
-                params![
-                    doc.id,
-                    doc.title,
-                    doc.file_path,
-                    doc.file_type.as_str(),
-                    doc.file_size,
-                    doc.file_hash,
-                    doc.mime_type,
-                    doc.status.as_str(),
-                    doc.metadata.to_string(),
-                ],
-            )?;
+            {
+                let mut stmt = tx.prepare(
+                    r#"
+                    INSERT INTO documents (id, title, file_path, file_type, file_size, file_hash, mime_type, status, metadata, ingestion_source)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )?;
+                for doc in docs {
+                    stmt.execute(params![
+                        doc.id,
+                        doc.title,
+                        doc.file_path,
+                        doc.file_type.as_str(),
+                        doc.file_size,
+                        doc.file_hash,
+                        doc.mime_type,
+                        doc.status.as_str(),
+                        doc.metadata.to_string(),
+                        doc.ingestion_source.as_str(),
+                    ])?;
+                }
+            }
             tx.commit()?;
-
-            tracing::debug!("Document inserted and committed: {}", doc.id);
             Ok(())
         })
     }
@@ -80,7 +141,8 @@ impl super::Database {
             let mut stmt = conn.prepare(
                 r#"
                 SELECT id, title, file_path, file_type, file_size, file_hash, mime_type,
-                       created_at, updated_at, ingested_at, status, error_message, metadata
+                       created_at, updated_at, ingested_at, status, error_message, metadata,
+                       ingestion_source, chunk_count, total_tokens, last_accessed, access_count
                 FROM documents WHERE id = ?
                 "#,
             )?;
@@ -98,7 +160,8 @@ impl super::Database {
             let mut stmt = conn.prepare(
                 r#"
                 SELECT id, title, file_path, file_type, file_size, file_hash, mime_type,
-                       created_at, updated_at, ingested_at, status, error_message, metadata
+                       created_at, updated_at, ingested_at, status, error_message, metadata,
+                       ingestion_source, chunk_count, total_tokens, last_accessed, access_count
                 FROM documents WHERE file_path = ?
                 "#,
             )?;
@@ -108,17 +171,54 @@ impl super::Database {
         })
     }
 
-    pub fn get_document_by_hash(&self, hash: &str) -> Result<Option<Document>> {
+    /// Every document with this file hash, oldest first
+    pub fn get_documents_by_hash(&self, hash: &str) -> Result<Vec<Document>> {
         self.with_conn(|conn| {
             let mut stmt = conn.prepare(
                 r#"
                 SELECT id, title, file_path, file_type, file_size, file_hash, mime_type,
-                       created_at, updated_at, ingested_at, status, error_message, metadata
+                       created_at, updated_at, ingested_at, status, error_message, metadata,
+                       ingestion_source, chunk_count, total_tokens, last_accessed, access_count
                 FROM documents WHERE file_hash = ?
+                ORDER BY created_at
+                "#,
+            )?;
+
+            let docs = stmt
+                .query_map([hash], Self::row_to_document)?
+                .filter_map(|r| r.ok())
+                .collect();
+            Ok(docs)
+        })
+    }
+
+    /// Record the hash of a document's normalized extracted text
+    pub fn set_document_content_hash(&self, id: &str, content_hash: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE documents SET content_hash = ? WHERE id = ?",
+                params![content_hash, id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Oldest other completed document with the same normalized content as `id`
+    pub fn find_content_duplicate(&self, id: &str) -> Result<Option<Document>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, title, file_path, file_type, file_size, file_hash, mime_type,
+                       created_at, updated_at, ingested_at, status, error_message, metadata,
+                       ingestion_source, chunk_count, total_tokens, last_accessed, access_count
+                FROM documents
+                WHERE content_hash = (SELECT content_hash FROM documents WHERE id = ?1)
+                  AND id != ?1 AND status = 'completed'
+                ORDER BY created_at ASC LIMIT 1
                 "#,
             )?;
 
-            let doc = stmt.query_row([hash], Self::row_to_document).optional()?;
+            let doc = stmt.query_row([id], Self::row_to_document).optional()?;
             Ok(doc)
         })
     }
@@ -128,7 +228,9 @@ impl super::Database {
             conn.execute(
                 r#"
                 UPDATE documents
-                SET file_path = ?, title = ?, updated_at = datetime('now')
+                SET file_path = ?, title = ?,
+                    metadata = json_remove(metadata, '$.source_missing'),
+                    updated_at = datetime('now')
                 WHERE id = ?
                 "#,
                 params![new_path, new_title, id],
@@ -157,7 +259,8 @@ impl super::Database {
             let mut stmt = conn.prepare(
                 r#"
                 SELECT id, title, file_path, file_type, file_size, file_hash, mime_type,
-                       created_at, updated_at, ingested_at, status, error_message, metadata
+                       created_at, updated_at, ingested_at, status, error_message, metadata,
+                       ingestion_source, chunk_count, total_tokens, last_accessed, access_count
                 FROM documents ORDER BY updated_at DESC
                 "#,
             )?;
@@ -170,6 +273,76 @@ impl super::Database {
         })
     }
 
+    /// Most recently opened, viewed or cited documents, newest first
+    pub fn get_recent_documents(&self, limit: usize) -> Result<Vec<Document>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, title, file_path, file_type, file_size, file_hash, mime_type,
+                       created_at, updated_at, ingested_at, status, error_message, metadata,
+                       ingestion_source, chunk_count, total_tokens, last_accessed, access_count
+                FROM documents WHERE last_accessed IS NOT NULL
+                ORDER BY last_accessed DESC LIMIT ?
+                "#,
+            )?;
+
+            let docs = stmt
+                .query_map([limit as i64], Self::row_to_document)?
+                .filter_map(|r| r.ok())
+                .collect();
+            Ok(docs)
+        })
+    }
+
+    /// Bump the access count and last-accessed time of each document once.
+    /// Does not touch `updated_at`, which tracks content changes.
+    pub fn record_document_access(&self, ids: &[String]) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let mut unique: Vec<&String> = ids.iter().collect();
+        unique.sort();
+        unique.dedup();
+
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare(
+                    "UPDATE documents SET access_count = access_count + 1, last_accessed = ? WHERE id = ?",
+                )?;
+                for id in unique {
+                    stmt.execute(params![now, id])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Access counts for the given documents; unknown ids are left out
+    pub fn get_access_counts(&self, ids: &[String]) -> Result<HashMap<String, i64>> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        self.with_conn(|conn| {
+            let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let sql = format!("SELECT id, access_count FROM documents WHERE id IN ({})", placeholders);
+
+            let mut stmt = conn.prepare(&sql)?;
+            let counts = stmt
+                .query_map(rusqlite::params_from_iter(ids.iter()), |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(counts)
+        })
+    }
+
     pub fn delete_document(&self, id: &str) -> Result<()> {
         self.with_conn_mut(|conn| {
             let tx = conn.transaction()?;
@@ -229,11 +402,22 @@ impl super::Database {
                     }
                 }
             },
+            ingestion_source: row
+                .get::<_, String>(13)?
+                .parse()
+                .unwrap_or_default(),
+            chunk_count: row.get(14)?,
+            total_tokens: row.get(15)?,
+            last_accessed: row
+                .get::<_, Option<String>>(16)?
+                .and_then(|s| s.parse().ok()),
+            access_count: row.get(17)?,
         })
     }
 
     // Chunk queries
     pub fn insert_chunk(&self, chunk: &Chunk) -> Result<i64> {
+        let compress = self.chunk_compression();
         self.with_conn(|conn| {
             conn.execute(
                 r#"
@@ -243,7 +427,7 @@ impl super::Database {
                 params![
                     chunk.document_id,
                     chunk.chunk_index,
-                    chunk.content,
+                    stored_text(&chunk.content, compress)?,
                     chunk.token_count,
                     chunk.start_offset,
                     chunk.end_offset,
@@ -263,6 +447,7 @@ impl super::Database {
         }
 
         let document_id = &chunks[0].document_id;
+        let compress = self.chunk_compression();
 
         self.with_conn_mut(|conn| {
             let tx = conn.transaction()?;
@@ -292,7 +477,7 @@ impl super::Database {
                     params![
                         chunk.document_id,
                         chunk.chunk_index,
-                        chunk.content,
+                        stored_text(&chunk.content, compress)?,
                         chunk.token_count,
                         chunk.start_offset,
                         chunk.end_offset,
@@ -343,6 +528,35 @@ impl super::Database {
         })
     }
 
+    /// Chunks of a document whose index lies within `radius` of `chunk_index`
+    pub fn get_neighbor_chunks(
+        &self,
+        document_id: &str,
+        chunk_index: i32,
+        radius: i32,
+    ) -> Result<Vec<Chunk>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, document_id, chunk_index, content, token_count, start_offset, end_offset,
+                       page_number, timestamp_start, timestamp_end, metadata, created_at
+                FROM chunks
+                WHERE document_id = ? AND chunk_index BETWEEN ? AND ?
+                ORDER BY chunk_index
+                "#,
+            )?;
+
+            let chunks = stmt
+                .query_map(
+                    params![document_id, chunk_index - radius, chunk_index + radius],
+                    Self::row_to_chunk,
+                )?
+                .filter_map(|r| r.ok())
+                .collect();
+            Ok(chunks)
+        })
+    }
+
     pub fn get_chunks_by_ids(&self, ids: &[i64]) -> Result<Vec<Chunk>> {
         if ids.is_empty() {
             return Ok(vec![]);
@@ -373,7 +587,7 @@ impl super::Database {
             id: row.get(0)?,
             document_id: row.get(1)?,
             chunk_index: row.get(2)?,
-            content: row.get(3)?,
+            content: column_text(row, 3)?,
             token_count: row.get(4)?,
             start_offset: row.get(5)?,
             end_offset: row.get(6)?,
@@ -440,36 +654,58 @@ impl super::Database {
         })
     }
 
-    pub fn vector_search(&self, query_embedding: &[f32], k: usize) -> Result<Vec<(i64, f64)>> {
+    /// Number of chunks that have no vector yet, leaving out `unindexed`
+    /// chunks, which are never embedded
+    pub fn count_chunks_without_embeddings(&self) -> Result<usize> {
         self.with_conn(|conn| {
-            let embedding_blob = query_embedding
-                .iter()
-                .flat_map(|f| f.to_le_bytes())
-                .collect::<Vec<u8>>();
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM chunks
+                 WHERE id NOT IN (SELECT chunk_id FROM vec_chunks)
+                   AND json_extract(metadata, '$.unindexed') IS NOT 1",
+                [],
+                |row| row.get(0),
+            )?;
+            Ok(count as usize)
+        })
+    }
 
-            // Note: sqlite-vec requires k=? constraint for KNN queries
+    /// Ids and content of up to `limit` chunks that have no vector yet,
+    /// leaving out `unindexed` chunks
+    pub fn get_chunks_without_embeddings(&self, limit: usize) -> Result<Vec<(i64, String)>> {
+        self.with_conn(|conn| {
             let mut stmt = conn.prepare(
                 r#"
-                SELECT chunk_id, distance
-                FROM vec_chunks
-                WHERE embedding MATCH ? AND k = ?
-                ORDER BY distance
+                SELECT id, content FROM chunks
+                WHERE id NOT IN (SELECT chunk_id FROM vec_chunks)
+                  AND json_extract(metadata, '$.unindexed') IS NOT 1
+                ORDER BY id
+                LIMIT ?
                 "#,
             )?;
 
-            let results = stmt
-                .query_map(params![embedding_blob, k as i64], |row| {
-                    Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?))
-                })?
+            let chunks = stmt
+                .query_map([limit as i64], |row| Ok((row.get(0)?, column_text(row, 1)?)))?
                 .filter_map(|r| r.ok())
                 .collect();
+            Ok(chunks)
+        })
+    }
 
-            Ok(results)
+    pub fn vector_search(&self, query_embedding: &[f32], k: usize) -> Result<Vec<(i64, f64)>> {
+        let metric = self.get_similarity_metric()?;
+        self.with_conn(|conn| {
+            let embedding_blob = query_embedding
+                .iter()
+                .flat_map(|f| f.to_le_bytes())
+                .collect::<Vec<u8>>();
+
+            nearest_vectors(conn, &embedding_blob, k, metric)
         })
     }
 
     /// Find similar chunks to an existing chunk using its embedding
     pub fn vector_search_by_chunk(&self, chunk_id: i64, k: usize) -> Result<Vec<(i64, f64)>> {
+        let metric = self.get_similarity_metric()?;
         self.with_conn(|conn| {
             // First get the embedding for the source chunk
             let embedding: Option<Vec<u8>> = conn.query_row(
@@ -483,44 +719,68 @@ impl super::Database {
             };
 
             // Search for similar chunks (excluding the source chunk)
-            // Note: sqlite-vec requires k=? constraint for KNN queries
+            let results = nearest_vectors(conn, &embedding_blob, k, metric)?
+                .into_iter()
+                .filter(|(id, _)| *id != chunk_id) // Exclude source chunk
+                .collect();
+
+            Ok(results)
+        })
+    }
+
+    // Full-text search. Title matches are weighted FTS_TITLE_WEIGHT times
+    // body matches.
+    pub fn fts_search(&self, query: &str, limit: usize) -> Result<Vec<(i64, f64)>> {
+        self.with_conn(|conn| {
             let mut stmt = conn.prepare(
                 r#"
-                SELECT chunk_id, distance
-                FROM vec_chunks
-                WHERE embedding MATCH ? AND k = ?
-                ORDER BY distance
+                SELECT rowid, bm25(chunks_fts, ?, 1.0) as score
+                FROM chunks_fts
+                WHERE chunks_fts MATCH ?
+                ORDER BY score
+                LIMIT ?
                 "#,
             )?;
 
-            let results: Vec<(i64, f64)> = stmt
-                .query_map(params![embedding_blob, k as i64], |row| {
-                    Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?))
+            let results = stmt
+                .query_map(params![FTS_TITLE_WEIGHT, query, limit as i64], |row| {
+                    Ok((row.get::<_, i64>(0)?, -row.get::<_, f64>(1)?)) // Negate BM25 score (lower is better)
                 })?
                 .filter_map(|r| r.ok())
-                .filter(|(id, _)| *id != chunk_id) // Exclude source chunk
                 .collect();
 
             Ok(results)
         })
     }
 
-    // Full-text search
-    pub fn fts_search(&self, query: &str, limit: usize) -> Result<Vec<(i64, f64)>> {
+    /// Changes whenever chunks are added or removed, so results computed
+    /// from the chunks can tell they are stale
+    pub fn chunks_version(&self) -> Result<(i64, i64)> {
+        self.with_conn(|conn| {
+            Ok(conn.query_row("SELECT COALESCE(MAX(id), 0), COUNT(*) FROM chunks", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?)
+        })
+    }
+
+    /// Full-text search over one document's chunk text. Titles are left out
+    /// since every chunk of the document shares one.
+    pub fn fts_search_in_document(&self, query: &str, document_id: &str, limit: usize) -> Result<Vec<(i64, f64)>> {
         self.with_conn(|conn| {
             let mut stmt = conn.prepare(
                 r#"
-                SELECT rowid, bm25(chunks_fts) as score
+                SELECT chunks_fts.rowid, bm25(chunks_fts) as score
                 FROM chunks_fts
-                WHERE chunks_fts MATCH ?
+                JOIN chunks c ON c.id = chunks_fts.rowid
+                WHERE chunks_fts MATCH ? AND c.document_id = ?
                 ORDER BY score
                 LIMIT ?
                 "#,
             )?;
 
             let results = stmt
-                .query_map(params![query, limit as i64], |row| {
-                    Ok((row.get::<_, i64>(0)?, -row.get::<_, f64>(1)?)) // Negate BM25 score (lower is better)
+                .query_map(params![format!("content : ({})", query), document_id, limit as i64], |row| {
+                    Ok((row.get::<_, i64>(0)?, -row.get::<_, f64>(1)?))
                 })?
                 .filter_map(|r| r.ok())
                 .collect();
@@ -529,6 +789,73 @@ impl super::Database {
         })
     }
 
+    /// Nearest chunks of one document, scanning its vectors directly rather
+    /// than searching the whole index
+    pub fn vector_search_in_document(&self, query_embedding: &[f32], document_id: &str, k: usize) -> Result<Vec<(i64, f64)>> {
+        let metric = self.get_similarity_metric()?;
+        let distance = match metric {
+            SimilarityMetric::Cosine => "vec_distance_cosine",
+            SimilarityMetric::L2 => "vec_distance_l2",
+            SimilarityMetric::Dot => "dot_distance",
+        };
+        self.with_conn(|conn| {
+            let embedding_blob = query_embedding
+                .iter()
+                .flat_map(|f| f.to_le_bytes())
+                .collect::<Vec<u8>>();
+
+            let mut stmt = conn.prepare(&format!(
+                r#"
+                SELECT v.chunk_id, {}(v.embedding, ?1) AS distance
+                FROM chunks c
+                JOIN vec_chunks v ON v.chunk_id = c.id
+                WHERE c.document_id = ?2
+                ORDER BY distance
+                LIMIT ?3
+                "#,
+                distance
+            ))?;
+
+            let results = stmt
+                .query_map(params![embedding_blob, document_id, k as i64], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?))
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(results)
+        })
+    }
+
+    /// Sum of the stored chunk embeddings of every completed document, by
+    /// document id. Summed while reading so only one vector per document is
+    /// held in memory.
+    pub fn get_document_embedding_sums(&self) -> Result<HashMap<String, Vec<f32>>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT c.document_id, v.embedding
+                FROM chunks c
+                JOIN vec_chunks v ON v.chunk_id = c.id
+                JOIN documents d ON d.id = c.document_id
+                WHERE d.status = 'completed'
+                "#,
+            )?;
+
+            let mut sums: HashMap<String, Vec<f32>> = HashMap::new();
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?)))?;
+            for (document_id, blob) in rows.filter_map(|r| r.ok()) {
+                let embedding = blob_to_floats(&blob);
+                let sum = sums.entry(document_id).or_insert_with(|| vec![0.0; embedding.len()]);
+                for (s, v) in sum.iter_mut().zip(&embedding) {
+                    *s += v;
+                }
+            }
+
+            Ok(sums)
+        })
+    }
+
     // Statistics
     pub fn get_ingestion_stats(&self) -> Result<IngestionStats> {
         self.with_conn(|conn| {
@@ -580,6 +907,47 @@ impl super::Database {
         })
     }
 
+    /// Document counts and sizes per period and file type, oldest period
+    /// first. Either end of the date range may be left open.
+    pub fn get_ingestion_timeline(
+        &self,
+        start_date: Option<chrono::NaiveDate>,
+        end_date: Option<chrono::NaiveDate>,
+        bucket: TimelineBucket,
+    ) -> Result<Vec<IngestionTimelineEntry>> {
+        self.with_conn(|conn| {
+            let sql = format!(
+                r#"
+                SELECT {} AS period, file_type, COUNT(*), COALESCE(SUM(file_size), 0)
+                FROM documents
+                WHERE (?1 IS NULL OR DATE(created_at) >= ?1)
+                  AND (?2 IS NULL OR DATE(created_at) <= ?2)
+                GROUP BY period, file_type
+                ORDER BY period, file_type
+                "#,
+                bucket.period_sql()
+            );
+
+            let mut stmt = conn.prepare(&sql)?;
+            let entries = stmt
+                .query_map(
+                    params![start_date.map(|d| d.to_string()), end_date.map(|d| d.to_string())],
+                    |row| {
+                        Ok(IngestionTimelineEntry {
+                            period: row.get(0)?,
+                            file_type: row.get::<_, String>(1)?.parse().unwrap_or(FileType::Unknown),
+                            document_count: row.get(2)?,
+                            total_size_bytes: row.get(3)?,
+                        })
+                    },
+                )?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(entries)
+        })
+    }
+
     // Conversations
     pub fn create_conversation(&self, title: Option<&str>) -> Result<Conversation> {
         let id = Uuid::new_v4().to_string();
@@ -598,6 +966,95 @@ impl super::Database {
             title: title.map(|s| s.to_string()),
             created_at: now,
             updated_at: now,
+            scope: ConversationScope::default(),
+        })
+    }
+
+    /// Replace a conversation's default retrieval scope; an empty scope clears it
+    pub fn set_conversation_scope(&self, id: &str, scope: &ConversationScope) -> Result<()> {
+        let scope_json = if scope.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(scope)?)
+        };
+
+        self.with_conn(|conn| {
+            let updated = conn.execute(
+                "UPDATE conversations SET scope = ? WHERE id = ?",
+                params![scope_json, id],
+            )?;
+            if updated == 0 {
+                return Err(RecallError::NotFound(format!("Conversation not found: {}", id)));
+            }
+            Ok(())
+        })
+    }
+
+    /// Pin a chunk into a conversation's context; pinning twice is a no-op
+    pub fn pin_chunk(&self, conversation_id: &str, chunk_id: i64) -> Result<()> {
+        self.with_conn(|conn| {
+            let exists = |sql: &str, id: &dyn rusqlite::ToSql| -> rusqlite::Result<bool> {
+                conn.query_row(sql, [id], |_| Ok(())).optional().map(|r| r.is_some())
+            };
+            if !exists("SELECT 1 FROM conversations WHERE id = ?", &conversation_id)? {
+                return Err(RecallError::NotFound(format!("Conversation not found: {}", conversation_id)));
+            }
+            if !exists("SELECT 1 FROM chunks WHERE id = ?", &chunk_id)? {
+                return Err(RecallError::NotFound(format!("Chunk not found: {}", chunk_id)));
+            }
+
+            conn.execute(
+                "INSERT OR IGNORE INTO conversation_pinned_chunks (conversation_id, chunk_id, pinned_at) VALUES (?, ?, ?)",
+                params![conversation_id, chunk_id, Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn unpin_chunk(&self, conversation_id: &str, chunk_id: i64) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM conversation_pinned_chunks WHERE conversation_id = ? AND chunk_id = ?",
+                params![conversation_id, chunk_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Chunks pinned to a conversation, in the order they were pinned
+    pub fn get_pinned_chunks(&self, conversation_id: &str) -> Result<Vec<Chunk>> {
+        let ids: Vec<i64> = self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT chunk_id FROM conversation_pinned_chunks WHERE conversation_id = ? ORDER BY pinned_at, chunk_id",
+            )?;
+            let ids = stmt
+                .query_map([conversation_id], |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect();
+            Ok(ids)
+        })?;
+
+        let mut chunks = self.get_chunks_by_ids(&ids)?;
+        chunks.sort_by_key(|c| ids.iter().position(|&id| id == c.id));
+        Ok(chunks)
+    }
+
+    fn row_to_conversation(row: &Row<'_>) -> rusqlite::Result<Conversation> {
+        Ok(Conversation {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            created_at: row
+                .get::<_, String>(2)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: row
+                .get::<_, String>(3)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            scope: row
+                .get::<_, Option<String>>(4)?
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default(),
         })
     }
 
@@ -687,26 +1144,13 @@ impl super::Database {
         self.with_conn(|conn| {
             let mut stmt = conn.prepare(
                 r#"
-                SELECT id, title, created_at, updated_at
+                SELECT id, title, created_at, updated_at, scope
                 FROM conversations ORDER BY updated_at DESC
                 "#,
             )?;
 
             let conversations = stmt
-                .query_map([], |row| {
-                    Ok(Conversation {
-                        id: row.get(0)?,
-                        title: row.get(1)?,
-                        created_at: row
-                            .get::<_, String>(2)?
-                            .parse()
-                            .unwrap_or_else(|_| Utc::now()),
-                        updated_at: row
-                            .get::<_, String>(3)?
-                            .parse()
-                            .unwrap_or_else(|_| Utc::now()),
-                    })
-                })?
+                .query_map([], Self::row_to_conversation)?
                 .filter_map(|r| r.ok())
                 .collect();
 
@@ -718,32 +1162,45 @@ impl super::Database {
         self.with_conn(|conn| {
             let mut stmt = conn.prepare(
                 r#"
-                SELECT id, title, created_at, updated_at
+                SELECT id, title, created_at, updated_at, scope
                 FROM conversations WHERE id = ?
                 "#,
             )?;
 
             let conversation = stmt
-                .query_row([id], |row| {
-                    Ok(Conversation {
-                        id: row.get(0)?,
-                        title: row.get(1)?,
-                        created_at: row
-                            .get::<_, String>(2)?
-                            .parse()
-                            .unwrap_or_else(|_| Utc::now()),
-                        updated_at: row
-                            .get::<_, String>(3)?
-                            .parse()
-                            .unwrap_or_else(|_| Utc::now()),
-                    })
-                })
+                .query_row([id], Self::row_to_conversation)
                 .optional()?;
 
             Ok(conversation)
         })
     }
 
+    /// Cached summary of a conversation's older messages and how many
+    /// messages (oldest first) it covers
+    pub fn get_conversation_summary(&self, id: &str) -> Result<Option<(String, usize)>> {
+        self.with_conn(|conn| {
+            let summary = conn
+                .query_row(
+                    "SELECT history_summary, summarized_messages FROM conversations WHERE id = ?",
+                    [id],
+                    |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, i64>(1)?)),
+                )
+                .optional()?;
+
+            Ok(summary.and_then(|(text, count)| text.map(|t| (t, count.max(0) as usize))))
+        })
+    }
+
+    pub fn set_conversation_summary(&self, id: &str, summary: &str, message_count: usize) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE conversations SET history_summary = ?, summarized_messages = ? WHERE id = ?",
+                params![summary, message_count as i64, id],
+            )?;
+            Ok(())
+        })
+    }
+
     pub fn delete_conversation(&self, id: &str) -> Result<()> {
         self.with_conn_mut(|conn| {
             let tx = conn.transaction()?;
@@ -786,4 +1243,594 @@ impl super::Database {
             Ok(())
         })
     }
+
+    /// Set a single top-level metadata key without rewriting the rest
+    pub fn set_document_metadata_key(&self, id: &str, key: &str, value: &serde_json::Value) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE documents SET metadata = json_set(COALESCE(metadata, '{}'), '$.' || ?, json(?)) WHERE id = ?",
+                params![key, value.to_string(), id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Remove a single top-level metadata key
+    pub fn remove_document_metadata_key(&self, id: &str, key: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE documents SET metadata = json_remove(metadata, '$.' || ?) WHERE id = ?",
+                params![key, id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Per-document facts for integrity checks, keyed by document id
+    pub fn get_document_record_facts(&self) -> Result<HashMap<String, DocumentRecordFacts>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT d.id, d.ingested_at IS NULL,
+                       (SELECT COUNT(*) FROM chunks c WHERE c.document_id = d.id)
+                FROM documents d
+                "#,
+            )?;
+
+            let facts = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        DocumentRecordFacts {
+                            ingested_at_missing: row.get(1)?,
+                            stored_chunks: row.get(2)?,
+                        },
+                    ))
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+            Ok(facts)
+        })
+    }
+
+    /// Overwrite the fields derived from a document's source file
+    pub fn update_document_file_fields(
+        &self,
+        id: &str,
+        file_type: FileType,
+        mime_type: Option<&str>,
+        file_hash: &str,
+    ) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                r#"
+                UPDATE documents
+                SET file_type = ?, mime_type = ?, file_hash = ?
+                WHERE id = ?
+                "#,
+                params![file_type.as_str(), mime_type, file_hash, id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Fill in a missing `ingested_at` from the last update of the record
+    pub fn backfill_ingested_at(&self, id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE documents SET ingested_at = updated_at WHERE id = ? AND ingested_at IS NULL",
+                [id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Recompute a document's chunk count and token total from its chunks
+    pub fn recount_document_chunks(&self, id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                r#"
+                UPDATE documents
+                SET chunk_count = (SELECT COUNT(*) FROM chunks WHERE document_id = ?1),
+                    total_tokens = (SELECT COALESCE(SUM(token_count), 0) FROM chunks WHERE document_id = ?1)
+                WHERE id = ?1
+                "#,
+                [id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Flag (or unflag) a document whose source file is no longer on disk
+    pub fn set_source_missing(&self, id: &str, missing: bool) -> Result<()> {
+        self.with_conn(|conn| {
+            let sql = if missing {
+                "UPDATE documents SET metadata = json_set(COALESCE(metadata, '{}'), '$.source_missing', json('true')) WHERE id = ?"
+            } else {
+                "UPDATE documents SET metadata = json_remove(metadata, '$.source_missing') WHERE id = ?"
+            };
+            conn.execute(sql, [id])?;
+            Ok(())
+        })
+    }
+
+    /// Re-check whether a document's source file exists and update its flag.
+    /// Returns true when the file is missing.
+    pub fn refresh_source_missing(&self, doc: &Document) -> Result<bool> {
+        let missing = !Path::new(&doc.file_path).exists();
+        let flagged = doc
+            .metadata
+            .get("source_missing")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if missing != flagged {
+            self.set_source_missing(&doc.id, missing)?;
+        }
+        Ok(missing)
+    }
+
+    /// Count documents per content category, bucketing uncategorized ones
+    pub fn get_category_distribution(&self) -> Result<Vec<CategoryCount>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT COALESCE(json_extract(metadata, '$.content_category'), 'Uncategorized') AS category,
+                       COUNT(*) AS count
+                FROM documents
+                GROUP BY category
+                ORDER BY count DESC, category ASC
+                "#,
+            )?;
+
+            let counts = stmt
+                .query_map([], |row| {
+                    Ok(CategoryCount {
+                        category: row.get(0)?,
+                        count: row.get(1)?,
+                    })
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(counts)
+        })
+    }
+
+    /// Get IDs of documents ingested from any of `sources`
+    pub fn get_document_ids_by_sources(&self, sources: &[IngestionSource]) -> Result<Vec<String>> {
+        if sources.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.with_conn(|conn| {
+            let placeholders = sources.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let sql = format!("SELECT id FROM documents WHERE ingestion_source IN ({})", placeholders);
+
+            let mut stmt = conn.prepare(&sql)?;
+            let ids = stmt
+                .query_map(
+                    rusqlite::params_from_iter(sources.iter().map(|s| s.as_str())),
+                    |row| row.get(0),
+                )?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(ids)
+        })
+    }
+
+    /// Get IDs of documents whose primary category or any category label matches
+    pub fn get_document_ids_by_categories(&self, categories: &[String]) -> Result<Vec<String>> {
+        if categories.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.with_conn(|conn| {
+            let placeholders = categories.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let sql = format!(
+                r#"
+                SELECT id FROM documents
+                WHERE json_extract(metadata, '$.content_category') IN ({0})
+                   OR EXISTS (
+                       SELECT 1 FROM json_each(documents.metadata, '$.content_categories')
+                       WHERE json_extract(json_each.value, '$.category') IN ({0})
+                   )
+                "#,
+                placeholders
+            );
+
+            // Placeholders are reused for both IN clauses
+            let params = categories.iter().chain(categories.iter());
+
+            let mut stmt = conn.prepare(&sql)?;
+            let ids = stmt
+                .query_map(rusqlite::params_from_iter(params), |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(ids)
+        })
+    }
+
+    /// Rewrite existing chunk text to match `compress`, returning how many
+    /// chunks changed. The update trigger reindexes each rewritten chunk.
+    pub fn convert_chunk_storage(&self, compress: bool) -> Result<usize> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+
+            let rows: Vec<(i64, String)> = {
+                let mut stmt = tx.prepare("SELECT id, content FROM chunks WHERE typeof(content) = ?")?;
+                let stored_as = if compress { "text" } else { "blob" };
+                let rows = stmt
+                    .query_map([stored_as], |row| Ok((row.get(0)?, column_text(row, 1)?)))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                rows
+            };
+
+            let mut converted = 0;
+            {
+                let mut update = tx.prepare("UPDATE chunks SET content = ? WHERE id = ?")?;
+                for (id, text) in &rows {
+                    let value = stored_text(text, compress)?;
+                    // Text too short to benefit stays as it is
+                    let is_blob = matches!(value, rusqlite::types::ToSqlOutput::Owned(rusqlite::types::Value::Blob(_)));
+                    if is_blob == compress {
+                        update.execute(params![value, id])?;
+                        converted += 1;
+                    }
+                }
+            }
+
+            tx.commit()?;
+            tracing::info!(
+                "{} {} chunks",
+                if compress { "Compressed" } else { "Decompressed" },
+                converted
+            );
+            Ok(converted)
+        })
+    }
+
+    /// Get the tokenizer the FTS index is currently built with
+    pub fn get_fts_tokenizer(&self) -> Result<FtsTokenizer> {
+        self.with_conn(|conn| {
+            let spec: Option<String> = conn
+                .query_row(
+                    "SELECT value FROM index_config WHERE key = 'fts_tokenizer'",
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            Ok(spec
+                .and_then(|s| FtsTokenizer::from_fts5_spec(&s))
+                .unwrap_or(FtsTokenizer::Porter))
+        })
+    }
+
+    /// Recreate the FTS index with a different tokenizer and reindex all chunks
+    pub fn rebuild_fts_index(&self, tokenizer: FtsTokenizer) -> Result<()> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+
+            // The sync triggers reference chunks_fts by name, so they keep working
+            tx.execute_batch(&format!(
+                r#"
+                DROP TABLE IF EXISTS chunks_fts;
+                CREATE VIRTUAL TABLE chunks_fts USING fts5(
+                    title,
+                    content,
+                    content='',
+                    contentless_delete=1,
+                    tokenize='{}'
+                );
+                INSERT INTO chunks_fts(rowid, title, content)
+                    SELECT c.id, COALESCE(t.fts_title, ''), chunk_text(c.content)
+                    FROM chunks c LEFT JOIN document_fts_titles t ON t.id = c.document_id
+                    WHERE json_extract(c.metadata, '$.unindexed') IS NOT 1;
+                "#,
+                tokenizer.fts5_spec()
+            ))?;
+
+            tx.execute(
+                "INSERT OR REPLACE INTO index_config (key, value) VALUES ('fts_tokenizer', ?)",
+                params![tokenizer.fts5_spec()],
+            )?;
+
+            tx.commit()?;
+            Ok(())
+        })?;
+
+        tracing::info!("Rebuilt FTS index with tokenizer '{}'", tokenizer.fts5_spec());
+        Ok(())
+    }
+
+    /// Rebuild the FTS index if it was built with a different tokenizer
+    pub fn ensure_fts_tokenizer(&self, tokenizer: FtsTokenizer) -> Result<bool> {
+        if self.get_fts_tokenizer()? == tokenizer {
+            return Ok(false);
+        }
+        self.rebuild_fts_index(tokenizer)?;
+        Ok(true)
+    }
+
+    /// Get the metric the vector index currently ranks by. Indexes from
+    /// before the metric was configurable were created with vec0's L2 default.
+    pub fn get_similarity_metric(&self) -> Result<SimilarityMetric> {
+        self.with_conn(|conn| {
+            let metric: Option<String> = conn
+                .query_row(
+                    "SELECT value FROM index_config WHERE key = 'similarity_metric'",
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            Ok(metric.and_then(|m| m.parse().ok()).unwrap_or(SimilarityMetric::L2))
+        })
+    }
+
+    /// Recreate the vector index with a different metric, carrying the
+    /// stored embeddings over
+    pub fn rebuild_vector_index(&self, metric: SimilarityMetric) -> Result<()> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+
+            // Without sqlite-vec there is no table to rebuild; just record the choice
+            let has_index: bool = tx
+                .query_row(
+                    "SELECT 1 FROM sqlite_master WHERE name = 'vec_chunks'",
+                    [],
+                    |_| Ok(true),
+                )
+                .optional()?
+                .unwrap_or(false);
+
+            if has_index {
+                tx.execute_batch(&format!(
+                    r#"
+                    CREATE TEMP TABLE vec_chunks_backup AS SELECT chunk_id, embedding FROM vec_chunks;
+                    DROP TABLE vec_chunks;
+                    CREATE VIRTUAL TABLE vec_chunks USING vec0(
+                        chunk_id INTEGER PRIMARY KEY,
+                        embedding FLOAT[{}] distance_metric={}
+                    );
+                    INSERT INTO vec_chunks(chunk_id, embedding)
+                        SELECT chunk_id, vec_f32(embedding) FROM vec_chunks_backup;
+                    DROP TABLE vec_chunks_backup;
+                    "#,
+                    EMBEDDING_DIMENSIONS,
+                    metric.vec0_metric()
+                ))?;
+            }
+
+            tx.execute(
+                "INSERT OR REPLACE INTO index_config (key, value) VALUES ('similarity_metric', ?)",
+                params![metric.as_str()],
+            )?;
+
+            tx.commit()?;
+            Ok(())
+        })?;
+
+        tracing::info!("Rebuilt vector index with metric '{}'", metric.as_str());
+        Ok(())
+    }
+
+    /// Rebuild the vector index if it was built with a different metric
+    pub fn ensure_similarity_metric(&self, metric: SimilarityMetric) -> Result<bool> {
+        if self.get_similarity_metric()? == metric {
+            return Ok(false);
+        }
+        self.rebuild_vector_index(metric)?;
+        Ok(true)
+    }
+
+    /// Store similarity edges for a document in both directions, keeping the
+    /// higher similarity when an edge already exists
+    pub fn upsert_document_relations(&self, document_id: &str, related: &[(String, f64)]) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare(
+                    r#"
+                    INSERT INTO document_relations (document_id, related_id, similarity, updated_at)
+                    VALUES (?1, ?2, ?3, ?4)
+                    ON CONFLICT(document_id, related_id) DO UPDATE SET
+                        similarity = MAX(similarity, excluded.similarity),
+                        updated_at = excluded.updated_at
+                    "#,
+                )?;
+                for (related_id, similarity) in related {
+                    if related_id == document_id {
+                        continue;
+                    }
+                    stmt.execute(params![document_id, related_id, similarity, now])?;
+                    stmt.execute(params![related_id, document_id, similarity, now])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Remove every stored edge touching a document
+    pub fn delete_document_relations(&self, document_id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM document_relations WHERE document_id = ?1 OR related_id = ?1",
+                params![document_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Get stored relations for a document, most similar first
+    pub fn get_document_relations(&self, document_id: &str, limit: usize) -> Result<Vec<DocumentRelation>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT r.document_id, r.related_id, d.title, r.similarity, r.updated_at
+                FROM document_relations r
+                JOIN documents d ON d.id = r.related_id
+                WHERE r.document_id = ?
+                ORDER BY r.similarity DESC
+                LIMIT ?
+                "#,
+            )?;
+
+            let relations = stmt
+                .query_map(params![document_id, limit as i64], |row| {
+                    Ok(DocumentRelation {
+                        document_id: row.get(0)?,
+                        related_id: row.get(1)?,
+                        related_title: row.get(2)?,
+                        similarity: row.get(3)?,
+                        updated_at: row
+                            .get::<_, String>(4)?
+                            .parse()
+                            .unwrap_or_else(|_| Utc::now()),
+                    })
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(relations)
+        })
+    }
+
+    /// Get stored relations as undirected edges at or above a similarity threshold
+    pub fn get_relation_edges(&self, min_similarity: f64) -> Result<Vec<GraphEdge>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT MIN(document_id, related_id) AS source,
+                       MAX(document_id, related_id) AS target,
+                       MAX(similarity) AS weight
+                FROM document_relations
+                GROUP BY source, target
+                HAVING weight >= ?
+                ORDER BY weight DESC
+                "#,
+            )?;
+
+            let edges = stmt
+                .query_map(params![min_similarity], |row| {
+                    Ok(GraphEdge {
+                        source: row.get(0)?,
+                        target: row.get(1)?,
+                        weight: row.get(2)?,
+                    })
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(edges)
+        })
+    }
+
+    /// Remove every stored document relation
+    pub fn clear_document_relations(&self) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute("DELETE FROM document_relations", [])?;
+            Ok(())
+        })
+    }
+
+    /// Snapshot a document's extracted text, keeping at most `keep` versions
+    /// for its path. Returns the new version id.
+    pub fn insert_document_version(&self, doc: &Document, content: &str, keep: usize) -> Result<i64> {
+        let compress = self.chunk_compression();
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                r#"
+                INSERT INTO document_versions (document_id, file_path, file_hash, content, created_at)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+                params![
+                    doc.id,
+                    doc.file_path,
+                    doc.file_hash,
+                    stored_text(content, compress)?,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+            let id = tx.last_insert_rowid();
+
+            tx.execute(
+                r#"
+                DELETE FROM document_versions
+                WHERE file_path = ?1 AND id NOT IN (
+                    SELECT id FROM document_versions WHERE file_path = ?1 ORDER BY id DESC LIMIT ?2
+                )
+                "#,
+                params![doc.file_path, keep as i64],
+            )?;
+            tx.commit()?;
+            Ok(id)
+        })
+    }
+
+    pub fn get_document_version(&self, id: i64) -> Result<Option<DocumentVersion>> {
+        self.with_conn(|conn| {
+            let version = conn
+                .query_row(
+                    r#"
+                    SELECT id, document_id, file_path, file_hash, content, change_summary, created_at
+                    FROM document_versions WHERE id = ?
+                    "#,
+                    [id],
+                    Self::row_to_document_version,
+                )
+                .optional()?;
+            Ok(version)
+        })
+    }
+
+    /// Prior versions of the file at `file_path`, newest first
+    pub fn get_document_versions(&self, file_path: &str) -> Result<Vec<DocumentVersion>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, document_id, file_path, file_hash, content, change_summary, created_at
+                FROM document_versions WHERE file_path = ?
+                ORDER BY id DESC
+                "#,
+            )?;
+
+            let versions = stmt
+                .query_map([file_path], Self::row_to_document_version)?
+                .filter_map(|r| r.ok())
+                .collect();
+            Ok(versions)
+        })
+    }
+
+    pub fn set_document_version_summary(&self, id: i64, summary: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE document_versions SET change_summary = ? WHERE id = ?",
+                params![summary, id],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn row_to_document_version(row: &Row<'_>) -> rusqlite::Result<DocumentVersion> {
+        Ok(DocumentVersion {
+            id: row.get(0)?,
+            document_id: row.get(1)?,
+            file_path: row.get(2)?,
+            file_hash: row.get(3)?,
+            content: column_text(row, 4)?,
+            change_summary: row.get(5)?,
+            created_at: row
+                .get::<_, String>(6)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
 }
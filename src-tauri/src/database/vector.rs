@@ -0,0 +1,64 @@
+//! Vector helpers that sqlite-vec doesn't provide.
+
+use crate::error::Result;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::Connection;
+
+/// Dimensions of the `vec_chunks` embedding column
+pub(super) const EMBEDDING_DIMENSIONS: usize = 768;
+
+/// Little-endian float32 blob to floats, as stored by `vec_f32()`
+pub(super) fn blob_to_floats(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+/// Register `dot_distance(a, b)`, the negated inner product of two float32
+/// vector blobs, so that smaller means closer like vec0's own distances
+pub(super) fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "dot_distance",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let a = blob_to_floats(&ctx.get::<Vec<u8>>(0)?);
+            let b = blob_to_floats(&ctx.get::<Vec<u8>>(1)?);
+            if a.len() != b.len() {
+                return Err(rusqlite::Error::UserFunctionError(
+                    format!("dot_distance: vector lengths differ ({} vs {})", a.len(), b.len()).into(),
+                ));
+            }
+            let dot: f64 = a.iter().zip(&b).map(|(x, y)| *x as f64 * *y as f64).sum();
+            Ok(-dot)
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dot_distance() {
+        let conn = Connection::open_in_memory().unwrap();
+        register_functions(&conn).unwrap();
+
+        let blob = |v: &[f32]| v.iter().flat_map(|f| f.to_le_bytes()).collect::<Vec<u8>>();
+        let distance: f64 = conn
+            .query_row(
+                "SELECT dot_distance(?, ?)",
+                [blob(&[1.0, 2.0, 3.0]), blob(&[4.0, 5.0, 6.0])],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(distance, -32.0);
+
+        assert!(conn
+            .query_row("SELECT dot_distance(?, ?)", [blob(&[1.0]), blob(&[1.0, 2.0])], |row| {
+                row.get::<_, f64>(0)
+            })
+            .is_err());
+    }
+}
@@ -50,6 +50,9 @@ pub enum RecallError {
     #[error("Screen capture error: {0}")]
     Capture(String),
 
+    #[error("Blocked by provider safety filter: {0}")]
+    ContentBlocked(String),
+
     #[error("Trial limit reached: {0}")]
     TrialLimitReached(String),
 
@@ -63,12 +66,84 @@ pub enum RecallError {
     Other(String),
 }
 
+/// Stable, machine-readable error kind sent to the frontend alongside the message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Database,
+    Io,
+    Json,
+    /// The provider could not be reached (connection failure or timeout)
+    Offline,
+    Http,
+    PdfExtract,
+    LlmApi,
+    RateLimit,
+    InvalidApiKey,
+    Embedding,
+    Ingestion,
+    Ffmpeg,
+    Ocr,
+    VectorSearch,
+    ExtensionLoad,
+    Config,
+    Capture,
+    ContentBlocked,
+    TrialLimitReached,
+    NotFound,
+    Tauri,
+    Other,
+}
+
+impl RecallError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Database(_) => ErrorCode::Database,
+            Self::Io(_) => ErrorCode::Io,
+            Self::Json(_) => ErrorCode::Json,
+            Self::Http(e) if e.is_connect() || e.is_timeout() => ErrorCode::Offline,
+            Self::Http(_) => ErrorCode::Http,
+            Self::PdfExtract(_) => ErrorCode::PdfExtract,
+            Self::LlmApi(_) => ErrorCode::LlmApi,
+            Self::RateLimit(_) => ErrorCode::RateLimit,
+            Self::InvalidApiKey => ErrorCode::InvalidApiKey,
+            Self::Embedding(_) => ErrorCode::Embedding,
+            Self::Ingestion(_) => ErrorCode::Ingestion,
+            Self::FFmpeg(_) => ErrorCode::Ffmpeg,
+            Self::Ocr(_) => ErrorCode::Ocr,
+            Self::VectorSearch(_) => ErrorCode::VectorSearch,
+            Self::ExtensionLoad(_) => ErrorCode::ExtensionLoad,
+            Self::Config(_) => ErrorCode::Config,
+            Self::Capture(_) => ErrorCode::Capture,
+            Self::ContentBlocked(_) => ErrorCode::ContentBlocked,
+            Self::TrialLimitReached(_) => ErrorCode::TrialLimitReached,
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::Tauri(_) => ErrorCode::Tauri,
+            Self::Other(_) => ErrorCode::Other,
+        }
+    }
+}
+
+/// Serialized as `{ code, message }`, plus `retry_after_secs` for rate limits
 impl serde::Serialize for RecallError {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: serde::ser::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        use serde::ser::SerializeStruct;
+
+        let retry_after = match self {
+            Self::RateLimit(secs) => Some(*secs),
+            _ => None,
+        };
+
+        let mut state = serializer.serialize_struct("RecallError", 2 + usize::from(retry_after.is_some()))?;
+        state.serialize_field("code", &self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        if let Some(secs) = retry_after {
+            state.serialize_field("retry_after_secs", &secs)?;
+        }
+        state.end()
     }
 }
 
@@ -91,3 +166,18 @@ impl From<notify::Error> for RecallError {
 }
 
 pub type Result<T> = std::result::Result<T, RecallError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_serializes_with_code() {
+        let json = serde_json::to_value(RecallError::InvalidApiKey).unwrap();
+        assert_eq!(json, serde_json::json!({ "code": "invalid_api_key", "message": "Invalid API key" }));
+
+        let json = serde_json::to_value(RecallError::RateLimit(30)).unwrap();
+        assert_eq!(json["code"], "rate_limit");
+        assert_eq!(json["retry_after_secs"], 30);
+    }
+}
@@ -0,0 +1,240 @@
+//! Content categorization: an LLM picks up to a few ranked labels from a
+//! fixed list, stored in document metadata as `content_category` (primary)
+//! and `content_categories` (all labels).
+
+use crate::database::{Chunk, Document};
+use crate::error::RecallError;
+use crate::llm::{GenerateRequest, LlmClient, LlmProvider};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentCategory {
+    pub category: String,
+    pub confidence: f32,
+}
+
+/// Result of categorizing a document: the primary category plus all ranked labels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentCategorization {
+    pub category: String,
+    pub confidence: f32,
+    pub labels: Vec<ContentCategory>,
+}
+
+/// Maximum number of category labels assigned to a single document
+const MAX_CATEGORY_LABELS: usize = 3;
+
+pub const CONTENT_CATEGORIES: &[&str] = &[
+    "Science & Technology",
+    "Business & Finance",
+    "Health & Medicine",
+    "Education & Learning",
+    "Arts & Entertainment",
+    "News & Current Events",
+    "Legal & Compliance",
+    "Personal & Lifestyle",
+    "Travel & Geography",
+    "Food & Cooking",
+    "Sports & Fitness",
+    "History & Culture",
+    "Politics & Government",
+    "Environment & Nature",
+    "Other",
+];
+
+/// Take the first few chunks as a content sample (limit to ~2000 chars)
+pub fn build_content_sample(chunks: &[Chunk]) -> String {
+    let mut content_sample = String::new();
+    for chunk in chunks.iter().take(5) {
+        content_sample.push_str(&chunk.content);
+        content_sample.push_str("\n\n");
+        if content_sample.len() > 2000 {
+            break;
+        }
+    }
+    content_sample
+}
+
+/// Ask the LLM for up to `MAX_CATEGORY_LABELS` ranked categories with confidences
+pub async fn request_category_labels(
+    llm: &LlmClient,
+    title: &str,
+    content_sample: &str,
+) -> Result<Vec<ContentCategory>, RecallError> {
+    let categories_list = CONTENT_CATEGORIES.join("\n- ");
+    let prompt = format!(
+        r#"Analyze this document and pick up to {} categories that apply, ranked from most to least relevant, from this list:
+- {}
+
+Document title: {}
+Content sample:
+{}
+
+Respond with ONLY a JSON array like [{{"category": "Health & Medicine", "confidence": 0.9}}], where confidence is between 0 and 1. Use "Other" only if no category fits."#,
+        MAX_CATEGORY_LABELS, categories_list, title, content_sample
+    );
+
+    let request = GenerateRequest {
+        prompt,
+        system_prompt: Some("You are a document categorization assistant. Respond with only a JSON array of categories.".to_string()),
+        context: vec![],
+        history: vec![],
+        max_tokens: Some(200),
+        temperature: Some(0.1),
+    };
+
+    let response = llm.generate(request).await?;
+    Ok(parse_category_labels(&response.content))
+}
+
+/// Ask the LLM for a short topic name covering a group of documents, given
+/// each one's title and a sample of its content
+pub async fn request_cluster_label(llm: &LlmClient, documents: &[(String, String)]) -> Result<String, RecallError> {
+    let documents = documents
+        .iter()
+        .map(|(title, sample)| format!("<document title=\"{}\">\n{}\n</document>", title, sample))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let prompt = format!(
+        r#"These documents were grouped together because their content is similar:
+
+{}
+
+Name the theme they share in 2 to 5 words. Respond with ONLY the name."#,
+        documents
+    );
+
+    let request = GenerateRequest {
+        prompt,
+        system_prompt: Some("You are a document categorization assistant. Respond with only a short topic name.".to_string()),
+        context: vec![],
+        history: vec![],
+        max_tokens: Some(30),
+        temperature: Some(0.1),
+    };
+
+    let response = llm.generate(request).await?;
+    Ok(response
+        .content
+        .trim()
+        .trim_matches(|c: char| c == '"' || c == '\'' || c == '*' || c == '.')
+        .to_string())
+}
+
+/// Categorize several documents in one request. Returns labels per document
+/// in batch order, None where the response skipped a document.
+pub async fn request_batch_category_labels(
+    llm: &LlmClient,
+    batch: &[(Document, String)],
+) -> Result<Vec<Option<Vec<ContentCategory>>>, RecallError> {
+    let categories_list = CONTENT_CATEGORIES.join("\n- ");
+    let documents = batch
+        .iter()
+        .enumerate()
+        .map(|(i, (doc, sample))| format!("<document id=\"{}\" title=\"{}\">\n{}\n</document>", i + 1, doc.title, sample))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let prompt = format!(
+        r#"Analyze each of these documents and pick up to {} categories that apply to it, ranked from most to least relevant, from this list:
+- {}
+
+{}
+
+Respond with ONLY a JSON object mapping each document id to its categories, like {{"1": [{{"category": "Health & Medicine", "confidence": 0.9}}]}}, where confidence is between 0 and 1. Use "Other" only if no category fits."#,
+        MAX_CATEGORY_LABELS, categories_list, documents
+    );
+
+    let request = GenerateRequest {
+        prompt,
+        system_prompt: Some("You are a document categorization assistant. Respond with only a JSON object of categories per document.".to_string()),
+        context: vec![],
+        history: vec![],
+        max_tokens: Some(200 * batch.len() as u32),
+        temperature: Some(0.1),
+    };
+
+    let response = llm.generate(request).await?;
+    Ok(parse_batch_category_labels(&response.content, batch.len()))
+}
+
+/// Parse a `{"1": [...], "2": [...]}` batch response into labels per document
+fn parse_batch_category_labels(response: &str, count: usize) -> Vec<Option<Vec<ContentCategory>>> {
+    let trimmed = response.trim();
+    let json_slice = match (trimmed.find('{'), trimmed.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &trimmed[start..=end],
+        _ => trimmed,
+    };
+
+    let parsed: HashMap<String, Vec<ContentCategory>> = serde_json::from_str(json_slice).unwrap_or_default();
+    (1..=count)
+        .map(|id| parsed.get(&id.to_string()).map(|raw| known_category_labels(raw.clone())))
+        .collect()
+}
+
+/// Parse ranked labels from the LLM response, keeping only known categories.
+/// Falls back to treating the whole response as a single category name.
+fn parse_category_labels(response: &str) -> Vec<ContentCategory> {
+    let trimmed = response.trim();
+    let json_slice = match (trimmed.find('['), trimmed.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &trimmed[start..=end],
+        _ => trimmed,
+    };
+
+    let raw: Vec<ContentCategory> = serde_json::from_str(json_slice).unwrap_or_else(|_| {
+        vec![ContentCategory {
+            category: trimmed.to_string(),
+            confidence: 1.0,
+        }]
+    });
+
+    known_category_labels(raw)
+}
+
+/// Keep known categories, deduplicated and ranked, falling back to "Other"
+fn known_category_labels(raw: Vec<ContentCategory>) -> Vec<ContentCategory> {
+    let mut labels: Vec<ContentCategory> = Vec::new();
+    for label in raw {
+        let Some(&known) = CONTENT_CATEGORIES
+            .iter()
+            .find(|&&c| label.category.trim().eq_ignore_ascii_case(c))
+        else {
+            continue;
+        };
+        if labels.iter().any(|l| l.category == known) {
+            continue;
+        }
+        labels.push(ContentCategory {
+            category: known.to_string(),
+            confidence: label.confidence.clamp(0.0, 1.0),
+        });
+    }
+
+    labels.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+    labels.truncate(MAX_CATEGORY_LABELS);
+
+    if labels.is_empty() {
+        labels.push(ContentCategory {
+            category: "Other".to_string(),
+            confidence: 0.0,
+        });
+    }
+
+    labels
+}
+
+/// Store labels in document metadata, keeping `content_category` as the primary label
+pub fn apply_category_labels(
+    metadata: &mut serde_json::Value,
+    labels: Vec<ContentCategory>,
+) -> DocumentCategorization {
+    let primary = labels[0].clone();
+    metadata["content_category"] = serde_json::json!(primary.category);
+    metadata["content_categories"] = serde_json::json!(labels);
+
+    DocumentCategorization {
+        category: primary.category,
+        confidence: primary.confidence,
+        labels,
+    }
+}
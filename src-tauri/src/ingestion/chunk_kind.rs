@@ -0,0 +1,197 @@
+//! Heuristic structure tags for chunks, so running headers, footers and
+//! captions can be kept out of search while staying in the document.
+
+use crate::database::Chunk;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Chunks longer than this (in non-whitespace characters) are always body
+const MAX_STRUCTURAL_CHARS: usize = 200;
+/// Pages a first or last chunk must repeat on to count as a header or footer
+const MIN_REPEATED_PAGES: usize = 2;
+
+static CAPTION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(figure|fig\.|table|chart|plate|exhibit)\s*\d+[a-z]?\s*[.:\-–]").unwrap());
+static PAGE_NUMBER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(page\s*)?\d+(\s*(of|/)\s*\d+)?$").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkKind {
+    Body,
+    /// Repeated at the top of pages
+    Header,
+    /// Repeated at the bottom of pages, or a bare page number
+    Footer,
+    /// Figure or table caption
+    Caption,
+}
+
+/// Store each chunk's kind in its metadata as `kind`. Headers and footers
+/// need page numbers; captions are recognized anywhere.
+pub fn tag_chunk_kinds(chunks: &mut [Chunk]) {
+    let kinds = classify(chunks);
+    for (chunk, kind) in chunks.iter_mut().zip(kinds) {
+        if let Some(metadata) = chunk.metadata.as_object_mut() {
+            metadata.insert("kind".to_string(), serde_json::json!(kind));
+        }
+    }
+}
+
+/// Kind recorded by `tag_chunk_kinds`; untagged chunks are body
+pub fn chunk_kind(chunk: &Chunk) -> ChunkKind {
+    chunk
+        .metadata
+        .get("kind")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or(ChunkKind::Body)
+}
+
+fn classify(chunks: &[Chunk]) -> Vec<ChunkKind> {
+    // First and last chunk index of each page
+    let mut page_bounds: HashMap<i32, (usize, usize)> = HashMap::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        if let Some(page) = chunk.page_number {
+            page_bounds.entry(page).and_modify(|(_, last)| *last = i).or_insert((i, i));
+        }
+    }
+
+    // How many pages open (or close) with each short text, ignoring digits
+    // so "Page 3" and "Page 4" match
+    let mut first_counts: HashMap<String, usize> = HashMap::new();
+    let mut last_counts: HashMap<String, usize> = HashMap::new();
+    for &(first, last) in page_bounds.values() {
+        if is_short(&chunks[first]) {
+            *first_counts.entry(repetition_key(&chunks[first].content)).or_default() += 1;
+        }
+        if last != first && is_short(&chunks[last]) {
+            *last_counts.entry(repetition_key(&chunks[last].content)).or_default() += 1;
+        }
+    }
+
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            if !is_short(chunk) {
+                return ChunkKind::Body;
+            }
+            let text = chunk.content.trim();
+            if CAPTION_REGEX.is_match(text) {
+                return ChunkKind::Caption;
+            }
+            let Some(&(first, last)) = chunk.page_number.and_then(|p| page_bounds.get(&p)) else {
+                return ChunkKind::Body;
+            };
+            let key = repetition_key(text);
+            if i == first && first_counts.get(&key).copied().unwrap_or(0) >= MIN_REPEATED_PAGES {
+                ChunkKind::Header
+            } else if i == last
+                && (PAGE_NUMBER_REGEX.is_match(text)
+                    || last_counts.get(&key).copied().unwrap_or(0) >= MIN_REPEATED_PAGES)
+            {
+                ChunkKind::Footer
+            } else {
+                ChunkKind::Body
+            }
+        })
+        .collect()
+}
+
+fn is_short(chunk: &Chunk) -> bool {
+    chunk.content.chars().filter(|c| !c.is_whitespace()).count() <= MAX_STRUCTURAL_CHARS
+}
+
+fn repetition_key(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_ascii_digit())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn chunk(content: &str, page: Option<i32>) -> Chunk {
+        Chunk {
+            id: 0,
+            document_id: "doc-1".to_string(),
+            chunk_index: 0,
+            content: content.to_string(),
+            token_count: 10,
+            start_offset: None,
+            end_offset: None,
+            page_number: page,
+            timestamp_start: None,
+            timestamp_end: None,
+            metadata: serde_json::json!({}),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_repeated_page_edges_and_captions_are_tagged() {
+        let body = "The committee reviewed the budget proposal in detail. ".repeat(6);
+        let mut chunks = vec![
+            chunk("ANNUAL REPORT 2024", Some(1)),
+            chunk(&body, Some(1)),
+            chunk("Figure 1: Revenue by quarter", Some(1)),
+            chunk("Page 1 of 2", Some(1)),
+            chunk("ANNUAL REPORT 2024", Some(2)),
+            chunk(&body, Some(2)),
+            chunk("Page 2 of 2", Some(2)),
+            chunk("A short closing remark.", None),
+        ];
+        tag_chunk_kinds(&mut chunks);
+
+        let kinds: Vec<ChunkKind> = chunks.iter().map(chunk_kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ChunkKind::Header,
+                ChunkKind::Body,
+                ChunkKind::Caption,
+                ChunkKind::Footer,
+                ChunkKind::Header,
+                ChunkKind::Body,
+                ChunkKind::Footer,
+                ChunkKind::Body,
+            ]
+        );
+        assert_eq!(chunks[0].metadata["kind"], "header");
+    }
+
+    #[test]
+    fn test_unindexed_chunks_are_not_backfilled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = crate::database::Database::new(&temp_dir.path().join("test.db"), std::path::Path::new("."))
+            .expect("Failed to create database");
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO documents (id, title, file_path, file_type, file_size, file_hash)
+                 VALUES ('doc-1', 'doc', 'doc', 'pdf', 0, 'hash')",
+                [],
+            )?;
+            // Stand-in for the sqlite-vec table when the extension isn't available
+            conn.execute("CREATE TABLE IF NOT EXISTS vec_chunks (chunk_id INTEGER PRIMARY KEY)", [])?;
+            Ok(())
+        })
+        .unwrap();
+
+        let mut footer = chunk("Page 1 of 2", Some(1));
+        footer.chunk_index = 1;
+        footer.metadata = serde_json::json!({ "kind": "footer", "unindexed": true });
+        let ids = db.insert_chunks(&[chunk("Quarterly revenue grew.", Some(1)), footer]).unwrap();
+
+        assert_eq!(db.count_chunks_without_embeddings().unwrap(), 1);
+        let missing = db.get_chunks_without_embeddings(10).unwrap();
+        assert_eq!(missing.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![ids[0]]);
+    }
+}
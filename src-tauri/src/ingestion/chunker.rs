@@ -1,7 +1,10 @@
+use super::chunk_kind::{chunk_kind, tag_chunk_kinds, ChunkKind};
+use super::language::tag_chunk_languages;
 use crate::database::Chunk;
 use crate::error::Result;
 use chrono::Utc;
 use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use tiktoken_rs::{cl100k_base, CoreBPE};
 
 // Initialize tokenizer once at startup (it's slow to load)
@@ -15,11 +18,43 @@ static TOKENIZER: Lazy<CoreBPE> = Lazy::new(|| {
 pub struct Chunker {
     chunk_size: usize,
     overlap: usize,
+    dedupe: bool,
+    min_chars: usize,
+    tag_languages: bool,
+    body_only_index: bool,
 }
 
 impl Chunker {
     pub fn new(chunk_size: usize, overlap: usize) -> Self {
-        Self { chunk_size, overlap }
+        Self { chunk_size, overlap, dedupe: false, min_chars: 0, tag_languages: false, body_only_index: false }
+    }
+
+    /// Drop chunks whose text repeats an earlier chunk of the same document
+    /// (running headers/footers, boilerplate), keeping the first occurrence
+    pub fn with_dedupe(mut self, dedupe: bool) -> Self {
+        self.dedupe = dedupe;
+        self
+    }
+
+    /// Merge chunks with fewer than `min_chars` non-whitespace characters
+    /// into the preceding chunk (or the following one, for the first chunk)
+    pub fn with_min_chars(mut self, min_chars: usize) -> Self {
+        self.min_chars = min_chars;
+        self
+    }
+
+    /// Record each chunk's detected language in its metadata, for documents
+    /// that mix languages
+    pub fn with_language_tags(mut self, tag_languages: bool) -> Self {
+        self.tag_languages = tag_languages;
+        self
+    }
+
+    /// Mark headers, footers and captions `unindexed` so they are stored but
+    /// neither embedded nor full-text indexed
+    pub fn with_body_only_index(mut self, body_only_index: bool) -> Self {
+        self.body_only_index = body_only_index;
+        self
     }
 
     pub fn chunk(&self, document_id: &str, content: &ExtractedContent) -> Result<Vec<Chunk>> {
@@ -31,16 +66,17 @@ impl Chunker {
                 if let Some(pages) = pages {
                     // Chunk by page, then by token count
                     for (page_num, page_text) in pages.iter().enumerate() {
+                        // Offsets are relative to the page
                         let page_chunks = self.chunk_text(&bpe, page_text);
-                        for (_i, (text, token_count)) in page_chunks.into_iter().enumerate() {
+                        for span in page_chunks {
                             chunks.push(Chunk {
                                 id: 0, // Will be set by database
                                 document_id: document_id.to_string(),
                                 chunk_index: chunks.len() as i32,
-                                content: text,
-                                token_count,
-                                start_offset: None,
-                                end_offset: None,
+                                content: span.text,
+                                token_count: span.token_count,
+                                start_offset: Some(span.start as i32),
+                                end_offset: Some(span.end as i32),
                                 page_number: Some((page_num + 1) as i32),
                                 timestamp_start: None,
                                 timestamp_end: None,
@@ -52,15 +88,15 @@ impl Chunker {
                 } else {
                     // Chunk entire text
                     let text_chunks = self.chunk_text(&bpe, text);
-                    for (i, (text, token_count)) in text_chunks.into_iter().enumerate() {
+                    for (i, span) in text_chunks.into_iter().enumerate() {
                         chunks.push(Chunk {
                             id: 0,
                             document_id: document_id.to_string(),
                             chunk_index: i as i32,
-                            content: text,
-                            token_count,
-                            start_offset: None,
-                            end_offset: None,
+                            content: span.text,
+                            token_count: span.token_count,
+                            start_offset: Some(span.start as i32),
+                            end_offset: Some(span.end as i32),
                             page_number: None,
                             timestamp_start: None,
                             timestamp_end: None,
@@ -72,13 +108,13 @@ impl Chunker {
             }
             ExtractedContent::Timed { segments } => {
                 // For timed content (video/audio), use segment boundaries
-                for segment in segments {
+                for segment in self.split_oversized_segments(bpe, segments) {
                     let segment_chunks = self.chunk_text(&bpe, &segment.text);
                     let duration = segment.end_time - segment.start_time;
                     let chunk_count = segment_chunks.len().max(1);
                     let time_per_chunk = duration / chunk_count as f64;
 
-                    for (i, (text, token_count)) in segment_chunks.into_iter().enumerate() {
+                    for (i, span) in segment_chunks.into_iter().enumerate() {
                         let start = segment.start_time + (i as f64 * time_per_chunk);
                         let end = start + time_per_chunk;
 
@@ -86,8 +122,8 @@ impl Chunker {
                             id: 0,
                             document_id: document_id.to_string(),
                             chunk_index: chunks.len() as i32,
-                            content: text,
-                            token_count,
+                            content: span.text,
+                            token_count: span.token_count,
                             start_offset: None,
                             end_offset: None,
                             page_number: None,
@@ -103,10 +139,187 @@ impl Chunker {
             }
         }
 
+        // Before dedupe, which would drop the repeats that identify headers
+        tag_chunk_kinds(&mut chunks);
+
+        if self.dedupe {
+            chunks = Self::dedupe_chunks(chunks);
+        }
+
+        if self.min_chars > 0 {
+            chunks = Self::merge_short_chunks(chunks, self.min_chars);
+        }
+
+        if self.tag_languages {
+            tag_chunk_languages(&mut chunks);
+        }
+
+        if self.body_only_index {
+            Self::mark_unindexed(&mut chunks);
+        }
+
         Ok(chunks)
     }
 
-    fn chunk_text(&self, bpe: &CoreBPE, text: &str) -> Vec<(String, i32)> {
+    /// Flag non-body chunks as `unindexed`, unless that would leave the
+    /// document with nothing searchable
+    fn mark_unindexed(chunks: &mut [Chunk]) {
+        if chunks.iter().all(|c| chunk_kind(c) != ChunkKind::Body) {
+            return;
+        }
+        let mut skipped = 0;
+        for chunk in chunks.iter_mut().filter(|c| chunk_kind(c) != ChunkKind::Body) {
+            if let Some(metadata) = chunk.metadata.as_object_mut() {
+                metadata.insert("unindexed".to_string(), true.into());
+                skipped += 1;
+            }
+        }
+        if skipped > 0 {
+            tracing::info!("Keeping {} header, footer and caption chunks out of the index", skipped);
+        }
+    }
+
+    /// Remove exact-duplicate chunks (ignoring whitespace differences), record
+    /// how many copies were dropped on the kept chunk, and renumber indexes
+    fn dedupe_chunks(chunks: Vec<Chunk>) -> Vec<Chunk> {
+        let total = chunks.len();
+        let mut first_index: HashMap<String, usize> = HashMap::new();
+        let mut duplicate_counts: Vec<u32> = Vec::new();
+        let mut kept: Vec<Chunk> = Vec::with_capacity(total);
+
+        for chunk in chunks {
+            let key = chunk.content.split_whitespace().collect::<Vec<_>>().join(" ");
+            match first_index.get(&key) {
+                Some(&index) => duplicate_counts[index] += 1,
+                None => {
+                    first_index.insert(key, kept.len());
+                    duplicate_counts.push(0);
+                    kept.push(chunk);
+                }
+            }
+        }
+
+        for (i, chunk) in kept.iter_mut().enumerate() {
+            chunk.chunk_index = i as i32;
+            if duplicate_counts[i] > 0 {
+                if let Some(metadata) = chunk.metadata.as_object_mut() {
+                    metadata.insert("duplicates_removed".to_string(), duplicate_counts[i].into());
+                }
+            }
+        }
+
+        if kept.len() < total {
+            tracing::info!("Removed {} duplicate chunks ({} kept)", total - kept.len(), kept.len());
+        }
+        kept
+    }
+
+    /// Fold trivial chunks into a neighbour so they aren't embedded on their
+    /// own. A document whose only chunk is short keeps it.
+    fn merge_short_chunks(chunks: Vec<Chunk>, min_chars: usize) -> Vec<Chunk> {
+        let total = chunks.len();
+        let is_short = |c: &Chunk| c.content.chars().filter(|ch| !ch.is_whitespace()).count() < min_chars;
+
+        let mut merged: Vec<Chunk> = Vec::with_capacity(total);
+        // Short chunks seen before any full-length chunk, prepended to the first one
+        let mut leading: Vec<Chunk> = Vec::new();
+
+        for chunk in chunks {
+            if !is_short(&chunk) {
+                let mut chunk = chunk;
+                for short in leading.drain(..).rev() {
+                    Self::absorb(&mut chunk, short, true);
+                }
+                merged.push(chunk);
+            } else if let Some(previous) = merged.last_mut() {
+                Self::absorb(previous, chunk, false);
+            } else {
+                leading.push(chunk);
+            }
+        }
+
+        // Nothing long enough to merge into: keep the short chunks together
+        if merged.is_empty() {
+            let mut rest = leading.into_iter();
+            if let Some(mut first) = rest.next() {
+                for short in rest {
+                    Self::absorb(&mut first, short, false);
+                }
+                merged.push(first);
+            }
+        }
+
+        for (i, chunk) in merged.iter_mut().enumerate() {
+            chunk.chunk_index = i as i32;
+        }
+
+        if merged.len() < total {
+            tracing::info!("Merged {} short chunks into neighbours ({} kept)", total - merged.len(), merged.len());
+        }
+        merged
+    }
+
+    /// Append (or prepend) `other`'s text to `target`, widening its time span
+    fn absorb(target: &mut Chunk, other: Chunk, prepend: bool) {
+        target.content = if prepend {
+            format!("{}\n{}", other.content.trim_end(), target.content)
+        } else {
+            format!("{}\n{}", target.content.trim_end(), other.content)
+        };
+        target.token_count += other.token_count;
+
+        if let Some(start) = other.timestamp_start {
+            target.timestamp_start = Some(target.timestamp_start.map_or(start, |t| t.min(start)));
+        }
+        if let Some(end) = other.timestamp_end {
+            target.timestamp_end = Some(target.timestamp_end.map_or(end, |t| t.max(end)));
+        }
+        // Offsets only stay meaningful when both chunks come from the same page
+        if other.page_number == target.page_number {
+            if let (Some(a), Some(b)) = (target.start_offset, other.start_offset) {
+                target.start_offset = Some(a.min(b));
+            }
+            if let (Some(a), Some(b)) = (target.end_offset, other.end_offset) {
+                target.end_offset = Some(a.max(b));
+            }
+        }
+        if prepend {
+            target.page_number = other.page_number.or(target.page_number);
+        }
+    }
+
+    /// Split segments too long for one chunk into consecutive sub-segments,
+    /// timing each by where its text falls in the segment. A 5-minute video
+    /// description then cites the minute it talks about, not the block start.
+    fn split_oversized_segments(&self, bpe: &CoreBPE, segments: &[TimedSegment]) -> Vec<TimedSegment> {
+        let splitter = Chunker::new(self.chunk_size, 0);
+        let mut split = Vec::with_capacity(segments.len());
+
+        for segment in segments {
+            let spans = splitter.chunk_text(bpe, &segment.text);
+            if spans.len() <= 1 {
+                split.push(segment.clone());
+                continue;
+            }
+
+            let total_chars = segment.text.chars().count().max(1) as f64;
+            let duration = segment.end_time - segment.start_time;
+            let at = |offset: usize| segment.start_time + duration * (offset as f64 / total_chars);
+            let last = spans.len() - 1;
+            for (i, span) in spans.into_iter().enumerate() {
+                split.push(TimedSegment {
+                    start_time: if i == 0 { segment.start_time } else { at(span.start) },
+                    end_time: if i == last { segment.end_time } else { at(span.end) },
+                    text: span.text,
+                    topics: segment.topics.clone(),
+                });
+            }
+        }
+
+        split
+    }
+
+    fn chunk_text(&self, bpe: &CoreBPE, text: &str) -> Vec<TextSpan> {
         // Use character-based chunking for speed, estimate ~4 chars per token
         let chars_per_token = 4;
         let target_chars = self.chunk_size * chars_per_token;
@@ -116,11 +329,18 @@ impl Chunker {
 
         if text_len <= target_chars {
             let token_count = bpe.encode_with_special_tokens(text).len();
-            return vec![(text.to_string(), token_count as i32)];
+            return vec![TextSpan {
+                text: text.to_string(),
+                token_count: token_count as i32,
+                start: 0,
+                end: text.chars().count(),
+            }];
         }
 
         let mut chunks = Vec::new();
         let mut start = 0;
+        // Chunk starts mostly move forward, so char offsets are counted incrementally
+        let mut cursor = CharCursor::default();
 
         while start < text_len {
             let mut end = (start + target_chars).min(text_len);
@@ -154,10 +374,18 @@ impl Chunker {
             let end_safe = Self::floor_char_boundary(text, end);
 
             if end_safe > start_safe {
-                let chunk_text = text[start_safe..end_safe].trim().to_string();
+                let slice = &text[start_safe..end_safe];
+                let chunk_text = slice.trim();
                 if !chunk_text.is_empty() {
-                    let token_count = bpe.encode_with_special_tokens(&chunk_text).len();
-                    chunks.push((chunk_text, token_count as i32));
+                    let token_count = bpe.encode_with_special_tokens(chunk_text).len();
+                    let byte_start = start_safe + (slice.len() - slice.trim_start().len());
+                    let char_start = cursor.advance_to(text, byte_start);
+                    chunks.push(TextSpan {
+                        text: chunk_text.to_string(),
+                        token_count: token_count as i32,
+                        start: char_start,
+                        end: char_start + chunk_text.chars().count(),
+                    });
                 }
             }
 
@@ -187,6 +415,34 @@ impl Chunker {
 
 }
 
+/// A piece of chunked text with its character offsets in the source
+struct TextSpan {
+    text: String,
+    token_count: i32,
+    start: usize,
+    end: usize,
+}
+
+/// Converts byte positions to character offsets, counting only the text
+/// between the previous position and the new one
+#[derive(Default)]
+struct CharCursor {
+    byte: usize,
+    chars: usize,
+}
+
+impl CharCursor {
+    fn advance_to(&mut self, text: &str, byte: usize) -> usize {
+        if byte >= self.byte {
+            self.chars += text[self.byte..byte].chars().count();
+        } else {
+            self.chars -= text[byte..self.byte].chars().count();
+        }
+        self.byte = byte;
+        self.chars
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ExtractedContent {
     Text {
@@ -198,6 +454,20 @@ pub enum ExtractedContent {
     },
 }
 
+impl ExtractedContent {
+    /// All extracted text, with timed segments joined by newlines
+    pub fn plain_text(&self) -> String {
+        match self {
+            Self::Text { text, .. } => text.clone(),
+            Self::Timed { segments } => segments
+                .iter()
+                .map(|s| s.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TimedSegment {
     pub start_time: f64,
@@ -209,6 +479,7 @@ pub struct TimedSegment {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ingestion::chunk_language;
 
     #[test]
     fn test_chunker_basic() {
@@ -222,4 +493,98 @@ mod tests {
         assert!(!chunks.is_empty());
         assert!(chunks[0].token_count > 0);
     }
+
+    #[test]
+    fn test_long_timed_segment_split_with_interpolated_times() {
+        let text = "The speaker walks through the quarterly numbers in detail. ".repeat(40);
+        let content = ExtractedContent::Timed {
+            segments: vec![TimedSegment {
+                start_time: 300.0,
+                end_time: 600.0,
+                text: text.clone(),
+                topics: vec!["finance".to_string()],
+            }],
+        };
+
+        let chunks = Chunker::new(128, 16).chunk("doc-1", &content).unwrap();
+        assert!(chunks.len() > 2);
+        assert_eq!(chunks[0].timestamp_start, Some(300.0));
+        assert_eq!(chunks.last().unwrap().timestamp_end, Some(600.0));
+        for pair in chunks.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            assert!(a.timestamp_end.unwrap() <= b.timestamp_start.unwrap() + 1e-9);
+            assert!(a.timestamp_end.unwrap() - a.timestamp_start.unwrap() < 300.0 / 2.0);
+        }
+        assert_eq!(chunks[1].metadata["topics"][0], "finance");
+    }
+
+    #[test]
+    fn test_dedupe_repeated_page_headers() {
+        let pages = vec![
+            "THE RUNNING TITLE".to_string(),
+            "Chapter one text.".to_string(),
+            "THE  RUNNING TITLE".to_string(),
+            "Chapter two text.".to_string(),
+        ];
+        let content = ExtractedContent::Text { text: String::new(), pages: Some(pages) };
+
+        let chunks = Chunker::new(512, 50).with_dedupe(true).chunk("doc-1", &content).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].metadata["duplicates_removed"], 1);
+        assert_eq!(chunks[2].page_number, Some(4));
+        assert_eq!(chunks.iter().map(|c| c.chunk_index).collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_chunk_offsets_point_into_source() {
+        let text = "Überschrift. ".repeat(300);
+        let content = ExtractedContent::Text { text: text.clone(), pages: None };
+
+        let chunks = Chunker::new(64, 8).chunk("doc-1", &content).unwrap();
+        assert!(chunks.len() > 1);
+        let chars: Vec<char> = text.chars().collect();
+        for chunk in &chunks {
+            let (start, end) = (chunk.start_offset.unwrap() as usize, chunk.end_offset.unwrap() as usize);
+            assert_eq!(chars[start..end].iter().collect::<String>(), chunk.content);
+        }
+    }
+
+    #[test]
+    fn test_short_chunks_merge_into_neighbours() {
+        let pages = vec![
+            "7".to_string(),
+            "The first real page of the document.".to_string(),
+            "8".to_string(),
+            "The second real page of the document.".to_string(),
+        ];
+        let content = ExtractedContent::Text { text: String::new(), pages: Some(pages) };
+
+        let chunks = Chunker::new(512, 50).with_min_chars(20).chunk("doc-1", &content).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].content.starts_with("7\nThe first"));
+        assert_eq!(chunks[0].page_number, Some(1));
+        assert!(chunks[0].content.ends_with("document.\n8"));
+        assert_eq!(chunks[1].chunk_index, 1);
+
+        // A document that is only short chunks still keeps its content
+        let tiny = ExtractedContent::Text { text: "Hi".to_string(), pages: None };
+        let chunks = Chunker::new(512, 50).with_min_chars(20).chunk("doc-2", &tiny).unwrap();
+        assert_eq!(chunks.len(), 1);
+    }
+
+    #[test]
+    fn test_chunks_tagged_with_their_language() {
+        let pages = vec![
+            "This paper studies how river sediment moves during spring floods and why it matters for farming.".to_string(),
+            "Cet article étudie comment les sédiments des rivières se déplacent pendant les crues du printemps.".to_string(),
+            "42".to_string(),
+        ];
+        let content = ExtractedContent::Text { text: String::new(), pages: Some(pages) };
+
+        let chunks = Chunker::new(512, 50).with_language_tags(true).chunk("doc-1", &content).unwrap();
+        assert_eq!(chunk_language(&chunks[0]), Some("eng"));
+        assert_eq!(chunk_language(&chunks[1]), Some("fra"));
+        // Too short to classify
+        assert_eq!(chunk_language(&chunks[2]), None);
+    }
 }
@@ -0,0 +1,209 @@
+//! Rough, offline projections of what ingesting a set of files will consume.
+//!
+//! Nothing is extracted or sent to the API: PDFs are opened only to count
+//! pages and check the first one for a text layer, media durations come from
+//! the container header, and everything else is sized from the file length.
+
+use super::ffmpeg::FFmpeg;
+use super::mime::detect_file_type;
+use crate::database::FileType;
+use crate::error::{RecallError, Result};
+use crate::state::Settings;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Average characters per embedding token for English prose
+const CHARS_PER_TOKEN: u64 = 4;
+/// Tokens of text on a typical document page
+const TOKENS_PER_PDF_PAGE: u64 = 500;
+/// PDF bytes per page, used when the file can't be parsed
+const BYTES_PER_PDF_PAGE: u64 = 100 * 1024;
+/// Tokens produced by OCR of a single image
+const TOKENS_PER_IMAGE: u64 = 150;
+/// Tokens of speech per minute (about 150 words)
+const SPOKEN_TOKENS_PER_MINUTE: u64 = 200;
+/// Tokens of description the vision model writes per video segment
+const TOKENS_PER_VIDEO_SEGMENT: u64 = 300;
+/// Bytes per second assumed when ffmpeg can't read the duration
+const VIDEO_BYTES_PER_SEC: u64 = 250 * 1024;
+const AUDIO_BYTES_PER_SEC: u64 = 16 * 1024;
+
+/// Prices used to turn projected usage into a cost, in any one currency
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriceTable {
+    #[serde(default)]
+    pub embedding_per_million_tokens: f64,
+    #[serde(default)]
+    pub ocr_per_page: f64,
+    #[serde(default)]
+    pub vision_per_frame: f64,
+    #[serde(default)]
+    pub transcription_per_minute: f64,
+}
+
+/// Projected usage for one file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileCostEstimate {
+    pub path: String,
+    pub file_type: FileType,
+    pub embedding_tokens: u64,
+    /// Scanned PDF pages and images sent for OCR
+    pub ocr_pages: u64,
+    /// Video frames sent to the vision model
+    pub vision_frames: u64,
+    /// Audio transcribed, from audio files and video soundtracks
+    pub audio_minutes: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IngestionCostEstimate {
+    pub files: Vec<FileCostEstimate>,
+    pub embedding_tokens: u64,
+    pub ocr_pages: u64,
+    pub vision_frames: u64,
+    pub audio_minutes: f64,
+    /// Projected cost, when a price table was given
+    pub estimated_cost: Option<f64>,
+    /// Files that couldn't be estimated
+    pub errors: Vec<String>,
+}
+
+impl IngestionCostEstimate {
+    pub fn add(&mut self, file: FileCostEstimate) {
+        self.embedding_tokens += file.embedding_tokens;
+        self.ocr_pages += file.ocr_pages;
+        self.vision_frames += file.vision_frames;
+        self.audio_minutes += file.audio_minutes;
+        self.files.push(file);
+    }
+
+    pub fn apply_prices(&mut self, prices: &PriceTable) {
+        self.estimated_cost = Some(
+            self.embedding_tokens as f64 / 1_000_000.0 * prices.embedding_per_million_tokens
+                + self.ocr_pages as f64 * prices.ocr_per_page
+                + self.vision_frames as f64 * prices.vision_per_frame
+                + self.audio_minutes * prices.transcription_per_minute,
+        );
+    }
+}
+
+/// Project the usage of ingesting `path` with `settings`. `ffmpeg` reads
+/// media durations; without it they are guessed from the file size.
+pub async fn estimate_file(path: &Path, settings: &Settings, ffmpeg: Option<&FFmpeg>) -> Result<FileCostEstimate> {
+    let size = std::fs::metadata(path)?.len();
+    let file_type = detect_file_type(path);
+
+    let mut estimate = FileCostEstimate {
+        path: path.to_string_lossy().to_string(),
+        file_type,
+        embedding_tokens: 0,
+        ocr_pages: 0,
+        vision_frames: 0,
+        audio_minutes: 0.0,
+    };
+
+    let text_tokens = match file_type {
+        FileType::Text | FileType::Markdown | FileType::Subtitle => size / CHARS_PER_TOKEN,
+        FileType::Pdf => {
+            let owned = path.to_path_buf();
+            let (pages, has_text) = tokio::task::spawn_blocking(move || pdf_page_info(&owned))
+                .await
+                .map_err(|e| RecallError::Ingestion(format!("PDF inspection task failed: {}", e)))?
+                .unwrap_or_else(|| (size.div_ceil(BYTES_PER_PDF_PAGE).max(1), false));
+            if !has_text {
+                // Pages past max_ocr_pages are never sent
+                estimate.ocr_pages = match settings.max_ocr_pages {
+                    0 => pages,
+                    max => pages.min(max as u64),
+                };
+            }
+            pages * TOKENS_PER_PDF_PAGE
+        }
+        FileType::Image | FileType::Screenshot => {
+            estimate.ocr_pages = 1;
+            TOKENS_PER_IMAGE
+        }
+        FileType::Video => {
+            let duration = media_duration(path, ffmpeg, size, VIDEO_BYTES_PER_SEC).await;
+            let segment_secs = (settings.video_segment_duration as f64).max(1.0);
+            estimate.vision_frames = video_frames(
+                duration,
+                settings.keyframe_interval,
+                segment_secs,
+                settings.max_video_frames_per_segment.max(1),
+            );
+            estimate.audio_minutes = duration / 60.0;
+            let segments = (duration / segment_secs).ceil() as u64;
+            spoken_tokens(duration) + segments * TOKENS_PER_VIDEO_SEGMENT
+        }
+        FileType::Audio => {
+            let duration = media_duration(path, ffmpeg, size, AUDIO_BYTES_PER_SEC).await;
+            estimate.audio_minutes = duration / 60.0;
+            spoken_tokens(duration)
+        }
+        FileType::Unknown => 0,
+    };
+
+    if settings.embeddings_enabled && settings.chunk_size > 0 {
+        // Overlapping chunks embed the overlap twice
+        let overlap = settings.chunk_overlap.min(settings.chunk_size) as u64;
+        estimate.embedding_tokens = text_tokens + text_tokens * overlap / settings.chunk_size as u64;
+    }
+
+    Ok(estimate)
+}
+
+/// Page count, and whether the first page has a text layer
+fn pdf_page_info(path: &Path) -> Option<(u64, bool)> {
+    let document = pdf_extract::Document::load(path).ok()?;
+    let pages = document.get_pages();
+    let has_text = pages
+        .keys()
+        .next()
+        .and_then(|&first| document.extract_text(&[first]).ok())
+        .is_some_and(|text| !text.trim().is_empty());
+    Some((pages.len() as u64, has_text))
+}
+
+/// Seconds of media, from the container header when ffmpeg is available
+async fn media_duration(path: &Path, ffmpeg: Option<&FFmpeg>, size: u64, bytes_per_sec: u64) -> f64 {
+    if let Some(ffmpeg) = ffmpeg {
+        match ffmpeg.get_video_info(path).await {
+            Ok(info) if info.duration > 0.0 => return info.duration,
+            Ok(_) => {}
+            Err(e) => tracing::debug!("Could not read duration of {:?}: {}", path, e),
+        }
+    }
+    size as f64 / bytes_per_sec as f64
+}
+
+fn spoken_tokens(duration_secs: f64) -> u64 {
+    (duration_secs / 60.0 * SPOKEN_TOKENS_PER_MINUTE as f64).ceil() as u64
+}
+
+/// Frames sent for a video: keyframes at `fps`, at most `max_per_segment`
+/// from each `segment_secs` segment
+fn video_frames(duration: f64, fps: f64, segment_secs: f64, max_per_segment: usize) -> u64 {
+    let mut frames = 0;
+    let mut start = 0.0;
+    while start < duration {
+        let length = (duration - start).min(segment_secs);
+        frames += ((length * fps).ceil() as u64).min(max_per_segment as u64);
+        start += segment_secs;
+    }
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_video_frames_capped_per_segment() {
+        // 12.5 minutes in 5 minute segments at one frame per 5 seconds:
+        // 60, 60 and 30 keyframes, capped at 20 per segment
+        assert_eq!(video_frames(750.0, 0.2, 300.0, 20), 60);
+        assert_eq!(video_frames(750.0, 0.2, 300.0, 100), 150);
+        assert_eq!(video_frames(0.0, 0.2, 300.0, 20), 0);
+    }
+}
@@ -0,0 +1,103 @@
+//! Exclude patterns that keep parts of a watched folder out of auto-ingest.
+//!
+//! A pattern is one of:
+//! - an absolute path: everything under it is excluded
+//! - a glob using `*` and `?`: matched against the path relative to the
+//!   watched folder and against each of its components
+//! - a plain name: matched against each path component
+//!
+//! Matching ignores case so the same patterns work on every platform.
+
+use std::path::{Component, Path};
+
+/// Applied to folders added without explicit patterns
+pub const DEFAULT_EXCLUDE_PATTERNS: &[&str] = &[".git", "node_modules", "$RECYCLE.BIN", "AppData"];
+
+pub fn default_exclude_patterns() -> Vec<String> {
+    DEFAULT_EXCLUDE_PATTERNS.iter().map(|p| p.to_string()).collect()
+}
+
+/// Whether `path`, inside the watched `folder`, matches any of `patterns`
+pub fn is_excluded(path: &Path, folder: &Path, patterns: &[String]) -> bool {
+    let relative = path.strip_prefix(folder).unwrap_or(path);
+    let relative_str = relative.to_string_lossy().replace('\\', "/").to_lowercase();
+    let components: Vec<String> = relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name.to_string_lossy().to_lowercase()),
+            _ => None,
+        })
+        .collect();
+
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return false;
+        }
+
+        let pattern_path = Path::new(pattern);
+        if pattern_path.is_absolute() {
+            return path.starts_with(pattern_path);
+        }
+
+        let pattern = pattern.replace('\\', "/").trim_matches('/').to_lowercase();
+        if pattern.contains('/') {
+            // Multi-segment patterns match the relative path or a prefix of it
+            return wildcard_match(&pattern, &relative_str)
+                || (!pattern.contains('*')
+                    && relative_str.starts_with(&pattern)
+                    && relative_str[pattern.len()..].starts_with('/'));
+        }
+        components.iter().any(|name| wildcard_match(&pattern, name))
+    })
+}
+
+/// Glob match where `*` is any run of characters and `?` any one character
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` swallow one more character
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_exclude_patterns() {
+        let folder = PathBuf::from("/home/me/Documents");
+        let defaults = default_exclude_patterns();
+
+        assert!(is_excluded(&folder.join("app/node_modules/pkg/README.md"), &folder, &defaults));
+        assert!(is_excluded(&folder.join(".git/HEAD.txt"), &folder, &defaults));
+        assert!(!is_excluded(&folder.join("notes/todo.md"), &folder, &defaults));
+
+        let patterns = vec!["*.tmp.md".to_string(), "build/out".to_string(), "/home/me/Documents/private".to_string()];
+        assert!(is_excluded(&folder.join("drafts/Scratch.TMP.md"), &folder, &patterns));
+        assert!(is_excluded(&folder.join("build/out/report.pdf"), &folder, &patterns));
+        assert!(!is_excluded(&folder.join("build/output.pdf"), &folder, &patterns));
+        assert!(is_excluded(&folder.join("private/diary.md"), &folder, &patterns));
+        assert!(!is_excluded(&folder.join("public/diary.md"), &folder, &patterns));
+    }
+}
@@ -1,10 +1,15 @@
 use super::chunker::{ExtractedContent, TimedSegment};
 use super::ffmpeg::FFmpeg;
+use super::subtitles::{merge_cues, parse_subtitles};
+use super::mime::detect_mime;
+use super::vision_ocr::OcrRenderScales;
 use crate::error::{RecallError, Result};
-use crate::llm::{LlmClient, LlmProvider, VideoAnalysisRequest, VideoFrame};
+use crate::database::FileType;
+use crate::llm::{LlmClient, LlmProvider, VideoAnalysisRequest, VideoFrame, MAX_FILES_API_SIZE};
 use crate::state::Settings;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::Serialize;
 use std::path::Path;
 
 /// Progress callback for long-running extraction operations
@@ -15,8 +20,8 @@ static TIMESTAMP_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"\[(\d+):(\d+)\]").unwrap()
 });
 
-/// Maximum file size allowed for ingestion (500 MB)
-const MAX_FILE_SIZE: u64 = 500 * 1024 * 1024;
+/// Largest file read into memory for extraction (500 MB)
+pub const MAX_IN_MEMORY_FILE_SIZE: u64 = 500 * 1024 * 1024;
 
 /// Fix common ligature issues in PDF-extracted text
 /// When pdf-extract can't decode ligatures like fi, fl, ff, ffi, ffl,
@@ -155,32 +160,158 @@ fn fix_ligatures(text: &str) -> String {
     result
 }
 
+/// Largest file accepted for ingestion, by type. PDFs over the in-memory
+/// limit skip local extraction and are streamed to Gemini OCR, which takes
+/// up to 2 GB. Video is read by ffmpeg in segments, so any size works.
+pub fn max_file_size(file_type: FileType) -> Option<u64> {
+    match file_type {
+        FileType::Pdf => Some(MAX_FILES_API_SIZE),
+        FileType::Video => None,
+        _ => Some(MAX_IN_MEMORY_FILE_SIZE),
+    }
+}
+
+/// Reject a file larger than `max_file_size` allows for its type, before
+/// any work is done on it
+pub fn check_file_size(path: &Path, file_type: FileType) -> Result<()> {
+    match max_file_size(file_type) {
+        Some(limit) => ensure_size_within(path, limit),
+        None => Ok(()),
+    }
+}
+
 /// Validate file size before reading into memory
 fn validate_file_size(path: &Path) -> Result<()> {
+    ensure_size_within(path, MAX_IN_MEMORY_FILE_SIZE)
+}
+
+fn ensure_size_within(path: &Path, limit: u64) -> Result<()> {
     let metadata = std::fs::metadata(path)?;
-    if metadata.len() > MAX_FILE_SIZE {
+    if metadata.len() > limit {
         return Err(RecallError::Ingestion(format!(
             "File too large ({:.1} MB). Maximum size is {:.0} MB.",
             metadata.len() as f64 / (1024.0 * 1024.0),
-            MAX_FILE_SIZE as f64 / (1024.0 * 1024.0)
+            limit as f64 / (1024.0 * 1024.0)
         )));
     }
     Ok(())
 }
 
-/// Extract PDF with optional progress callback for UI updates
+/// Pages of a scanned PDF left out of vision OCR, by `max_ocr_pages` or
+/// because a page's text didn't fit in the OCR reply
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct OcrTruncation {
+    pub pages_processed: u32,
+    pub total_pages: u32,
+}
+
+/// Start of the error for a PDF that can't be opened without a password;
+/// `ingest_file_with_password` retries it with one
+pub const PDF_PASSWORD_REQUIRED: &str = "PDF is password-protected";
+
+/// Text and OCR outcome of a PDF
+#[derive(Debug)]
+pub struct PdfExtraction {
+    pub content: ExtractedContent,
+    /// Pages left out of vision OCR
+    pub ocr_truncation: Option<OcrTruncation>,
+    /// Whether opening it took a user password
+    pub password_protected: bool,
+}
+
+/// An encrypted PDF after decryption
+struct UnlockedPdf {
+    bytes: Vec<u8>,
+    password_protected: bool,
+}
+
+/// Page count of a PDF, when it parses
+fn pdf_page_count(bytes: &[u8]) -> Option<u32> {
+    pdf_extract::Document::load_mem(bytes)
+        .ok()
+        .map(|document| document.get_pages().len() as u32)
+}
+
+/// Decrypt an encrypted PDF, returning `None` for one that isn't. PDFs that
+/// only restrict permissions open with the empty password; the others need
+/// `password`.
+fn unlock_pdf(bytes: &[u8], password: Option<&str>) -> Result<Option<UnlockedPdf>> {
+    use pdf_extract::encryption::DecryptionError;
+
+    // Unparseable files are left for text extraction to report
+    let Ok(document) = pdf_extract::Document::load_mem(bytes) else {
+        return Ok(None);
+    };
+    if !document.is_encrypted() {
+        return Ok(None);
+    }
+
+    let try_password = |password: &str| -> Result<Option<pdf_extract::Document>> {
+        let mut document = pdf_extract::Document::load_mem(bytes)
+            .map_err(|e| RecallError::PdfExtract(e.to_string()))?;
+        match document.decrypt(password) {
+            Ok(()) => Ok(Some(document)),
+            Err(pdf_extract::Error::Decryption(DecryptionError::IncorrectPassword)) => Ok(None),
+            Err(e) => Err(RecallError::Ingestion(format!("PDF uses encryption that can't be read: {}", e))),
+        }
+    };
+
+    let (mut document, password_protected) = match try_password("")? {
+        Some(document) => (document, false),
+        None => {
+            let password = password.ok_or_else(|| {
+                RecallError::Ingestion(format!("{}; add it again with its password", PDF_PASSWORD_REQUIRED))
+            })?;
+            let document = try_password(password)?.ok_or_else(|| {
+                RecallError::Ingestion(format!("{}; the password given is incorrect", PDF_PASSWORD_REQUIRED))
+            })?;
+            (document, true)
+        }
+    };
+
+    let mut decrypted = Vec::with_capacity(bytes.len());
+    document
+        .save_to(&mut decrypted)
+        .map_err(|e| RecallError::PdfExtract(format!("Failed to write decrypted PDF: {}", e)))?;
+    Ok(Some(UnlockedPdf { bytes: decrypted, password_protected }))
+}
+
+/// Extract PDF with optional progress callback for UI updates. Encrypted
+/// PDFs are opened with `password` when the empty one doesn't work. Vision
+/// OCR reads at most `max_ocr_pages` pages (0 = all); a truncated read is
+/// reported so the document can be marked partial.
 pub async fn extract_pdf_with_progress(
     path: &Path,
     llm: Option<&LlmClient>,
+    render_scales: OcrRenderScales,
+    max_ocr_pages: u32,
+    password: Option<&str>,
     on_progress: Option<&ProgressCallback>,
-) -> Result<ExtractedContent> {
-    validate_file_size(path)?;
-    let bytes = std::fs::read(path)?;
+) -> Result<PdfExtraction> {
+    if std::fs::metadata(path)?.len() > MAX_IN_MEMORY_FILE_SIZE {
+        return extract_large_pdf(path, llm, max_ocr_pages, on_progress).await;
+    }
+    let mut bytes = std::fs::read(path)?;
 
     if let Some(cb) = on_progress {
         cb("Reading PDF file...");
     }
 
+    let unlocked = unlock_pdf(&bytes, password)?;
+    let password_protected = unlocked.as_ref().is_some_and(|u| u.password_protected);
+    if let Some(unlocked) = &unlocked {
+        tracing::info!("Decrypted encrypted PDF (user password: {}): {:?}", unlocked.password_protected, path);
+    }
+    let extraction = |content: ExtractedContent, ocr_truncation: Option<OcrTruncation>| PdfExtraction {
+        content,
+        ocr_truncation,
+        password_protected,
+    };
+
+    if let Some(unlocked) = unlocked {
+        bytes = unlocked.bytes;
+    }
+
     // First try direct text extraction (fast, works for text-based PDFs)
     match pdf_extract::extract_text_from_mem(&bytes) {
         Ok(text) => {
@@ -189,7 +320,7 @@ pub async fn extract_pdf_with_progress(
                 // Fix common ligature issues from pdf-extract
                 let fixed_text = fix_ligatures(&text);
                 let pages = extract_pdf_pages(&bytes);
-                return Ok(ExtractedContent::Text { text: fixed_text, pages });
+                return Ok(extraction(ExtractedContent::Text { text: fixed_text, pages }, None));
             }
             tracing::warn!("PDF has no extractable text, trying OCR: {:?}", path);
             if let Some(cb) = on_progress {
@@ -204,29 +335,62 @@ pub async fn extract_pdf_with_progress(
         }
     }
 
+    // The OCR engines render from a file, so give them the decrypted copy
+    let decrypted_file = if password_protected {
+        let file = tempfile::Builder::new().suffix(".pdf").tempfile()?;
+        std::fs::write(file.path(), &bytes)?;
+        Some(file)
+    } else {
+        None
+    };
+    let ocr_path = decrypted_file.as_ref().map_or(path, |file| file.path());
+
     // Use Gemini Vision OCR (high quality, requires API key)
-    #[cfg(windows)]
     if let Some(llm_client) = llm {
         tracing::info!("Starting Gemini Vision OCR for PDF: {:?}", path);
         if let Some(cb) = on_progress {
             cb("Running Gemini Vision OCR (this may take a while)...");
         }
-        match super::windows_ocr::ocr_pdf_gemini_with_progress(path, llm_client, on_progress).await {
+
+        // Cap the pages sent so a huge scan can't run up the API bill
+        let truncation = match pdf_page_count(&bytes) {
+            Some(total_pages) if max_ocr_pages > 0 && total_pages > max_ocr_pages => {
+                tracing::warn!(
+                    "PDF has {} pages, only the first {} will be OCR'd (max_ocr_pages): {:?}",
+                    total_pages,
+                    max_ocr_pages,
+                    path
+                );
+                if let Some(cb) = on_progress {
+                    cb(&format!("Large scan: OCR limited to the first {} of {} pages", max_ocr_pages, total_pages));
+                }
+                Some(OcrTruncation { pages_processed: max_ocr_pages, total_pages })
+            }
+            _ => None,
+        };
+        let max_pages = (max_ocr_pages > 0).then_some(max_ocr_pages);
+
+        match super::vision_ocr::ocr_pdf_gemini_with_progress(ocr_path, llm_client, render_scales.vision, max_pages, on_progress).await {
             Ok(ocr_text) => {
                 if !ocr_text.trim().is_empty() {
                     tracing::info!("Gemini Vision OCR successful: {} characters extracted", ocr_text.len());
-                    return Ok(ExtractedContent::Text {
-                        text: ocr_text,
-                        pages: None,
-                    });
+                    return Ok(extraction(
+                        ExtractedContent::Text {
+                            text: ocr_text,
+                            pages: None,
+                        },
+                        truncation,
+                    ));
                 }
-                tracing::warn!("Gemini Vision OCR returned empty text, falling back to Windows OCR");
+                tracing::warn!("Gemini Vision OCR returned empty text");
+                #[cfg(windows)]
                 if let Some(cb) = on_progress {
                     cb("Gemini OCR returned empty, trying Windows OCR...");
                 }
             }
             Err(e) => {
-                tracing::warn!("Gemini Vision OCR failed: {}, falling back to Windows OCR", e);
+                tracing::warn!("Gemini Vision OCR failed: {}", e);
+                #[cfg(windows)]
                 if let Some(cb) = on_progress {
                     cb("Gemini OCR failed, trying Windows OCR...");
                 }
@@ -241,14 +405,17 @@ pub async fn extract_pdf_with_progress(
         if let Some(cb) = on_progress {
             cb("Running Windows OCR...");
         }
-        match super::windows_ocr::ocr_pdf_windows_with_progress(path, on_progress).await {
+        match super::windows_ocr::ocr_pdf_windows_with_progress(ocr_path, render_scales.windows, on_progress).await {
             Ok(ocr_text) => {
                 if !ocr_text.trim().is_empty() {
                     tracing::info!("Windows OCR successful: {} characters extracted", ocr_text.len());
-                    return Ok(ExtractedContent::Text {
-                        text: ocr_text,
-                        pages: None,
-                    });
+                    return Ok(extraction(
+                        ExtractedContent::Text {
+                            text: ocr_text,
+                            pages: None,
+                        },
+                        None,
+                    ));
                 }
                 tracing::warn!("Windows OCR returned empty text");
             }
@@ -266,9 +433,59 @@ pub async fn extract_pdf_with_progress(
     )))
 }
 
+/// PDFs too large to load are streamed to Gemini's Files API for OCR
+/// instead. Local text extraction and decryption need the file in memory,
+/// so they don't apply. OCR stops at `max_ocr_pages` pages (0 = all) or at
+/// a page whose text doesn't fit in a reply, and reports the truncation.
+async fn extract_large_pdf(
+    path: &Path,
+    llm: Option<&LlmClient>,
+    max_ocr_pages: u32,
+    on_progress: Option<&ProgressCallback>,
+) -> Result<PdfExtraction> {
+    check_file_size(path, FileType::Pdf)?;
+    let size_mb = std::fs::metadata(path)?.len() as f64 / (1024.0 * 1024.0);
+    let Some(llm_client) = llm else {
+        return Err(RecallError::Ingestion(format!(
+            "PDF is {:.0} MB. PDFs over {:.0} MB are read with Gemini OCR, which needs an API key.",
+            size_mb,
+            MAX_IN_MEMORY_FILE_SIZE as f64 / (1024.0 * 1024.0)
+        )));
+    };
+
+    tracing::info!("PDF is {:.1} MB, streaming it to Gemini OCR: {:?}", size_mb, path);
+    if let Some(cb) = on_progress {
+        cb(&format!("Large PDF ({:.0} MB): uploading for OCR...", size_mb));
+    }
+
+    let max_pages = (max_ocr_pages > 0).then_some(max_ocr_pages);
+    let ocr = llm_client.ocr_pdf_file(path, max_pages).await?;
+    if ocr.text.trim().is_empty() {
+        return Err(RecallError::Ingestion(format!(
+            "Failed to extract text from PDF: {:?}. Gemini OCR returned no text.",
+            path
+        )));
+    }
+
+    let ocr_truncation = (ocr.pages_read < ocr.total_pages).then(|| {
+        if let Some(cb) = on_progress {
+            cb(&format!("Large scan: OCR covered the first {} of {} pages", ocr.pages_read, ocr.total_pages));
+        }
+        OcrTruncation { pages_processed: ocr.pages_read, total_pages: ocr.total_pages }
+    });
+    Ok(PdfExtraction {
+        content: ExtractedContent::Text { text: ocr.text, pages: None },
+        ocr_truncation,
+        password_protected: false,
+    })
+}
+
 /// Backward compatible wrapper without progress
 pub async fn extract_pdf(path: &Path, llm: Option<&LlmClient>) -> Result<ExtractedContent> {
-    extract_pdf_with_progress(path, llm, None).await
+    let max_ocr_pages = Settings::default().max_ocr_pages;
+    extract_pdf_with_progress(path, llm, OcrRenderScales::default(), max_ocr_pages, None, None)
+        .await
+        .map(|extraction| extraction.content)
 }
 
 fn extract_pdf_pages(_bytes: &[u8]) -> Option<Vec<String>> {
@@ -284,12 +501,28 @@ pub async fn extract_text(path: &Path) -> Result<ExtractedContent> {
     Ok(ExtractedContent::Text { text, pages: None })
 }
 
+/// Parse an SRT/VTT file into timed segments, no transcription needed
+pub async fn extract_subtitles(path: &Path) -> Result<ExtractedContent> {
+    validate_file_size(path)?;
+    let content = std::fs::read_to_string(path)?;
+
+    let cues = parse_subtitles(&content);
+    if cues.is_empty() {
+        return Err(RecallError::Ingestion("No subtitle cues found in file".to_string()));
+    }
+
+    let segments = merge_cues(&cues);
+    tracing::info!("Parsed {} subtitle cues into {} segments", cues.len(), segments.len());
+
+    Ok(ExtractedContent::Timed { segments })
+}
+
 pub async fn extract_video(
     path: &Path,
     llm: &LlmClient,
     settings: &Settings,
 ) -> Result<ExtractedContent> {
-    let ffmpeg = FFmpeg::new()?;
+    let ffmpeg = FFmpeg::with_override(settings.ffmpeg_path.as_deref()).await?;
 
     // Get video duration
     let duration = ffmpeg.get_duration(path).await?;
@@ -321,6 +554,7 @@ pub async fn extract_video(
             let request = VideoAnalysisRequest {
                 video_path: path.to_string_lossy().to_string(),
                 frames: segment_frames,
+                max_frames: settings.max_video_frames_per_segment.max(1),
             };
 
             let analysis = llm.analyze_video(request).await?;
@@ -348,15 +582,15 @@ pub async fn extract_video(
     });
 
     if let Ok(transcript) = transcribe_audio_file(&audio_path, llm).await {
-        // Merge transcript with video analysis
-        // For simplicity, add transcript as a single timed segment
-        if !transcript.is_empty() {
-            all_segments.push(TimedSegment {
-                start_time: 0.0,
-                end_time: duration,
-                text: transcript,
-                topics: vec!["transcript".to_string()],
-            });
+        // Keep the transcript's own timestamps where it has them; the
+        // chunker splits long stretches into shorter timed pieces
+        let mut segments = parse_transcript_timestamps(&transcript);
+        if let Some(last) = segments.last_mut() {
+            last.end_time = duration.max(last.start_time);
+        }
+        for mut segment in segments {
+            segment.topics = vec!["transcript".to_string()];
+            all_segments.push(segment);
         }
     }
 
@@ -367,8 +601,12 @@ pub async fn extract_video(
     Ok(ExtractedContent::Timed { segments: all_segments })
 }
 
-pub async fn extract_audio(path: &Path, llm: &LlmClient) -> Result<ExtractedContent> {
-    let ffmpeg = FFmpeg::new()?;
+pub async fn extract_audio(
+    path: &Path,
+    llm: &LlmClient,
+    settings: &Settings,
+) -> Result<ExtractedContent> {
+    let ffmpeg = FFmpeg::with_override(settings.ffmpeg_path.as_deref()).await?;
 
     // Convert to mono MP3 for optimal transcription
     let mono_path = ffmpeg.convert_to_mono_mp3(path).await?;
@@ -452,11 +690,8 @@ pub async fn extract_image(path: &Path, llm: &LlmClient) -> Result<ExtractedCont
     // Read image data
     let image_data = std::fs::read(path)?;
 
-    // Determine MIME type from extension
-    let mime_type = mime_guess::from_path(path)
-        .first()
-        .map(|m| m.to_string())
-        .unwrap_or_else(|| "image/jpeg".to_string());
+    // Content first, so a mislabeled file is sent with its real type
+    let mime_type = detect_mime(path).unwrap_or_else(|| "image/jpeg".to_string());
 
     tracing::info!(
         "extract_image: path={:?}, size={} bytes, mime_type={}",
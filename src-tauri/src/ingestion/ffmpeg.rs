@@ -2,6 +2,7 @@ use crate::error::{RecallError, Result};
 use crate::llm::VideoFrame;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::Serialize;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use tempfile::TempDir;
@@ -31,12 +32,60 @@ static FPS_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(\d+(?:\.\d+)?)\s*fps").unwrap()
 });
 
+/// Shown when ffmpeg can't be run, since video/audio ingestion depends on it
+pub const FFMPEG_INSTALL_HELP: &str = "ffmpeg was not found or could not be run. Video and audio ingestion require ffmpeg. \
+Install it (Windows: `winget install ffmpeg`, macOS: `brew install ffmpeg`, Linux: your package manager) \
+and make sure it is on your PATH, or set the ffmpeg path in Settings.";
+
+/// Result of checking whether ffmpeg is usable
+#[derive(Debug, Clone, Serialize)]
+pub struct FFmpegStatus {
+    /// Whether video/audio ingestion is available
+    pub available: bool,
+    pub ffmpeg_path: String,
+    pub ffmpeg_version: Option<String>,
+    pub ffprobe_version: Option<String>,
+    pub error: Option<String>,
+}
+
 pub struct FFmpeg {
     binary_path: PathBuf,
 }
 
 impl FFmpeg {
-    pub fn new() -> Result<Self> {
+    pub async fn new() -> Result<Self> {
+        Self::with_override(None).await
+    }
+
+    /// Resolve ffmpeg, preferring a user-configured path, and verify it runs
+    pub async fn with_override(override_path: Option<&str>) -> Result<Self> {
+        let binary_path = Self::resolve_binary(override_path)?;
+
+        let probe_path = binary_path.clone();
+        let version = tokio::task::spawn_blocking(move || binary_version(&probe_path))
+            .await
+            .map_err(|e| RecallError::Other(format!("ffmpeg probe task failed: {}", e)))?;
+        if version.is_none() {
+            tracing::warn!("ffmpeg is not runnable at {:?}", binary_path);
+            return Err(RecallError::FFmpeg(FFMPEG_INSTALL_HELP.to_string()));
+        }
+
+        Ok(Self { binary_path })
+    }
+
+    fn resolve_binary(override_path: Option<&str>) -> Result<PathBuf> {
+        if let Some(custom) = override_path.map(str::trim).filter(|p| !p.is_empty()) {
+            let custom_path = PathBuf::from(custom);
+            if !custom_path.is_file() {
+                return Err(RecallError::FFmpeg(format!(
+                    "Configured ffmpeg path does not exist: {}",
+                    custom
+                )));
+            }
+            tracing::debug!("Using configured ffmpeg: {:?}", custom_path);
+            return Ok(custom_path);
+        }
+
         // Look for ffmpeg in resources or PATH
         let binary_path = if cfg!(debug_assertions) {
             // Development: look in src-tauri/resources folder or PATH
@@ -57,11 +106,44 @@ impl FFmpeg {
                 }
             }
         } else {
-            // Production: use bundled binary
-            PathBuf::from("resources/ffmpeg.exe")
+            // Production: use bundled binary, falling back to PATH
+            let bundled = PathBuf::from("resources/ffmpeg.exe");
+            if bundled.exists() {
+                bundled
+            } else {
+                PathBuf::from("ffmpeg")
+            }
         };
 
-        Ok(Self { binary_path })
+        Ok(binary_path)
+    }
+
+    /// Check that ffmpeg (and ffprobe, if present) can be run and report their versions
+    pub fn check(override_path: Option<&str>) -> FFmpegStatus {
+        let binary_path = match Self::resolve_binary(override_path) {
+            Ok(path) => path,
+            Err(e) => {
+                return FFmpegStatus {
+                    available: false,
+                    ffmpeg_path: override_path.unwrap_or_default().to_string(),
+                    ffmpeg_version: None,
+                    ffprobe_version: None,
+                    error: Some(e.to_string()),
+                };
+            }
+        };
+
+        let ffmpeg_version = binary_version(&binary_path);
+        let ffprobe_version = binary_version(&sibling_binary(&binary_path, "ffprobe"));
+        let available = ffmpeg_version.is_some();
+
+        FFmpegStatus {
+            available,
+            ffmpeg_path: binary_path.to_string_lossy().to_string(),
+            ffmpeg_version,
+            ffprobe_version,
+            error: if available { None } else { Some(FFMPEG_INSTALL_HELP.to_string()) },
+        }
     }
 
     pub async fn get_duration(&self, video_path: &Path) -> Result<f64> {
@@ -254,6 +336,31 @@ impl FFmpeg {
     }
 }
 
+/// Run `<binary> -version` and return the first line of its output
+fn binary_version(binary: &Path) -> Option<String> {
+    let output = hidden_command(binary).arg("-version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+}
+
+/// Path of a companion tool (e.g. ffprobe) next to the ffmpeg binary
+fn sibling_binary(ffmpeg: &Path, name: &str) -> PathBuf {
+    let file_name = match ffmpeg.extension() {
+        Some(ext) => format!("{}.{}", name, ext.to_string_lossy()),
+        None => name.to_string(),
+    };
+    match ffmpeg.parent().filter(|p| !p.as_os_str().is_empty()) {
+        Some(dir) => dir.join(file_name),
+        None => PathBuf::from(file_name),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct VideoInfo {
     pub duration: f64,
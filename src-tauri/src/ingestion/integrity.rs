@@ -0,0 +1,282 @@
+//! Consistency checks for document records that drifted across app
+//! versions, and repairs for the ones that can be re-derived from the file.
+
+use super::mime::{check_file_type_override, detect_file_type, detect_mime};
+use super::compute_file_hash;
+use crate::database::{Database, Document, DocumentRecordFacts, DocumentStatus, FileType};
+use crate::error::Result;
+use serde::Serialize;
+use std::path::Path;
+
+/// Something wrong with a document record
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DocumentIssue {
+    /// The source file is no longer on disk
+    SourceMissing,
+    /// The file changed since it was ingested; re-ingest to pick it up
+    SourceChanged,
+    MissingFileHash,
+    MissingMimeType,
+    /// The stored type can't read the file's content
+    WrongFileType { stored: FileType, detected: FileType },
+    /// Completed without an `ingested_at` timestamp
+    MissingIngestedAt,
+    /// Completed but has no chunks to search
+    NoChunks,
+    /// `chunk_count` disagrees with the chunks actually stored
+    ChunkCountDrift { stored: i64, actual: i64 },
+    /// Left pending or processing by an ingestion that is no longer running
+    Interrupted { status: DocumentStatus },
+}
+
+impl DocumentIssue {
+    /// Whether `repair_documents` can fix this without re-ingesting
+    fn repairable(&self) -> bool {
+        !matches!(self, Self::SourceMissing | Self::SourceChanged)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentValidation {
+    pub document_id: String,
+    pub title: String,
+    pub file_path: String,
+    pub issues: Vec<DocumentIssue>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DocumentRepairSummary {
+    pub checked: usize,
+    /// Issues fixed in place
+    pub repaired: usize,
+    /// Documents with issues left, which need re-ingesting or their file back
+    pub remaining: Vec<DocumentValidation>,
+}
+
+/// Check every document not currently being ingested, returning those with
+/// issues. `is_ingesting` tells which documents to leave alone.
+pub fn validate_documents(
+    database: &Database,
+    is_ingesting: impl Fn(&str) -> bool,
+) -> Result<Vec<DocumentValidation>> {
+    let facts = database.get_document_record_facts()?;
+    let mut results = Vec::new();
+
+    for doc in database.get_all_documents()? {
+        if is_ingesting(&doc.id) {
+            continue;
+        }
+        let Some(facts) = facts.get(&doc.id) else {
+            continue;
+        };
+        let issues = check_document(&doc, facts);
+        if !issues.is_empty() {
+            results.push(DocumentValidation {
+                document_id: doc.id,
+                title: doc.title,
+                file_path: doc.file_path,
+                issues,
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+/// Fix what can be re-derived from the file or the chunks: hashes, mime and
+/// file types, timestamps, chunk counts and stale statuses. Missing or
+/// changed source files are flagged and left for the user.
+pub fn repair_documents(
+    database: &Database,
+    is_ingesting: impl Fn(&str) -> bool,
+) -> Result<DocumentRepairSummary> {
+    let mut summary = DocumentRepairSummary {
+        checked: database.get_all_documents()?.len(),
+        ..Default::default()
+    };
+
+    for validation in validate_documents(database, &is_ingesting)? {
+        let mut remaining = Vec::new();
+        for issue in validation.issues.iter().cloned() {
+            if !issue.repairable() {
+                if issue == DocumentIssue::SourceMissing {
+                    database.set_source_missing(&validation.document_id, true)?;
+                }
+                remaining.push(issue);
+                continue;
+            }
+
+            // Re-read so each repair sees the fields the previous one fixed
+            let Some(doc) = database.get_document(&validation.document_id)? else {
+                break;
+            };
+            match repair(database, &doc, &issue) {
+                Ok(()) => summary.repaired += 1,
+                Err(e) => {
+                    tracing::warn!("Could not repair {:?} on {}: {}", issue, doc.id, e);
+                    remaining.push(issue);
+                }
+            }
+        }
+
+        if !remaining.is_empty() {
+            summary.remaining.push(DocumentValidation {
+                issues: remaining,
+                ..validation
+            });
+        }
+    }
+
+    tracing::info!(
+        "Checked {} documents: {} issues repaired, {} documents need attention",
+        summary.checked,
+        summary.repaired,
+        summary.remaining.len()
+    );
+    Ok(summary)
+}
+
+fn check_document(doc: &Document, facts: &DocumentRecordFacts) -> Vec<DocumentIssue> {
+    let mut issues = Vec::new();
+    let path = Path::new(&doc.file_path);
+
+    if !path.exists() {
+        issues.push(DocumentIssue::SourceMissing);
+    } else {
+        if doc.file_hash.is_empty() {
+            issues.push(DocumentIssue::MissingFileHash);
+        } else if compute_file_hash(path).is_ok_and(|hash| hash != doc.file_hash) {
+            issues.push(DocumentIssue::SourceChanged);
+        }
+
+        if doc.mime_type.is_none() && detect_mime(path).is_some() {
+            issues.push(DocumentIssue::MissingMimeType);
+        }
+
+        // A type the content supports may be a deliberate override
+        let detected = detect_file_type(path);
+        if detected != FileType::Unknown
+            && detected != doc.file_type
+            && check_file_type_override(path, doc.file_type).is_err()
+        {
+            issues.push(DocumentIssue::WrongFileType { stored: doc.file_type, detected });
+        }
+    }
+
+    match doc.status {
+        DocumentStatus::Completed => {
+            if facts.ingested_at_missing {
+                issues.push(DocumentIssue::MissingIngestedAt);
+            }
+            if facts.stored_chunks == 0 {
+                issues.push(DocumentIssue::NoChunks);
+            }
+        }
+        DocumentStatus::Pending | DocumentStatus::Processing => {
+            issues.push(DocumentIssue::Interrupted { status: doc.status });
+        }
+        _ => {}
+    }
+
+    if doc.chunk_count != facts.stored_chunks {
+        issues.push(DocumentIssue::ChunkCountDrift {
+            stored: doc.chunk_count,
+            actual: facts.stored_chunks,
+        });
+    }
+
+    issues
+}
+
+fn repair(database: &Database, doc: &Document, issue: &DocumentIssue) -> Result<()> {
+    let path = Path::new(&doc.file_path);
+    match issue {
+        DocumentIssue::MissingFileHash => {
+            let hash = compute_file_hash(path)?;
+            database.update_document_file_fields(&doc.id, doc.file_type, doc.mime_type.as_deref(), &hash)
+        }
+        DocumentIssue::MissingMimeType => {
+            let mime = detect_mime(path);
+            database.update_document_file_fields(&doc.id, doc.file_type, mime.as_deref(), &doc.file_hash)
+        }
+        DocumentIssue::WrongFileType { detected, .. } => {
+            database.update_document_file_fields(&doc.id, *detected, doc.mime_type.as_deref(), &doc.file_hash)
+        }
+        DocumentIssue::MissingIngestedAt => database.backfill_ingested_at(&doc.id),
+        DocumentIssue::NoChunks => database.update_document_status(
+            &doc.id,
+            DocumentStatus::Failed,
+            Some("Completed without any content; re-ingest to rebuild"),
+        ),
+        DocumentIssue::ChunkCountDrift { .. } => database.recount_document_chunks(&doc.id),
+        DocumentIssue::Interrupted { .. } => database.update_document_status(
+            &doc.id,
+            DocumentStatus::Failed,
+            Some("Ingestion was interrupted; re-ingest to finish"),
+        ),
+        DocumentIssue::SourceMissing | DocumentIssue::SourceChanged => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_repair_rederives_fields_and_keeps_missing_sources() {
+        let temp_dir = tempdir().unwrap();
+        let database = Database::new(&temp_dir.path().join("test.db"), Path::new(".")).unwrap();
+
+        let notes = temp_dir.path().join("notes.md");
+        std::fs::write(&notes, "# Orbital mechanics").unwrap();
+        let document = |id: &str, path: &Path| Document {
+            id: id.to_string(),
+            title: id.to_string(),
+            file_path: path.to_string_lossy().to_string(),
+            file_type: FileType::Markdown,
+            file_size: 19,
+            file_hash: String::new(),
+            mime_type: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            ingested_at: None,
+            status: DocumentStatus::Processing,
+            error_message: None,
+            metadata: serde_json::json!({}),
+            ingestion_source: Default::default(),
+            chunk_count: 0,
+            total_tokens: 0,
+            last_accessed: None,
+            access_count: 0,
+        };
+        database.insert_document(&document("notes", &notes)).unwrap();
+        database.insert_document(&document("gone", &temp_dir.path().join("gone.md"))).unwrap();
+
+        let issues = validate_documents(&database, |_| false).unwrap();
+        let notes_issues = &issues.iter().find(|v| v.document_id == "notes").unwrap().issues;
+        assert!(notes_issues.contains(&DocumentIssue::MissingFileHash));
+        assert!(notes_issues.contains(&DocumentIssue::MissingMimeType));
+        assert!(notes_issues.contains(&DocumentIssue::Interrupted { status: DocumentStatus::Processing }));
+
+        // Documents being ingested are left alone
+        assert!(validate_documents(&database, |id| id == "notes")
+            .unwrap()
+            .iter()
+            .all(|v| v.document_id != "notes"));
+
+        let summary = repair_documents(&database, |_| false).unwrap();
+        assert_eq!(summary.checked, 2);
+        assert_eq!(summary.remaining.len(), 1);
+        assert_eq!(summary.remaining[0].document_id, "gone");
+        assert_eq!(summary.remaining[0].issues, vec![DocumentIssue::SourceMissing]);
+
+        let repaired = database.get_document("notes").unwrap().unwrap();
+        assert_eq!(repaired.file_hash, compute_file_hash(&notes).unwrap());
+        assert!(repaired.mime_type.is_some());
+        assert_eq!(repaired.status, DocumentStatus::Failed);
+        assert_eq!(database.get_document("gone").unwrap().unwrap().metadata["source_missing"], true);
+    }
+}
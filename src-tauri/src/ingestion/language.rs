@@ -0,0 +1,93 @@
+use crate::database::Chunk;
+use serde::{Deserialize, Serialize};
+
+/// Maximum characters sampled from a document for language detection
+const LANGUAGE_SAMPLE_CHARS: usize = 4000;
+/// Number of chunks sampled, spread evenly across the document
+const LANGUAGE_SAMPLE_CHUNKS: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedLanguage {
+    /// ISO 639-3 language code (e.g. "eng", "jpn")
+    pub code: String,
+    /// English name of the language
+    pub name: String,
+    pub confidence: f64,
+    pub reliable: bool,
+}
+
+/// Detect the language of a text using an offline trigram-based detector
+pub fn detect_language(text: &str) -> Option<DetectedLanguage> {
+    let info = whatlang::detect(text)?;
+
+    Some(DetectedLanguage {
+        code: info.lang().code().to_string(),
+        name: info.lang().eng_name().to_string(),
+        confidence: info.confidence(),
+        reliable: info.is_reliable(),
+    })
+}
+
+/// Detect a document's primary language from an evenly spaced sample of its chunks
+pub fn detect_chunks_language(chunks: &[Chunk]) -> Option<DetectedLanguage> {
+    if chunks.is_empty() {
+        return None;
+    }
+
+    let step = chunks.len().div_ceil(LANGUAGE_SAMPLE_CHUNKS);
+    let mut sample = String::new();
+    for chunk in chunks.iter().step_by(step) {
+        sample.push_str(&chunk.content);
+        sample.push('\n');
+        if sample.len() >= LANGUAGE_SAMPLE_CHARS {
+            break;
+        }
+    }
+
+    detect_language(&sample)
+}
+
+/// Record a detected language in document metadata
+pub fn apply_language(metadata: &mut serde_json::Value, language: &DetectedLanguage) {
+    metadata["language"] = serde_json::json!(language.code);
+    metadata["language_name"] = serde_json::json!(language.name);
+    metadata["language_confidence"] = serde_json::json!(language.confidence);
+}
+
+/// Store each chunk's language in its metadata as `language`. Chunks too
+/// short or mixed to classify reliably are left untagged.
+pub fn tag_chunk_languages(chunks: &mut [Chunk]) {
+    for chunk in chunks {
+        let Some(language) = detect_language(&chunk.content).filter(|l| l.reliable) else {
+            continue;
+        };
+        if let Some(metadata) = chunk.metadata.as_object_mut() {
+            metadata.insert("language".to_string(), language.code.into());
+        }
+    }
+}
+
+/// Language tag of a chunk, if detection recorded one
+pub fn chunk_language(chunk: &Chunk) -> Option<&str> {
+    chunk.metadata.get("language").and_then(|v| v.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        let english = detect_language(
+            "The quick brown fox jumps over the lazy dog while the farmer watches from the porch.",
+        )
+        .expect("Should detect English");
+        assert_eq!(english.code, "eng");
+
+        let japanese = detect_language("これは日本語の文章です。今日はとても良い天気ですね。")
+            .expect("Should detect Japanese");
+        assert_eq!(japanese.code, "jpn");
+
+        assert!(detect_language("").is_none());
+    }
+}
@@ -0,0 +1,117 @@
+//! Mime type detection from file content, falling back to the extension
+
+use crate::database::FileType;
+use crate::error::{RecallError, Result};
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes inspected when checking that a file is plain text
+const TEXT_SNIFF_BYTES: u64 = 8192;
+
+/// Mime type from the file's magic bytes, if recognizable
+pub fn sniff_mime(path: &Path) -> Option<String> {
+    match infer::get_from_path(path) {
+        Ok(kind) => kind.map(|k| k.mime_type().to_string()),
+        Err(e) => {
+            tracing::debug!("Could not sniff {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Mime type from content, or from the extension when the content has no
+/// recognizable signature (plain text, subtitles)
+pub fn detect_mime(path: &Path) -> Option<String> {
+    sniff_mime(path).or_else(|| mime_guess::from_path(path).first().map(|m| m.to_string()))
+}
+
+/// File type from content, falling back to the extension. A PNG renamed to
+/// `.jpg` is still an image, and a PDF saved as `.txt` is still a PDF.
+pub fn detect_file_type(path: &Path) -> FileType {
+    let by_extension = FileType::from_extension(
+        path.extension().and_then(|e| e.to_str()).unwrap_or(""),
+    );
+
+    match sniff_mime(path).as_deref().and_then(FileType::from_mime) {
+        Some(sniffed) => {
+            if sniffed != by_extension {
+                tracing::info!(
+                    "{:?} looks like {} by content, not {}",
+                    path,
+                    sniffed,
+                    by_extension
+                );
+            }
+            sniffed
+        }
+        None => by_extension,
+    }
+}
+
+/// Check that the extractor for `file_type` can handle the file's content,
+/// before forcing that type over the detected one
+pub fn check_file_type_override(path: &Path, file_type: FileType) -> Result<()> {
+    let sniffed = sniff_mime(path);
+    let sniffed_type = sniffed.as_deref().and_then(FileType::from_mime);
+
+    let supported = match file_type {
+        FileType::Pdf => sniffed_type == Some(FileType::Pdf),
+        FileType::Image | FileType::Screenshot => sniffed_type == Some(FileType::Image),
+        FileType::Video => sniffed_type == Some(FileType::Video),
+        // ffmpeg can pull the audio track out of a video container
+        FileType::Audio => matches!(sniffed_type, Some(FileType::Audio | FileType::Video)),
+        FileType::Text | FileType::Markdown | FileType::Subtitle => sniffed.is_none() && looks_like_text(path)?,
+        FileType::Unknown => false,
+    };
+
+    if supported {
+        Ok(())
+    } else {
+        Err(RecallError::Ingestion(format!(
+            "{} cannot be read as {} (detected content: {})",
+            path.display(),
+            file_type,
+            sniffed.as_deref().unwrap_or("unrecognized")
+        )))
+    }
+}
+
+/// No NUL bytes in the first few KB, which rules out most binary formats
+fn looks_like_text(path: &Path) -> Result<bool> {
+    let mut head = Vec::new();
+    std::fs::File::open(path)?.take(TEXT_SNIFF_BYTES).read_to_end(&mut head)?;
+    Ok(!head.contains(&0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_content_wins_over_extension() {
+        let mut file = tempfile::Builder::new().suffix(".jpg").tempfile().unwrap();
+        file.write_all(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+
+        assert_eq!(detect_mime(file.path()).as_deref(), Some("image/png"));
+        assert_eq!(detect_file_type(file.path()), FileType::Image);
+
+        let mut text = tempfile::Builder::new().suffix(".md").tempfile().unwrap();
+        text.write_all(b"# Notes").unwrap();
+        assert_eq!(detect_file_type(text.path()), FileType::Markdown);
+    }
+
+    #[test]
+    fn test_file_type_override_checks_content() {
+        let mut log = tempfile::Builder::new().suffix(".log").tempfile().unwrap();
+        log.write_all(b"2024-01-01 INFO started").unwrap();
+        assert!(check_file_type_override(log.path(), FileType::Text).is_ok());
+        assert!(check_file_type_override(log.path(), FileType::Pdf).is_err());
+        assert!(check_file_type_override(log.path(), FileType::Unknown).is_err());
+
+        let mut png = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
+        png.write_all(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        assert!(check_file_type_override(png.path(), FileType::Image).is_ok());
+        assert!(check_file_type_override(png.path(), FileType::Text).is_err());
+    }
+}
@@ -1,35 +1,93 @@
+mod category;
+mod chunk_kind;
 mod chunker;
+mod estimate;
+mod exclude;
 mod extractor;
 mod ffmpeg;
+mod integrity;
+mod language;
+mod mime;
+#[cfg(not(windows))]
+mod pdf_render;
+mod pii;
+mod preview;
+mod subtitles;
+mod vision_ocr;
 mod watcher;
 mod watcher_manager;
+mod webhook;
 #[cfg(windows)]
 mod windows_ocr;
 
+pub use category::*;
+pub use chunk_kind::*;
 pub use chunker::*;
+pub use estimate::*;
+pub use exclude::*;
 pub use extractor::*;
 pub use ffmpeg::*;
+pub use integrity::*;
+pub use language::*;
+pub use mime::*;
+pub use pii::*;
+pub use preview::*;
+pub use subtitles::*;
+pub use vision_ocr::{OcrRenderScales, GEMINI_RENDER_SCALE, WINDOWS_OCR_RENDER_SCALE};
 pub use watcher::*;
 pub use watcher_manager::*;
+pub use webhook::*;
 
-use crate::commands::license::TRIAL_DOCUMENT_LIMIT;
-use crate::database::{Database, Document, DocumentStatus, FileType, IngestionProgress, IngestionStage};
+use crate::commands::license::{license_tier, trial_documents_used, LicenseTier, TRIAL_DOCUMENT_LIMIT};
+use crate::database::{
+    Chunk, Database, Document, DocumentStatus, FileType, IngestionProgress, IngestionSource, IngestionStage,
+};
 use crate::llm::LlmProvider;
 use crate::error::{RecallError, Result};
-use crate::llm::LlmClient;
+use crate::llm::{GenerateRequest, LlmClient};
 use crate::rag::{HybridRetriever, RelatedDocument};
 use crate::state::Settings;
 use chrono::Utc;
+use once_cell::sync::Lazy;
 use parking_lot::RwLock;
+use regex::Regex;
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tauri::Emitter;
-use tokio::sync::Semaphore;
+use tokio::sync::{Notify, Semaphore, SemaphorePermit};
+use tracing::Instrument;
 use uuid::Uuid;
 
+/// Characters of each version sent when summarizing changes
+const MAX_CHANGE_SUMMARY_CHARS: usize = 12_000;
+
+/// Screenshots with less text than this are not auto-categorized
+const AUTO_CATEGORIZE_MIN_SCREENSHOT_CHARS: usize = 200;
+
+/// Outcome of `IngestionEngine::prepare_file`
+enum PreparedFile {
+    /// Already ingested and unchanged, or a renamed completed document
+    UpToDate(Document),
+    /// A pending record still to be inserted and processed. A replacement
+    /// for a stale record at the same path doesn't count against the trial.
+    New { doc: Document, replaces_existing: bool },
+}
+
+fn file_type_disabled_error(file_type: FileType) -> RecallError {
+    RecallError::Ingestion(format!("Skipped: {} files are disabled in settings", file_type))
+}
+
+fn trial_limit_error() -> RecallError {
+    RecallError::TrialLimitReached(format!(
+        "Trial limit reached: {} documents. Upgrade to a license for unlimited documents.",
+        TRIAL_DOCUMENT_LIMIT
+    ))
+}
+
 /// Event emitted when related content is found after ingestion
 #[derive(Debug, Clone, Serialize)]
 pub struct RelatedContentNotification {
@@ -38,11 +96,97 @@ pub struct RelatedContentNotification {
     pub related_documents: Vec<RelatedDocument>,
 }
 
+/// Event emitted when a newly ingested document has the same text as an
+/// existing one, so the user can link the two or drop the new copy
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateContentNotification {
+    pub document_id: String,
+    pub document_title: String,
+    pub file_type: FileType,
+    pub duplicate_of_id: String,
+    pub duplicate_of_title: String,
+    pub duplicate_of_file_type: FileType,
+}
+
+/// Order in which queued files are processed, highest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestionPriority {
+    /// Watched folders and screen capture, which can queue large backlogs
+    Background,
+    /// Files the user asked for, which go ahead of any backlog
+    UserRequested,
+}
+
+impl From<IngestionSource> for IngestionPriority {
+    fn from(source: IngestionSource) -> Self {
+        match source {
+            IngestionSource::Manual | IngestionSource::Url => Self::UserRequested,
+            IngestionSource::Watched | IngestionSource::Capture => Self::Background,
+        }
+    }
+}
+
 /// Queue entry for pending ingestion
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct QueuedFile {
     pub path: String,
+    pub document_id: String,
+    pub priority: IngestionPriority,
     pub queued_at: chrono::DateTime<Utc>,
+    /// Enqueue order; unlike `queued_at` it never ties or runs backwards
+    #[serde(skip)]
+    pub sequence: u64,
+    /// Set for files queued together by `ingest_files`
+    pub batch_id: Option<String>,
+    /// Order of the watched folder a background file came from; earlier
+    /// folders go first among files of the same priority
+    pub folder_rank: Option<usize>,
+}
+
+impl QueuedFile {
+    /// Highest priority first, then by watched folder order (other
+    /// background files after every folder), then first queued
+    fn order_key(&self) -> (std::cmp::Reverse<IngestionPriority>, usize, u64) {
+        (std::cmp::Reverse(self.priority), self.folder_rank.unwrap_or(usize::MAX), self.sequence)
+    }
+}
+
+/// A file in an ingestion batch that didn't make it into the library
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchFailure {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Event emitted once every file of an `ingest_files` batch is done
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestionBatchSummary {
+    pub batch_id: String,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Already ingested and unchanged, or of a disabled file type
+    pub skipped: usize,
+    /// Size and chunks of the files ingested by this batch
+    pub total_bytes: i64,
+    pub total_chunks: i64,
+    pub elapsed_ms: u64,
+    pub failures: Vec<BatchFailure>,
+}
+
+/// The entry to process next, by `QueuedFile::order_key`
+fn next_in_queue(queue: &[QueuedFile]) -> Option<&QueuedFile> {
+    queue.iter().min_by_key(|q| q.order_key())
+}
+
+/// Outcome of removing a single file from the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueRemoval {
+    /// Taken off the queue before processing and its record deleted
+    Removed,
+    /// Already processing, so a normal cancellation was requested
+    Cancelled,
 }
 
 pub struct IngestionEngine {
@@ -56,6 +200,13 @@ pub struct IngestionEngine {
     ingestion_semaphore: Arc<Semaphore>,
     /// Queue of files waiting to be ingested
     pending_queue: Arc<RwLock<Vec<QueuedFile>>>,
+    /// Notified when an entry leaves the queue, waking files waiting their turn
+    queue_changed: Arc<Notify>,
+    /// Sequence number of the next queue entry
+    next_queue_sequence: AtomicU64,
+    /// Passwords for protected PDFs being ingested, keyed by path. Held only
+    /// for the ingestion and never stored.
+    pdf_passwords: Arc<RwLock<HashMap<String, String>>>,
 }
 
 impl IngestionEngine {
@@ -73,6 +224,9 @@ impl IngestionEngine {
             // Only allow 1 concurrent ingestion to prevent API rate limiting
             ingestion_semaphore: Arc::new(Semaphore::new(1)),
             pending_queue: Arc::new(RwLock::new(Vec::new())),
+            queue_changed: Arc::new(Notify::new()),
+            next_queue_sequence: AtomicU64::new(0),
+            pdf_passwords: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -83,35 +237,48 @@ impl IngestionEngine {
         (queue_len, is_processing)
     }
 
-    /// Get all queued files
+    /// Get all queued files, in the order they will be processed
     pub fn get_queued_files(&self) -> Vec<QueuedFile> {
-        self.pending_queue.read().clone()
+        let mut queue = self.pending_queue.read().clone();
+        queue.sort_by_key(QueuedFile::order_key);
+        queue
+    }
+
+    /// The detected type of `path` when that type is disabled in settings.
+    /// Unknown types aren't a setting; `check_file_type_allowed` reports
+    /// them as unsupported.
+    pub fn disabled_file_type(&self, path: &Path) -> Option<FileType> {
+        let file_type = detect_file_type(path);
+        (file_type != FileType::Unknown && !self.settings.read().file_type_enabled(file_type)).then_some(file_type)
+    }
+
+    /// Reject `path` when its type is unsupported or disabled in settings
+    fn check_file_type_allowed(&self, path: &Path) -> Result<()> {
+        if detect_file_type(path) == FileType::Unknown {
+            return Err(RecallError::Ingestion("Unsupported file type".to_string()));
+        }
+        match self.disabled_file_type(path) {
+            Some(file_type) => Err(file_type_disabled_error(file_type)),
+            None => Ok(()),
+        }
     }
 
     /// Check if the trial document limit has been reached.
     /// Licensed users bypass this check entirely.
     fn check_trial_limit(&self) -> Result<()> {
-        let settings = self.settings.read();
-
-        // Licensed users have no limit
-        if let Some(ref key) = settings.license_key {
-            if key.starts_with("RO-") && key.len() == 17 {
-                return Ok(());
-            }
+        match self.trial_slots_remaining()? {
+            Some(0) => Err(trial_limit_error()),
+            _ => Ok(()),
         }
+    }
 
-        // Trial user — check document count
-        drop(settings);
-        let stats = self.database.get_ingestion_stats()?;
-
-        if stats.total_documents >= TRIAL_DOCUMENT_LIMIT as i64 {
-            return Err(RecallError::TrialLimitReached(format!(
-                "Trial limit reached: {} documents. Upgrade to a license for unlimited documents.",
-                TRIAL_DOCUMENT_LIMIT
-            )));
+    /// Documents a trial user can still add, or None when licensed
+    fn trial_slots_remaining(&self) -> Result<Option<usize>> {
+        if license_tier(&self.settings.read()) == LicenseTier::Licensed {
+            return Ok(None);
         }
-
-        Ok(())
+        let used = trial_documents_used(&self.database)?;
+        Ok(Some(TRIAL_DOCUMENT_LIMIT.saturating_sub(used)))
     }
 
     pub async fn ingest_file<R: tauri::Runtime>(
@@ -119,59 +286,54 @@ impl IngestionEngine {
         path: &Path,
         app_handle: &tauri::AppHandle<R>,
     ) -> Result<Document> {
-        let path_str = path.to_string_lossy().to_string();
-        let current_hash = compute_file_hash(path)?;
-
-        // Check if file already exists at this path
-        if let Some(existing) = self.database.get_document_by_path(&path_str)? {
-            // If completed and unchanged, return existing
-            if existing.file_hash == current_hash && existing.status == DocumentStatus::Completed {
-                tracing::info!("File already ingested and unchanged: {}", path_str);
-                return Ok(existing);
-            }
-            // Delete old version (changed content OR incomplete/failed status)
-            tracing::info!("Re-ingesting file: {} (status: {:?}, hash_changed: {})",
-                path_str, existing.status, existing.file_hash != current_hash);
-            self.database.delete_document(&existing.id)?;
-        }
-
-        // Check if same content exists at a different path (file was renamed)
-        if let Some(existing) = self.database.get_document_by_hash(&current_hash)? {
-            if existing.status == DocumentStatus::Completed {
-                // File was renamed - just update the path
-                let new_title = path.file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
+        self.ingest_file_from(path, IngestionSource::Manual, app_handle).await
+    }
 
-                tracing::info!(
-                    "File renamed: {} -> {} (updating path only)",
-                    existing.file_path,
-                    path_str
-                );
+    /// Ingest a password-protected PDF. The password is only kept in memory
+    /// while this file is processed.
+    pub async fn ingest_file_with_password<R: tauri::Runtime>(
+        &self,
+        path: &Path,
+        password: &str,
+        app_handle: &tauri::AppHandle<R>,
+    ) -> Result<Document> {
+        let path_str = path.to_string_lossy().to_string();
+        self.pdf_passwords.write().insert(path_str.clone(), password.to_string());
+        let passwords = self.pdf_passwords.clone();
+        let _forget_password = scopeguard::guard(path_str, move |path_str| {
+            passwords.write().remove(&path_str);
+        });
 
-                self.database.update_document_path(&existing.id, &path_str, &new_title)?;
+        self.ingest_file_from(path, IngestionSource::Manual, app_handle).await
+    }
 
-                // Fetch and return the updated document
-                return self.database.get_document(&existing.id)?
-                    .ok_or_else(|| RecallError::NotFound("Document not found after path update".to_string()));
+    /// Ingest a file, recording how it arrived (manual pick, watched folder, ...)
+    pub async fn ingest_file_from<R: tauri::Runtime>(
+        &self,
+        path: &Path,
+        source: IngestionSource,
+        app_handle: &tauri::AppHandle<R>,
+    ) -> Result<Document> {
+        let path_str = path.to_string_lossy().to_string();
+        let doc = match self.prepare_file(path, source)? {
+            PreparedFile::UpToDate(existing) => return Ok(existing),
+            PreparedFile::New { doc, replaces_existing } => {
+                // Check trial document limit before creating a new document
+                if !replaces_existing {
+                    self.check_trial_limit()?;
+                }
+                doc
             }
-        }
-
-        // Check trial document limit before creating a new document
-        self.check_trial_limit()?;
+        };
 
         // Create document record
-        let doc = self.create_document(path)?;
         self.database.insert_document(&doc)?;
 
         // Add to queue and show queued status
         {
+            let entry = self.queue_entry(&doc, None);
             let mut queue = self.pending_queue.write();
-            queue.push(QueuedFile {
-                path: path_str.clone(),
-                queued_at: Utc::now(),
-            });
+            queue.push(entry);
             let queue_position = queue.len();
             tracing::info!("File queued for ingestion (position {}): {}", queue_position, path_str);
         }
@@ -188,28 +350,25 @@ impl IngestionEngine {
         self.update_progress(&doc.id, &path_str, IngestionStage::Queued, 0.0, &queue_msg);
         self.emit_progress(app_handle, &doc.id);
 
-        // Acquire semaphore to ensure only one file processes at a time
-        // This will block until the semaphore is available
-        let _permit = self.ingestion_semaphore.acquire().await
-            .map_err(|_| RecallError::Ingestion("Ingestion queue closed".to_string()))?;
-
-        // Remove from pending queue now that we're processing
-        {
-            let mut queue = self.pending_queue.write();
-            queue.retain(|q| q.path != path_str);
-        }
+        // Wait for this file's turn; only one file processes at a time
+        let _permit = self.acquire_turn(&doc.id).await?;
 
         tracing::info!("Starting ingestion (semaphore acquired): {}", path_str);
 
         // Process the file (only one at a time due to semaphore)
-        match self.process_document(&doc, app_handle).await {
+        match self.process_with_watchdog(&doc, app_handle).await {
             Ok(_) => {
-                self.database.update_document_status(&doc.id, DocumentStatus::Completed, None)?;
+                let note = self.completion_note(&doc.id);
+                self.database.update_document_status(&doc.id, DocumentStatus::Completed, note.as_deref())?;
                 self.update_progress(&doc.id, &path_str, IngestionStage::Completed, 1.0, "Ingestion complete");
                 self.emit_progress(app_handle, &doc.id);
 
                 tracing::info!("Ingestion complete, releasing semaphore: {}", path_str);
 
+                if let Err(e) = self.detect_and_store_language(&doc.id) {
+                    tracing::warn!("Language detection failed: {}", e);
+                }
+
                 // Generate content-aware title from extracted text
                 if let Some(title) = self.generate_content_title(&doc).await {
                     if let Err(e) = self.database.update_document_title(&doc.id, &title) {
@@ -217,6 +376,10 @@ impl IngestionEngine {
                     }
                 }
 
+                self.summarize_changes(&doc).await;
+
+                self.check_and_emit_duplicate_content(&doc, app_handle);
+
                 // Check for related content after successful ingestion
                 self.check_and_emit_related_content(&doc, app_handle).await;
 
@@ -224,17 +387,18 @@ impl IngestionEngine {
                 // Only delay if there are more files in the queue
                 let queue_len = self.pending_queue.read().len();
                 if queue_len > 0 {
-                    tracing::info!("Cooldown: waiting 2s before next file ({} remaining in queue)", queue_len);
-                    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                    self.cooldown_before_next_file(queue_len).await;
                 }
 
                 // Fetch updated document
-                self.database.get_document(&doc.id)?
-                    .ok_or_else(|| RecallError::NotFound("Document not found after ingestion".to_string()))
+                let ingested = self.database.get_document(&doc.id)?
+                    .ok_or_else(|| RecallError::NotFound("Document not found after ingestion".to_string()))?;
+                self.send_ingestion_webhook(&ingested);
+                Ok(ingested)
             }
             Err(e) => {
                 let error_msg = e.to_string();
-                self.database.update_document_status(&doc.id, DocumentStatus::Failed, Some(&error_msg))?;
+                self.database.update_document_status(&doc.id, failure_status(&e), Some(&error_msg))?;
                 self.update_progress(&doc.id, &path_str, IngestionStage::Failed, 0.0, &error_msg);
                 self.emit_progress(app_handle, &doc.id);
 
@@ -245,6 +409,225 @@ impl IngestionEngine {
         // Semaphore permit is automatically released when _permit goes out of scope
     }
 
+    /// Decide what ingesting `path` needs: nothing for an unchanged file or
+    /// a rename of a completed document (the path is updated in place), or a
+    /// new pending record, not yet inserted. A stale record at the same path
+    /// is deleted.
+    fn prepare_file(&self, path: &Path, source: IngestionSource) -> Result<PreparedFile> {
+        self.check_file_type_allowed(path)?;
+
+        let path_str = path.to_string_lossy().to_string();
+        let current_hash = compute_file_hash(path)?;
+        let mut replaces_existing = false;
+        let mut previous_version = None;
+
+        // Check if file already exists at this path
+        if let Some(existing) = self.database.get_document_by_path(&path_str)? {
+            // If completed and unchanged, return existing
+            if existing.file_hash == current_hash && existing.status == DocumentStatus::Completed {
+                tracing::info!("File already ingested and unchanged: {}", path_str);
+                return Ok(PreparedFile::UpToDate(existing));
+            }
+            // Delete old version (changed content OR incomplete/failed status)
+            tracing::info!("Re-ingesting file: {} (status: {:?}, hash_changed: {})",
+                path_str, existing.status, existing.file_hash != current_hash);
+            // A completed record here means the content changed; keep its text
+            if existing.status == DocumentStatus::Completed {
+                previous_version = self.snapshot_version(&existing);
+            }
+            self.database.delete_document(&existing.id)?;
+            replaces_existing = true;
+        }
+
+        // Check if same content exists at a different path (file was renamed)
+        if let Some(existing) = self.find_moved_document(path, &current_hash)? {
+            // File was renamed - just update the path
+            let new_title = path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+
+            tracing::info!(
+                "File renamed: {} -> {} (updating path only)",
+                existing.file_path,
+                path_str
+            );
+
+            self.database.update_document_path(&existing.id, &path_str, &new_title)?;
+
+            // Fetch and return the updated document
+            let updated = self.database.get_document(&existing.id)?
+                .ok_or_else(|| RecallError::NotFound("Document not found after path update".to_string()))?;
+            return Ok(PreparedFile::UpToDate(updated));
+        }
+
+        let mut doc = self.create_document(path, source)?;
+        if let Some(version_id) = previous_version {
+            doc.metadata["previous_version_id"] = serde_json::json!(version_id);
+        }
+        Ok(PreparedFile::New { doc, replaces_existing })
+    }
+
+    /// A completed document with the same content that a file at `path`
+    /// should be treated as a move of, within the `dedupe_scope` setting
+    pub fn find_moved_document(&self, path: &Path, file_hash: &str) -> Result<Option<Document>> {
+        let candidates = self.database.get_documents_by_hash(file_hash)?;
+        let settings = self.settings.read();
+        Ok(candidates.into_iter().find(|doc| {
+            doc.status == DocumentStatus::Completed && settings.in_dedupe_scope(path, Path::new(&doc.file_path))
+        }))
+    }
+
+    /// Save the extracted text of `doc` before a re-ingest replaces it, when
+    /// `document_versions_kept` is set
+    fn snapshot_version(&self, doc: &Document) -> Option<i64> {
+        let keep = self.settings.read().document_versions_kept;
+        if keep == 0 {
+            return None;
+        }
+
+        let result = self.database.get_chunks_for_document(&doc.id).and_then(|chunks| {
+            let text = chunks.iter().map(|c| c.content.as_str()).collect::<Vec<_>>().join("\n\n");
+            self.database.insert_document_version(doc, &text, keep)
+        });
+        match result {
+            Ok(id) => Some(id),
+            Err(e) => {
+                tracing::warn!("Failed to snapshot previous version of {}: {}", doc.file_path, e);
+                None
+            }
+        }
+    }
+
+    /// Label the document with content categories from a sample of its new
+    /// chunks. Failures are logged and leave the document uncategorized.
+    async fn auto_categorize(&self, doc: &Document, chunks: &[Chunk]) {
+        if doc.file_type == FileType::Screenshot {
+            let text_chars: usize = chunks.iter().map(|c| c.content.trim().len()).sum();
+            if text_chars < AUTO_CATEGORIZE_MIN_SCREENSHOT_CHARS {
+                tracing::debug!("Screenshot {} has too little text to categorize", doc.id);
+                return;
+            }
+        }
+
+        let Some(llm) = self.llm_client.read().clone() else {
+            return;
+        };
+
+        let labels = match request_category_labels(&llm, &doc.title, &build_content_sample(chunks)).await {
+            Ok(labels) => labels,
+            Err(e) => {
+                tracing::warn!("Auto-categorization failed for {}: {}", doc.id, e);
+                return;
+            }
+        };
+
+        let result = self.database.get_document(&doc.id).and_then(|current| {
+            let Some(current) = current else {
+                return Ok(None);
+            };
+            let mut metadata = current.metadata;
+            let categorization = apply_category_labels(&mut metadata, labels);
+            self.database.update_document_metadata(&doc.id, metadata)?;
+            Ok(Some(categorization))
+        });
+        match result {
+            Ok(Some(categorization)) => tracing::info!("Categorized {} as {}", doc.id, categorization.category),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to store categories for {}: {}", doc.id, e),
+        }
+    }
+
+    /// Summarize how a re-ingested document differs from the version it
+    /// replaced. The summary is stored on the version and in the new
+    /// document's `change_summary` metadata.
+    async fn summarize_changes(&self, doc: &Document) {
+        let Some(version_id) = doc.metadata.get("previous_version_id").and_then(|v| v.as_i64()) else {
+            return;
+        };
+        let client = self.llm_client.read().clone();
+        let Some(client) = client else {
+            return;
+        };
+
+        let (previous, current) = match (
+            self.database.get_document_version(version_id),
+            self.database.get_chunks_for_document(&doc.id),
+        ) {
+            (Ok(Some(version)), Ok(chunks)) => (
+                version.content,
+                chunks.iter().map(|c| c.content.as_str()).collect::<Vec<_>>().join("\n\n"),
+            ),
+            (Err(e), _) | (_, Err(e)) => {
+                tracing::warn!("Failed to load versions for change summary of {}: {}", doc.id, e);
+                return;
+            }
+            _ => return,
+        };
+
+        let clip = |text: &str| text.chars().take(MAX_CHANGE_SUMMARY_CHARS).collect::<String>();
+        let request = GenerateRequest {
+            prompt: format!(
+                "PREVIOUS VERSION:\n{}\n\nNEW VERSION:\n{}\n\nSummarize what changed.",
+                clip(&previous),
+                clip(&current)
+            ),
+            system_prompt: Some(
+                "You compare two versions of the same document. List the meaningful changes \
+                 (added, removed or reworded content, changed numbers and dates) as short bullet \
+                 points. Ignore whitespace and formatting. Reply with the bullets only, or \
+                 \"No meaningful changes.\" if there are none."
+                    .to_string(),
+            ),
+            context: vec![],
+            history: vec![],
+            max_tokens: Some(500),
+            temperature: Some(0.2),
+        };
+
+        let summary = match client.generate(request).await {
+            Ok(response) => response.content.trim().to_string(),
+            Err(e) => {
+                tracing::warn!("Change summary failed for {}: {}", doc.id, e);
+                return;
+            }
+        };
+        if summary.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.database.set_document_version_summary(version_id, &summary) {
+            tracing::warn!("Failed to store change summary: {}", e);
+        }
+        if let Err(e) = self.database.set_document_metadata_key(&doc.id, "change_summary", &serde_json::json!(summary)) {
+            tracing::warn!("Failed to store change summary: {}", e);
+        }
+    }
+
+    /// Wait `ingestion_cooldown_ms` between queued files, unless the rate
+    /// limiter has plenty of headroom
+    async fn cooldown_before_next_file(&self, queue_len: usize) {
+        let cooldown_ms = self.settings.read().ingestion_cooldown_ms;
+        if cooldown_ms == 0 {
+            return;
+        }
+
+        let client = self.llm_client.read().clone();
+        if let Some(client) = client {
+            if client.has_rate_headroom().await {
+                tracing::debug!("Skipping cooldown, rate limiter has headroom");
+                return;
+            }
+        }
+
+        tracing::info!(
+            "Cooldown: waiting {}ms before next file ({} remaining in queue)",
+            cooldown_ms,
+            queue_len
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(cooldown_ms)).await;
+    }
+
     /// Process an existing document (for screenshots or re-ingestion)
     /// This method is for documents that already exist in the database
     pub async fn ingest_existing_document<R: tauri::Runtime>(
@@ -252,42 +635,202 @@ impl IngestionEngine {
         doc: &Document,
         app_handle: &tauri::AppHandle<R>,
     ) -> Result<Document> {
-        let path_str = doc.file_path.clone();
+        self.enqueue_document(doc, "Queued for processing", None, app_handle);
+        self.process_queued_document(doc, app_handle).await
+    }
 
-        // Add to queue and show queued status
-        {
-            let mut queue = self.pending_queue.write();
-            queue.push(QueuedFile {
-                path: path_str.clone(),
-                queued_at: Utc::now(),
-            });
+    /// Ingest many files at once, as for a directory. New document records
+    /// are inserted in one transaction and queued together up front, then
+    /// processed one at a time. Returns the ingested or already up to date
+    /// documents and a message per failed file; stops adding files once the
+    /// trial limit is reached. Files of disabled types are skipped. Always
+    /// emits "ingestion-batch-complete" with an `IngestionBatchSummary` at
+    /// the end, even when the batch could not be queued.
+    pub async fn ingest_files<R: tauri::Runtime>(
+        &self,
+        paths: &[PathBuf],
+        source: IngestionSource,
+        app_handle: &tauri::AppHandle<R>,
+    ) -> (Vec<Document>, Vec<String>) {
+        let started = std::time::Instant::now();
+        let batch_id = Uuid::new_v4().to_string();
+        let mut documents = Vec::new();
+        let mut failures = Vec::new();
+        let mut skipped = 0;
+        let failure = |path: &str, reason: String| BatchFailure { path: path.to_string(), reason };
+
+        let mut pending = Vec::new();
+        // Without the license state no file can be added
+        let (mut slots, paths) = match self.trial_slots_remaining() {
+            Ok(slots) => (slots, paths),
+            Err(e) => {
+                failures.extend(paths.iter().map(|path| failure(&path.to_string_lossy(), e.to_string())));
+                (Some(0), &[][..])
+            }
+        };
+
+        for path in paths {
+            if self.disabled_file_type(path).is_some() {
+                skipped += 1;
+                continue;
+            }
+            match self.prepare_file(path, source) {
+                Ok(PreparedFile::UpToDate(doc)) => {
+                    skipped += 1;
+                    documents.push(doc);
+                }
+                Ok(PreparedFile::New { doc, replaces_existing: true }) => pending.push(doc),
+                Ok(PreparedFile::New { doc, replaces_existing: false }) => {
+                    if slots == Some(0) {
+                        let msg = trial_limit_error().to_string();
+                        tracing::warn!("Trial limit reached during batch ingest: {}", msg);
+                        let _ = app_handle.emit("trial-limit-reached", &msg);
+                        failures.push(failure(&path.to_string_lossy(), msg));
+                        break;
+                    }
+                    slots = slots.map(|n| n - 1);
+                    pending.push(doc);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to ingest {:?}: {}", path, e);
+                    failures.push(failure(&path.to_string_lossy(), e.to_string()));
+                }
+            }
         }
 
-        // Update progress to show queued status
-        self.update_progress(&doc.id, &path_str, IngestionStage::Queued, 0.0, "Queued for processing");
-        self.emit_progress(app_handle, &doc.id);
+        if let Err(e) = self.database.insert_documents(&pending) {
+            let reason = format!("Failed to create document records: {}", e);
+            failures.extend(pending.drain(..).map(|doc| failure(&doc.file_path, reason.clone())));
+        }
+        tracing::info!("Queued {} files for ingestion in batch {}", pending.len(), batch_id);
 
-        // Acquire semaphore to ensure only one file processes at a time
-        let _permit = self.ingestion_semaphore.acquire().await
-            .map_err(|_| RecallError::Ingestion("Ingestion queue closed".to_string()))?;
+        let total = pending.len();
+        for (i, doc) in pending.iter().enumerate() {
+            let msg = format!("Queued (position {} of {})", i + 1, total);
+            self.enqueue_document(doc, &msg, Some(&batch_id), app_handle);
+        }
 
-        // Remove from pending queue now that we're processing
-        {
-            let mut queue = self.pending_queue.write();
-            queue.retain(|q| q.path != path_str);
+        let (mut succeeded, mut total_bytes, mut total_chunks) = (0, 0, 0);
+        for doc in &pending {
+            match self.process_queued_document(doc, app_handle).await {
+                Ok(doc) => {
+                    succeeded += 1;
+                    total_bytes += doc.file_size;
+                    total_chunks += doc.chunk_count;
+                    documents.push(doc);
+                }
+                Err(e) => failures.push(failure(&doc.file_path, e.to_string())),
+            }
+
+            let queue_len = self.pending_queue.read().len();
+            if queue_len > 0 {
+                self.cooldown_before_next_file(queue_len).await;
+            }
         }
 
+        let summary = IngestionBatchSummary {
+            batch_id,
+            succeeded,
+            failed: failures.len(),
+            skipped,
+            total_bytes,
+            total_chunks,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            failures,
+        };
+        tracing::info!(
+            "Batch {} complete: {} ingested, {} failed, {} skipped in {}ms",
+            summary.batch_id,
+            summary.succeeded,
+            summary.failed,
+            summary.skipped,
+            summary.elapsed_ms
+        );
+        let _ = app_handle.emit("ingestion-batch-complete", &summary);
+
+        let errors = summary.failures.into_iter().map(|f| format!("{}: {}", f.path, f.reason)).collect();
+        (documents, errors)
+    }
+
+    fn queue_entry(&self, doc: &Document, batch_id: Option<&str>) -> QueuedFile {
+        let priority = doc.ingestion_source.into();
+        QueuedFile {
+            path: doc.file_path.clone(),
+            document_id: doc.id.clone(),
+            priority,
+            queued_at: Utc::now(),
+            sequence: self.next_queue_sequence.fetch_add(1, Ordering::Relaxed),
+            batch_id: batch_id.map(str::to_string),
+            folder_rank: self.folder_rank(&doc.file_path, priority),
+        }
+    }
+
+    /// Watched folder order only ranks background files; user requests are
+    /// first come, first served
+    fn folder_rank(&self, path: &str, priority: IngestionPriority) -> Option<usize> {
+        match priority {
+            IngestionPriority::Background => self.settings.read().watched_folder_rank(Path::new(path)),
+            IngestionPriority::UserRequested => None,
+        }
+    }
+
+    /// Re-rank queued files after the watched folders were reordered
+    pub fn refresh_folder_ranks(&self) {
+        let ranks: HashMap<String, Option<usize>> = {
+            let queue = self.pending_queue.read();
+            queue.iter().map(|q| (q.document_id.clone(), self.folder_rank(&q.path, q.priority))).collect()
+        };
+        for entry in self.pending_queue.write().iter_mut() {
+            if let Some(rank) = ranks.get(&entry.document_id) {
+                entry.folder_rank = *rank;
+            }
+        }
+        // Waiting files re-check whose turn it is
+        self.queue_changed.notify_waiters();
+    }
+
+    /// Add an inserted document to the pending queue and show it as queued
+    fn enqueue_document<R: tauri::Runtime>(
+        &self,
+        doc: &Document,
+        message: &str,
+        batch_id: Option<&str>,
+        app_handle: &tauri::AppHandle<R>,
+    ) {
+        let entry = self.queue_entry(doc, batch_id);
+        self.pending_queue.write().push(entry);
+        self.update_progress(&doc.id, &doc.file_path, IngestionStage::Queued, 0.0, message);
+        self.emit_progress(app_handle, &doc.id);
+    }
+
+    /// Process a document already in the pending queue once the ingestion
+    /// semaphore is free
+    async fn process_queued_document<R: tauri::Runtime>(
+        &self,
+        doc: &Document,
+        app_handle: &tauri::AppHandle<R>,
+    ) -> Result<Document> {
+        let path_str = doc.file_path.clone();
+
+        // Wait for this document's turn; only one file processes at a time
+        let _permit = self.acquire_turn(&doc.id).await?;
+
         tracing::info!("Starting ingestion for existing document: {}", doc.id);
 
         // Process the file
-        match self.process_document(doc, app_handle).await {
+        match self.process_with_watchdog(doc, app_handle).await {
             Ok(_) => {
-                self.database.update_document_status(&doc.id, DocumentStatus::Completed, None)?;
+                let note = self.completion_note(&doc.id);
+                self.database.update_document_status(&doc.id, DocumentStatus::Completed, note.as_deref())?;
                 self.update_progress(&doc.id, &path_str, IngestionStage::Completed, 1.0, "Ingestion complete");
                 self.emit_progress(app_handle, &doc.id);
 
                 tracing::info!("Existing document ingestion complete: {}", doc.id);
 
+                if let Err(e) = self.detect_and_store_language(&doc.id) {
+                    tracing::warn!("Language detection failed: {}", e);
+                }
+
                 // Generate content-aware title from extracted text (skip screenshots)
                 if let Some(title) = self.generate_content_title(doc).await {
                     if let Err(e) = self.database.update_document_title(&doc.id, &title) {
@@ -295,16 +838,22 @@ impl IngestionEngine {
                     }
                 }
 
+                self.summarize_changes(doc).await;
+
+                self.check_and_emit_duplicate_content(doc, app_handle);
+
                 // Check for related content after successful ingestion
                 self.check_and_emit_related_content(doc, app_handle).await;
 
                 // Fetch updated document
-                self.database.get_document(&doc.id)?
-                    .ok_or_else(|| RecallError::NotFound("Document not found after ingestion".to_string()))
+                let ingested = self.database.get_document(&doc.id)?
+                    .ok_or_else(|| RecallError::NotFound("Document not found after ingestion".to_string()))?;
+                self.send_ingestion_webhook(&ingested);
+                Ok(ingested)
             }
             Err(e) => {
                 let error_msg = e.to_string();
-                self.database.update_document_status(&doc.id, DocumentStatus::Failed, Some(&error_msg))?;
+                self.database.update_document_status(&doc.id, failure_status(&e), Some(&error_msg))?;
                 self.update_progress(&doc.id, &path_str, IngestionStage::Failed, 0.0, &error_msg);
                 self.emit_progress(app_handle, &doc.id);
 
@@ -314,12 +863,123 @@ impl IngestionEngine {
         }
     }
 
+    /// Re-ingest a document as `file_type`, overriding the detected type.
+    /// The old chunks and embeddings are removed once the new record is built.
+    pub async fn reingest_as<R: tauri::Runtime>(
+        &self,
+        doc: &Document,
+        file_type: FileType,
+        app_handle: &tauri::AppHandle<R>,
+    ) -> Result<Document> {
+        let path = Path::new(&doc.file_path);
+        check_file_type_override(path, file_type)?;
+
+        // Build the new record first so an unreadable file keeps its document
+        let mut new_doc = self.create_document(path, doc.ingestion_source)?;
+        new_doc.file_type = file_type;
+        self.database.delete_document(&doc.id)?;
+        self.database.insert_document(&new_doc)?;
+
+        tracing::info!("Re-ingesting {} as {} (detected {})", doc.file_path, file_type, doc.file_type);
+        self.ingest_existing_document(&new_doc, app_handle).await
+    }
+
+    /// Replace a document with a fresh pending record for the same file,
+    /// ready for `ingest_existing_document`. The old chunks and embeddings
+    /// are removed once the new record is built, so a file that can no
+    /// longer be read keeps its document. Fails like `prepare_file` for an
+    /// unsupported or disabled file type; like any replacement it doesn't
+    /// count toward the trial limit.
+    pub fn requeue_document(&self, doc: &Document) -> Result<Document> {
+        let path = Path::new(&doc.file_path);
+        self.check_file_type_allowed(path)?;
+
+        let new_doc = self.create_document(path, doc.ingestion_source)?;
+        self.database.delete_document(&doc.id)?;
+        self.database.insert_document(&new_doc)?;
+        Ok(new_doc)
+    }
+
+    /// Run `process_document`, aborting it if progress stalls for longer than
+    /// `ingestion_timeout_secs`. Dropping the processing future releases the
+    /// semaphore permit held by the caller, so the queue keeps moving.
+    async fn process_with_watchdog<R: tauri::Runtime>(
+        &self,
+        doc: &Document,
+        app_handle: &tauri::AppHandle<R>,
+    ) -> Result<()> {
+        // Events under this span are kept in the document's ingestion log
+        let span = tracing::info_span!("ingest", document_id = %doc.id);
+        async {
+            tracing::info!("Processing {} as {}", doc.file_path, doc.file_type);
+            let result = self.run_with_watchdog(doc, app_handle).await;
+            match &result {
+                Ok(()) => tracing::info!("Processing finished"),
+                // Recorded directly; the caller already logs the failure
+                Err(e) => crate::logging::push_document_log(
+                    &doc.id,
+                    tracing::Level::ERROR,
+                    module_path!(),
+                    format!("Ingestion failed: {}", e),
+                ),
+            }
+            result
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn run_with_watchdog<R: tauri::Runtime>(
+        &self,
+        doc: &Document,
+        app_handle: &tauri::AppHandle<R>,
+    ) -> Result<()> {
+        let timeout_secs = self.settings.read().ingestion_timeout_secs;
+        if timeout_secs == 0 {
+            return self.process_document(doc, app_handle).await;
+        }
+
+        let timeout = chrono::Duration::seconds(timeout_secs as i64);
+        let started_at = Utc::now();
+        let watchdog = async {
+            let mut interval = tokio::time::interval(WATCHDOG_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                // Time spent waiting in the queue doesn't count as a stall
+                let last_update = self
+                    .progress
+                    .read()
+                    .get(&doc.id)
+                    .map(|p| p.updated_at.max(started_at))
+                    .unwrap_or(started_at);
+                if Utc::now() - last_update > timeout {
+                    return;
+                }
+            }
+        };
+
+        tokio::select! {
+            result = self.process_document(doc, app_handle) => result,
+            _ = watchdog => {
+                tracing::error!(
+                    "Ingestion watchdog fired for {}: no progress for {}s",
+                    doc.id,
+                    timeout_secs
+                );
+                self.clear_cancelled(&doc.id);
+                Err(RecallError::Ingestion(format!(
+                    "Ingestion timed out: no progress for {} seconds",
+                    timeout_secs
+                )))
+            }
+        }
+    }
+
     async fn process_document<R: tauri::Runtime>(
         &self,
         doc: &Document,
         app_handle: &tauri::AppHandle<R>,
     ) -> Result<()> {
-        let path = Path::new(&doc.file_path);
         let path_str = doc.file_path.clone();
 
         // Check for cancellation before starting
@@ -337,72 +997,16 @@ impl IngestionEngine {
             FileType::Image | FileType::Screenshot => "Running OCR on image...",
             FileType::Video => "Processing video frames...",
             FileType::Audio => "Transcribing audio...",
+            FileType::Subtitle => "Parsing subtitles...",
             _ => "Extracting content...",
         };
         self.update_progress(&doc.id, &path_str, IngestionStage::Extracting, 0.1, extraction_msg);
         self.emit_progress(app_handle, &doc.id);
 
-        // Extract text based on file type
-        let extracted = match doc.file_type {
-            FileType::Pdf => {
-                let llm = {
-                    let guard = self.llm_client.read();
-                    guard.clone()
-                };
-
-                // Create progress callback that updates the UI
-                let doc_id = doc.id.clone();
-                let _path_for_cb = path_str.clone();
-                let progress_map = self.progress.clone();
-                let app_handle_for_cb = app_handle.clone();
-
-                let progress_callback: extractor::ProgressCallback = Box::new(move |msg: &str| {
-                    // Update progress with the message
-                    {
-                        let mut map = progress_map.write();
-                        if let Some(progress) = map.get_mut(&doc_id) {
-                            progress.message = msg.to_string();
-                        }
-                    }
-                    // Emit to frontend
-                    {
-                        let map = progress_map.read();
-                        if let Some(progress) = map.get(&doc_id) {
-                            let _ = app_handle_for_cb.emit("ingestion-progress", progress.clone());
-                        }
-                    }
-                });
-
-                extract_pdf_with_progress(path, llm.as_ref(), Some(&progress_callback)).await?
-            }
-            FileType::Text | FileType::Markdown => extract_text(path).await?,
-            FileType::Video => {
-                let (llm, settings) = {
-                    let llm_guard = self.llm_client.read();
-                    let llm = llm_guard.as_ref().ok_or(RecallError::Config("LLM client not configured".to_string()))?.clone();
-                    let settings = self.settings.read().clone();
-                    (llm, settings)
-                };
-                extract_video(path, &llm, &settings).await?
-            }
-            FileType::Audio => {
-                let llm = {
-                    let guard = self.llm_client.read();
-                    guard.as_ref().ok_or(RecallError::Config("LLM client not configured".to_string()))?.clone()
-                };
-                extract_audio(path, &llm).await?
-            }
-            FileType::Image | FileType::Screenshot => {
-                let llm = {
-                    let guard = self.llm_client.read();
-                    guard.as_ref().ok_or(RecallError::Config("LLM client not configured".to_string()))?.clone()
-                };
-                extract_image(path, &llm).await?
-            }
-            FileType::Unknown => {
-                return Err(RecallError::Ingestion("Unsupported file type".to_string()));
-            }
-        };
+        // Extract text, retrying transient provider failures
+        let extracted = self
+            .with_stage_retries(doc, "extraction", app_handle, || self.extract_content(doc, app_handle))
+            .await?;
 
         // Check for cancellation after extraction
         if self.is_cancelled(&doc.id) {
@@ -410,16 +1014,31 @@ impl IngestionEngine {
             return Err(RecallError::Ingestion("Ingestion cancelled".to_string()));
         }
 
+        if let Some(content_hash) = normalized_content_hash(&extracted.plain_text()) {
+            self.database.set_document_content_hash(&doc.id, &content_hash)?;
+        }
+
         // Chunk the content
         tracing::info!("Starting chunking for document: {}", doc.id);
         self.update_progress(&doc.id, &path_str, IngestionStage::Chunking, 0.3, "Splitting into chunks...");
         self.emit_progress(app_handle, &doc.id);
 
-        let (chunk_size, chunk_overlap) = {
+        let (chunk_size, chunk_overlap, dedupe_chunks, min_chunk_chars, detect_language, body_only) = {
             let settings = self.settings.read();
-            (settings.chunk_size, settings.chunk_overlap)
+            (
+                settings.chunk_size,
+                settings.chunk_overlap,
+                settings.dedupe_chunks,
+                settings.min_chunk_chars,
+                settings.detect_language_on_ingest,
+                settings.index_body_chunks_only,
+            )
         };
-        let chunker = Chunker::new(chunk_size, chunk_overlap);
+        let chunker = Chunker::new(chunk_size, chunk_overlap)
+            .with_dedupe(dedupe_chunks)
+            .with_min_chars(min_chunk_chars)
+            .with_language_tags(detect_language)
+            .with_body_only_index(body_only);
 
         let chunks = chunker.chunk(&doc.id, &extracted)?;
         tracing::info!("Chunking complete: {} chunks created", chunks.len());
@@ -439,9 +1058,28 @@ impl IngestionEngine {
             return Err(RecallError::Ingestion("Ingestion cancelled".to_string()));
         }
 
+        if self.settings.read().auto_categorize {
+            self.update_progress(&doc.id, &path_str, IngestionStage::Chunking, 0.4, "Categorizing...");
+            self.emit_progress(app_handle, &doc.id);
+            self.auto_categorize(doc, &chunks).await;
+        }
+
+        if !self.settings.read().embeddings_enabled {
+            tracing::info!("Embeddings disabled, document is searchable by keyword only");
+            return Ok(());
+        }
+
+        // Headers, footers and captions marked unindexed are stored but not embedded
+        let (chunk_ids, texts): (Vec<i64>, Vec<String>) = chunk_ids
+            .iter()
+            .zip(&chunks)
+            .filter(|(_, c)| c.metadata.get("unindexed") != Some(&serde_json::Value::Bool(true)))
+            .map(|(id, c)| (*id, c.content.clone()))
+            .unzip();
+
         // Generate embeddings
         tracing::info!("Starting embedding generation");
-        let embedding_msg = format!("Generating embeddings for {} chunks...", chunks.len());
+        let embedding_msg = format!("Generating embeddings for {} chunks...", texts.len());
         self.update_progress(&doc.id, &path_str, IngestionStage::Embedding, 0.5, &embedding_msg);
         self.emit_progress(app_handle, &doc.id);
 
@@ -452,11 +1090,12 @@ impl IngestionEngine {
         };
 
         if let Some(ref client) = llm_client {
-            let texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
             tracing::info!("Calling embed API for {} texts", texts.len());
 
             // Batch embeddings
-            let embeddings = client.embed(&texts).await?;
+            let embeddings = self
+                .with_stage_retries(doc, "embedding", app_handle, || client.embed(&texts))
+                .await?;
             tracing::info!("Embeddings received: {} vectors", embeddings.len());
 
             // Store embeddings
@@ -473,16 +1112,202 @@ impl IngestionEngine {
         Ok(())
     }
 
-    fn create_document(&self, path: &Path) -> Result<Document> {
+    /// Note kept on a completed document that was only partly ingested
+    fn completion_note(&self, doc_id: &str) -> Option<String> {
+        let doc = self.database.get_document(doc_id).ok()??;
+        let truncation = doc.metadata.get("ocr_truncated")?;
+        Some(format!(
+            "Partial: OCR covered the first {} of {} pages",
+            truncation.get("pages_processed")?,
+            truncation.get("total_pages")?
+        ))
+    }
+
+    /// Extract a document's content according to its file type
+    async fn extract_content<R: tauri::Runtime>(
+        &self,
+        doc: &Document,
+        app_handle: &tauri::AppHandle<R>,
+    ) -> Result<ExtractedContent> {
+        let path = Path::new(&doc.file_path);
+        let path_str = doc.file_path.clone();
+
+        let extracted = match doc.file_type {
+            FileType::Pdf => {
+                let llm = {
+                    let guard = self.llm_client.read();
+                    guard.clone()
+                };
+
+                // Create progress callback that updates the UI
+                let doc_id = doc.id.clone();
+                let _path_for_cb = path_str.clone();
+                let progress_map = self.progress.clone();
+                let app_handle_for_cb = app_handle.clone();
+
+                let progress_callback: extractor::ProgressCallback = Box::new(move |msg: &str| {
+                    // Update progress with the message
+                    {
+                        let mut map = progress_map.write();
+                        if let Some(progress) = map.get_mut(&doc_id) {
+                            progress.message = msg.to_string();
+                            progress.updated_at = Utc::now();
+                        }
+                    }
+                    // Emit to frontend
+                    {
+                        let map = progress_map.read();
+                        if let Some(progress) = map.get(&doc_id) {
+                            let _ = app_handle_for_cb.emit("ingestion-progress", progress.clone());
+                        }
+                    }
+                });
+
+                let (render_scales, max_ocr_pages) = {
+                    let settings = self.settings.read();
+                    (OcrRenderScales::from_settings(&settings), settings.max_ocr_pages)
+                };
+                let password = self.pdf_passwords.read().get(&path_str).cloned();
+                let extraction = extract_pdf_with_progress(
+                    path,
+                    llm.as_ref(),
+                    render_scales,
+                    max_ocr_pages,
+                    password.as_deref(),
+                    Some(&progress_callback),
+                )
+                .await;
+
+                // Record protection (never the password) so the UI can offer to retry with one
+                let password_protected = match &extraction {
+                    Ok(extraction) => extraction.password_protected,
+                    Err(RecallError::Ingestion(msg)) => msg.starts_with(PDF_PASSWORD_REQUIRED),
+                    Err(_) => false,
+                };
+                if password_protected {
+                    self.database.set_document_metadata_key(&doc.id, "password_protected", &serde_json::json!(true))?;
+                } else {
+                    self.database.remove_document_metadata_key(&doc.id, "password_protected")?;
+                }
+
+                let PdfExtraction { content: extracted, ocr_truncation: truncation, .. } = extraction?;
+                match truncation {
+                    Some(truncation) => self.database.set_document_metadata_key(
+                        &doc.id,
+                        "ocr_truncated",
+                        &serde_json::to_value(truncation)?,
+                    )?,
+                    None => self.database.remove_document_metadata_key(&doc.id, "ocr_truncated")?,
+                }
+                extracted
+            }
+            FileType::Text | FileType::Markdown => extract_text(path).await?,
+            FileType::Subtitle => extract_subtitles(path).await?,
+            FileType::Video => {
+                let (llm, settings) = {
+                    let llm_guard = self.llm_client.read();
+                    let llm = llm_guard.as_ref().ok_or(RecallError::Config("LLM client not configured".to_string()))?.clone();
+                    let settings = self.settings.read().clone();
+                    (llm, settings)
+                };
+                extract_video(path, &llm, &settings).await?
+            }
+            FileType::Audio => {
+                let (llm, settings) = {
+                    let llm_guard = self.llm_client.read();
+                    let llm = llm_guard.as_ref().ok_or(RecallError::Config("LLM client not configured".to_string()))?.clone();
+                    let settings = self.settings.read().clone();
+                    (llm, settings)
+                };
+                extract_audio(path, &llm, &settings).await?
+            }
+            FileType::Image | FileType::Screenshot => {
+                let llm = {
+                    let guard = self.llm_client.read();
+                    guard.as_ref().ok_or(RecallError::Config("LLM client not configured".to_string()))?.clone()
+                };
+                let mut extracted = extract_image(path, &llm).await?;
+                // Backstop for the pixelated screenshot: anything the region
+                // pass missed still stays out of the index
+                if doc.metadata.get("redact_sensitive").and_then(|v| v.as_bool()) == Some(true) {
+                    let (text, redacted) = redact_sensitive_text(&extracted.plain_text());
+                    if redacted > 0 {
+                        tracing::info!("Masked {} sensitive spans in OCR text of {}", redacted, doc.id);
+                        extracted = ExtractedContent::Text { text, pages: None };
+                    }
+                }
+                match doc.metadata.get("note").and_then(|v| v.as_str()) {
+                    Some(note) => with_capture_note(extracted, note),
+                    None => extracted,
+                }
+            }
+            FileType::Unknown => {
+                return Err(RecallError::Ingestion("Unsupported file type".to_string()));
+            }
+        };
+        Ok(extracted)
+    }
+
+    /// Run an ingestion stage, retrying transient failures up to
+    /// `ingestion_max_retries` times with backoff. Permanent errors (bad file,
+    /// safety block, missing key) fail at once. A stage that needed more than
+    /// one attempt records the count as `<stage>_attempts` in the metadata.
+    async fn with_stage_retries<T, F, Fut, R>(
+        &self,
+        doc: &Document,
+        stage: &str,
+        app_handle: &tauri::AppHandle<R>,
+        mut run: F,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+        R: tauri::Runtime,
+    {
+        let max_retries = self.settings.read().ingestion_max_retries;
+        let mut attempt = 1;
+        let result = loop {
+            match run().await {
+                Err(e) if attempt <= max_retries && is_transient_error(&e) && !self.is_cancelled(&doc.id) => {
+                    let wait_secs = retry_backoff_secs(&e, attempt);
+                    tracing::warn!(
+                        "Ingestion {} failed for {} (attempt {}/{}), retrying in {}s: {}",
+                        stage,
+                        doc.id,
+                        attempt,
+                        max_retries + 1,
+                        wait_secs,
+                        e
+                    );
+                    let progress = self.progress.read().get(&doc.id).map(|p| (p.stage, p.progress));
+                    if let Some((current_stage, progress)) = progress {
+                        let msg = format!("Retrying {} in {}s (attempt {} of {})...", stage, wait_secs, attempt + 1, max_retries + 1);
+                        self.update_progress(&doc.id, &doc.file_path, current_stage, progress, &msg);
+                        self.emit_progress(app_handle, &doc.id);
+                    }
+                    tokio::time::sleep(std::time::Duration::from_secs(wait_secs)).await;
+                    attempt += 1;
+                }
+                result => break result,
+            }
+        };
+
+        if attempt > 1 {
+            let key = format!("{}_attempts", stage);
+            if let Err(e) = self.database.set_document_metadata_key(&doc.id, &key, &serde_json::json!(attempt)) {
+                tracing::warn!("Failed to record {} for {}: {}", key, doc.id, e);
+            }
+        }
+        result
+    }
+
+    fn create_document(&self, path: &Path, source: IngestionSource) -> Result<Document> {
         let metadata = std::fs::metadata(path)?;
         let file_name = path.file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown")
             .to_string();
-        let extension = path.extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("");
-        let file_type = FileType::from_extension(extension);
+        let file_type = detect_file_type(path);
         let file_hash = compute_file_hash(path)?;
 
         Ok(Document {
@@ -492,15 +1317,18 @@ impl IngestionEngine {
             file_type,
             file_size: metadata.len() as i64,
             file_hash,
-            mime_type: mime_guess::from_path(path)
-                .first()
-                .map(|m| m.to_string()),
+            mime_type: detect_mime(path),
             created_at: Utc::now(),
             updated_at: Utc::now(),
             ingested_at: None,
             status: DocumentStatus::Pending,
             error_message: None,
             metadata: serde_json::json!({}),
+            ingestion_source: source,
+            chunk_count: 0,
+            total_tokens: 0,
+            last_accessed: None,
+            access_count: 0,
         })
     }
 
@@ -512,6 +1340,7 @@ impl IngestionEngine {
             stage,
             progress,
             message: message.to_string(),
+            updated_at: Utc::now(),
         });
     }
 
@@ -522,6 +1351,63 @@ impl IngestionEngine {
         }
     }
 
+    /// Emit a notification when another completed document has the same
+    /// normalized text, e.g. an article saved both as PDF and as a web page.
+    /// Screenshots are left out since repeated captures of a screen are
+    /// expected.
+    fn check_and_emit_duplicate_content<R: tauri::Runtime>(&self, doc: &Document, app_handle: &tauri::AppHandle<R>) {
+        if doc.file_type == FileType::Screenshot {
+            return;
+        }
+
+        let original = match self.database.find_content_duplicate(&doc.id) {
+            Ok(Some(original)) => original,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to check for duplicate content: {}", e);
+                return;
+            }
+        };
+
+        tracing::info!("'{}' has the same content as '{}'", doc.file_path, original.file_path);
+
+        // Pick up a title generated after ingestion
+        let document_title = self
+            .database
+            .get_document(&doc.id)
+            .ok()
+            .flatten()
+            .map_or_else(|| doc.title.clone(), |d| d.title);
+
+        let notification = DuplicateContentNotification {
+            document_id: doc.id.clone(),
+            document_title,
+            file_type: doc.file_type,
+            duplicate_of_id: original.id,
+            duplicate_of_title: original.title,
+            duplicate_of_file_type: original.file_type,
+        };
+        if let Err(e) = app_handle.emit("duplicate-content-found", &notification) {
+            tracing::warn!("Failed to emit duplicate content notification: {}", e);
+        }
+    }
+
+    /// Notify `ingestion_webhook_url`, if set, without waiting for delivery
+    fn send_ingestion_webhook(&self, doc: &Document) {
+        let Some(url) = self
+            .settings
+            .read()
+            .ingestion_webhook_url
+            .clone()
+            .filter(|url| !url.trim().is_empty())
+        else {
+            return;
+        };
+
+        let payload = IngestionWebhookPayload::ingested(doc);
+        tokio::spawn(async move { deliver_webhook(&url, &payload).await });
+    }
+
     /// Check for related content and emit notification if found
     async fn check_and_emit_related_content<R: tauri::Runtime>(
         &self,
@@ -550,7 +1436,24 @@ impl IngestionEngine {
 
         // Find related documents
         let retriever = HybridRetriever::new(self.database.clone(), llm);
-        match retriever.find_related_documents(&doc.id, 5, 0.3).await {
+        let related = retriever.find_related_documents(&doc.id, 5, 0.3).await;
+
+        // Persist the edges so older documents see this one as related too
+        if let Ok(related) = &related {
+            let edges: Vec<(String, f64)> = related
+                .iter()
+                .map(|r| (r.id.clone(), r.similarity))
+                .collect();
+            if let Err(e) = self
+                .database
+                .delete_document_relations(&doc.id)
+                .and_then(|_| self.database.upsert_document_relations(&doc.id, &edges))
+            {
+                tracing::warn!("Failed to store document relations: {}", e);
+            }
+        }
+
+        match related {
             Ok(related) if !related.is_empty() => {
                 tracing::info!(
                     "Found {} related documents for '{}'",
@@ -596,6 +1499,27 @@ impl IngestionEngine {
         }
     }
 
+    /// Detect the document's language from its chunks and store it in metadata
+    fn detect_and_store_language(&self, doc_id: &str) -> Result<()> {
+        if !self.settings.read().detect_language_on_ingest {
+            return Ok(());
+        }
+
+        let chunks = self.database.get_chunks_for_document(doc_id)?;
+        let Some(language) = detect_chunks_language(&chunks) else {
+            return Ok(());
+        };
+        let Some(doc) = self.database.get_document(doc_id)? else {
+            return Ok(());
+        };
+
+        let mut metadata = doc.metadata;
+        apply_language(&mut metadata, &language);
+        self.database.update_document_metadata(doc_id, metadata)?;
+        tracing::info!("Detected language for {}: {} ({:.2})", doc_id, language.code, language.confidence);
+        Ok(())
+    }
+
     /// Generate a content-aware title from the extracted text
     /// This now handles all file types including screenshots (for reingest support)
     async fn generate_content_title(&self, doc: &Document) -> Option<String> {
@@ -660,6 +1584,14 @@ impl IngestionEngine {
         self.progress.read().get(doc_id).cloned()
     }
 
+    /// Whether a document is queued or being processed right now
+    pub fn is_ingesting(&self, doc_id: &str) -> bool {
+        self.progress
+            .read()
+            .get(doc_id)
+            .is_some_and(|p| !matches!(p.stage, IngestionStage::Completed | IngestionStage::Failed))
+    }
+
     pub fn get_all_progress(&self) -> Vec<IngestionProgress> {
         self.progress.read().values().cloned().collect()
     }
@@ -670,6 +1602,97 @@ impl IngestionEngine {
         self.progress.write().clear();
         self.cancelled_docs.write().clear();
         self.pending_queue.write().clear();
+        // Files waiting their turn find themselves removed and fail
+        self.queue_changed.notify_waiters();
+    }
+
+    /// Wait until `doc_id` is next in the queue and the ingestion semaphore
+    /// is free, then take it off the queue. The semaphore hands out permits
+    /// first come first served, so a holder whose entry isn't next gives the
+    /// permit back and waits for the queue to change.
+    async fn acquire_turn(&self, doc_id: &str) -> Result<SemaphorePermit<'_>> {
+        loop {
+            let permit = self.ingestion_semaphore.acquire().await
+                .map_err(|_| RecallError::Ingestion("Ingestion queue closed".to_string()))?;
+
+            // Register before checking so a change in between still wakes us
+            let queue_changed = self.queue_changed.notified();
+            tokio::pin!(queue_changed);
+            queue_changed.as_mut().enable();
+
+            let waiting_behind = {
+                let queue = self.pending_queue.read();
+                queue.iter().any(|q| q.document_id == doc_id)
+                    && next_in_queue(&queue).is_some_and(|next| next.document_id != doc_id)
+            };
+            if !waiting_behind {
+                self.take_from_queue(doc_id)?;
+                return Ok(permit);
+            }
+
+            drop(permit);
+            queue_changed.await;
+        }
+    }
+
+    /// Claim a queued document for processing. Fails when it was removed
+    /// from the queue while waiting for the semaphore.
+    fn take_from_queue(&self, doc_id: &str) -> Result<()> {
+        let removed = {
+            let mut queue = self.pending_queue.write();
+            let before = queue.len();
+            queue.retain(|q| q.document_id != doc_id);
+            queue.len() < before
+        };
+        if !removed {
+            return Err(RecallError::Ingestion("Removed from the ingestion queue".to_string()));
+        }
+        self.queue_changed.notify_waiters();
+        Ok(())
+    }
+
+    /// Remove one file, by path or document id, from the pending queue and
+    /// delete its pending record. A file that has already left the queue is
+    /// cancelled instead.
+    pub fn remove_from_queue(&self, path_or_id: &str) -> Result<QueueRemoval> {
+        let queued = {
+            let mut queue = self.pending_queue.write();
+            queue
+                .iter()
+                .position(|q| q.document_id == path_or_id || q.path == path_or_id)
+                .map(|i| queue.remove(i))
+        };
+
+        if let Some(queued) = queued {
+            // Its waiting task sees the entry gone and skips processing
+            self.queue_changed.notify_waiters();
+            tracing::info!("Removed from ingestion queue: {}", queued.path);
+            self.progress.write().remove(&queued.document_id);
+            if let Some(doc) = self.database.get_document(&queued.document_id)? {
+                if doc.status == DocumentStatus::Pending {
+                    self.database.delete_document(&doc.id)?;
+                }
+            }
+            return Ok(QueueRemoval::Removed);
+        }
+
+        // Lost the race with the processor: fall back to a normal cancel
+        let active = self
+            .progress
+            .read()
+            .values()
+            .find(|p| {
+                (p.document_id == path_or_id || p.file_path == path_or_id)
+                    && !matches!(p.stage, IngestionStage::Completed | IngestionStage::Failed)
+            })
+            .map(|p| p.document_id.clone());
+        match active {
+            Some(doc_id) => {
+                self.cancel(&doc_id);
+                Ok(QueueRemoval::Cancelled)
+            }
+            None => Err(RecallError::NotFound(format!("Not in the ingestion queue: {}", path_or_id))),
+        }
     }
 
     /// Request cancellation of a document's ingestion
@@ -690,22 +1713,264 @@ impl IngestionEngine {
     }
 }
 
-/// Maximum file size allowed for ingestion (500 MB)
-const MAX_FILE_SIZE: u64 = 500 * 1024 * 1024;
+/// How often the watchdog checks for stalled ingestions
+const WATCHDOG_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
 
-fn compute_file_hash(path: &Path) -> Result<String> {
-    // Check file size before reading to prevent OOM
-    let metadata = std::fs::metadata(path)?;
-    if metadata.len() > MAX_FILE_SIZE {
-        return Err(RecallError::Ingestion(format!(
-            "File too large ({:.1} MB). Maximum size is {:.0} MB.",
-            metadata.len() as f64 / (1024.0 * 1024.0),
-            MAX_FILE_SIZE as f64 / (1024.0 * 1024.0)
-        )));
-    }
+/// Bytes read per step when hashing a file
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+/// SHA-256 of a file, streamed through a fixed-size buffer so memory use
+/// doesn't grow with file size. Files over their type's `max_file_size` are
+/// rejected before anything is read.
+pub fn compute_file_hash(path: &Path) -> Result<String> {
+    check_file_size(path, detect_file_type(path))?;
 
-    let data = std::fs::read(path)?;
+    // io::copy retries reads interrupted by a signal
+    let mut reader = std::io::BufReader::with_capacity(HASH_BUFFER_SIZE, std::fs::File::open(path)?);
     let mut hasher = Sha256::new();
-    hasher.update(&data);
+    std::io::copy(&mut reader, &mut hasher)?;
     Ok(hex::encode(hasher.finalize()))
 }
+
+/// Put the note the user wrote when capturing ahead of the OCR text, so
+/// it is chunked and embedded with it
+fn with_capture_note(extracted: ExtractedContent, note: &str) -> ExtractedContent {
+    let text = extracted.plain_text();
+    let text = if text.trim().is_empty() {
+        format!("Note: {}", note)
+    } else {
+        format!("Note: {}\n\n{}", note, text)
+    };
+    ExtractedContent::Text { text, pages: None }
+}
+
+/// SHA-256 of extracted text, lowercased with whitespace collapsed, so the
+/// same text matches across formats. `None` when there is no text.
+pub fn normalized_content_hash(text: &str) -> Option<String> {
+    let mut hasher = Sha256::new();
+    let mut words = 0;
+    for word in text.split_whitespace() {
+        if words > 0 {
+            hasher.update(b" ");
+        }
+        hasher.update(word.to_lowercase().as_bytes());
+        words += 1;
+    }
+    (words > 0).then(|| hex::encode(hasher.finalize()))
+}
+
+/// Longest wait between ingestion retries
+const MAX_RETRY_BACKOFF_SECS: u64 = 120;
+
+/// A 5xx status in an API error message, as in "API error 503 Service
+/// Unavailable" or "File upload failed at byte 1024 500 ..."
+static SERVER_ERROR_STATUS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:API error|failed(?: at byte \d+)?) 5\d\d\b").unwrap());
+
+/// Errors worth retrying: rate limits, network failures and 5xx responses
+/// from the provider. Everything else (unsupported or corrupt files, safety
+/// blocks, bad requests, configuration) fails the same way on every attempt.
+pub(crate) fn is_transient_error(error: &RecallError) -> bool {
+    match error {
+        RecallError::RateLimit(_) | RecallError::Http(_) => true,
+        RecallError::LlmApi(msg) | RecallError::Embedding(msg) => SERVER_ERROR_STATUS_REGEX.is_match(msg),
+        _ => false,
+    }
+}
+
+/// Seconds to wait before retry `attempt`: the provider's hint for rate
+/// limits, otherwise exponential (5s, 10s, 20s, ...)
+fn retry_backoff_secs(error: &RecallError, attempt: u32) -> u64 {
+    let secs = match error {
+        RecallError::RateLimit(wait_secs) => *wait_secs,
+        _ => 5u64.saturating_mul(1 << (attempt - 1).min(10)),
+    };
+    secs.min(MAX_RETRY_BACKOFF_SECS)
+}
+
+/// Status to record for a failed ingestion, keeping provider refusals distinct from errors
+fn failure_status(error: &RecallError) -> DocumentStatus {
+    match error {
+        RecallError::ContentBlocked(_) => DocumentStatus::Blocked,
+        _ => DocumentStatus::Failed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmProvider;
+    use std::sync::atomic::Ordering;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_ingest_existing_document_with_mock_llm() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let database = Arc::new(
+            Database::new(&temp_dir.path().join("test.db"), Path::new("."))
+                .expect("Failed to create database"),
+        );
+
+        // Without sqlite-vec there is nowhere to store vectors; ingest FTS-only
+        let vec_available = database.validate_vec_extension().unwrap_or(false);
+        let settings = Settings {
+            embeddings_enabled: vec_available,
+            ..Settings::default()
+        };
+
+        let mock = Arc::new(MockLlmProvider::default());
+        let engine = IngestionEngine::new(
+            database.clone(),
+            Arc::new(RwLock::new(Some(LlmClient::mock(mock.clone())))),
+            Arc::new(RwLock::new(settings)),
+        );
+
+        let path = temp_dir.path().join("notes.md");
+        std::fs::write(
+            &path,
+            "# Orbital mechanics\n\nA Hohmann transfer moves a spacecraft between two circular \
+             orbits using two engine burns at opposite points of an elliptical transfer orbit.",
+        )
+        .unwrap();
+
+        let doc = engine.create_document(&path, IngestionSource::Manual).unwrap();
+        database.insert_document(&doc).unwrap();
+
+        let app = tauri::test::mock_app();
+        let ingested = engine
+            .ingest_existing_document(&doc, app.handle())
+            .await
+            .expect("Ingestion failed");
+
+        assert_eq!(ingested.status, DocumentStatus::Completed);
+        assert_eq!(ingested.title, "Mock title");
+        assert!(!database.get_chunks_for_document(&doc.id).unwrap().is_empty());
+        assert!(!database.fts_search("Hohmann", 5).unwrap().is_empty());
+        assert_eq!(mock.embed_calls.load(Ordering::SeqCst) > 0, vec_available);
+    }
+
+    #[test]
+    fn test_unknown_files_are_unsupported_not_disabled() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let database = Arc::new(
+            Database::new(&temp_dir.path().join("test.db"), Path::new("."))
+                .expect("Failed to create database"),
+        );
+        let settings = Settings {
+            enabled_file_types: vec![FileType::Text],
+            ..Settings::default()
+        };
+        let engine = IngestionEngine::new(database, Arc::new(RwLock::new(None)), Arc::new(RwLock::new(settings)));
+
+        let log = temp_dir.path().join("server.log");
+        assert_eq!(engine.disabled_file_type(&log), None);
+        assert_eq!(engine.check_file_type_allowed(&log).unwrap_err().to_string(), "Ingestion error: Unsupported file type");
+
+        let pdf = temp_dir.path().join("report.pdf");
+        assert_eq!(engine.disabled_file_type(&pdf), Some(FileType::Pdf));
+        assert!(engine.check_file_type_allowed(&pdf).unwrap_err().to_string().contains("disabled in settings"));
+        assert!(engine.check_file_type_allowed(&temp_dir.path().join("notes.txt")).is_ok());
+    }
+
+    #[test]
+    fn test_only_transient_errors_are_retried() {
+        assert!(is_transient_error(&RecallError::RateLimit(30)));
+        assert!(is_transient_error(&RecallError::Embedding("Batch API error 503".to_string())));
+        assert!(!is_transient_error(&RecallError::ContentBlocked("SAFETY".to_string())));
+        assert!(!is_transient_error(&RecallError::Ingestion("Unsupported file type".to_string())));
+        assert!(is_transient_error(&RecallError::LlmApi("File upload failed at byte 500 502 Bad Gateway: ".to_string())));
+        assert!(!is_transient_error(&RecallError::LlmApi("API error 400 Bad Request: invalid argument".to_string())));
+        assert!(!is_transient_error(&RecallError::LlmApi("Could not parse text regions: EOF".to_string())));
+
+        assert_eq!(retry_backoff_secs(&RecallError::RateLimit(45), 1), 45);
+        assert_eq!(retry_backoff_secs(&RecallError::RateLimit(600), 1), MAX_RETRY_BACKOFF_SECS);
+        assert_eq!(retry_backoff_secs(&RecallError::LlmApi("API error 500".to_string()), 3), 20);
+    }
+
+    #[test]
+    fn test_folder_dedupe_scope_keeps_watched_folders_apart() {
+        let root = tempdir().unwrap();
+        let (work, archive) = (root.path().join("work"), root.path().join("archive"));
+        std::fs::create_dir_all(work.join("sub")).unwrap();
+        std::fs::create_dir_all(&archive).unwrap();
+
+        let mut settings = Settings {
+            watched_folders: vec![work.to_string_lossy().to_string(), archive.to_string_lossy().to_string()],
+            ..Settings::default()
+        };
+        let in_work = work.join("report.pdf");
+        let in_subfolder = work.join("sub").join("report.pdf");
+        let in_archive = archive.join("report.pdf");
+
+        assert!(settings.in_dedupe_scope(&in_work, &in_archive));
+
+        settings.dedupe_scope = "folder".to_string();
+        assert!(settings.in_dedupe_scope(&in_work, &in_subfolder));
+        assert!(!settings.in_dedupe_scope(&in_work, &in_archive));
+    }
+
+    #[test]
+    fn test_user_requested_files_jump_the_queue() {
+        let start = Utc::now();
+        let queued = |id: &str, source: IngestionSource, sequence: u64| QueuedFile {
+            path: format!("/tmp/{}", id),
+            document_id: id.to_string(),
+            priority: source.into(),
+            // The clock stepped back while queueing; the sequence still decides
+            queued_at: start - chrono::Duration::seconds(sequence as i64),
+            sequence,
+            batch_id: None,
+            folder_rank: None,
+        };
+        // Downloads is watched after Work, so its older file waits
+        let ranked = |id: &str, sequence: u64, rank: usize| QueuedFile {
+            folder_rank: Some(rank),
+            ..queued(id, IngestionSource::Watched, sequence)
+        };
+        let mut queue = vec![
+            ranked("downloads", 0, 1),
+            ranked("work", 1, 0),
+            queued("watched-1", IngestionSource::Watched, 2),
+            queued("capture", IngestionSource::Capture, 3),
+            queued("manual-1", IngestionSource::Manual, 4),
+            queued("manual-2", IngestionSource::Manual, 5),
+        ];
+
+        let mut order = Vec::new();
+        while let Some(next) = next_in_queue(&queue) {
+            let id = next.document_id.clone();
+            queue.retain(|q| q.document_id != id);
+            order.push(id);
+        }
+        assert_eq!(order, ["manual-1", "manual-2", "work", "downloads", "watched-1", "capture"]);
+    }
+
+    #[test]
+    fn test_capture_note_leads_extracted_text() {
+        let ocr = ExtractedContent::Text { text: "Quarterly revenue chart".to_string(), pages: None };
+        let text = with_capture_note(ocr, "Numbers for the board deck").plain_text();
+        assert_eq!(text, "Note: Numbers for the board deck\n\nQuarterly revenue chart");
+
+        let blank = ExtractedContent::Text { text: " ".to_string(), pages: None };
+        assert_eq!(with_capture_note(blank, "Error dialog").plain_text(), "Note: Error dialog");
+    }
+
+    #[test]
+    fn test_file_hash_streams_across_buffer_boundaries() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("notes.txt");
+        let data = "a line of notes\n".repeat(HASH_BUFFER_SIZE / 8);
+        std::fs::write(&path, &data).unwrap();
+
+        assert_eq!(compute_file_hash(&path).unwrap(), hex::encode(Sha256::digest(data.as_bytes())));
+    }
+
+    #[test]
+    fn test_normalized_content_hash_ignores_case_and_whitespace() {
+        let pdf = normalized_content_hash("The  Article\n\nTitle\r\n  Body text.").unwrap();
+        let web = normalized_content_hash("the article title body TEXT.").unwrap();
+        assert_eq!(pdf, web);
+        assert_ne!(pdf, normalized_content_hash("the article title body text!").unwrap());
+        assert_eq!(normalized_content_hash(" \n\t"), None);
+    }
+}
@@ -0,0 +1,79 @@
+//! Portable PDF page rendering via pdfium, used for vision OCR on platforms
+//! without Windows.Data.Pdf. pdfium is loaded at runtime from
+//! `PDFIUM_LIBRARY_PATH`, the executable's directory, or the system library path.
+
+use super::vision_ocr::capped_render_scale;
+use crate::error::{RecallError, Result};
+use image::codecs::jpeg::JpegEncoder;
+use pdfium_render::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// JPEG quality for rendered pages (matches the size/legibility tradeoff of the Windows encoder)
+const JPEG_QUALITY: u8 = 85;
+
+fn bind_pdfium() -> Result<Pdfium> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Ok(path) = std::env::var("PDFIUM_LIBRARY_PATH") {
+        candidates.push(PathBuf::from(path));
+    }
+    if let Some(exe_dir) = std::env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf)) {
+        candidates.push(Pdfium::pdfium_platform_library_name_at_path(&exe_dir));
+    }
+
+    for candidate in &candidates {
+        if let Ok(bindings) = Pdfium::bind_to_library(candidate) {
+            tracing::debug!("Loaded pdfium from {:?}", candidate);
+            return Ok(Pdfium::new(bindings));
+        }
+    }
+
+    Pdfium::bind_to_system_library().map(Pdfium::new).map_err(|e| {
+        RecallError::Ocr(format!(
+            "PDF rendering needs the pdfium library, which was not found ({}). \
+             Install pdfium or set PDFIUM_LIBRARY_PATH to its location.",
+            e
+        ))
+    })
+}
+
+/// Render PDF pages to JPEG images for Gemini Vision OCR at `scale`,
+/// stopping after `max_pages` when given
+pub(super) fn render_pdf_pages_to_jpeg(
+    pdf_path: &Path,
+    max_pages: Option<u32>,
+    scale: f64,
+) -> Result<Vec<(u32, Vec<u8>)>> {
+    let pdfium = bind_pdfium()?;
+
+    tracing::info!("Opening PDF for Gemini Vision rendering: {:?}", pdf_path);
+
+    let document = pdfium
+        .load_pdf_from_file(pdf_path, None)
+        .map_err(|e| RecallError::Ocr(format!("Failed to load PDF: {}", e)))?;
+
+    let page_count = document.pages().len();
+    tracing::info!("PDF has {} pages (rendering at {}x scale)", page_count, scale);
+
+    let mut page_images: Vec<(u32, Vec<u8>)> = Vec::new();
+
+    let pages_to_render = max_pages.map_or(usize::MAX, |n| n as usize);
+    for (i, page) in document.pages().iter().enumerate().take(pages_to_render) {
+        let page_number = i as u32 + 1;
+        let page_scale = capped_render_scale(page.width().value as f64, page.height().value as f64, scale, page_number);
+        let render_config = PdfRenderConfig::new().scale_page_by_factor(page_scale as f32);
+        let bitmap = page
+            .render_with_config(&render_config)
+            .map_err(|e| RecallError::Ocr(format!("Failed to render page {}: {}", page_number, e)))?;
+
+        let rgb = bitmap.as_image().to_rgb8();
+        let mut jpeg_data = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg_data, JPEG_QUALITY)
+            .encode_image(&rgb)
+            .map_err(|e| RecallError::Ocr(format!("Failed to encode page {}: {}", page_number, e)))?;
+
+        tracing::info!("Page {}/{} rendered: {} KB JPEG", page_number, page_count, jpeg_data.len() / 1024);
+        page_images.push((page_number, jpeg_data));
+    }
+
+    Ok(page_images)
+}
@@ -0,0 +1,101 @@
+//! Lightweight document previews for the library view.
+//!
+//! Visual previews (first PDF page, image thumbnails) are written as PNGs to a
+//! cache directory keyed by file hash, so a file is only rendered once.
+
+#[cfg(not(windows))]
+use super::pdf_render::render_pdf_pages_to_jpeg;
+#[cfg(windows)]
+use super::windows_ocr::render_pdf_pages_to_jpeg;
+use super::vision_ocr::GEMINI_RENDER_SCALE;
+use crate::database::{Document, FileType};
+use crate::error::{RecallError, Result};
+use image::DynamicImage;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Width of rendered preview images in pixels
+const PREVIEW_WIDTH: u32 = 320;
+
+/// Lines returned for text previews when the caller does not ask for a count
+pub const DEFAULT_PREVIEW_LINES: usize = 20;
+
+/// Preview of a document's content
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DocumentPreview {
+    /// Cached PNG preview image
+    Image { path: String },
+    /// Leading lines of a text document
+    Text { lines: Vec<String>, truncated: bool },
+    /// No preview is available for this file type
+    None,
+}
+
+/// Build a preview for a document, reusing a cached image when one exists
+pub async fn render_document_preview(
+    document: &Document,
+    cache_dir: &Path,
+    max_lines: usize,
+) -> Result<DocumentPreview> {
+    let source = PathBuf::from(&document.file_path);
+
+    match document.file_type {
+        FileType::Text | FileType::Markdown | FileType::Subtitle => {
+            let content = tokio::fs::read(&source).await?;
+            Ok(text_preview(&String::from_utf8_lossy(&content), max_lines))
+        }
+        FileType::Pdf | FileType::Image | FileType::Screenshot => {
+            let cached = cache_dir.join(format!("{}.png", document.file_hash));
+            if cached.exists() {
+                return Ok(DocumentPreview::Image {
+                    path: cached.to_string_lossy().to_string(),
+                });
+            }
+
+            std::fs::create_dir_all(cache_dir)?;
+            let is_pdf = document.file_type == FileType::Pdf;
+            let target = cached.clone();
+            tokio::task::spawn_blocking(move || {
+                let image = if is_pdf {
+                    render_first_pdf_page(&source)?
+                } else {
+                    image::open(&source)
+                        .map_err(|e| RecallError::Ingestion(format!("Failed to open image: {}", e)))?
+                };
+                image
+                    .thumbnail(PREVIEW_WIDTH, u32::MAX)
+                    .save(&target)
+                    .map_err(|e| RecallError::Ingestion(format!("Failed to save preview: {}", e)))
+            })
+            .await
+            .map_err(|e| RecallError::Ingestion(format!("Preview task failed: {}", e)))??;
+
+            Ok(DocumentPreview::Image {
+                path: cached.to_string_lossy().to_string(),
+            })
+        }
+        _ => Ok(DocumentPreview::None),
+    }
+}
+
+/// Render page 1 of a PDF with the same renderer used for Vision OCR
+fn render_first_pdf_page(path: &Path) -> Result<DynamicImage> {
+    let (_, jpeg) = render_pdf_pages_to_jpeg(path, Some(1), GEMINI_RENDER_SCALE)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| RecallError::PdfExtract("PDF has no pages".to_string()))?;
+
+    image::load_from_memory(&jpeg)
+        .map_err(|e| RecallError::PdfExtract(format!("Failed to decode rendered page: {}", e)))
+}
+
+fn text_preview(content: &str, max_lines: usize) -> DocumentPreview {
+    let mut lines = content.lines();
+    let preview: Vec<String> = lines.by_ref().take(max_lines).map(str::to_string).collect();
+
+    DocumentPreview::Text {
+        lines: preview,
+        truncated: lines.next().is_some(),
+    }
+}
@@ -0,0 +1,166 @@
+use super::TimedSegment;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Merged segments are closed once they span this many seconds
+const SEGMENT_TARGET_SECS: f64 = 60.0;
+/// ...or once their text reaches this many characters
+const SEGMENT_TARGET_CHARS: usize = 1500;
+
+// Markup inside cue text: HTML-like tags (<i>, <v Speaker>, <00:01.000>) and SSA overrides ({\an8})
+static CUE_MARKUP_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>|\{\\[^}]*\}").unwrap());
+
+/// A single subtitle cue
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleCue {
+    pub start_time: f64,
+    pub end_time: f64,
+    pub text: String,
+}
+
+/// Parse SRT or WebVTT content into cues. Blocks without a timing line
+/// (the WEBVTT header, NOTE, STYLE and REGION blocks) are skipped.
+pub fn parse_subtitles(content: &str) -> Vec<SubtitleCue> {
+    let normalized = content.trim_start_matches('\u{feff}').replace("\r\n", "\n").replace('\r', "\n");
+    let mut cues = Vec::new();
+
+    for block in normalized.split("\n\n") {
+        let mut lines = block.lines().skip_while(|l| !l.contains("-->"));
+        let Some(timing) = lines.next() else {
+            continue;
+        };
+        let Some((start, end)) = parse_timing_line(timing) else {
+            continue;
+        };
+
+        let text = lines
+            .map(|l| CUE_MARKUP_REGEX.replace_all(l, "").trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if !text.is_empty() {
+            cues.push(SubtitleCue {
+                start_time: start,
+                end_time: end.max(start),
+                text,
+            });
+        }
+    }
+
+    cues.sort_by(|a, b| a.start_time.partial_cmp(&b.start_time).unwrap_or(std::cmp::Ordering::Equal));
+    cues
+}
+
+/// Parse "00:01:02,345 --> 00:01:04,000" (SRT) or "01:02.345 --> 01:04.000 align:start" (VTT)
+fn parse_timing_line(line: &str) -> Option<(f64, f64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    Some((parse_timestamp(start.trim())?, parse_timestamp(end)?))
+}
+
+/// Parse "hh:mm:ss,mmm", "hh:mm:ss.mmm" or "mm:ss.mmm" into seconds
+fn parse_timestamp(ts: &str) -> Option<f64> {
+    let ts = ts.replace(',', ".");
+    let parts: Vec<&str> = ts.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m, s] => (h.parse::<f64>().ok()?, m.parse::<f64>().ok()?, s.parse::<f64>().ok()?),
+        [m, s] => (0.0, m.parse::<f64>().ok()?, s.parse::<f64>().ok()?),
+        _ => return None,
+    };
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Merge cues into segments sized for chunking. Overlapping cues are folded
+/// into the current segment, and rolling captions that repeat the previous
+/// line are dropped.
+pub fn merge_cues(cues: &[SubtitleCue]) -> Vec<TimedSegment> {
+    let mut segments: Vec<TimedSegment> = Vec::new();
+    let mut current: Option<TimedSegment> = None;
+    let mut last_text = "";
+
+    for cue in cues {
+        if cue.text == last_text {
+            if let Some(ref mut seg) = current {
+                seg.end_time = seg.end_time.max(cue.end_time);
+            }
+            continue;
+        }
+        last_text = &cue.text;
+
+        match current {
+            Some(ref mut seg)
+                if cue.start_time < seg.end_time
+                    || (seg.end_time - seg.start_time < SEGMENT_TARGET_SECS
+                        && seg.text.len() < SEGMENT_TARGET_CHARS) =>
+            {
+                seg.text.push(' ');
+                seg.text.push_str(&cue.text);
+                seg.end_time = seg.end_time.max(cue.end_time);
+            }
+            _ => {
+                if let Some(seg) = current.take() {
+                    segments.push(seg);
+                }
+                current = Some(TimedSegment {
+                    start_time: cue.start_time,
+                    end_time: cue.end_time,
+                    text: cue.text.clone(),
+                    topics: vec!["subtitle".to_string()],
+                });
+            }
+        }
+    }
+
+    if let Some(seg) = current {
+        segments.push(seg);
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_srt_and_vtt() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:03,500\r\n<i>Hello</i> there\r\n\r\n2\r\n00:01:05,250 --> 00:01:07,000\r\nSecond line\r\nwraps here\r\n";
+        let cues = parse_subtitles(srt);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].start_time, 1.0);
+        assert_eq!(cues[0].text, "Hello there");
+        assert_eq!(cues[1].start_time, 65.25);
+        assert_eq!(cues[1].text, "Second line wraps here");
+
+        let vtt = "WEBVTT\n\nNOTE a comment\n\n00:02.000 --> 00:04.000 align:start\n<v Alice>Hi</v>\n\nintro\n01:00:00.000 --> 01:00:02.000\nLater\n";
+        let cues = parse_subtitles(vtt);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].start_time, 2.0);
+        assert_eq!(cues[0].text, "Hi");
+        assert_eq!(cues[1].start_time, 3600.0);
+    }
+
+    #[test]
+    fn test_merge_cues() {
+        let cue = |start: f64, end: f64, text: &str| SubtitleCue {
+            start_time: start,
+            end_time: end,
+            text: text.to_string(),
+        };
+        let cues = vec![
+            cue(0.0, 2.0, "one"),
+            cue(2.0, 4.0, "one"),
+            cue(4.0, 30.0, "two"),
+            cue(30.0, 62.0, "three"),
+            cue(62.0, 64.0, "four"),
+        ];
+
+        let segments = merge_cues(&cues);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "one two three");
+        assert_eq!(segments[0].end_time, 62.0);
+        assert_eq!(segments[1].start_time, 62.0);
+        assert_eq!(segments[1].text, "four");
+    }
+}
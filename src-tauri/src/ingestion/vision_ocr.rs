@@ -0,0 +1,140 @@
+//! Gemini Vision OCR for scanned PDFs. Pages are rasterized by the platform
+//! renderer (Windows.Data.Pdf on Windows, pdfium elsewhere) and sent in batches.
+
+use crate::error::{RecallError, Result};
+use std::path::Path;
+
+#[cfg(not(windows))]
+use super::pdf_render::render_pdf_pages_to_jpeg;
+#[cfg(windows)]
+use super::windows_ocr::render_pdf_pages_to_jpeg;
+
+/// Scale factor for Gemini Vision OCR (lower is fine - AI understands context)
+/// 2.0 = 144 DPI equivalent - sufficient for Gemini's vision capabilities
+pub const GEMINI_RENDER_SCALE: f64 = 2.0;
+
+/// Scale factor for rendering PDFs for Windows OCR (higher = better quality but slower)
+/// 3.0 = 216 DPI equivalent - needed for character-level OCR accuracy
+pub const WINDOWS_OCR_RENDER_SCALE: f64 = 3.0;
+
+/// Range accepted for the configured render scales
+pub const MIN_RENDER_SCALE: f64 = 0.5;
+pub const MAX_RENDER_SCALE: f64 = 8.0;
+
+/// Longest side of a rendered page, in pixels
+const MAX_RENDER_DIMENSION: f64 = 12_000.0;
+/// Pixels in a rendered page (48 MP is about 190 MB as BGRA)
+const MAX_RENDER_PIXELS: f64 = 48_000_000.0;
+
+/// Render scales for PDF OCR, taken from settings
+#[derive(Debug, Clone, Copy)]
+pub struct OcrRenderScales {
+    /// Windows.Media.Ocr needs more pixels per glyph than vision models
+    pub windows: f64,
+    pub vision: f64,
+}
+
+impl Default for OcrRenderScales {
+    fn default() -> Self {
+        Self {
+            windows: WINDOWS_OCR_RENDER_SCALE,
+            vision: GEMINI_RENDER_SCALE,
+        }
+    }
+}
+
+impl OcrRenderScales {
+    pub fn from_settings(settings: &crate::state::Settings) -> Self {
+        Self {
+            windows: settings.ocr_render_scale,
+            vision: settings.vision_ocr_render_scale,
+        }
+    }
+}
+
+/// Scale to render a `width` x `height` point page at, lowered when the
+/// image would exceed the pixel caps so huge pages can't exhaust memory
+pub(super) fn capped_render_scale(width: f64, height: f64, scale: f64, page_number: u32) -> f64 {
+    let scale = scale.clamp(MIN_RENDER_SCALE, MAX_RENDER_SCALE);
+    let longest = width.max(height).max(1.0);
+    let area = (width * height).max(1.0);
+    let capped = scale
+        .min(MAX_RENDER_DIMENSION / longest)
+        .min((MAX_RENDER_PIXELS / area).sqrt());
+
+    if capped < scale {
+        tracing::warn!(
+            "Page {} ({:.0}x{:.0} pt) is too large to render at {}x, using {:.2}x",
+            page_number,
+            width,
+            height,
+            scale,
+            capped
+        );
+    }
+    capped
+}
+
+/// Extract text from a PDF using Gemini Vision API with progress callback,
+/// reading at most `max_pages` pages when given
+pub async fn ocr_pdf_gemini_with_progress(
+    pdf_path: &Path,
+    llm: &crate::llm::LlmClient,
+    render_scale: f64,
+    max_pages: Option<u32>,
+    on_progress: Option<&super::extractor::ProgressCallback>,
+) -> Result<String> {
+    tracing::info!("Starting Gemini Vision OCR for PDF: {:?}", pdf_path);
+
+    let path_owned = pdf_path.to_path_buf();
+
+    if let Some(cb) = on_progress {
+        cb("Rendering PDF pages...");
+    }
+
+    // Render PDF pages to optimized JPEG images in a blocking thread
+    let page_images = tokio::task::spawn_blocking(move || {
+        render_pdf_pages_to_jpeg(&path_owned, max_pages, render_scale)
+    })
+    .await
+    .map_err(|e| RecallError::Ocr(format!("Task join error: {}", e)))??;
+
+    if page_images.is_empty() {
+        return Err(RecallError::Ocr("No pages rendered from PDF".to_string()));
+    }
+
+    let total_pages = page_images.len();
+    let total_size: usize = page_images.iter().map(|(_, d)| d.len()).sum();
+    tracing::info!(
+        "Rendered {} pages ({} KB total), sending to Gemini Vision...",
+        total_pages,
+        total_size / 1024
+    );
+
+    if let Some(cb) = on_progress {
+        cb(&format!("OCR processing {} pages with Gemini...", total_pages));
+    }
+
+    // Send pages to Gemini Vision OCR with batching
+    let text = llm.ocr_pages_batched(page_images).await?;
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capped_render_scale() {
+        // US Letter is 612x792 pt
+        assert_eq!(capped_render_scale(612.0, 792.0, 3.0, 1), 3.0);
+        assert_eq!(capped_render_scale(612.0, 792.0, 100.0, 1), MAX_RENDER_SCALE);
+
+        // A 5000 pt poster can't render at 4x
+        let scale = capped_render_scale(5000.0, 3000.0, 4.0, 1);
+        assert!(scale < 4.0);
+        assert!(5000.0 * scale <= MAX_RENDER_DIMENSION + 1e-6);
+        assert!(5000.0 * 3000.0 * scale * scale <= MAX_RENDER_PIXELS + 1.0);
+    }
+}
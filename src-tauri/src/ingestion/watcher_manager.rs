@@ -1,19 +1,34 @@
-use crate::database::Database;
+use crate::database::{Database, IngestionSource};
 use crate::error::{RecallError, Result};
-use crate::ingestion::{FileWatcher, IngestionEngine, WatchEvent};
+use crate::ingestion::{is_excluded, FileWatcher, IngestionEngine, WatchEvent};
 use parking_lot::RwLock;
 use std::collections::{HashSet, HashMap};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Runtime, async_runtime};
 use tokio::sync::mpsc;
 
+/// Whether the watcher is watching, and whether it acts on what it sees
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatcherState {
+    Running,
+    /// Still watching, but changes wait until the watcher is resumed
+    Paused,
+    Stopped,
+}
+
 pub struct WatcherManager {
     watcher: RwLock<Option<FileWatcher>>,
     event_rx: RwLock<Option<mpsc::Receiver<WatchEvent>>>,
     watched_paths: RwLock<HashSet<PathBuf>>,
+    /// Exclude patterns per watched folder, shared with the event processor
+    exclusions: Arc<RwLock<HashMap<PathBuf, Vec<String>>>>,
     is_running: RwLock<bool>,
+    /// Shared with the event processor, which holds events while set
+    paused: Arc<AtomicBool>,
 }
 
 impl WatcherManager {
@@ -22,7 +37,9 @@ impl WatcherManager {
             watcher: RwLock::new(None),
             event_rx: RwLock::new(None),
             watched_paths: RwLock::new(HashSet::new()),
+            exclusions: Arc::new(RwLock::new(HashMap::new())),
             is_running: RwLock::new(false),
+            paused: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -48,25 +65,36 @@ impl WatcherManager {
         *self.watcher.write() = None;
         *self.event_rx.write() = None;
         *self.is_running.write() = false;
+        self.paused.store(false, Ordering::SeqCst);
         self.watched_paths.write().clear();
+        self.exclusions.write().clear();
         tracing::info!("File watcher stopped");
     }
 
-    pub fn add_folder(&self, path: PathBuf) -> Result<()> {
+    pub fn add_folder(&self, path: PathBuf, excludes: Vec<String>) -> Result<()> {
         let mut watcher_guard = self.watcher.write();
         if let Some(ref mut watcher) = *watcher_guard {
             watcher.watch(&path)?;
+            self.exclusions.write().insert(path.clone(), excludes);
             self.watched_paths.write().insert(path);
         }
         Ok(())
     }
 
+    /// Replace the exclude patterns of a watched folder
+    pub fn set_excludes(&self, path: &Path, excludes: Vec<String>) {
+        if let Some(current) = self.exclusions.write().get_mut(path) {
+            *current = excludes;
+        }
+    }
+
     pub fn remove_folder(&self, path: &PathBuf) -> Result<()> {
         tracing::info!("remove_folder called for: {:?}", path);
         let mut watcher_guard = self.watcher.write();
         if let Some(ref mut watcher) = *watcher_guard {
             watcher.unwatch(path)?;
             self.watched_paths.write().remove(path);
+            self.exclusions.write().remove(path);
         }
         Ok(())
     }
@@ -79,6 +107,33 @@ impl WatcherManager {
         *self.is_running.read()
     }
 
+    /// Hold file events without dropping the watches or folder config.
+    /// Changes made while paused are ingested on resume.
+    pub fn pause(&self) -> Result<()> {
+        if !self.is_running() {
+            return Err(RecallError::Config("File watcher is not running".to_string()));
+        }
+        self.paused.store(true, Ordering::SeqCst);
+        tracing::info!("File watcher paused");
+        Ok(())
+    }
+
+    pub fn resume(&self) {
+        if self.paused.swap(false, Ordering::SeqCst) {
+            tracing::info!("File watcher resumed");
+        }
+    }
+
+    pub fn state(&self) -> WatcherState {
+        if !self.is_running() {
+            WatcherState::Stopped
+        } else if self.paused.load(Ordering::SeqCst) {
+            WatcherState::Paused
+        } else {
+            WatcherState::Running
+        }
+    }
+
     /// Start the background task that processes file events
     pub fn spawn_event_processor<R: Runtime + 'static>(
         &self,
@@ -97,11 +152,16 @@ impl WatcherManager {
             }
         };
 
+        let exclusions = self.exclusions.clone();
+        let paused = self.paused.clone();
+
         async_runtime::spawn(async move {
             tracing::info!("File watcher event processor started - waiting for events");
 
             // Debounce tracking: path -> last event time
             let mut pending_files: HashMap<PathBuf, Instant> = HashMap::new();
+            // Deletions seen while paused, applied on resume
+            let mut pending_deletes: HashSet<PathBuf> = HashSet::new();
             // Files currently being processed (to avoid duplicate processing)
             let mut processing_files: HashSet<PathBuf> = HashSet::new();
 
@@ -114,6 +174,11 @@ impl WatcherManager {
                     Ok(Some(event)) => {
                         match event {
                             WatchEvent::Created(path) | WatchEvent::Modified(path) => {
+                                if excluded_by_folder(&exclusions.read(), &path) {
+                                    tracing::debug!("Path excluded from watched folder, skipping: {:?}", path);
+                                    continue;
+                                }
+
                                 // Skip if already being processed
                                 if processing_files.contains(&path) {
                                     tracing::debug!("File already being processed, skipping event: {:?}", path);
@@ -121,6 +186,7 @@ impl WatcherManager {
                                 }
 
                                 // Update debounce timestamp (reset timer on each event)
+                                pending_deletes.remove(&path);
                                 pending_files.insert(path.clone(), Instant::now());
                                 tracing::debug!("Debouncing file event: {:?}", path);
                             }
@@ -129,15 +195,10 @@ impl WatcherManager {
                                 pending_files.remove(&path);
                                 processing_files.remove(&path);
 
-                                let path_str = path.to_string_lossy().to_string();
-                                tracing::info!("File deleted, removing from index: {:?}", path);
-
-                                if let Ok(Some(doc)) = database.get_document_by_path(&path_str) {
-                                    if let Err(e) = database.delete_document(&doc.id) {
-                                        tracing::error!("Failed to delete document: {}", e);
-                                    } else {
-                                        let _ = app_handle.emit("document-deleted", &doc.id);
-                                    }
+                                if paused.load(Ordering::SeqCst) {
+                                    pending_deletes.insert(path);
+                                } else {
+                                    remove_deleted_file(&database, &app_handle, &path);
                                 }
                             }
                         }
@@ -151,6 +212,14 @@ impl WatcherManager {
                     }
                 }
 
+                // Hold everything until resumed
+                if paused.load(Ordering::SeqCst) {
+                    continue;
+                }
+                for path in pending_deletes.drain() {
+                    remove_deleted_file(&database, &app_handle, &path);
+                }
+
                 // Process files that have been debounced long enough
                 let now = Instant::now();
                 let ready_files: Vec<PathBuf> = pending_files
@@ -175,13 +244,21 @@ impl WatcherManager {
                         continue;
                     }
 
+                    if let Some(file_type) = ingestion_engine.disabled_file_type(&path) {
+                        tracing::debug!("File type {} disabled, skipping: {:?}", file_type, path);
+                        continue;
+                    }
+
                     // Mark as processing to prevent duplicate events
                     processing_files.insert(path.clone());
 
                     tracing::info!("Auto-ingesting file (after debounce): {:?}", path);
                     let _ = app_handle.emit("auto-ingest-start", path_str.clone());
 
-                    match ingestion_engine.ingest_file(&path, &app_handle).await {
+                    match ingestion_engine
+                        .ingest_file_from(&path, IngestionSource::Watched, &app_handle)
+                        .await
+                    {
                         Ok(doc) => {
                             tracing::info!("Auto-ingested successfully: {}", doc.title);
                             let _ = app_handle.emit("auto-ingest-complete", &doc);
@@ -205,3 +282,24 @@ impl WatcherManager {
         });
     }
 }
+
+/// Remove the document of a file deleted from a watched folder
+fn remove_deleted_file<R: Runtime>(database: &Database, app_handle: &AppHandle<R>, path: &Path) {
+    let path_str = path.to_string_lossy().to_string();
+    tracing::info!("File deleted, removing from index: {:?}", path);
+
+    if let Ok(Some(doc)) = database.get_document_by_path(&path_str) {
+        if let Err(e) = database.delete_document(&doc.id) {
+            tracing::error!("Failed to delete document: {}", e);
+        } else {
+            let _ = app_handle.emit("document-deleted", &doc.id);
+        }
+    }
+}
+
+/// Whether `path` matches the exclude patterns of the watched folder it is in
+fn excluded_by_folder(exclusions: &HashMap<PathBuf, Vec<String>>, path: &Path) -> bool {
+    exclusions
+        .iter()
+        .any(|(folder, patterns)| path.starts_with(folder) && is_excluded(path, folder, patterns))
+}
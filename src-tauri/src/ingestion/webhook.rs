@@ -0,0 +1,73 @@
+//! POSTs a notification to `ingestion_webhook_url` when a document finishes
+//! ingesting, so external workflows can pick it up. Delivery runs in the
+//! background and never affects the ingestion result.
+
+use crate::database::{Document, FileType, IngestionSource};
+use serde::Serialize;
+use std::time::Duration;
+
+/// Attempts per notification, including the first
+const WEBHOOK_MAX_ATTEMPTS: u32 = 3;
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestionWebhookPayload {
+    pub event: &'static str,
+    pub document_id: String,
+    pub title: String,
+    pub file_type: FileType,
+    pub file_path: String,
+    pub ingestion_source: IngestionSource,
+    pub chunk_count: i64,
+    pub ingested_at: String,
+}
+
+impl IngestionWebhookPayload {
+    pub fn ingested(doc: &Document) -> Self {
+        Self {
+            event: "document.ingested",
+            document_id: doc.id.clone(),
+            title: doc.title.clone(),
+            file_type: doc.file_type,
+            file_path: doc.file_path.clone(),
+            ingestion_source: doc.ingestion_source,
+            chunk_count: doc.chunk_count,
+            ingested_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Deliver `payload` to `url`, retrying failures and non-2xx responses
+/// with a short backoff (1s, 2s). Gives up with a warning.
+pub async fn deliver_webhook(url: &str, payload: &IngestionWebhookPayload) {
+    let client = match reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Failed to create webhook client: {}", e);
+            return;
+        }
+    };
+
+    for attempt in 1..=WEBHOOK_MAX_ATTEMPTS {
+        let error = match client.post(url).json(payload).send().await {
+            Ok(response) if response.status().is_success() => {
+                tracing::debug!("Webhook delivered for document {}", payload.document_id);
+                return;
+            }
+            Ok(response) => format!("HTTP {}", response.status()),
+            Err(e) => e.to_string(),
+        };
+
+        if attempt == WEBHOOK_MAX_ATTEMPTS {
+            tracing::warn!(
+                "Giving up on webhook for document {} after {} attempts: {}",
+                payload.document_id,
+                attempt,
+                error
+            );
+        } else {
+            tracing::debug!("Webhook attempt {} failed, retrying: {}", attempt, error);
+            tokio::time::sleep(Duration::from_secs(1 << (attempt - 1))).await;
+        }
+    }
+}
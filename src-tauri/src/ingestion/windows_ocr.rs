@@ -15,18 +15,14 @@ use windows::{
     Storage::{StorageFile, Streams::InMemoryRandomAccessStream},
 };
 
-/// Scale factor for rendering PDFs for Windows OCR (higher = better quality but slower)
-/// 3.0 = 216 DPI equivalent - needed for character-level OCR accuracy
-const RENDER_SCALE: f64 = 3.0;
-
-/// Scale factor for Gemini Vision OCR (lower is fine - AI understands context)
-/// 2.0 = 144 DPI equivalent - sufficient for Gemini's vision capabilities
-const GEMINI_RENDER_SCALE: f64 = 2.0;
+#[cfg(windows)]
+use super::vision_ocr::{capped_render_scale, WINDOWS_OCR_RENDER_SCALE};
 
 /// Extract text from a PDF using Windows built-in APIs with progress callback
 #[cfg(windows)]
 pub async fn ocr_pdf_windows_with_progress(
     pdf_path: &Path,
+    render_scale: f64,
     on_progress: Option<&super::extractor::ProgressCallback>,
 ) -> Result<String> {
     tracing::info!("Starting Windows OCR for PDF: {:?}", pdf_path);
@@ -40,7 +36,7 @@ pub async fn ocr_pdf_windows_with_progress(
     // Run the entire OCR process in a blocking thread
     // Windows COM APIs don't play well with tokio's async runtime
     let result = tokio::task::spawn_blocking(move || {
-        ocr_pdf_sync(&path_owned)
+        ocr_pdf_sync(&path_owned, render_scale)
     })
     .await
     .map_err(|e| RecallError::Ocr(format!("Task join error: {}", e)))?;
@@ -51,12 +47,12 @@ pub async fn ocr_pdf_windows_with_progress(
 /// Extract text from a PDF using Windows built-in APIs (backward compatible)
 #[cfg(windows)]
 pub async fn ocr_pdf_windows(pdf_path: &Path) -> Result<String> {
-    ocr_pdf_windows_with_progress(pdf_path, None).await
+    ocr_pdf_windows_with_progress(pdf_path, WINDOWS_OCR_RENDER_SCALE, None).await
 }
 
 /// Synchronous OCR implementation
 #[cfg(windows)]
-fn ocr_pdf_sync(pdf_path: &Path) -> Result<String> {
+fn ocr_pdf_sync(pdf_path: &Path, render_scale: f64) -> Result<String> {
     let path_str = pdf_path.to_string_lossy().to_string();
     let hstring_path = HSTRING::from(&path_str);
 
@@ -101,8 +97,9 @@ fn ocr_pdf_sync(pdf_path: &Path) -> Result<String> {
         let page_size = page.Size()
             .map_err(|e| RecallError::Ocr(format!("Failed to get page size: {}", e)))?;
 
-        let scaled_width = (page_size.Width as f64 * RENDER_SCALE) as u32;
-        let scaled_height = (page_size.Height as f64 * RENDER_SCALE) as u32;
+        let scale = capped_render_scale(page_size.Width as f64, page_size.Height as f64, render_scale, i + 1);
+        let scaled_width = (page_size.Width as f64 * scale) as u32;
+        let scaled_height = (page_size.Height as f64 * scale) as u32;
 
         // Create render options with higher resolution
         let render_options = PdfPageRenderOptions::new()
@@ -116,7 +113,7 @@ fn ocr_pdf_sync(pdf_path: &Path) -> Result<String> {
         let stream = InMemoryRandomAccessStream::new()
             .map_err(|e| RecallError::Ocr(format!("Failed to create stream: {}", e)))?;
 
-        tracing::debug!("Rendering page {} at {}x{} ({}x scale)...", i + 1, scaled_width, scaled_height, RENDER_SCALE);
+        tracing::debug!("Rendering page {} at {}x{} ({:.2}x scale)...", i + 1, scaled_width, scaled_height, scale);
 
         // Render page to stream at higher resolution
         page.RenderWithOptionsToStreamAsync(&stream, &render_options)
@@ -253,60 +250,15 @@ pub async fn ocr_pdf_windows(_pdf_path: &Path) -> Result<String> {
     Err(RecallError::Ocr("Windows OCR is only available on Windows".to_string()))
 }
 
-/// Extract text from a PDF using Gemini Vision API with progress callback
-#[cfg(windows)]
-pub async fn ocr_pdf_gemini_with_progress(
-    pdf_path: &Path,
-    llm: &crate::llm::LlmClient,
-    on_progress: Option<&super::extractor::ProgressCallback>,
-) -> Result<String> {
-    tracing::info!("Starting Gemini Vision OCR for PDF: {:?}", pdf_path);
-
-    let path_owned = pdf_path.to_path_buf();
-
-    if let Some(cb) = on_progress {
-        cb("Rendering PDF pages...");
-    }
-
-    // Render PDF pages to optimized JPEG images in a blocking thread
-    let page_images = tokio::task::spawn_blocking(move || {
-        render_pdf_pages_to_jpeg(&path_owned)
-    })
-    .await
-    .map_err(|e| RecallError::Ocr(format!("Task join error: {}", e)))??;
-
-    if page_images.is_empty() {
-        return Err(RecallError::Ocr("No pages rendered from PDF".to_string()));
-    }
-
-    let total_pages = page_images.len();
-    let total_size: usize = page_images.iter().map(|(_, d)| d.len()).sum();
-    tracing::info!(
-        "Rendered {} pages ({} KB total), sending to Gemini Vision...",
-        total_pages,
-        total_size / 1024
-    );
-
-    if let Some(cb) = on_progress {
-        cb(&format!("OCR processing {} pages with Gemini...", total_pages));
-    }
-
-    // Send pages to Gemini Vision OCR with batching
-    let text = llm.ocr_pages_batched(page_images).await?;
-
-    Ok(text)
-}
-
-/// Extract text from a PDF using Gemini Vision API (backward compatible)
-#[cfg(windows)]
-pub async fn ocr_pdf_gemini(pdf_path: &Path, llm: &crate::llm::LlmClient) -> Result<String> {
-    ocr_pdf_gemini_with_progress(pdf_path, llm, None).await
-}
-
 /// Render PDF pages to optimized JPEG images for Gemini Vision OCR
-/// Uses lower resolution and JPEG compression for smaller file sizes
+/// Uses lower resolution and JPEG compression for smaller file sizes.
+/// Stops after `max_pages` when given.
 #[cfg(windows)]
-fn render_pdf_pages_to_jpeg(pdf_path: &Path) -> Result<Vec<(u32, Vec<u8>)>> {
+pub(super) fn render_pdf_pages_to_jpeg(
+    pdf_path: &Path,
+    max_pages: Option<u32>,
+    render_scale: f64,
+) -> Result<Vec<(u32, Vec<u8>)>> {
     use windows::{
         Graphics::Imaging::{BitmapEncoder, BitmapPixelFormat},
         Storage::Streams::{DataReader, InMemoryRandomAccessStream},
@@ -332,12 +284,14 @@ fn render_pdf_pages_to_jpeg(pdf_path: &Path) -> Result<Vec<(u32, Vec<u8>)>> {
     let page_count = pdf_doc.PageCount()
         .map_err(|e| RecallError::Ocr(format!("Failed to get page count: {}", e)))?;
 
-    tracing::info!("PDF has {} pages (rendering at {}x scale)", page_count, GEMINI_RENDER_SCALE);
+    tracing::info!("PDF has {} pages (rendering at {}x scale)", page_count, render_scale);
 
     let mut page_images: Vec<(u32, Vec<u8>)> = Vec::new();
 
+    let pages_to_render = max_pages.map_or(page_count, |n| n.min(page_count));
+
     // Process each page
-    for i in 0..page_count {
+    for i in 0..pages_to_render {
         // Get page
         let page = pdf_doc.GetPage(i)
             .map_err(|e| RecallError::Ocr(format!("Failed to get page {}: {}", i + 1, e)))?;
@@ -346,8 +300,9 @@ fn render_pdf_pages_to_jpeg(pdf_path: &Path) -> Result<Vec<(u32, Vec<u8>)>> {
         let page_size = page.Size()
             .map_err(|e| RecallError::Ocr(format!("Failed to get page size: {}", e)))?;
 
-        let scaled_width = (page_size.Width as f64 * GEMINI_RENDER_SCALE) as u32;
-        let scaled_height = (page_size.Height as f64 * GEMINI_RENDER_SCALE) as u32;
+        let scale = capped_render_scale(page_size.Width as f64, page_size.Height as f64, render_scale, i + 1);
+        let scaled_width = (page_size.Width as f64 * scale) as u32;
+        let scaled_height = (page_size.Height as f64 * scale) as u32;
 
         // Create render options
         let render_options = PdfPageRenderOptions::new()
@@ -436,9 +391,3 @@ fn render_pdf_pages_to_jpeg(pdf_path: &Path) -> Result<Vec<(u32, Vec<u8>)>> {
 
     Ok(page_images)
 }
-
-/// Fallback for non-Windows platforms
-#[cfg(not(windows))]
-pub async fn ocr_pdf_gemini(_pdf_path: &Path, _llm: &crate::llm::LlmClient) -> Result<String> {
-    Err(RecallError::Ocr("Gemini Vision OCR requires Windows for PDF rendering".to_string()))
-}
@@ -4,6 +4,8 @@ pub mod database;
 pub mod error;
 pub mod ingestion;
 pub mod llm;
+pub mod local_api;
+pub mod logging;
 pub mod notifications;
 pub mod rag;
 pub mod state;
@@ -13,22 +15,14 @@ use std::sync::Arc;
 use tauri::Manager;
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Load environment variables from .env file (for development)
     let _ = dotenvy::dotenv();
 
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "recall_os=debug,tauri=info".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    // Initialize tracing (the log file is attached once app data is known)
+    logging::init();
 
     tracing::info!("Starting RECALL.OS");
 
@@ -91,43 +85,39 @@ pub fn run() {
                 }
             }
 
-            // Register global hotkey for screen capture
-            let hotkey_str = state.settings.read().capture_hotkey.clone();
-            if let Ok(shortcut) = hotkey_str.parse::<Shortcut>() {
-                let state_for_hotkey = state.clone();
-                let app_handle_for_hotkey = app_handle.clone();
+            // Start the local HTTP API if enabled
+            if state.settings.read().enable_local_api {
+                let state = state.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = local_api::start(state).await {
+                        tracing::warn!("Failed to start local API: {}", e);
+                    }
+                });
+            }
 
-                if let Err(e) = app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, event| {
-                    if event.state == ShortcutState::Pressed {
-                        tracing::info!("Global hotkey triggered for screen capture");
-                        let state = state_for_hotkey.clone();
-                        let app_handle = app_handle_for_hotkey.clone();
+            // Warm up search so the first question isn't slowed by a cold start
+            if state.settings.read().warmup_on_startup {
+                let state = state.clone();
+                tauri::async_runtime::spawn(async move {
+                    state.warm_up().await;
+                });
+            }
 
-                        // Spawn async task for capture
-                        tauri::async_runtime::spawn(async move {
-                            match state.capture_manager.capture_now(&app_handle).await {
-                                Ok(result) => {
-                                    tracing::info!("Hotkey capture successful: {:?}", result.file_path);
-                                }
-                                Err(e) => {
-                                    tracing::warn!("Hotkey capture failed: {}", e);
-                                }
-                            }
-                        });
-                    }
-                }) {
-                    tracing::warn!("Failed to register global shortcut '{}': {}", hotkey_str, e);
-                } else {
-                    tracing::info!("Registered global shortcut: {}", hotkey_str);
-                }
-            } else {
-                tracing::warn!("Invalid hotkey format: {}", hotkey_str);
+            // Register global hotkey for screen capture
+            let hotkey_str = state.settings.read().capture_hotkey.clone();
+            if let Err(e) = capture::register_capture_hotkey(
+                &app_handle,
+                state.capture_manager.clone(),
+                &hotkey_str,
+            ) {
+                tracing::warn!("{}", e);
             }
 
             // Set up system tray
             let show_item = MenuItem::with_id(app, "show", "Show RECALL.OS", true, None::<&str>)?;
+            let snooze_item = MenuItem::with_id(app, "snooze", "Snooze Notifications (1 hour)", true, None::<&str>)?;
             let quit_item = MenuItem::with_id(app, "quit", "Exit", true, None::<&str>)?;
-            let tray_menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+            let tray_menu = Menu::with_items(app, &[&show_item, &snooze_item, &quit_item])?;
 
             let _tray = TrayIconBuilder::new()
                 .icon(app.default_window_icon().unwrap().clone())
@@ -141,6 +131,10 @@ pub fn run() {
                                 let _ = window.set_focus();
                             }
                         }
+                        "snooze" => {
+                            let until = notifications::snooze(chrono::Duration::hours(1));
+                            tracing::info!("Notifications snoozed from tray until {}", until);
+                        }
                         "quit" => {
                             app.exit(0);
                         }
@@ -212,24 +206,60 @@ pub fn run() {
             // Database commands
             commands::database::get_documents,
             commands::database::get_document,
+            commands::database::record_document_access,
+            commands::database::get_recent_documents,
+            commands::database::get_document_history,
+            commands::database::get_document_metadata,
+            commands::database::set_document_metadata_field,
             commands::database::delete_document,
             commands::database::get_chunks_for_document,
+            commands::database::get_chunk_context,
+            commands::database::render_document_preview,
             commands::database::get_ingestion_stats,
+            commands::database::get_ingestion_timeline,
             commands::database::open_file_in_default_app,
             commands::database::reset_database,
+            commands::database::compact_database,
+            commands::database::validate_documents,
+            commands::database::repair_documents,
+            commands::database::convert_chunk_storage,
             commands::database::categorize_document,
             commands::database::categorize_all_documents,
+            commands::database::cancel_categorization,
             commands::database::get_content_categories,
+            commands::database::get_category_distribution,
+            commands::database::detect_document_language,
             // Ingestion commands
             commands::ingestion::ingest_file,
+            commands::ingestion::ingest_file_with_password,
             commands::ingestion::ingest_directory,
+            commands::ingestion::sync_directory,
+            commands::ingestion::relink_document,
+            commands::ingestion::check_missing_sources,
+            commands::ingestion::backfill_embeddings,
+            commands::ingestion::preview_chunking,
+            commands::ingestion::estimate_ingestion_cost,
             commands::ingestion::cancel_ingestion,
+            commands::ingestion::remove_from_queue,
+            commands::ingestion::resolve_duplicate_content,
             commands::ingestion::get_ingestion_progress,
+            commands::ingestion::get_document_ingestion_log,
             commands::ingestion::reingest_document,
+            commands::ingestion::reingest_as,
+            commands::ingestion::reingest_documents,
             commands::ingestion::get_ingestion_queue,
+            commands::ingestion::get_ffmpeg_status,
             // Search commands
             commands::search::search_documents,
             commands::search::hybrid_search,
+            commands::search::search_json,
+            commands::search::find_similar_chunks,
+            commands::search::search_documents_grouped,
+            commands::search::search_within_document,
+            commands::search::rebuild_document_relations,
+            commands::search::get_related_documents,
+            commands::search::get_document_graph,
+            commands::search::cluster_documents,
             // RAG commands
             commands::rag::query,
             commands::rag::query_with_sources,
@@ -240,31 +270,53 @@ pub fn run() {
             commands::conversations::create_conversation,
             commands::conversations::delete_conversation,
             commands::conversations::rename_conversation,
+            commands::conversations::set_conversation_scope,
+            commands::conversations::pin_chunk,
+            commands::conversations::unpin_chunk,
+            commands::conversations::get_pinned_chunks,
             // Settings commands
             commands::settings::get_settings,
             commands::settings::update_settings,
             commands::settings::validate_api_key,
+            commands::settings::get_llm_status,
             commands::settings::get_api_key_unmasked,
             commands::settings::clear_api_key,
+            commands::settings::get_log_file_path,
+            commands::settings::export_logs,
             // Watcher commands
             commands::watcher::get_watcher_status,
             commands::watcher::start_watcher,
             commands::watcher::stop_watcher,
+            commands::watcher::pause_watcher,
+            commands::watcher::resume_watcher,
             commands::watcher::add_watched_folder,
             commands::watcher::remove_watched_folder,
+            commands::watcher::set_watched_folder_excludes,
+            commands::watcher::reorder_watched_folders,
             commands::watcher::toggle_auto_ingest,
+            // Local API commands
+            commands::local_api::get_local_api_status,
+            commands::local_api::start_local_api,
+            commands::local_api::stop_local_api,
+            commands::local_api::regenerate_local_api_token,
             // Notification commands
             commands::notification::notification_window_ready,
             commands::notification::focus_main_window,
             commands::notification::focus_main_window_with_highlights,
             commands::notification::test_notification,
+            commands::notification::snooze_notifications,
+            commands::notification::get_notification_snooze,
             // Capture commands
             commands::capture::start_screen_capture,
             commands::capture::stop_screen_capture,
             commands::capture::capture_now,
+            commands::capture::capture_with_note,
+            commands::capture::test_capture,
             commands::capture::get_capture_status,
             commands::capture::get_running_applications,
             commands::capture::update_capture_settings,
+            commands::capture::set_capture_app_intervals,
+            commands::capture::validate_hotkey,
             commands::capture::pause_screen_capture,
             commands::capture::resume_screen_capture,
             commands::capture::cleanup_old_captures,
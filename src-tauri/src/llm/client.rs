@@ -1,6 +1,8 @@
 use super::{
-    EmbeddingClient, GenerateRequest, GenerateResponse, LlmProvider, RateLimiter, TokenUsage,
-    VideoAnalysisRequest, VideoAnalysisResponse, CitationRef,
+    retry_after_secs, EmbeddingClient, HealthTracker, ProviderHealth, QueryEmbeddingCache, GenerateRequest,
+    GenerateResponse, LlmProvider, OcrTextRegion, RateLimiter,
+    TokenUsage, VideoAnalysisRequest, VideoAnalysisResponse, CitationRef, ContextImage,
+    DEFAULT_RATE_LIMIT_WAIT_SECS, MAX_RATE_LIMIT_WAIT_SECS,
 };
 use crate::error::{RecallError, Result};
 use async_trait::async_trait;
@@ -9,16 +11,32 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
 const GEMINI_API_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
 const GEMINI_FILES_API_URL: &str = "https://generativelanguage.googleapis.com/upload/v1beta/files";
 
 /// Default request timeout (2 minutes)
-const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
 /// Timeout for file uploads (5 minutes for large files)
-const UPLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+pub const UPLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+/// Bytes sent per resumable upload request (a multiple of the protocol's 256 KiB granularity)
+const UPLOAD_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+/// Times an interrupted upload is resumed before giving up
+const UPLOAD_MAX_RESUMES: u32 = 5;
+/// Largest file the Gemini Files API accepts (2 GB)
+pub const MAX_FILES_API_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+/// Pages of an uploaded PDF transcribed per OCR request
+const UPLOADED_PDF_PAGES_PER_REQUEST: u32 = 10;
+/// Output token limit of each uploaded PDF OCR request
+const UPLOADED_PDF_OCR_MAX_TOKENS: u32 = 8192;
+/// Requests per minute allowed before the local limiter throttles (free tier)
+pub const DEFAULT_REQUESTS_PER_MINUTE: u64 = 60;
+/// Attempts `generate` makes when Gemini returns no text
+const EMPTY_RESPONSE_MAX_ATTEMPTS: u32 = 3;
 
 // Pre-compiled regex patterns for performance
 static CITATION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[(\d+)\]").unwrap());
@@ -30,6 +48,13 @@ pub struct LlmClient {
     api_key: String,
     embedding_client: EmbeddingClient,
     rate_limiter: Arc<RateLimiter>,
+    generation_timeout: Duration,
+    upload_timeout: Duration,
+    /// Outcomes of recent API calls, for status reporting
+    health: Arc<HealthTracker>,
+    /// Test-only provider that replaces every Gemini call
+    #[cfg(test)]
+    mock: Option<Arc<super::MockLlmProvider>>,
 }
 
 impl LlmClient {
@@ -44,7 +69,21 @@ impl LlmClient {
             client,
             embedding_client: EmbeddingClient::new(api_key.clone(), "gemini-embedding-001".to_string()),
             api_key,
-            rate_limiter: Arc::new(RateLimiter::new(60)), // 60 RPM default
+            rate_limiter: Arc::new(RateLimiter::new(DEFAULT_REQUESTS_PER_MINUTE)),
+            generation_timeout: DEFAULT_REQUEST_TIMEOUT,
+            upload_timeout: UPLOAD_TIMEOUT,
+            health: Arc::new(HealthTracker::default()),
+            #[cfg(test)]
+            mock: None,
+        }
+    }
+
+    /// Client whose provider calls are answered by `provider` instead of Gemini
+    #[cfg(test)]
+    pub fn mock(provider: Arc<super::MockLlmProvider>) -> Self {
+        Self {
+            mock: Some(provider),
+            ..Self::new(String::new())
         }
     }
 
@@ -53,12 +92,122 @@ impl LlmClient {
         self
     }
 
+    /// Turn a 429 response into a `RateLimit` error, honouring the provider's
+    /// retry hint and pausing the shared limiter until it expires
+    async fn rate_limited(&self, headers: &reqwest::header::HeaderMap, body: &str) -> RecallError {
+        let wait_secs = retry_after_secs(headers, body).unwrap_or(DEFAULT_RATE_LIMIT_WAIT_SECS);
+        self.rate_limiter.pause_for(Duration::from_secs(wait_secs)).await;
+        RecallError::RateLimit(wait_secs)
+    }
+
+    /// Reuse an existing query embedding cache
+    pub fn with_query_cache(mut self, cache: Arc<QueryEmbeddingCache>) -> Self {
+        self.embedding_client = self.embedding_client.with_query_cache(cache);
+        self
+    }
+
+    pub fn query_cache(&self) -> Arc<QueryEmbeddingCache> {
+        self.embedding_client.query_cache()
+    }
+
+    /// Embed a search query, skipping the API for recently seen queries
+    pub async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        #[cfg(test)]
+        if self.mock.is_some() {
+            return Ok(super::MockLlmProvider::embedding_for(query));
+        }
+        let result = self.embedding_client.embed_query(query).await;
+        self.health.record(&result);
+        result
+    }
+
+    /// Provider health derived from recent calls (no request is made)
+    pub fn health(&self) -> ProviderHealth {
+        self.health.snapshot()
+    }
+
+    /// Requests per minute the local limiter allows
+    pub fn requests_per_minute(&self) -> u64 {
+        self.rate_limiter.requests_per_minute()
+    }
+
+    /// Whether the local limiter has plenty of requests to spare
+    pub async fn has_rate_headroom(&self) -> bool {
+        self.rate_limiter.has_headroom().await
+    }
+
+    pub fn with_embedding_batch_size(mut self, batch_size: usize) -> Self {
+        self.embedding_client = self.embedding_client.with_batch_size(batch_size);
+        self
+    }
+
+    /// Override per-operation request timeouts (zero keeps the default)
+    pub fn with_timeouts(mut self, generation: Duration, upload: Duration, embedding: Duration) -> Self {
+        if !generation.is_zero() {
+            self.generation_timeout = generation;
+        }
+        if !upload.is_zero() {
+            self.upload_timeout = upload;
+        }
+        if !embedding.is_zero() {
+            self.embedding_client = self.embedding_client.with_timeout(embedding);
+        }
+        self
+    }
+
     /// Upload a file to Gemini's Files API for use in generation
-    /// Uses resumable upload protocol for reliability
-    async fn upload_file(&self, data: &[u8], mime_type: &str, display_name: &str) -> Result<String> {
+    /// Uses resumable upload protocol for reliability. `session` holds the
+    /// upload URL of an interrupted upload, which is continued from the
+    /// server's received offset instead of starting over.
+    async fn upload_file(
+        &self,
+        source: &UploadSource<'_>,
+        mime_type: &str,
+        display_name: &str,
+        session: &mut Option<String>,
+    ) -> Result<String> {
+        let result = self.send_upload_file(source, mime_type, display_name, session).await;
+        self.health.record(&result);
+        result
+    }
+
+    async fn send_upload_file(
+        &self,
+        source: &UploadSource<'_>,
+        mime_type: &str,
+        display_name: &str,
+        session: &mut Option<String>,
+    ) -> Result<String> {
+        let total = source.len().await?;
+        let (upload_url, offset) = match session.clone() {
+            Some(upload_url) => match self.query_upload(&upload_url).await {
+                Ok(UploadStatus::Active(offset)) => {
+                    tracing::info!("Resuming file upload at {} of {} bytes", offset, total);
+                    (upload_url, offset)
+                }
+                Ok(UploadStatus::Final(file_uri)) => return Ok(file_uri),
+                Err(e) => {
+                    // The session expired or was rejected; start a new one next time
+                    if !matches!(e, RecallError::Http(_)) {
+                        *session = None;
+                    }
+                    return Err(e);
+                }
+            },
+            None => {
+                let upload_url = self.start_upload(total, mime_type, display_name).await?;
+                *session = Some(upload_url.clone());
+                (upload_url, 0)
+            }
+        };
+
+        self.upload_from_offset(&upload_url, source, total, mime_type, offset).await
+    }
+
+    /// Step 1: Initiate a resumable upload, returning its upload URL
+    async fn start_upload(&self, content_length: usize, mime_type: &str, display_name: &str) -> Result<String> {
         self.rate_limiter.wait().await;
 
-        // Step 1: Initiate resumable upload
         let init_url = format!(
             "{}?key={}",
             GEMINI_FILES_API_URL, self.api_key
@@ -75,20 +224,21 @@ impl LlmClient {
             .post(&init_url)
             .header("X-Goog-Upload-Protocol", "resumable")
             .header("X-Goog-Upload-Command", "start")
-            .header("X-Goog-Upload-Header-Content-Length", data.len().to_string())
+            .header("X-Goog-Upload-Header-Content-Length", content_length.to_string())
             .header("X-Goog-Upload-Header-Content-Type", mime_type)
             .header("Content-Type", "application/json")
-            .timeout(UPLOAD_TIMEOUT)
+            .timeout(self.upload_timeout)
             .body(metadata.to_string())
             .send()
             .await?;
 
         if !init_response.status().is_success() {
             let status = init_response.status();
+            let headers = init_response.headers().clone();
             let error_text = init_response.text().await.unwrap_or_default();
 
             if status.as_u16() == 429 {
-                return Err(RecallError::RateLimit(60));
+                return Err(self.rate_limited(&headers, &error_text).await);
             }
 
             return Err(RecallError::LlmApi(format!(
@@ -98,51 +248,102 @@ impl LlmClient {
         }
 
         // Get upload URL from response header
-        let upload_url = init_response
+        Ok(init_response
             .headers()
             .get("x-goog-upload-url")
             .and_then(|v| v.to_str().ok())
             .ok_or_else(|| RecallError::LlmApi("No upload URL in init response".to_string()))?
-            .to_string();
+            .to_string())
+    }
 
-        // Step 2: Upload the file data
-        let upload_response = self
+    /// Ask the server how much of an interrupted upload it received
+    async fn query_upload(&self, upload_url: &str) -> Result<UploadStatus> {
+        let response = self
             .client
-            .post(&upload_url)
-            .header("X-Goog-Upload-Command", "upload, finalize")
-            .header("X-Goog-Upload-Offset", "0")
-            .header("Content-Type", mime_type)
-            .timeout(UPLOAD_TIMEOUT)
-            .body(data.to_vec())
+            .post(upload_url)
+            .header("X-Goog-Upload-Command", "query")
             .send()
             .await?;
 
-        if !upload_response.status().is_success() {
-            let status = upload_response.status();
-            let error_text = upload_response.text().await.unwrap_or_default();
-
-            if status.as_u16() == 429 {
-                return Err(RecallError::RateLimit(60));
-            }
-
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
             return Err(RecallError::LlmApi(format!(
-                "File upload failed {}: {}",
+                "File upload status query failed {}: {}",
                 status, error_text
             )));
         }
 
-        let response_json: serde_json::Value = upload_response.json().await?;
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        if header("x-goog-upload-status").as_deref() == Some("final") {
+            let response_json: serde_json::Value = response.json().await?;
+            return uploaded_file_uri(&response_json).map(UploadStatus::Final);
+        }
 
-        // Extract file URI from response
-        let file_uri = response_json
-            .get("file")
-            .and_then(|f| f.get("uri"))
-            .and_then(|u| u.as_str())
-            .ok_or_else(|| RecallError::LlmApi("No file URI in upload response".to_string()))?
-            .to_string();
+        let received = header("x-goog-upload-size-received")
+            .and_then(|v| v.parse::<usize>().ok())
+            .ok_or_else(|| RecallError::LlmApi("No received size in upload status response".to_string()))?;
+        Ok(UploadStatus::Active(received))
+    }
 
-        tracing::info!("Uploaded file to Gemini: {}", file_uri);
-        Ok(file_uri)
+    /// Step 2: Send the `total` bytes of `source` from `offset` in chunks,
+    /// finalizing with the last one. Only one chunk is in memory at a time.
+    /// A failed chunk leaves the session resumable from the server's
+    /// received offset.
+    async fn upload_from_offset(
+        &self,
+        upload_url: &str,
+        source: &UploadSource<'_>,
+        total: usize,
+        mime_type: &str,
+        offset: usize,
+    ) -> Result<String> {
+        let mut offset = offset.min(total);
+        loop {
+            let end = (offset + UPLOAD_CHUNK_SIZE).min(total);
+            let command = if end == total { "upload, finalize" } else { "upload" };
+            let chunk = source.read(offset, end - offset).await?;
+
+            let upload_response = self
+                .client
+                .post(upload_url)
+                .header("X-Goog-Upload-Command", command)
+                .header("X-Goog-Upload-Offset", offset.to_string())
+                .header("Content-Type", mime_type)
+                .timeout(self.upload_timeout)
+                .body(chunk)
+                .send()
+                .await?;
+
+            if !upload_response.status().is_success() {
+                let status = upload_response.status();
+                let headers = upload_response.headers().clone();
+                let error_text = upload_response.text().await.unwrap_or_default();
+
+                if status.as_u16() == 429 {
+                    return Err(self.rate_limited(&headers, &error_text).await);
+                }
+
+                return Err(RecallError::LlmApi(format!(
+                    "File upload failed at byte {} {}: {}",
+                    offset, status, error_text
+                )));
+            }
+
+            if end == total {
+                let response_json: serde_json::Value = upload_response.json().await?;
+                let file_uri = uploaded_file_uri(&response_json)?;
+                tracing::info!("Uploaded file to Gemini: {}", file_uri);
+                return Ok(file_uri);
+            }
+            offset = end;
+        }
     }
 
     /// Delete a file from Gemini's Files API
@@ -173,6 +374,20 @@ impl LlmClient {
         contents: Vec<GeminiContent>,
         system_instruction: Option<&str>,
         generation_config: Option<GenerationConfig>,
+    ) -> Result<GeminiResponse> {
+        let result = self
+            .send_generate_content(model, contents, system_instruction, generation_config)
+            .await;
+        self.health.record(&result);
+        result
+    }
+
+    async fn send_generate_content(
+        &self,
+        model: &str,
+        contents: Vec<GeminiContent>,
+        system_instruction: Option<&str>,
+        generation_config: Option<GenerationConfig>,
     ) -> Result<GeminiResponse> {
         self.rate_limiter.wait().await;
 
@@ -193,15 +408,17 @@ impl LlmClient {
             .client
             .post(&url)
             .json(&request)
+            .timeout(self.generation_timeout)
             .send()
             .await?;
 
         let status = response.status();
+        let headers = response.headers().clone();
         let response_text = response.text().await.unwrap_or_default();
 
         if !status.is_success() {
             if status.as_u16() == 429 {
-                return Err(RecallError::RateLimit(60));
+                return Err(self.rate_limited(&headers, &response_text).await);
             } else if status.as_u16() == 401 || status.as_u16() == 403 {
                 return Err(RecallError::InvalidApiKey);
             }
@@ -212,6 +429,8 @@ impl LlmClient {
             )));
         }
 
+        self.rate_limiter.observe_headers(&headers).await;
+
         // Parse JSON with better error context
         let gemini_response: GeminiResponse = serde_json::from_str(&response_text)
             .map_err(|e| RecallError::LlmApi(format!(
@@ -220,6 +439,8 @@ impl LlmClient {
                 if response_text.len() > 500 { &response_text[..500] } else { &response_text }
             )))?;
 
+        check_content_blocked(&gemini_response)?;
+
         // Log raw response when no candidates returned - use error level for visibility
         if gemini_response.candidates.is_empty() {
             tracing::error!(
@@ -235,6 +456,11 @@ impl LlmClient {
 #[async_trait]
 impl LlmProvider for LlmClient {
     async fn generate(&self, request: GenerateRequest) -> Result<GenerateResponse> {
+        #[cfg(test)]
+        if let Some(mock) = &self.mock {
+            return mock.generate(request).await;
+        }
+
         // Build context XML
         let context_xml = if !request.context.is_empty() {
             let chunks_xml: String = request
@@ -274,6 +500,35 @@ When you use information from a chunk, cite it like this: [123] where 123 is the
 
         let full_prompt = format!("{}{}", context_xml, request.prompt);
 
+        // Source images go between the context and the question. Gemini
+        // mishandles system_instruction alongside InlineData (see
+        // analyze_image), so with images the instructions lead the prompt.
+        let images: Vec<(i64, &ContextImage)> = request
+            .context
+            .iter()
+            .filter_map(|c| c.image.as_ref().map(|image| (c.id, image)))
+            .collect();
+        let (prompt_parts, system_instruction) = if images.is_empty() {
+            (vec![GeminiPart::Text { text: full_prompt }], Some(system_prompt.as_str()))
+        } else {
+            let mut parts = vec![GeminiPart::Text {
+                text: format!("{}\n\n{}", system_prompt, context_xml),
+            }];
+            for (chunk_id, image) in images {
+                parts.push(GeminiPart::Text {
+                    text: format!("Image for chunk [{}]:", chunk_id),
+                });
+                parts.push(GeminiPart::InlineData {
+                    inline_data: InlineData {
+                        mime_type: image.mime_type.clone(),
+                        data: BASE64.encode(&image.data),
+                    },
+                });
+            }
+            parts.push(GeminiPart::Text { text: request.prompt.clone() });
+            (parts, None)
+        };
+
         // Build contents with conversation history
         let mut contents: Vec<GeminiContent> = Vec::new();
 
@@ -289,7 +544,7 @@ When you use information from a chunk, cite it like this: [123] where 123 is the
         // Add current user prompt with context
         contents.push(GeminiContent {
             role: "user".to_string(),
-            parts: vec![GeminiPart::Text { text: full_prompt }],
+            parts: prompt_parts,
         });
 
         let config = GenerationConfig {
@@ -298,20 +553,55 @@ When you use information from a chunk, cite it like this: [123] where 123 is the
             ..Default::default()
         };
 
-        let response = self
-            .generate_content("gemini-2.0-flash", contents, Some(&system_prompt), Some(config))
-            .await?;
+        // Gemini intermittently returns empty candidates (same bug analyze_image
+        // works around); retry with backoff rather than hand back a blank answer
+        let mut attempt = 1;
+        let (response, content) = loop {
+            let response = self
+                .generate_content("gemini-2.0-flash", contents.clone(), system_instruction, Some(config.clone()))
+                .await?;
 
-        let content = response
-            .candidates
-            .first()
-            .and_then(|c| c.content.as_ref())
+            let content = response
+                .candidates
+                .first()
+                .and_then(|c| c.content.as_ref())
                 .and_then(|content| content.parts.first())
-            .map(|p| match p {
-                GeminiPart::Text { text } => text.clone(),
-                _ => String::new(),
-            })
-            .unwrap_or_default();
+                .map(|p| match p {
+                    GeminiPart::Text { text } => text.clone(),
+                    _ => String::new(),
+                })
+                .unwrap_or_default();
+
+            if !content.trim().is_empty() {
+                if attempt > 1 {
+                    tracing::info!("Generation succeeded on attempt {}", attempt);
+                }
+                break (response, content);
+            }
+
+            if attempt >= EMPTY_RESPONSE_MAX_ATTEMPTS {
+                tracing::error!(
+                    "Generation returned empty after {} attempts (finish_reason={:?})",
+                    attempt,
+                    response.candidates.first().and_then(|c| c.finish_reason.clone())
+                );
+                return Err(RecallError::LlmApi(format!(
+                    "The model returned an empty response {} times in a row. Please try again.",
+                    attempt
+                )));
+            }
+
+            // Exponential backoff: 1s, 2s
+            let delay_ms = 1000 * (1 << (attempt - 1));
+            tracing::warn!(
+                "Generation attempt {}/{} returned empty, retrying in {}ms",
+                attempt,
+                EMPTY_RESPONSE_MAX_ATTEMPTS,
+                delay_ms
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+            attempt += 1;
+        };
 
         // Parse citations from content (looking for [id] patterns)
         let citations = parse_citations(&content);
@@ -334,10 +624,21 @@ When you use information from a chunk, cite it like this: [123] where 123 is the
     }
 
     async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
-        self.embedding_client.embed_batch(texts).await
+        #[cfg(test)]
+        if let Some(mock) = &self.mock {
+            return mock.embed(texts).await;
+        }
+        let result = self.embedding_client.embed_batch(texts).await;
+        self.health.record(&result);
+        result
     }
 
     async fn analyze_video(&self, request: VideoAnalysisRequest) -> Result<VideoAnalysisResponse> {
+        #[cfg(test)]
+        if let Some(mock) = &self.mock {
+            return mock.analyze_video(request).await;
+        }
+
         if request.frames.is_empty() {
             return Ok(VideoAnalysisResponse { segments: vec![] });
         }
@@ -371,18 +672,8 @@ Respond in JSON format:
         });
 
         // Add frames as inline images (limit to avoid context overflow)
-        let max_frames = 20;
-        let step = if request.frames.len() > max_frames {
-            request.frames.len() / max_frames
-        } else {
-            1
-        };
-
-        for (i, frame) in request.frames.iter().enumerate() {
-            if i % step != 0 {
-                continue;
-            }
-
+        for i in evenly_spaced_indices(request.frames.len(), request.max_frames) {
+            let frame = &request.frames[i];
             parts.push(GeminiPart::InlineData {
                 inline_data: InlineData {
                     mime_type: "image/jpeg".to_string(),
@@ -429,6 +720,11 @@ Respond in JSON format:
     }
 
     async fn transcribe_audio(&self, audio_data: &[u8]) -> Result<String> {
+        #[cfg(test)]
+        if let Some(mock) = &self.mock {
+            return mock.transcribe_audio(audio_data).await;
+        }
+
         let parts = vec![
             GeminiPart::Text {
                 text: "Transcribe the following audio. Provide a verbatim transcription with timestamps for each speaker turn or paragraph. Format: [MM:SS] text".to_string(),
@@ -466,6 +762,135 @@ Respond in JSON format:
     }
 }
 
+/// Finish reasons Gemini uses when it refuses to return content
+const BLOCKED_FINISH_REASONS: &[&str] = &["SAFETY", "RECITATION", "BLOCKLIST", "PROHIBITED_CONTENT", "SPII"];
+
+/// Where upload bytes come from: memory, or a file read one chunk at a time
+enum UploadSource<'a> {
+    Bytes(&'a [u8]),
+    File(&'a Path),
+}
+
+impl UploadSource<'_> {
+    async fn len(&self) -> Result<usize> {
+        match self {
+            Self::Bytes(data) => Ok(data.len()),
+            Self::File(path) => Ok(tokio::fs::metadata(path).await?.len() as usize),
+        }
+    }
+
+    /// `len` bytes starting at `offset`
+    async fn read(&self, offset: usize, len: usize) -> Result<Vec<u8>> {
+        match self {
+            Self::Bytes(data) => Ok(data[offset..offset + len].to_vec()),
+            Self::File(path) => {
+                let mut file = tokio::fs::File::open(path).await?;
+                file.seek(std::io::SeekFrom::Start(offset as u64)).await?;
+                let mut chunk = vec![0; len];
+                file.read_exact(&mut chunk).await?;
+                Ok(chunk)
+            }
+        }
+    }
+}
+
+/// Server-side state of a resumable upload session
+enum UploadStatus {
+    /// Still open, with this many bytes received
+    Active(usize),
+    /// Already finalized; holds the uploaded file's URI
+    Final(String),
+}
+
+/// File URI from a finalized upload's response body
+fn uploaded_file_uri(response_json: &serde_json::Value) -> Result<String> {
+    response_json
+        .get("file")
+        .and_then(|f| f.get("uri"))
+        .and_then(|u| u.as_str())
+        .map(|u| u.to_string())
+        .ok_or_else(|| RecallError::LlmApi("No file URI in upload response".to_string()))
+}
+
+/// Indices of at most `max` items spread evenly over `len`, always keeping the
+/// first and last
+fn evenly_spaced_indices(len: usize, max: usize) -> Vec<usize> {
+    if len <= max {
+        return (0..len).collect();
+    }
+    match max {
+        0 => vec![],
+        1 => vec![0],
+        _ => (0..max)
+            .map(|i| (i * (len - 1) + (max - 1) / 2) / (max - 1))
+            .collect(),
+    }
+}
+
+/// A prompt about a PDF uploaded to the Files API
+fn uploaded_pdf_request(file_uri: &str, prompt: String) -> Vec<GeminiContent> {
+    vec![GeminiContent {
+        role: "user".to_string(),
+        parts: vec![
+            GeminiPart::Text { text: prompt },
+            GeminiPart::FileData {
+                file_data: FileData {
+                    mime_type: "application/pdf".to_string(),
+                    file_uri: file_uri.to_string(),
+                },
+            },
+        ],
+    }]
+}
+
+/// Text of the first candidate's parts
+fn response_text(response: &GeminiResponse) -> String {
+    response
+        .candidates
+        .first()
+        .and_then(|c| c.content.as_ref())
+        .map(|content| {
+            content
+                .parts
+                .iter()
+                .filter_map(|p| match p {
+                    GeminiPart::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Turn a provider safety refusal into `RecallError::ContentBlocked`
+fn check_content_blocked(response: &GeminiResponse) -> Result<()> {
+    if let Some(reason) = response
+        .prompt_feedback
+        .as_ref()
+        .and_then(|f| f.block_reason.as_ref())
+    {
+        tracing::warn!("Request blocked by provider: {}", reason);
+        return Err(RecallError::ContentBlocked(reason.clone()));
+    }
+
+    if let Some(candidate) = response.candidates.first() {
+        let has_content = candidate
+            .content
+            .as_ref()
+            .map(|c| !c.parts.is_empty())
+            .unwrap_or(false);
+
+        if let Some(ref reason) = candidate.finish_reason {
+            if !has_content && BLOCKED_FINISH_REASONS.contains(&reason.as_str()) {
+                tracing::warn!("Response blocked with finish_reason: {}", reason);
+                return Err(RecallError::ContentBlocked(reason.clone()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Truncate a string at a word boundary, ensuring it doesn't exceed max_chars
 fn truncate_at_word_boundary(s: &str, max_chars: usize) -> String {
     if s.len() <= max_chars {
@@ -599,94 +1024,164 @@ struct UsageMetadata {
     total_token_count: u32,
 }
 
+/// Text of a PDF read through the Files API
+#[derive(Debug, Clone)]
+pub struct UploadedPdfOcr {
+    pub text: String,
+    /// Pages transcribed in full, counted from the first
+    pub pages_read: u32,
+    pub total_pages: u32,
+}
+
+/// Read pages `1..=last_page` with `read_range(first, last)`, which returns
+/// the range's text and whether the reply was cut off at the output limit.
+/// A cut-off range is retried in halves; a single page that still doesn't
+/// fit ends the read with its partial text. Returns the text and the number
+/// of pages read in full.
+async fn read_page_ranges<F, Fut>(last_page: u32, mut read_range: F) -> Result<(String, u32)>
+where
+    F: FnMut(u32, u32) -> Fut,
+    Fut: std::future::Future<Output = Result<(String, bool)>>,
+{
+    let mut text = String::new();
+    let mut pages_per_request = UPLOADED_PDF_PAGES_PER_REQUEST;
+    let mut first = 1;
+    while first <= last_page {
+        let last = (first + pages_per_request - 1).min(last_page);
+        let (range_text, cut_off) = read_range(first, last).await?;
+        if cut_off && last > first {
+            pages_per_request = (last - first).div_ceil(2);
+            tracing::info!("OCR of pages {}-{} hit the output limit, retrying {} pages at a time", first, last, pages_per_request);
+            continue;
+        }
+
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str(range_text.trim());
+        if cut_off {
+            tracing::warn!("OCR of page {} hit the output limit, stopping there", first);
+            return Ok((text, first - 1));
+        }
+        first = last + 1;
+    }
+    Ok((text, last_page))
+}
+
 impl LlmClient {
-    /// OCR a PDF file using Gemini's Files API for reliable processing
-    /// Supports PDFs up to 2GB
-    pub async fn ocr_pdf(&self, pdf_data: &[u8]) -> Result<String> {
-        // Check file size - Files API supports up to 2GB
-        const MAX_FILE_SIZE: usize = 2 * 1024 * 1024 * 1024; // 2GB limit
-        if pdf_data.len() > MAX_FILE_SIZE {
+    /// OCR a PDF file using Gemini's Files API for reliable processing.
+    /// Supports PDFs up to 2GB; reads at most `max_pages` pages when set.
+    pub async fn ocr_pdf(&self, pdf_data: &[u8], max_pages: Option<u32>) -> Result<UploadedPdfOcr> {
+        self.ocr_uploaded_pdf(UploadSource::Bytes(pdf_data), max_pages).await
+    }
+
+    /// OCR a PDF on disk through the Files API. The file is streamed in
+    /// upload chunks rather than read into memory, so PDFs too large to
+    /// extract locally (up to 2GB) still work.
+    pub async fn ocr_pdf_file(&self, path: &Path, max_pages: Option<u32>) -> Result<UploadedPdfOcr> {
+        self.ocr_uploaded_pdf(UploadSource::File(path), max_pages).await
+    }
+
+    async fn ocr_uploaded_pdf(&self, source: UploadSource<'_>, max_pages: Option<u32>) -> Result<UploadedPdfOcr> {
+        let size = source.len().await? as u64;
+        if size > MAX_FILES_API_SIZE {
             return Err(RecallError::Ingestion(format!(
                 "PDF too large ({:.1} MB). Maximum supported size is 2 GB.",
-                pdf_data.len() as f64 / (1024.0 * 1024.0)
+                size as f64 / (1024.0 * 1024.0)
             )));
         }
 
         tracing::info!("Uploading PDF ({:.1} MB) to Gemini Files API for OCR...",
-            pdf_data.len() as f64 / (1024.0 * 1024.0));
+            size as f64 / (1024.0 * 1024.0));
 
         // Upload file to Gemini Files API with retry
-        let file_uri = self.upload_file_with_retry(pdf_data, "application/pdf", "document.pdf").await?;
+        let file_uri = self.upload_file_with_retry(&source, "application/pdf", "document.pdf").await?;
 
-        // Build request using file reference
-        let parts = vec![
-            GeminiPart::Text {
-                text: "Extract ALL text from this PDF document. \
-                    This appears to be a scanned or image-based PDF. \
-                    Transcribe every word visible on each page, preserving:\n\
-                    1. Paragraph structure\n\
-                    2. Headers and section titles\n\
-                    3. Lists and bullet points\n\
-                    4. Table content (as plain text)\n\n\
-                    Do NOT summarize. Provide the complete verbatim text content.".to_string(),
-            },
-            GeminiPart::FileData {
-                file_data: FileData {
-                    mime_type: "application/pdf".to_string(),
-                    file_uri: file_uri.clone(),
-                },
-            },
-        ];
+        let result = self.ocr_uploaded_pages(&file_uri, max_pages).await;
 
-        let contents = vec![GeminiContent {
-            role: "user".to_string(),
-            parts,
-        }];
+        // Clean up uploaded file (best effort)
+        let _ = self.delete_file(&file_uri).await;
+
+        let ocr = result?;
+        tracing::info!(
+            "OCR completed, extracted {} characters from {} of {} pages",
+            ocr.text.len(),
+            ocr.pages_read,
+            ocr.total_pages
+        );
+        Ok(ocr)
+    }
 
+    /// Transcribe an uploaded PDF a few pages per request, so no reply runs
+    /// into the output token limit
+    async fn ocr_uploaded_pages(&self, file_uri: &str, max_pages: Option<u32>) -> Result<UploadedPdfOcr> {
+        let total_pages = self.uploaded_pdf_page_count(file_uri).await?;
+        let last_page = max_pages.map_or(total_pages, |max| max.min(total_pages));
+        if last_page < total_pages {
+            tracing::warn!("PDF has {} pages, only the first {} will be OCR'd (max_ocr_pages)", total_pages, last_page);
+        }
+
+        let (text, pages_read) = read_page_ranges(last_page, |first, last| async move {
+            let prompt = format!(
+                "Extract ALL text from pages {} to {} of this PDF document. \
+                 This appears to be a scanned or image-based PDF. \
+                 Transcribe every word visible on each of those pages, preserving:\n\
+                 1. Paragraph structure\n\
+                 2. Headers and section titles\n\
+                 3. Lists and bullet points\n\
+                 4. Table content (as plain text)\n\n\
+                 Do NOT summarize and do not include other pages. Provide the complete verbatim text content.",
+                first, last
+            );
+            let config = GenerationConfig {
+                max_output_tokens: Some(UPLOADED_PDF_OCR_MAX_TOKENS),
+                ..Default::default()
+            };
+            let response = self
+                .generate_content_with_retry("gemini-2.0-flash", uploaded_pdf_request(file_uri, prompt), None, Some(config), 3)
+                .await?;
+            check_content_blocked(&response)?;
+
+            let candidate = response.candidates.first();
+            let cut_off = candidate.and_then(|c| c.finish_reason.as_deref()) == Some("MAX_TOKENS");
+            Ok((response_text(&response), cut_off))
+        })
+        .await?;
+
+        Ok(UploadedPdfOcr { text, pages_read, total_pages })
+    }
+
+    /// Ask Gemini how many pages an uploaded PDF has
+    async fn uploaded_pdf_page_count(&self, file_uri: &str) -> Result<u32> {
+        let prompt = "How many pages does this PDF document have? Reply with the number only.".to_string();
         let config = GenerationConfig {
-            max_output_tokens: Some(8192), // Allow for large documents
+            max_output_tokens: Some(16),
+            temperature: Some(0.0),
             ..Default::default()
         };
+        let response = self
+            .generate_content_with_retry("gemini-2.0-flash", uploaded_pdf_request(file_uri, prompt), None, Some(config), 3)
+            .await?;
 
-        // Generate content with retry
-        let result = self.generate_content_with_retry(
-            "gemini-2.0-flash",
-            contents,
-            None,
-            Some(config),
-            3 // max retries
-        ).await;
-
-        // Clean up uploaded file (best effort)
-        let _ = self.delete_file(&file_uri).await;
-
-        match result {
-            Ok(response) => {
-                let content = response
-                    .candidates
-                    .first()
-                    .and_then(|c| c.content.as_ref())
-                .and_then(|content| content.parts.first())
-                    .map(|p| match p {
-                        GeminiPart::Text { text } => text.clone(),
-                        _ => String::new(),
-                    })
-                    .unwrap_or_default();
-
-                tracing::info!("OCR completed successfully, extracted {} characters", content.len());
-                Ok(content)
-            }
-            Err(e) => Err(e),
-        }
+        let reply = response_text(&response);
+        reply
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|&pages| pages > 0)
+            .ok_or_else(|| RecallError::Ocr(format!("Could not read the page count of the uploaded PDF (got {:?})", reply)))
     }
 
-    /// Upload a file with retry logic for rate limiting
-    async fn upload_file_with_retry(&self, data: &[u8], mime_type: &str, display_name: &str) -> Result<String> {
+    /// Upload a file with retry logic for rate limiting. Network errors
+    /// resume the same upload session rather than restarting it.
+    async fn upload_file_with_retry(&self, source: &UploadSource<'_>, mime_type: &str, display_name: &str) -> Result<String> {
         let mut retry_count = 0;
         let max_retries = 3;
+        let mut resume_count = 0;
+        let mut session = None;
 
         loop {
-            match self.upload_file(data, mime_type, display_name).await {
+            match self.upload_file(source, mime_type, display_name, &mut session).await {
                 Ok(uri) => return Ok(uri),
                 Err(RecallError::RateLimit(wait_secs)) => {
                     retry_count += 1;
@@ -694,13 +1189,27 @@ impl LlmClient {
                         return Err(RecallError::RateLimit(wait_secs));
                     }
 
-                    let backoff = std::cmp::min(wait_secs * retry_count as u64, 120);
+                    // The provider's hint is exact; only escalate on the fallback wait
+                    let backoff = if wait_secs == DEFAULT_RATE_LIMIT_WAIT_SECS {
+                        std::cmp::min(wait_secs * retry_count as u64, 120)
+                    } else {
+                        wait_secs.min(MAX_RATE_LIMIT_WAIT_SECS)
+                    };
                     tracing::warn!(
                         "Rate limited during file upload, waiting {} seconds (retry {}/{})",
                         backoff, retry_count, max_retries
                     );
                     tokio::time::sleep(std::time::Duration::from_secs(backoff)).await;
                 }
+                Err(e @ RecallError::Http(_)) if resume_count < UPLOAD_MAX_RESUMES => {
+                    resume_count += 1;
+                    let backoff = 2u64 << resume_count.min(5);
+                    tracing::warn!(
+                        "File upload interrupted ({}), resuming in {} seconds (attempt {}/{})",
+                        e, backoff, resume_count, UPLOAD_MAX_RESUMES
+                    );
+                    tokio::time::sleep(std::time::Duration::from_secs(backoff)).await;
+                }
                 Err(e) => return Err(e),
             }
         }
@@ -729,7 +1238,12 @@ impl LlmClient {
                         return Err(RecallError::RateLimit(wait_secs));
                     }
 
-                    let backoff = std::cmp::min(wait_secs * retry_count as u64, 120);
+                    // The provider's hint is exact; only escalate on the fallback wait
+                    let backoff = if wait_secs == DEFAULT_RATE_LIMIT_WAIT_SECS {
+                        std::cmp::min(wait_secs * retry_count as u64, 120)
+                    } else {
+                        wait_secs.min(MAX_RATE_LIMIT_WAIT_SECS)
+                    };
                     tracing::warn!(
                         "Rate limited during generation, waiting {} seconds (retry {}/{})",
                         backoff, retry_count, max_retries
@@ -742,6 +1256,11 @@ impl LlmClient {
     }
 
     pub async fn analyze_image(&self, image_data: &[u8], mime_type: &str) -> Result<String> {
+        #[cfg(test)]
+        if let Some(mock) = &self.mock {
+            return Ok(mock.analyze_image());
+        }
+
         tracing::info!(
             "analyze_image called: {} bytes, mime_type={}",
             image_data.len(),
@@ -802,10 +1321,7 @@ impl LlmClient {
             if let Some(ref feedback) = response.prompt_feedback {
                 if let Some(ref reason) = feedback.block_reason {
                     tracing::warn!("Image OCR blocked by API: {}", reason);
-                    return Err(RecallError::LlmApi(format!(
-                        "Image blocked by safety filter: {}",
-                        reason
-                    )));
+                    return Err(RecallError::ContentBlocked(reason.clone()));
                 }
             }
 
@@ -866,10 +1382,7 @@ impl LlmClient {
                 if let Some(ref reason) = candidate.finish_reason {
                     if reason == "SAFETY" || reason == "RECITATION" {
                         tracing::warn!("OCR blocked with finish_reason: {}", reason);
-                        return Err(RecallError::LlmApi(format!(
-                            "Image processing blocked: {}",
-                            reason
-                        )));
+                        return Err(RecallError::ContentBlocked(reason.clone()));
                     }
                 }
             }
@@ -896,6 +1409,65 @@ impl LlmClient {
         ))
     }
 
+    /// Find each line of text in an image along with where it sits, so
+    /// parts of the image can be redacted. Lines come back in reading order.
+    pub async fn ocr_text_regions(&self, image_data: &[u8], mime_type: &str) -> Result<Vec<OcrTextRegion>> {
+        #[cfg(test)]
+        if let Some(mock) = &self.mock {
+            return Ok(mock.ocr_text_regions());
+        }
+
+        // Inline prompt only, as with analyze_image
+        let parts = vec![
+            GeminiPart::Text {
+                text: r#"Find every line of text in this image. For each line, give the exact text and its bounding box as [ymin, xmin, ymax, xmax] scaled to 0-1000.
+
+Respond in JSON format:
+[{"text": "...", "box_2d": [ymin, xmin, ymax, xmax]}]
+
+If no text is visible, respond with []"#
+                    .to_string(),
+            },
+            GeminiPart::InlineData {
+                inline_data: InlineData {
+                    mime_type: mime_type.to_string(),
+                    data: BASE64.encode(image_data),
+                },
+            },
+        ];
+
+        let contents = vec![GeminiContent {
+            role: "user".to_string(),
+            parts,
+        }];
+
+        let config = GenerationConfig {
+            max_output_tokens: Some(16384),
+            temperature: Some(0.1),
+            response_mime_type: Some("application/json".to_string()),
+        };
+
+        let response = self
+            .generate_content_with_retry("gemini-2.0-flash", contents, None, Some(config), 3)
+            .await?;
+
+        let content = response
+            .candidates
+            .first()
+            .and_then(|c| c.content.as_ref())
+            .and_then(|content| content.parts.first())
+            .map(|p| match p {
+                GeminiPart::Text { text } => text.clone(),
+                _ => String::new(),
+            })
+            .unwrap_or_default();
+
+        // Unlike plain OCR, an unreadable answer is an error: callers redacting
+        // the image must not assume it was clean
+        serde_json::from_str(&content)
+            .map_err(|e| RecallError::LlmApi(format!("Could not parse text regions: {}", e)))
+    }
+
     /// OCR multiple pages in a single request (batched for efficiency)
     /// Sends up to 4 pages per request to minimize API calls
     async fn ocr_batch(&self, pages: &[(u32, &[u8])]) -> Result<Vec<(u32, String)>> {
@@ -1060,6 +1632,11 @@ impl LlmClient {
     /// Includes retry logic to handle cases where the API returns empty responses.
     /// Returns an error if title generation fails after retries.
     pub async fn generate_title(&self, text: &str, max_chars: usize) -> Result<String> {
+        #[cfg(test)]
+        if let Some(mock) = &self.mock {
+            return Ok(mock.generate_title());
+        }
+
         // If text is too short, don't bother with LLM
         let trimmed = text.trim();
         if trimmed.len() < 20 {
@@ -1230,6 +1807,7 @@ pub async fn validate_api_key(api_key: &str) -> Result<bool> {
         .await?;
 
     let status = response.status();
+    let headers = response.headers().clone();
     let body = response.text().await.unwrap_or_default();
 
     // Check for HTTP-level errors
@@ -1244,7 +1822,9 @@ pub async fn validate_api_key(api_key: &str) -> Result<bool> {
                     "API quota exceeded. Please enable billing in Google AI Studio or wait for quota reset.".to_string()
                 ));
             }
-            return Err(RecallError::RateLimit(60));
+            return Err(RecallError::RateLimit(
+                retry_after_secs(&headers, &body).unwrap_or(DEFAULT_RATE_LIMIT_WAIT_SECS),
+            ));
         }
         tracing::error!("API validation failed: {} - {}", status, body);
         return Err(RecallError::LlmApi(format!("API error {}: {}", status, extract_error_message(&body))));
@@ -1336,3 +1916,56 @@ fn extract_error_message(body: &str) -> String {
         body.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evenly_spaced_indices_keep_both_ends() {
+        assert_eq!(evenly_spaced_indices(5, 20), vec![0, 1, 2, 3, 4]);
+        assert_eq!(evenly_spaced_indices(10, 4), vec![0, 3, 6, 9]);
+
+        let picked = evenly_spaced_indices(45, 20);
+        assert_eq!(picked.len(), 20);
+        assert_eq!((picked[0], picked[19]), (0, 44));
+        assert!(picked.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[tokio::test]
+    async fn test_file_upload_source_reads_chunks_from_disk() {
+        let data: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), &data).unwrap();
+
+        let source = UploadSource::File(file.path());
+        assert_eq!(source.len().await.unwrap(), data.len());
+        assert_eq!(source.read(4_000, 3_000).await.unwrap(), data[4_000..7_000]);
+        assert_eq!(
+            source.read(9_000, 1_000).await.unwrap(),
+            UploadSource::Bytes(&data).read(9_000, 1_000).await.unwrap()
+        );
+        assert!(source.read(9_500, 1_000).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_page_ranges_shrink_until_replies_fit() {
+        let requests = std::sync::Mutex::new(Vec::new());
+        let read = |first: u32, last: u32| {
+            requests.lock().unwrap().push((first, last));
+            // Three pages fit in a reply, and page 14 never fits
+            let cut_off = last - first >= 3 || (first..=last).contains(&14);
+            let text = (first..=last).map(|p| format!("page {}", p)).collect::<Vec<_>>().join("\n");
+            async move { Ok((text, cut_off)) }
+        };
+
+        let (text, pages_read) = read_page_ranges(20, read).await.unwrap();
+        assert_eq!(pages_read, 13);
+        assert!(text.starts_with("page 1\npage 2\n\npage 3"));
+        assert!(text.ends_with("page 13\n\npage 14"));
+        assert_eq!(requests.lock().unwrap()[..3], [(1, 10), (1, 5), (1, 2)]);
+
+        let (_, pages_read) = read_page_ranges(2, |_, _| async { Ok((String::new(), false)) }).await.unwrap();
+        assert_eq!(pages_read, 2);
+    }
+}
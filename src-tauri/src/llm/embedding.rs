@@ -1,9 +1,20 @@
+use super::{retry_after_secs, QueryEmbeddingCache, DEFAULT_RATE_LIMIT_WAIT_SECS};
 use crate::error::{RecallError, Result};
+use crate::ingestion::is_transient_error;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
 
 const EMBEDDING_API_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
 
+/// Gemini's batchEmbedContents endpoint accepts at most 100 requests per call
+pub const MAX_EMBEDDING_BATCH_SIZE: usize = 100;
+/// Default timeout for a single embedding request
+pub const EMBEDDING_TIMEOUT: Duration = Duration::from_secs(120);
+/// Attempts per sub-batch before the whole embed call fails
+const SUB_BATCH_MAX_ATTEMPTS: u32 = 3;
+
 #[derive(Debug, Serialize)]
 struct EmbedRequest {
     model: String,
@@ -55,6 +66,9 @@ pub struct EmbeddingClient {
     client: Client,
     api_key: String,
     model: String,
+    batch_size: usize,
+    timeout: Duration,
+    query_cache: Arc<QueryEmbeddingCache>,
 }
 
 impl EmbeddingClient {
@@ -63,7 +77,51 @@ impl EmbeddingClient {
             client: Client::new(),
             api_key,
             model,
+            batch_size: MAX_EMBEDDING_BATCH_SIZE,
+            timeout: EMBEDDING_TIMEOUT,
+            query_cache: Arc::new(QueryEmbeddingCache::default()),
+        }
+    }
+
+    /// Share a query cache, e.g. to keep it across client rebuilds
+    pub fn with_query_cache(mut self, cache: Arc<QueryEmbeddingCache>) -> Self {
+        self.query_cache = cache;
+        self
+    }
+
+    pub fn query_cache(&self) -> Arc<QueryEmbeddingCache> {
+        self.query_cache.clone()
+    }
+
+    /// Embed a search query, reusing the cached vector for repeated queries
+    pub async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        if let Some(embedding) = self.query_cache.get(&self.model, query) {
+            tracing::debug!("Query embedding cache hit");
+            return Ok(embedding);
+        }
+
+        let embedding = self
+            .embed_batch(&[query.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        if !embedding.is_empty() {
+            self.query_cache.insert(&self.model, query, embedding.clone());
         }
+        Ok(embedding)
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set how many texts are sent per request, capped at the provider limit
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.clamp(1, MAX_EMBEDDING_BATCH_SIZE);
+        self
     }
 
     pub async fn embed_single(&self, text: &str) -> Result<Vec<f32>> {
@@ -86,6 +144,7 @@ impl EmbeddingClient {
             .client
             .post(&url)
             .json(&request)
+            .timeout(self.timeout)
             .send()
             .await?;
 
@@ -113,58 +172,119 @@ impl EmbeddingClient {
             return Ok(vec![]);
         }
 
-        // Gemini API allows up to 100 texts per batch
-        const BATCH_SIZE: usize = 100;
         let mut all_embeddings = Vec::with_capacity(texts.len());
 
-        for chunk in texts.chunks(BATCH_SIZE) {
-            let url = format!(
-                "{}/{}:batchEmbedContents?key={}",
-                EMBEDDING_API_URL, self.model, self.api_key
-            );
-
-            let requests: Vec<EmbedContentRequest> = chunk
-                .iter()
-                .map(|text| EmbedContentRequest {
-                    model: format!("models/{}", self.model),
-                    content: EmbedContent {
-                        parts: vec![EmbedPart { text: text.clone() }],
-                    },
-                    output_dimensionality: Some(768),
-                })
-                .collect();
-
-            let batch_request = BatchEmbedRequest { requests };
-
-            let response = self
-                .client
-                .post(&url)
-                .json(&batch_request)
-                .send()
-                .await?;
-
-            let status = response.status();
-            let response_text = response.text().await.unwrap_or_default();
-
-            if !status.is_success() {
-                return Err(RecallError::Embedding(format!(
-                    "Batch API error {}: {}",
-                    status, response_text
-                )));
+        // Sub-batches are sent sequentially so results stay in input order
+        for (index, sub_batch) in texts.chunks(self.batch_size).enumerate() {
+            let embeddings = self.embed_sub_batch_with_retry(sub_batch, index).await?;
+            all_embeddings.extend(embeddings);
+        }
+
+        Ok(all_embeddings)
+    }
+
+    /// Embed one sub-batch, retrying only this sub-batch on a transient failure
+    async fn embed_sub_batch_with_retry(
+        &self,
+        texts: &[String],
+        index: usize,
+    ) -> Result<Vec<Vec<f32>>> {
+        let mut last_error: Option<RecallError> = None;
+
+        for attempt in 0..SUB_BATCH_MAX_ATTEMPTS {
+            if attempt > 0 {
+                let delay = match last_error {
+                    Some(RecallError::RateLimit(wait_secs)) => Duration::from_secs(wait_secs),
+                    _ => Duration::from_secs(2u64.pow(attempt)),
+                };
+                tracing::warn!(
+                    "Retrying embedding sub-batch {} ({} texts) in {:?} (attempt {}/{})",
+                    index,
+                    texts.len(),
+                    delay,
+                    attempt + 1,
+                    SUB_BATCH_MAX_ATTEMPTS
+                );
+                tokio::time::sleep(delay).await;
             }
 
-            let batch_response: BatchEmbedResponse = serde_json::from_str(&response_text)
-                .map_err(|e| RecallError::Embedding(format!(
-                    "Failed to parse batch response: {} - Body: {}",
-                    e,
-                    if response_text.len() > 200 { &response_text[..200] } else { &response_text }
-                )))?;
+            match self.embed_sub_batch(texts).await {
+                Ok(embeddings) => return Ok(embeddings),
+                Err(e) if !is_transient_error(&e) => return Err(e),
+                Err(e) => {
+                    tracing::warn!("Embedding sub-batch {} failed: {}", index, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            RecallError::Embedding(format!("Embedding sub-batch {} failed", index))
+        }))
+    }
+
+    async fn embed_sub_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let url = format!(
+            "{}/{}:batchEmbedContents?key={}",
+            EMBEDDING_API_URL, self.model, self.api_key
+        );
+
+        let requests: Vec<EmbedContentRequest> = texts
+            .iter()
+            .map(|text| EmbedContentRequest {
+                model: format!("models/{}", self.model),
+                content: EmbedContent {
+                    parts: vec![EmbedPart { text: text.clone() }],
+                },
+                output_dimensionality: Some(768),
+            })
+            .collect();
+
+        let batch_request = BatchEmbedRequest { requests };
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&batch_request)
+            .timeout(self.timeout)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let response_text = response.text().await.unwrap_or_default();
 
-            for embedding in batch_response.embeddings {
-                all_embeddings.push(embedding.values);
+        if !status.is_success() {
+            if status.as_u16() == 429 {
+                return Err(RecallError::RateLimit(
+                    retry_after_secs(&headers, &response_text).unwrap_or(DEFAULT_RATE_LIMIT_WAIT_SECS),
+                ));
             }
+            return Err(RecallError::Embedding(format!(
+                "Batch API error {}: {}",
+                status, response_text
+            )));
         }
 
-        Ok(all_embeddings)
+        let batch_response: BatchEmbedResponse = serde_json::from_str(&response_text)
+            .map_err(|e| RecallError::Embedding(format!(
+                "Failed to parse batch response: {} - Body: {}",
+                e,
+                if response_text.len() > 200 { &response_text[..200] } else { &response_text }
+            )))?;
+
+        if batch_response.embeddings.len() != texts.len() {
+            return Err(RecallError::Embedding(format!(
+                "Batch API returned {} embeddings for {} texts",
+                batch_response.embeddings.len(),
+                texts.len()
+            )));
+        }
+
+        Ok(batch_response
+            .embeddings
+            .into_iter()
+            .map(|embedding| embedding.values)
+            .collect())
     }
 }
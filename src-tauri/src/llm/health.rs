@@ -0,0 +1,137 @@
+use crate::error::{RecallError, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Outcomes kept for the recent success/failure counts
+const RECENT_OUTCOMES: usize = 20;
+
+/// Provider state as of the most recent API call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderState {
+    /// No call has been made since the client was created
+    #[default]
+    Unknown,
+    Healthy,
+    RateLimited,
+    InvalidKey,
+    /// The provider could not be reached (connection failure or timeout)
+    Offline,
+    /// The provider answered with an error
+    Degraded,
+    /// No API key is configured
+    NotConfigured,
+}
+
+/// Snapshot of recent provider outcomes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    pub state: ProviderState,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    /// Successes among the last few calls
+    pub recent_successes: usize,
+    /// Failures among the last few calls
+    pub recent_failures: usize,
+    /// When a provider-requested pause ends
+    pub rate_limited_until: Option<DateTime<Utc>>,
+}
+
+/// Records the outcome of each API call so status checks need no extra request.
+/// Shared between clones of a client.
+#[derive(Default)]
+pub struct HealthTracker {
+    inner: Mutex<TrackerState>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    health: ProviderHealth,
+    recent: VecDeque<bool>,
+}
+
+impl HealthTracker {
+    /// Record the outcome of an API call
+    pub fn record<T>(&self, result: &Result<T>) {
+        let now = Utc::now();
+        let mut inner = self.inner.lock();
+
+        let (state, success) = match result {
+            Ok(_) => (ProviderState::Healthy, true),
+            Err(e) => (classify(e), matches!(e, RecallError::ContentBlocked(_))),
+        };
+
+        if inner.recent.len() == RECENT_OUTCOMES {
+            inner.recent.pop_front();
+        }
+        inner.recent.push_back(success);
+
+        let health = &mut inner.health;
+        health.state = state;
+        if success {
+            health.last_success_at = Some(now);
+        } else if let Err(e) = result {
+            health.last_failure_at = Some(now);
+            health.last_error = Some(e.to_string());
+        }
+        if let Err(RecallError::RateLimit(wait_secs)) = result {
+            health.rate_limited_until = Some(now + chrono::Duration::seconds(*wait_secs as i64));
+        }
+    }
+
+    /// Current health, with an expired rate-limit pause reported as healthy
+    pub fn snapshot(&self) -> ProviderHealth {
+        let inner = self.inner.lock();
+        let mut health = inner.health.clone();
+        health.recent_successes = inner.recent.iter().filter(|ok| **ok).count();
+        health.recent_failures = inner.recent.len() - health.recent_successes;
+
+        if health.rate_limited_until.is_some_and(|until| until <= Utc::now()) {
+            health.rate_limited_until = None;
+            if health.state == ProviderState::RateLimited {
+                health.state = ProviderState::Healthy;
+            }
+        }
+        health
+    }
+}
+
+/// Map an API error to the provider state it implies
+fn classify(error: &RecallError) -> ProviderState {
+    match error {
+        RecallError::RateLimit(_) => ProviderState::RateLimited,
+        RecallError::InvalidApiKey => ProviderState::InvalidKey,
+        RecallError::Http(e) if e.is_connect() || e.is_timeout() => ProviderState::Offline,
+        // A blocked prompt still means the provider answered
+        RecallError::ContentBlocked(_) => ProviderState::Healthy,
+        _ => ProviderState::Degraded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_follows_latest_outcome() {
+        let tracker = HealthTracker::default();
+        assert_eq!(tracker.snapshot().state, ProviderState::Unknown);
+
+        tracker.record::<()>(&Ok(()));
+        tracker.record::<()>(&Err(RecallError::RateLimit(60)));
+        let health = tracker.snapshot();
+        assert_eq!(health.state, ProviderState::RateLimited);
+        assert!(health.rate_limited_until.is_some());
+        assert_eq!((health.recent_successes, health.recent_failures), (1, 1));
+
+        tracker.record::<()>(&Err(RecallError::InvalidApiKey));
+        assert_eq!(tracker.snapshot().state, ProviderState::InvalidKey);
+
+        // An elapsed pause no longer counts as rate limited
+        tracker.record::<()>(&Err(RecallError::RateLimit(0)));
+        assert_eq!(tracker.snapshot().state, ProviderState::Healthy);
+    }
+}
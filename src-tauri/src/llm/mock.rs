@@ -0,0 +1,120 @@
+//! Deterministic stand-in for Gemini so the ingestion and RAG pipelines can
+//! be tested without network access. Only compiled for tests; an `LlmClient`
+//! built with `LlmClient::mock` routes every provider call here.
+
+use super::{
+    CitationRef, GenerateRequest, GenerateResponse, LlmProvider, OcrTextRegion, TokenUsage,
+    VideoAnalysisRequest, VideoAnalysisResponse,
+};
+use crate::error::Result;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Matches the `vec_chunks` column width
+pub const MOCK_EMBEDDING_DIM: usize = 768;
+
+pub struct MockLlmProvider {
+    pub answer: String,
+    pub title: String,
+    pub transcript: String,
+    pub generate_calls: AtomicUsize,
+    pub embed_calls: AtomicUsize,
+    /// The most recent generation request, for asserting on prompts
+    pub last_request: Mutex<Option<GenerateRequest>>,
+}
+
+impl Default for MockLlmProvider {
+    fn default() -> Self {
+        Self {
+            answer: "Mock answer".to_string(),
+            title: "Mock title".to_string(),
+            transcript: "Mock transcript".to_string(),
+            generate_calls: AtomicUsize::new(0),
+            embed_calls: AtomicUsize::new(0),
+            last_request: Mutex::new(None),
+        }
+    }
+}
+
+impl MockLlmProvider {
+    /// Hashed bag-of-words vector: texts sharing words land close together,
+    /// so similarity search behaves sensibly
+    pub fn embedding_for(text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; MOCK_EMBEDDING_DIM];
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            // FNV-1a, so vectors are stable across runs
+            let hash = word
+                .to_lowercase()
+                .bytes()
+                .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+            vector[(hash % MOCK_EMBEDDING_DIM as u64) as usize] += 1.0;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+        vector
+    }
+
+    pub fn generate_title(&self) -> String {
+        self.title.clone()
+    }
+
+    pub fn analyze_image(&self) -> String {
+        "[NO TEXT DETECTED]".to_string()
+    }
+
+    pub fn ocr_text_regions(&self) -> Vec<OcrTextRegion> {
+        Vec::new()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for MockLlmProvider {
+    /// Answers with the canned text, citing the first context chunk if any.
+    /// An empty `answer` comes back empty, like Gemini's blank responses.
+    async fn generate(&self, request: GenerateRequest) -> Result<GenerateResponse> {
+        self.generate_calls.fetch_add(1, Ordering::SeqCst);
+
+        let (content, citations) = match request.context.first() {
+            Some(chunk) if !self.answer.is_empty() => (
+                format!("{} [{}]", self.answer, chunk.id),
+                vec![CitationRef {
+                    chunk_id: chunk.id,
+                    quote: chunk.content.chars().take(50).collect(),
+                }],
+            ),
+            _ => (self.answer.clone(), vec![]),
+        };
+
+        *self.last_request.lock() = Some(request);
+
+        Ok(GenerateResponse {
+            content,
+            citations,
+            usage: TokenUsage {
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                total_tokens: 0,
+            },
+        })
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.embed_calls.fetch_add(1, Ordering::SeqCst);
+        Ok(texts.iter().map(|t| Self::embedding_for(t)).collect())
+    }
+
+    async fn analyze_video(&self, _request: VideoAnalysisRequest) -> Result<VideoAnalysisResponse> {
+        Ok(VideoAnalysisResponse { segments: vec![] })
+    }
+
+    async fn transcribe_audio(&self, _audio_data: &[u8]) -> Result<String> {
+        Ok(self.transcript.clone())
+    }
+}
@@ -1,9 +1,17 @@
 mod client;
 mod embedding;
+mod health;
+#[cfg(test)]
+mod mock;
+mod query_cache;
 mod rate_limiter;
 
 pub use client::*;
 pub use embedding::*;
+pub use health::*;
+#[cfg(test)]
+pub use mock::*;
+pub use query_cache::*;
 pub use rate_limiter::*;
 
 use crate::error::Result;
@@ -33,6 +41,16 @@ pub struct ContextChunk {
     pub source: String,
     pub page: Option<i32>,
     pub timestamp: Option<f64>,
+    /// The source image itself, for image and screenshot chunks when
+    /// multimodal answering is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<ContextImage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextImage {
+    pub mime_type: String,
+    pub data: Vec<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +77,13 @@ pub struct TokenUsage {
 pub struct VideoAnalysisRequest {
     pub video_path: String,
     pub frames: Vec<VideoFrame>,
+    /// Most frames sent to the vision model; longer segments are subsampled
+    #[serde(default = "default_max_video_frames")]
+    pub max_frames: usize,
+}
+
+fn default_max_video_frames() -> usize {
+    20
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +105,14 @@ pub struct VideoSegment {
     pub topics: Vec<String>,
 }
 
+/// A line of text found in an image, with its bounding box as Gemini
+/// reports it: `[ymin, xmin, ymax, xmax]` scaled to 0-1000
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrTextRegion {
+    pub text: String,
+    pub box_2d: [u32; 4],
+}
+
 #[async_trait]
 pub trait LlmProvider: Send + Sync {
     async fn generate(&self, request: GenerateRequest) -> Result<GenerateResponse>;
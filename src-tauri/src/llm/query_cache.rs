@@ -0,0 +1,118 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Query embeddings kept in memory by default
+pub const DEFAULT_QUERY_CACHE_SIZE: usize = 256;
+
+/// Bounded LRU cache of query embeddings, keyed by the exact query text.
+///
+/// Entries belong to a single embedding model; looking up or inserting with a
+/// different model clears the cache, since vectors from different models
+/// aren't comparable.
+pub struct QueryEmbeddingCache {
+    capacity: usize,
+    inner: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    model: String,
+    /// Query -> (embedding, last-used tick)
+    entries: HashMap<String, (Vec<f32>, u64)>,
+    tick: u64,
+}
+
+impl CacheState {
+    fn switch_model(&mut self, model: &str) {
+        if self.model != model {
+            if !self.entries.is_empty() {
+                tracing::debug!("Embedding model changed to {}, clearing query cache", model);
+            }
+            self.entries.clear();
+            self.model = model.to_string();
+        }
+    }
+}
+
+impl QueryEmbeddingCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            inner: Mutex::new(CacheState::default()),
+        }
+    }
+
+    pub fn get(&self, model: &str, query: &str) -> Option<Vec<f32>> {
+        let mut state = self.inner.lock();
+        state.switch_model(model);
+        state.tick += 1;
+        let tick = state.tick;
+        state.entries.get_mut(query).map(|(embedding, last_used)| {
+            *last_used = tick;
+            embedding.clone()
+        })
+    }
+
+    pub fn insert(&self, model: &str, query: &str, embedding: Vec<f32>) {
+        let mut state = self.inner.lock();
+        state.switch_model(model);
+        state.tick += 1;
+        let tick = state.tick;
+
+        if !state.entries.contains_key(query) && state.entries.len() >= self.capacity {
+            // Evict the least recently used entry; the cache is small enough
+            // that a linear scan is cheaper than maintaining an ordered list
+            if let Some(oldest) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone())
+            {
+                state.entries.remove(&oldest);
+            }
+        }
+
+        state.entries.insert(query.to_string(), (embedding, tick));
+    }
+
+    pub fn clear(&self) {
+        self.inner.lock().entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for QueryEmbeddingCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_QUERY_CACHE_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used_and_clears_on_model_change() {
+        let cache = QueryEmbeddingCache::new(2);
+        cache.insert("m1", "a", vec![1.0]);
+        cache.insert("m1", "b", vec![2.0]);
+
+        // Touch "a" so "b" becomes the eviction candidate
+        assert_eq!(cache.get("m1", "a"), Some(vec![1.0]));
+        cache.insert("m1", "c", vec![3.0]);
+
+        assert_eq!(cache.get("m1", "b"), None);
+        assert_eq!(cache.get("m1", "c"), Some(vec![3.0]));
+        assert_eq!(cache.len(), 2);
+
+        assert_eq!(cache.get("m2", "a"), None);
+        assert!(cache.is_empty());
+    }
+}
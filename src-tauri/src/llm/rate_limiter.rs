@@ -1,7 +1,13 @@
+use reqwest::header::HeaderMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
+/// Wait used when a 429 carries no retry hint
+pub const DEFAULT_RATE_LIMIT_WAIT_SECS: u64 = 60;
+/// Longest wait honoured from a provider hint
+pub const MAX_RATE_LIMIT_WAIT_SECS: u64 = 300;
+
 /// Leaky bucket rate limiter for API calls
 pub struct RateLimiter {
     /// Maximum requests allowed
@@ -12,20 +18,68 @@ pub struct RateLimiter {
     leak_rate: f64,
     /// Last update time
     last_update: Mutex<Instant>,
+    /// Provider-requested pause; no tokens are handed out before this
+    paused_until: Mutex<Option<Instant>>,
 }
 
 impl RateLimiter {
     pub fn new(requests_per_minute: u64) -> Self {
+        let requests_per_minute = requests_per_minute.max(1);
         Self {
             capacity: requests_per_minute,
             tokens: AtomicU64::new(requests_per_minute),
             leak_rate: requests_per_minute as f64 / 60.0,
             last_update: Mutex::new(Instant::now()),
+            paused_until: Mutex::new(None),
+        }
+    }
+
+    /// Configured requests per minute
+    pub fn requests_per_minute(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Stop handing out tokens for `duration`, e.g. after a 429 or when the
+    /// provider reports no remaining quota
+    pub async fn pause_for(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut paused_until = self.paused_until.lock().await;
+        if paused_until.is_none_or(|current| current < until) {
+            *paused_until = Some(until);
         }
+        self.tokens.store(0, Ordering::Release);
+    }
+
+    /// Adjust to rate-limit headers from a successful response
+    pub async fn observe_headers(&self, headers: &HeaderMap) {
+        if remaining_from_headers(headers) == Some(0) {
+            let wait = reset_from_headers(headers).unwrap_or(1.0 / self.leak_rate);
+            tracing::debug!("Provider reports no remaining requests, pausing {:.1}s", wait);
+            self.pause_for(Duration::from_secs_f64(wait)).await;
+        }
+    }
+
+    /// Whether at least half the bucket is available and no pause is active
+    pub async fn has_headroom(&self) -> bool {
+        if self.paused_until.lock().await.is_some_and(|until| until > Instant::now()) {
+            return false;
+        }
+
+        let elapsed = self.last_update.lock().await.elapsed();
+        let replenished = (elapsed.as_secs_f64() * self.leak_rate) as u64;
+        let available = (self.tokens.load(Ordering::Acquire) + replenished).min(self.capacity);
+        available * 2 >= self.capacity
     }
 
     /// Try to acquire a token, returns wait time if rate limited
     pub async fn acquire(&self) -> Option<Duration> {
+        if let Some(until) = *self.paused_until.lock().await {
+            let now = Instant::now();
+            if until > now {
+                return Some(until - now);
+            }
+        }
+
         let mut last_update = self.last_update.lock().await;
         let now = Instant::now();
         let elapsed = now.duration_since(*last_update);
@@ -61,6 +115,61 @@ impl RateLimiter {
     }
 }
 
+fn header_f64(headers: &HeaderMap, names: &[&str]) -> Option<f64> {
+    names.iter().find_map(|name| {
+        headers
+            .get(*name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+    })
+}
+
+/// Requests left in the current window, from `X-RateLimit-Remaining`
+pub fn remaining_from_headers(headers: &HeaderMap) -> Option<u64> {
+    header_f64(headers, &["x-ratelimit-remaining", "x-ratelimit-remaining-requests"]).map(|v| v as u64)
+}
+
+/// Seconds until the window resets, from `X-RateLimit-Reset`
+fn reset_from_headers(headers: &HeaderMap) -> Option<f64> {
+    header_f64(headers, &["x-ratelimit-reset", "x-ratelimit-reset-requests"])
+        .filter(|v| *v > 0.0 && *v <= MAX_RATE_LIMIT_WAIT_SECS as f64)
+}
+
+/// Seconds to wait before retrying a rate-limited request.
+///
+/// Checks `Retry-After` (seconds or HTTP date), then `X-RateLimit-Reset`, then
+/// the `retryDelay` Gemini puts in the error body's RetryInfo detail.
+pub fn retry_after_secs(headers: &HeaderMap, body: &str) -> Option<u64> {
+    let from_retry_after = headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| {
+            let v = v.trim();
+            v.parse::<u64>().ok().or_else(|| {
+                chrono::DateTime::parse_from_rfc2822(v)
+                    .ok()
+                    .map(|at| (at.timestamp() - chrono::Utc::now().timestamp()).max(0) as u64)
+            })
+        });
+
+    let wait = from_retry_after
+        .or_else(|| reset_from_headers(headers).map(|v| v.ceil() as u64))
+        .or_else(|| retry_delay_from_body(body))?;
+
+    Some(wait.clamp(1, MAX_RATE_LIMIT_WAIT_SECS))
+}
+
+fn retry_delay_from_body(body: &str) -> Option<u64> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    json.get("error")?
+        .get("details")?
+        .as_array()?
+        .iter()
+        .filter_map(|d| d.get("retryDelay")?.as_str())
+        .find_map(|delay| delay.trim_end_matches('s').parse::<f64>().ok())
+        .map(|secs| secs.ceil() as u64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,4 +191,29 @@ mod tests {
         }
         assert!(rate_limited);
     }
+
+    #[tokio::test]
+    async fn test_has_headroom() {
+        let limiter = RateLimiter::new(60);
+        assert!(limiter.has_headroom().await);
+
+        // Draining the bucket leaves less than half available
+        for _ in 0..100 {
+            if limiter.acquire().await.is_some() {
+                break;
+            }
+        }
+        assert!(!limiter.has_headroom().await);
+    }
+
+    #[test]
+    fn test_retry_after_parsing() {
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, "17".parse().unwrap());
+        assert_eq!(retry_after_secs(&headers, ""), Some(17));
+
+        let body = r#"{"error": {"code": 429, "details": [{"@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "34.5s"}]}}"#;
+        assert_eq!(retry_after_secs(&HeaderMap::new(), body), Some(35));
+        assert_eq!(retry_after_secs(&HeaderMap::new(), "not json"), None);
+    }
 }
@@ -0,0 +1,247 @@
+//! Optional local HTTP API for querying the knowledge base from scripts.
+//!
+//! Off by default. When enabled it listens on 127.0.0.1 only, unless
+//! `local_api_allow_lan` is explicitly set, and every request must carry
+//! `Authorization: Bearer <local_api_token>`.
+//!
+//! Endpoints (JSON in, JSON out):
+//! - `GET  /v1/status`  → `{ "version": 1 }`
+//! - `POST /v1/search`  → body is a `SearchRequest`, returns `SearchJsonResponse`
+//! - `POST /v1/query`   → body `{ query, conversation_id?, max_chunks?, document_ids?, categories? }`,
+//!   returns a `RagResponse` with sources
+//!
+//! Errors use the same `{ code, message }` body as IPC errors.
+
+use crate::commands::search::{build_search_json, SearchRequest, SEARCH_JSON_VERSION};
+use crate::error::{RecallError, Result};
+use crate::rag::RagQuery;
+use crate::state::AppState;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use parking_lot::Mutex;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// How long `stop` lets in-flight requests finish before dropping them
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Handle to the running server, if any
+#[derive(Default)]
+pub struct LocalApiServer {
+    running: Mutex<Option<RunningApi>>,
+}
+
+struct RunningApi {
+    address: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    /// The server task; it owns the listener, so the port is free once it ends
+    task: JoinHandle<()>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalApiStatus {
+    pub running: bool,
+    pub address: Option<String>,
+    pub token: Option<String>,
+    pub allow_lan: bool,
+}
+
+impl LocalApiServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn address(&self) -> Option<SocketAddr> {
+        self.running.lock().as_ref().map(|r| r.address)
+    }
+
+    /// Stop the server if it is running, returning once its port is free
+    pub async fn stop(&self) {
+        let Some(running) = self.running.lock().take() else {
+            return;
+        };
+        let _ = running.shutdown.send(());
+
+        let mut task = running.task;
+        if tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, &mut task).await.is_err() {
+            tracing::warn!("Local API requests still running after {:?}, dropping them", SHUTDOWN_GRACE_PERIOD);
+            task.abort();
+            let _ = task.await;
+        }
+        tracing::info!("Local API stopped on {}", running.address);
+    }
+}
+
+/// Start the local API with the current settings, restarting it if it is
+/// already running. Generates a bearer token on first use.
+pub async fn start(app: Arc<AppState>) -> Result<SocketAddr> {
+    app.local_api.stop().await;
+
+    let (port, allow_lan) = {
+        let mut settings = app.settings.write();
+        if settings.local_api_token.is_none() {
+            settings.local_api_token = Some(generate_token());
+        }
+        (settings.local_api_port, settings.local_api_allow_lan)
+    };
+    app.save_settings()?;
+
+    // Listening beyond this machine is an explicit opt-in
+    let ip = if allow_lan { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+    let listener = tokio::net::TcpListener::bind((ip, port)).await?;
+    let address = listener.local_addr()?;
+
+    let router = Router::new()
+        .route("/v1/status", get(status))
+        .route("/v1/search", post(search))
+        .route("/v1/query", post(query))
+        .with_state(app.clone());
+
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    let task = tokio::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        });
+        if let Err(e) = server.await {
+            tracing::error!("Local API server failed: {}", e);
+        }
+    });
+
+    *app.local_api.running.lock() = Some(RunningApi { address, shutdown, task });
+    if allow_lan {
+        tracing::warn!("Local API listening on all interfaces at port {}", address.port());
+    } else {
+        tracing::info!("Local API listening on {}", address);
+    }
+    Ok(address)
+}
+
+/// Random 32-byte bearer token, hex encoded
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Compare without short-circuiting so timing doesn't leak the token
+fn tokens_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn authorize(app: &AppState, headers: &HeaderMap) -> std::result::Result<(), ApiError> {
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    let settings = app.settings.read();
+    match (settings.local_api_token.as_deref(), given) {
+        (Some(expected), Some(given)) if tokens_match(expected, given.trim()) => Ok(()),
+        _ => Err(ApiError::Unauthorized),
+    }
+}
+
+enum ApiError {
+    Unauthorized,
+    Recall(RecallError),
+}
+
+impl From<RecallError> for ApiError {
+    fn from(err: RecallError) -> Self {
+        ApiError::Recall(err)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let err = match self {
+            ApiError::Unauthorized => {
+                let body = serde_json::json!({ "code": "unauthorized", "message": "Missing or invalid bearer token" });
+                return (StatusCode::UNAUTHORIZED, Json(body)).into_response();
+            }
+            ApiError::Recall(err) => err,
+        };
+
+        let status = match err {
+            RecallError::NotFound(_) => StatusCode::NOT_FOUND,
+            RecallError::RateLimit(_) => StatusCode::TOO_MANY_REQUESTS,
+            RecallError::Config(_) => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(err)).into_response()
+    }
+}
+
+async fn status(
+    State(app): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> std::result::Result<Json<serde_json::Value>, ApiError> {
+    authorize(&app, &headers)?;
+    Ok(Json(serde_json::json!({ "version": SEARCH_JSON_VERSION })))
+}
+
+async fn search(
+    State(app): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<SearchRequest>,
+) -> std::result::Result<Response, ApiError> {
+    authorize(&app, &headers)?;
+    Ok(Json(build_search_json(&app, request).await?).into_response())
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryRequest {
+    query: String,
+    conversation_id: Option<String>,
+    max_chunks: Option<usize>,
+    document_ids: Option<Vec<String>>,
+    categories: Option<Vec<String>>,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+}
+
+async fn query(
+    State(app): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<QueryRequest>,
+) -> std::result::Result<Response, ApiError> {
+    authorize(&app, &headers)?;
+    let request = RagQuery {
+        query: request.query,
+        conversation_id: request.conversation_id,
+        max_chunks: request.max_chunks,
+        include_sources: true,
+        document_ids: request.document_ids,
+        categories: request.categories,
+        max_tokens: request.max_tokens,
+        temperature: request.temperature,
+    };
+    Ok(Json(app.rag_engine.query(request).await?).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_match() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert!(tokens_match(&token, &token.clone()));
+        assert!(!tokens_match(&token, &generate_token()));
+        assert!(!tokens_match(&token, &token[..63]));
+    }
+}
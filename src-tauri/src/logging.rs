@@ -0,0 +1,378 @@
+//! Logging setup: env-filtered stdout for development plus a daily-rotated
+//! log file in `app_data_dir/logs` whose level comes from settings.
+//!
+//! Events inside a span with a `document_id` field (see
+//! `IngestionEngine::process_with_watchdog`) are also kept in a small
+//! in-memory ring buffer per document, read by `get_document_ingestion_log`.
+
+use crate::error::{RecallError, Result};
+use chrono::{DateTime, Utc};
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::writer::{EitherWriter, MakeWriter};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{filter, layer::SubscriberExt, util::SubscriberInitExt, Layer};
+
+const LOG_FILE_PREFIX: &str = "recall";
+const LOG_FILE_SUFFIX: &str = "log";
+/// Daily log files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 14;
+/// Log levels accepted by `Settings.log_level`
+pub const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+/// Lines kept per document; older lines are dropped first
+const MAX_DOCUMENT_LOG_LINES: usize = 500;
+/// Documents with a kept log; the least recently started is dropped first
+const MAX_DOCUMENT_LOGS: usize = 100;
+
+struct FileLogging {
+    writer: RwLock<Option<NonBlocking>>,
+    /// Index into `LOG_LEVELS`
+    level: AtomicU8,
+    log_dir: RwLock<Option<PathBuf>>,
+    /// Flushes buffered lines when dropped; held for the life of the process
+    guard: Mutex<Option<WorkerGuard>>,
+}
+
+static FILE_LOGGING: OnceCell<FileLogging> = OnceCell::new();
+
+fn file_logging() -> &'static FileLogging {
+    FILE_LOGGING.get_or_init(|| FileLogging {
+        writer: RwLock::new(None),
+        level: AtomicU8::new(2),
+        log_dir: RwLock::new(None),
+        guard: Mutex::new(None),
+    })
+}
+
+fn level_index(level: &str) -> Option<u8> {
+    LOG_LEVELS
+        .iter()
+        .position(|l| l.eq_ignore_ascii_case(level.trim()))
+        .map(|i| i as u8)
+}
+
+fn level_from_index(index: u8) -> Level {
+    match index {
+        0 => Level::ERROR,
+        1 => Level::WARN,
+        2 => Level::INFO,
+        3 => Level::DEBUG,
+        _ => Level::TRACE,
+    }
+}
+
+/// Writes to the log file once one is attached, and nowhere before that
+struct FileMakeWriter;
+
+impl<'a> MakeWriter<'a> for FileMakeWriter {
+    type Writer = EitherWriter<NonBlocking, std::io::Sink>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        match file_logging().writer.read().as_ref() {
+            Some(writer) => EitherWriter::A(writer.clone()),
+            None => EitherWriter::B(std::io::sink()),
+        }
+    }
+}
+
+/// Install the global subscriber. Called once, before the app starts.
+pub fn init() {
+    let stdout_filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| "recall_os=debug,tauri=info".into());
+
+    // Level is read per event so `set_log_level` applies without reinstalling.
+    // Dependencies are capped at info to keep the file readable.
+    let file_filter = filter::filter_fn(|metadata| {
+        let logging = file_logging();
+        if logging.writer.read().is_none() {
+            return false;
+        }
+        let mut level = level_from_index(logging.level.load(Ordering::Relaxed));
+        if !metadata.target().starts_with("recall_os") && level > Level::INFO {
+            level = Level::INFO;
+        }
+        *metadata.level() <= level
+    });
+
+    // Only our own events, so the capture doesn't enable dependency tracing
+    let document_filter = filter::filter_fn(|metadata| {
+        metadata.target().starts_with("recall_os") && *metadata.level() <= Level::DEBUG
+    });
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(stdout_filter))
+        .with(DocumentLogLayer.with_filter(document_filter))
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(FileMakeWriter)
+                .with_filter(file_filter),
+        )
+        .init();
+}
+
+/// Start writing the log file into `log_dir` at `level`
+pub fn attach_file_logging(log_dir: &Path, level: &str) -> Result<()> {
+    std::fs::create_dir_all(log_dir)?;
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir)
+        .map_err(|e| RecallError::Config(format!("Failed to open log file: {}", e)))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    let logging = file_logging();
+    set_log_level(level)?;
+    *logging.writer.write() = Some(writer);
+    *logging.guard.lock() = Some(guard);
+    *logging.log_dir.write() = Some(log_dir.to_path_buf());
+
+    tracing::info!("Writing logs to {:?} at level {}", log_dir, level);
+    Ok(())
+}
+
+fn checked_level_index(level: &str) -> Result<u8> {
+    level_index(level).ok_or_else(|| {
+        RecallError::Config(format!(
+            "Unknown log level '{}', expected one of: {}",
+            level,
+            LOG_LEVELS.join(", ")
+        ))
+    })
+}
+
+/// Check a log level name without applying it
+pub fn validate_log_level(level: &str) -> Result<()> {
+    checked_level_index(level).map(|_| ())
+}
+
+/// Change the file log level
+pub fn set_log_level(level: &str) -> Result<()> {
+    let index = checked_level_index(level)?;
+    file_logging().level.store(index, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Log files in the log directory, oldest first
+fn log_files() -> Vec<PathBuf> {
+    let Some(dir) = file_logging().log_dir.read().clone() else {
+        return vec![];
+    };
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(LOG_FILE_PREFIX) && n.ends_with(LOG_FILE_SUFFIX))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // File names end in the date, so name order is chronological
+    files.sort();
+    files
+}
+
+/// The directory holding log files, if file logging is active
+pub fn log_dir() -> Option<PathBuf> {
+    file_logging().log_dir.read().clone()
+}
+
+/// The log file currently being written
+pub fn current_log_file() -> Option<PathBuf> {
+    log_files().pop()
+}
+
+/// Concatenate the most recent `days` of logs into `destination`
+pub fn export_logs(destination: &Path, days: usize) -> Result<PathBuf> {
+    let files = log_files();
+    if files.is_empty() {
+        return Err(RecallError::NotFound("No log files to export".to_string()));
+    }
+
+    let mut out = std::fs::File::create(destination)?;
+    for path in files.iter().skip(files.len().saturating_sub(days.max(1))) {
+        writeln!(out, "===== {} =====", path.file_name().unwrap_or_default().to_string_lossy())?;
+        out.write_all(&std::fs::read(path)?)?;
+    }
+    out.flush()?;
+
+    Ok(destination.to_path_buf())
+}
+
+/// One captured line of a document's ingestion log
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentLogLine {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct DocumentLogs {
+    lines: HashMap<String, VecDeque<DocumentLogLine>>,
+    /// Document ids, oldest first, for evicting whole logs
+    order: VecDeque<String>,
+}
+
+static DOCUMENT_LOGS: Lazy<Mutex<DocumentLogs>> = Lazy::new(|| Mutex::new(DocumentLogs::default()));
+
+/// Append a line to a document's log directly, without a tracing event
+pub fn push_document_log(document_id: &str, level: Level, target: &str, message: String) {
+    let mut logs = DOCUMENT_LOGS.lock();
+    if !logs.lines.contains_key(document_id) {
+        if logs.order.len() >= MAX_DOCUMENT_LOGS {
+            if let Some(oldest) = logs.order.pop_front() {
+                logs.lines.remove(&oldest);
+            }
+        }
+        logs.order.push_back(document_id.to_string());
+    }
+
+    let lines = logs.lines.entry(document_id.to_string()).or_default();
+    if lines.len() >= MAX_DOCUMENT_LOG_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(DocumentLogLine {
+        timestamp: Utc::now(),
+        level: level.to_string(),
+        target: target.to_string(),
+        message,
+    });
+}
+
+/// Captured ingestion log lines for a document, oldest first
+pub fn document_log(document_id: &str) -> Vec<DocumentLogLine> {
+    DOCUMENT_LOGS
+        .lock()
+        .lines
+        .get(document_id)
+        .map(|lines| lines.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// The `document_id` a span was created with
+struct SpanDocumentId(String);
+
+/// Routes events inside a `document_id` span into that document's log
+struct DocumentLogLayer;
+
+impl<S> Layer<S> for DocumentLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = DocumentIdVisitor(None);
+        attrs.record(&mut visitor);
+        if let (Some(document_id), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(SpanDocumentId(document_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(scope) = ctx.event_scope(event) else {
+            return;
+        };
+        let Some(document_id) = scope
+            .from_root()
+            .find_map(|span| span.extensions().get::<SpanDocumentId>().map(|d| d.0.clone()))
+        else {
+            return;
+        };
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        push_document_log(&document_id, *metadata.level(), metadata.target(), visitor.finish());
+    }
+}
+
+struct DocumentIdVisitor(Option<String>);
+
+impl Visit for DocumentIdVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "document_id" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "document_id" {
+            // `%id` fields arrive here already Display-formatted
+            self.0 = Some(format!("{:?}", value));
+        }
+    }
+}
+
+/// Formats the message followed by any other fields as `key=value`
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn finish(self) -> String {
+        if self.fields.is_empty() {
+            self.message
+        } else {
+            format!("{}{}", self.message, self.fields)
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::Instrument;
+
+    #[tokio::test]
+    async fn test_document_log_capture() {
+        let subscriber = tracing_subscriber::registry().with(DocumentLogLayer);
+        let _default = tracing::subscriber::set_default(subscriber);
+
+        tracing::info!("outside any document");
+        async {
+            tracing::info!(pages = 3, "Extracting text");
+            tracing::warn!("OCR fallback");
+        }
+        .instrument(tracing::info_span!("ingest", document_id = %"doc-log-test"))
+        .await;
+
+        let lines = document_log("doc-log-test");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].message, "Extracting text pages=3");
+        assert_eq!(lines[1].level, "WARN");
+        assert!(document_log("other-doc").is_empty());
+    }
+}
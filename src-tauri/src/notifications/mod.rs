@@ -4,17 +4,20 @@
 //! - Custom notification windows with full CSS styling control
 //! - AUMID registration for Windows toast fallback
 //! - Toast notification builders with RECALL.OS styling
+//! - A session-wide snooze for the automatic notifications
 
 #[cfg(windows)]
 mod aumid;
 #[cfg(windows)]
 mod toast;
+mod snooze;
 mod window;
 
 #[cfg(windows)]
 pub use aumid::ensure_aumid_registered;
 #[cfg(windows)]
 pub use toast::NotificationBuilder;
+pub use snooze::{clear_snooze, is_snoozed, snooze, snoozed_until, MAX_SNOOZE_MINUTES};
 pub use window::{show_notification, show_related_content_notification, show_processing_notification, NotificationData, RelatedDocumentInfo};
 
 /// The Application User Model ID for RECALL.OS
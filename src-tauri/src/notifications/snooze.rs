@@ -0,0 +1,51 @@
+//! Session-wide snooze for notification windows.
+//!
+//! Held in memory only, so a restart always brings notifications back.
+
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::RwLock;
+
+/// Longest snooze accepted (7 days)
+pub const MAX_SNOOZE_MINUTES: u64 = 7 * 24 * 60;
+
+static SNOOZED_UNTIL: RwLock<Option<DateTime<Utc>>> = RwLock::new(None);
+
+/// Suppress notifications for `duration` from now, replacing any earlier snooze
+pub fn snooze(duration: Duration) -> DateTime<Utc> {
+    let until = Utc::now() + duration;
+    *SNOOZED_UNTIL.write() = Some(until);
+    until
+}
+
+/// End the snooze early
+pub fn clear_snooze() {
+    *SNOOZED_UNTIL.write() = None;
+}
+
+/// When the current snooze ends, or None if notifications are showing
+pub fn snoozed_until() -> Option<DateTime<Utc>> {
+    SNOOZED_UNTIL.read().filter(|until| *until > Utc::now())
+}
+
+pub fn is_snoozed() -> bool {
+    snoozed_until().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snooze_and_clear() {
+        let until = snooze(Duration::minutes(5));
+        assert!(is_snoozed());
+        assert_eq!(snoozed_until(), Some(until));
+
+        clear_snooze();
+        assert!(!is_snoozed());
+
+        snooze(Duration::minutes(-1));
+        assert!(!is_snoozed());
+        clear_snooze();
+    }
+}
@@ -137,6 +137,11 @@ pub fn show_related_content_notification<R: Runtime>(
     document_title: &str,
     related: &[(String, String, f64)], // (id, title, similarity)
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if super::is_snoozed() {
+        tracing::debug!("Notifications snoozed, skipping related content for {}", new_document_id);
+        return Ok(());
+    }
+
     let related_docs: Vec<RelatedDocumentInfo> = related
         .iter()
         .map(|(id, title, sim)| RelatedDocumentInfo {
@@ -165,6 +170,11 @@ pub fn show_processing_notification<R: Runtime>(
     app: &AppHandle<R>,
     source_app: Option<&str>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if super::is_snoozed() {
+        tracing::debug!("Notifications snoozed, skipping processing notification");
+        return Ok(());
+    }
+
     let title = "Processing Screenshot".to_string();
     let message = match source_app {
         Some(app_name) => format!("From {}", app_name),
@@ -0,0 +1,110 @@
+//! Short-lived cache of retrieval results, so follow-up turns that repeat a
+//! query in the same scope skip the embedding call and both searches.
+
+use crate::database::ChunkWithScore;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long cached results stay usable
+const RETRIEVAL_CACHE_TTL: Duration = Duration::from_secs(300);
+/// Most queries remembered at once
+const RETRIEVAL_CACHE_CAPACITY: usize = 64;
+
+/// What a retrieval depends on, besides the library contents
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RetrievalKey {
+    query: String,
+    document_ids: Option<Vec<String>>,
+    limit: usize,
+    hybrid: bool,
+}
+
+impl RetrievalKey {
+    /// Key on the query with case, spacing and trailing punctuation ignored,
+    /// and the scope in any order
+    pub fn new(query: &str, document_ids: Option<&[String]>, limit: usize, hybrid: bool) -> Self {
+        let query = query
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim_end_matches(['?', '.', '!'])
+            .to_lowercase();
+        let document_ids = document_ids.map(|ids| {
+            let mut ids = ids.to_vec();
+            ids.sort();
+            ids.dedup();
+            ids
+        });
+        Self { query, document_ids, limit, hybrid }
+    }
+}
+
+struct CachedRetrieval {
+    chunks: Vec<ChunkWithScore>,
+    /// `Database::chunks_version` when stored; any ingest or delete changes it
+    version: (i64, i64),
+    stored_at: Instant,
+}
+
+#[derive(Default)]
+pub struct RetrievalCache {
+    entries: Mutex<HashMap<RetrievalKey, CachedRetrieval>>,
+}
+
+impl RetrievalCache {
+    /// Cached results for `key`, unless they expired or the library changed
+    pub fn get(&self, key: &RetrievalKey, version: (i64, i64)) -> Option<Vec<ChunkWithScore>> {
+        let mut entries = self.entries.lock();
+        match entries.get(key) {
+            Some(entry) if entry.version == version && entry.stored_at.elapsed() < RETRIEVAL_CACHE_TTL => {
+                Some(entry.chunks.clone())
+            }
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: RetrievalKey, version: (i64, i64), chunks: Vec<ChunkWithScore>) {
+        let mut entries = self.entries.lock();
+        entries.retain(|_, entry| entry.version == version && entry.stored_at.elapsed() < RETRIEVAL_CACHE_TTL);
+        if entries.len() >= RETRIEVAL_CACHE_CAPACITY {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.stored_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, CachedRetrieval { chunks, version, stored_at: Instant::now() });
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_ignores_query_formatting_and_drops_stale_versions() {
+        let cache = RetrievalCache::default();
+        let scope = vec!["b".to_string(), "a".to_string()];
+        cache.insert(RetrievalKey::new("What is the refund policy?", Some(&scope), 10, true), (5, 5), vec![]);
+
+        let reversed = vec!["a".to_string(), "b".to_string()];
+        let same = RetrievalKey::new("  what is the  REFUND policy", Some(&reversed), 10, true);
+        assert!(cache.get(&same, (5, 5)).is_some());
+        assert!(cache.get(&RetrievalKey::new("what is the refund policy", None, 10, true), (5, 5)).is_none());
+
+        // A new chunk changes the version and invalidates the entry
+        assert!(cache.get(&same, (6, 6)).is_none());
+        assert!(cache.get(&same, (5, 5)).is_none());
+    }
+}
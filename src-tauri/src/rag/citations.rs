@@ -0,0 +1,103 @@
+//! How citations appear in answers. The model always cites with
+//! `[chunk_id]`; the answer is rewritten to the user's chosen style once
+//! the citations are known.
+
+use crate::database::Citation;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A `[123]` marker with the spaces before it, so removed markers don't
+/// leave a gap before punctuation
+static CITATION_MARKER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"([ \t]*)\[(\d+)\]").unwrap());
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationStyle {
+    /// Numbered markers `[1]` in the text, where each source is used
+    #[default]
+    Inline,
+    /// Numbered markers in the text plus a numbered source list below
+    Footnotes,
+    /// No markers in the text, just a source list below
+    EndList,
+}
+
+impl CitationStyle {
+    /// Placement instructions added to the system prompt
+    pub fn prompt_instructions(&self) -> &'static str {
+        match self {
+            Self::Inline => "Place each citation right after the sentence or clause it supports.",
+            Self::Footnotes => {
+                "Place each citation at the end of the sentence it supports. Do not write your own list of sources; one is added below your answer."
+            }
+            Self::EndList => {
+                "Cite at the end of each paragraph rather than mid-sentence. Citations are moved into a source list below your answer, so do not write one yourself."
+            }
+        }
+    }
+}
+
+/// Rewrite the `[chunk_id]` markers in `answer` for `style`. Citation n in
+/// `citations` becomes marker `[n]`, or the markers are removed for
+/// `EndList`. Bracketed numbers that aren't cited chunks are left alone.
+pub fn format_citations(answer: &str, citations: &[Citation], style: CitationStyle) -> String {
+    let numbers: HashMap<i64, usize> = citations
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, c)| (c.chunk_id, i + 1))
+        .collect();
+
+    CITATION_MARKER_REGEX
+        .replace_all(answer, |caps: &Captures| {
+            let number = caps[2].parse::<i64>().ok().and_then(|id| numbers.get(&id));
+            match (number, style) {
+                (None, _) => caps[0].to_string(),
+                (Some(_), CitationStyle::EndList) => String::new(),
+                (Some(n), _) => format!("{}[{}]", &caps[1], n),
+            }
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn citation(chunk_id: i64) -> Citation {
+        Citation {
+            chunk_id,
+            document_id: "doc-1".to_string(),
+            document_title: "Transfers".to_string(),
+            content_snippet: String::new(),
+            page_number: None,
+            timestamp: None,
+            relevance_score: 1.0,
+            thumbnail_path: None,
+            start_offset: None,
+            end_offset: None,
+        }
+    }
+
+    #[test]
+    fn test_markers_follow_citation_style() {
+        let answer = "Two burns are needed [482]. The first raises apoapsis [97][482]. See table [3].";
+        let citations = [citation(482), citation(97)];
+
+        assert_eq!(
+            format_citations(answer, &citations, CitationStyle::Inline),
+            "Two burns are needed [1]. The first raises apoapsis [2][1]. See table [3]."
+        );
+        assert_eq!(
+            format_citations(answer, &citations, CitationStyle::Footnotes),
+            format_citations(answer, &citations, CitationStyle::Inline)
+        );
+        assert_eq!(
+            format_citations(answer, &citations, CitationStyle::EndList),
+            "Two burns are needed. The first raises apoapsis. See table [3]."
+        );
+        assert_eq!(serde_json::json!(CitationStyle::EndList), "end_list");
+    }
+}
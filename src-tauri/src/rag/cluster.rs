@@ -0,0 +1,258 @@
+//! Topic clusters over the library. Each document is represented by the mean
+//! of its chunk embeddings, and documents are grouped with spherical k-means
+//! (cosine similarity on unit vectors).
+
+use crate::database::{Database, Document};
+use crate::error::{RecallError, Result};
+use crate::ingestion::request_cluster_label;
+use crate::llm::LlmClient;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Clusters requested when the caller doesn't say
+pub const DEFAULT_CLUSTER_COUNT: usize = 8;
+/// Upper bound on the requested cluster count
+pub const MAX_CLUSTER_COUNT: usize = 50;
+/// k-means stops after this many rounds even if assignments still move
+const MAX_KMEANS_ITERATIONS: usize = 50;
+/// Most central documents of a cluster shown to the LLM when naming it
+const LABEL_SAMPLE_DOCUMENTS: usize = 5;
+/// Characters of each sample document's first chunk shown to the LLM
+const LABEL_SAMPLE_CHARS: usize = 400;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentCluster {
+    /// Suggested name for the theme the documents share
+    pub label: String,
+    /// Most central first
+    pub documents: Vec<ClusterMember>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterMember {
+    pub id: String,
+    pub title: String,
+    /// Cosine similarity to the cluster centre
+    pub similarity: f64,
+}
+
+/// Group completed documents with stored embeddings into at most `k`
+/// clusters, largest first. Clusters are named by the LLM when one is
+/// given, otherwise (or if naming fails) by their most common category or
+/// their most central document.
+pub async fn cluster_library(database: &Database, llm: Option<&LlmClient>, k: usize) -> Result<Vec<DocumentCluster>> {
+    let mut sums = database.get_document_embedding_sums()?;
+    let mut documents: Vec<(Document, Vec<f32>)> = database
+        .get_all_documents()?
+        .into_iter()
+        .filter_map(|doc| {
+            let sum = sums.remove(&doc.id)?;
+            let mean = mean_unit_vector([sum.as_slice()])?;
+            Some((doc, mean))
+        })
+        .collect();
+    // Stable input order keeps the clusters stable between runs
+    documents.sort_by(|a, b| a.0.created_at.cmp(&b.0.created_at).then_with(|| a.0.id.cmp(&b.0.id)));
+    let (documents, vectors): (Vec<Document>, Vec<Vec<f32>>) = documents.into_iter().unzip();
+
+    let k = k.clamp(1, MAX_CLUSTER_COUNT);
+    let (vectors, assignments, centroids) = tokio::task::spawn_blocking(move || {
+        let (assignments, centroids) = kmeans(&vectors, k);
+        (vectors, assignments, centroids)
+    })
+    .await
+    .map_err(|e| RecallError::Other(format!("Clustering task failed: {}", e)))?;
+
+    let mut groups: Vec<Vec<(&Document, f64)>> = vec![Vec::new(); centroids.len()];
+    for ((doc, vector), &cluster) in documents.iter().zip(&vectors).zip(&assignments) {
+        groups[cluster].push((doc, dot(vector, &centroids[cluster]) as f64));
+    }
+    groups.retain(|g| !g.is_empty());
+    for group in &mut groups {
+        group.sort_by(|a, b| b.1.total_cmp(&a.1));
+    }
+    groups.sort_by_key(|g| std::cmp::Reverse(g.len()));
+
+    let mut clusters = Vec::with_capacity(groups.len());
+    for group in groups {
+        let label = match llm {
+            Some(llm) => {
+                let samples = label_samples(database, &group)?;
+                match request_cluster_label(llm, &samples).await {
+                    Ok(label) if !label.is_empty() => label,
+                    Ok(_) => fallback_label(&group),
+                    Err(e) => {
+                        tracing::warn!("Failed to name document cluster: {}", e);
+                        fallback_label(&group)
+                    }
+                }
+            }
+            None => fallback_label(&group),
+        };
+
+        clusters.push(DocumentCluster {
+            label,
+            documents: group
+                .into_iter()
+                .map(|(doc, similarity)| ClusterMember {
+                    id: doc.id.clone(),
+                    title: doc.title.clone(),
+                    similarity,
+                })
+                .collect(),
+        });
+    }
+
+    Ok(clusters)
+}
+
+/// Title and opening text of a cluster's most central documents
+fn label_samples(database: &Database, group: &[(&Document, f64)]) -> Result<Vec<(String, String)>> {
+    group
+        .iter()
+        .take(LABEL_SAMPLE_DOCUMENTS)
+        .map(|(doc, _)| {
+            let sample = database
+                .get_chunks_for_document(&doc.id)?
+                .first()
+                .map(|chunk| chunk.content.chars().take(LABEL_SAMPLE_CHARS).collect())
+                .unwrap_or_default();
+            Ok((doc.title.clone(), sample))
+        })
+        .collect()
+}
+
+/// Most common primary category in the cluster, else its central document's title
+fn fallback_label(group: &[(&Document, f64)]) -> String {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for (doc, _) in group {
+        if let Some(category) = doc.metadata.get("content_category").and_then(|c| c.as_str()) {
+            *counts.entry(category).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(category, _)| category.to_string())
+        .or_else(|| group.first().map(|(doc, _)| doc.title.clone()))
+        .unwrap_or_default()
+}
+
+/// Mean of `vectors` scaled to unit length; None when there are none or
+/// they cancel out
+pub fn mean_unit_vector<'a>(vectors: impl IntoIterator<Item = &'a [f32]>) -> Option<Vec<f32>> {
+    let mut sum: Vec<f32> = Vec::new();
+    for vector in vectors {
+        if sum.is_empty() {
+            sum = vec![0.0; vector.len()];
+        }
+        for (s, v) in sum.iter_mut().zip(vector) {
+            *s += v;
+        }
+    }
+
+    let norm = dot(&sum, &sum).sqrt();
+    if norm == 0.0 || !norm.is_finite() {
+        return None;
+    }
+    Some(sum.into_iter().map(|s| s / norm).collect())
+}
+
+/// Spherical k-means over unit vectors. Returns each vector's cluster index
+/// and the cluster centres; a cluster can end up empty when vectors repeat.
+pub fn kmeans(vectors: &[Vec<f32>], k: usize) -> (Vec<usize>, Vec<Vec<f32>>) {
+    let k = k.min(vectors.len());
+    if k == 0 {
+        return (vec![], vec![]);
+    }
+
+    // Farthest-first seeding: deterministic, and spreads the seeds across
+    // distinct topics instead of several in the densest one
+    let mut centroids = vec![vectors[0].clone()];
+    let mut best: Vec<f32> = vectors.iter().map(|v| dot(v, &centroids[0])).collect();
+    while centroids.len() < k {
+        let farthest = best
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        let seed = vectors[farthest].clone();
+        for (b, v) in best.iter_mut().zip(vectors) {
+            *b = b.max(dot(v, &seed));
+        }
+        centroids.push(seed);
+    }
+
+    let mut assignments = vec![usize::MAX; vectors.len()];
+    for _ in 0..MAX_KMEANS_ITERATIONS {
+        let mut changed = false;
+        for (assignment, vector) in assignments.iter_mut().zip(vectors) {
+            let closest = closest_centroid(vector, &centroids);
+            if *assignment != closest {
+                *assignment = closest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        for (i, centroid) in centroids.iter_mut().enumerate() {
+            let members = vectors
+                .iter()
+                .zip(&assignments)
+                .filter(|(_, &a)| a == i)
+                .map(|(v, _)| v.as_slice());
+            // An emptied cluster keeps its old centre
+            if let Some(mean) = mean_unit_vector(members) {
+                *centroid = mean;
+            }
+        }
+    }
+
+    (assignments, centroids)
+}
+
+fn closest_centroid(vector: &[f32], centroids: &[Vec<f32>]) -> usize {
+    centroids
+        .iter()
+        .map(|centroid| dot(vector, centroid))
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kmeans_separates_distinct_directions() {
+        let unit = |x: f32, y: f32, z: f32| mean_unit_vector([[x, y, z].as_slice()]).unwrap();
+        let vectors = vec![
+            unit(1.0, 0.1, 0.0),
+            unit(0.0, 1.0, 0.1),
+            unit(1.0, 0.0, 0.1),
+            unit(0.1, 1.0, 0.0),
+            unit(0.0, 0.1, 1.0),
+        ];
+
+        let (assignments, centroids) = kmeans(&vectors, 3);
+        assert_eq!(centroids.len(), 3);
+        assert_eq!(assignments[0], assignments[2]);
+        assert_eq!(assignments[1], assignments[3]);
+        assert_ne!(assignments[0], assignments[1]);
+        assert_ne!(assignments[4], assignments[0]);
+        assert_ne!(assignments[4], assignments[1]);
+
+        // Never more clusters than vectors
+        assert_eq!(kmeans(&vectors[..2], 5).1.len(), 2);
+        assert!(mean_unit_vector([[1.0, 0.0].as_slice(), [-1.0, 0.0].as_slice()]).is_none());
+    }
+}
@@ -1,20 +1,45 @@
+mod cache;
+mod citations;
+mod cluster;
 mod retriever;
 
+pub use cache::*;
+pub use citations::*;
+pub use cluster::*;
 pub use retriever::*;
 
-use crate::database::{ChunkWithScore, Citation, Database, MessageRole, SearchType};
+use crate::database::{
+    Chunk, ChunkWithScore, Citation, ConversationScope, Database, Document, FileType, IngestionSource,
+    Message, MessageRole, SearchType,
+};
 use crate::error::{RecallError, Result};
-use crate::llm::{ContextChunk, ConversationMessage, GenerateRequest, LlmClient, LlmProvider};
+use crate::ingestion::detect_mime;
+use crate::llm::{ContextChunk, ContextImage, ConversationMessage, GenerateRequest, LlmClient, LlmProvider};
 use crate::state::Settings;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 
+/// Answer length and sampling temperature when a query doesn't set them
+const DEFAULT_ANSWER_MAX_TOKENS: u32 = 2000;
+const DEFAULT_ANSWER_TEMPERATURE: f32 = 0.7;
+/// Bounds accepted for a query's own `max_tokens` and `temperature`
+const MAX_ANSWER_TOKENS: u32 = 8192;
+const MAX_ANSWER_TEMPERATURE: f32 = 2.0;
+/// Most neighbours added on each side of a retrieved chunk
+const MAX_CONTEXT_NEIGHBOR_WINDOW: usize = 5;
+/// Upper bound on `max_context_images`, whatever the setting says
+const MAX_CONTEXT_IMAGES: usize = 10;
+/// Larger source images are left as text only, to keep requests small
+const MAX_CONTEXT_IMAGE_BYTES: u64 = 4 * 1024 * 1024;
+
 pub struct RagEngine {
     database: Arc<Database>,
     llm_client: Arc<RwLock<Option<LlmClient>>>,
     settings: Arc<RwLock<Settings>>,
+    retrieval_cache: RetrievalCache,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +49,38 @@ pub struct RagQuery {
     pub max_chunks: Option<usize>,
     pub include_sources: bool,
     pub document_ids: Option<Vec<String>>,
+    /// Restrict retrieval to documents labelled with any of these categories
+    #[serde(default)]
+    pub categories: Option<Vec<String>>,
+    /// Override the answer length (default 2000 tokens, at most 8192)
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Override the sampling temperature (default 0.7, from 0.0 to 2.0)
+    #[serde(default)]
+    pub temperature: Option<f32>,
+}
+
+impl RagQuery {
+    /// Answer length and temperature for this query, rejecting out of range overrides
+    fn generation_params(&self) -> Result<(u32, f32)> {
+        let max_tokens = self.max_tokens.unwrap_or(DEFAULT_ANSWER_MAX_TOKENS);
+        if !(1..=MAX_ANSWER_TOKENS).contains(&max_tokens) {
+            return Err(RecallError::Other(format!(
+                "max_tokens must be between 1 and {}, got {}",
+                MAX_ANSWER_TOKENS, max_tokens
+            )));
+        }
+
+        let temperature = self.temperature.unwrap_or(DEFAULT_ANSWER_TEMPERATURE);
+        if !(0.0..=MAX_ANSWER_TEMPERATURE).contains(&temperature) {
+            return Err(RecallError::Other(format!(
+                "temperature must be between 0 and {}, got {}",
+                MAX_ANSWER_TEMPERATURE, temperature
+            )));
+        }
+
+        Ok((max_tokens, temperature))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +89,12 @@ pub struct RagResponse {
     pub citations: Vec<Citation>,
     pub sources: Vec<SourceChunk>,
     pub conversation_id: String,
+    /// Suggested next questions, when `suggest_followups` is enabled
+    #[serde(default)]
+    pub follow_up_questions: Vec<String>,
+    /// How `answer` marks its citations; marker `[n]` refers to `citations[n - 1]`
+    #[serde(default)]
+    pub citation_style: CitationStyle,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +107,10 @@ pub struct SourceChunk {
     pub timestamp: Option<f64>,
     pub relevance_score: f64,
     pub search_type: SearchType,
+    /// Image to show beside the source, for image and screenshot documents
+    pub thumbnail_path: Option<String>,
+    pub start_offset: Option<i32>,
+    pub end_offset: Option<i32>,
 }
 
 impl RagEngine {
@@ -56,9 +123,49 @@ impl RagEngine {
             database,
             llm_client,
             settings,
+            retrieval_cache: RetrievalCache::default(),
         }
     }
 
+    /// Forget cached retrievals, e.g. after settings that change ranking
+    pub fn clear_retrieval_cache(&self) {
+        self.retrieval_cache.clear();
+    }
+
+    /// Hybrid (or keyword-only) retrieval, reusing a recent result for the
+    /// same query and scope while the library is unchanged
+    async fn retrieve(
+        &self,
+        llm: &LlmClient,
+        query: &str,
+        limit: usize,
+        document_ids: Option<&[String]>,
+    ) -> Result<Vec<ChunkWithScore>> {
+        let (hybrid, use_cache) = {
+            let settings = self.settings.read();
+            (settings.embeddings_enabled, settings.cache_retrieval)
+        };
+        let retriever = if hybrid {
+            HybridRetriever::new(self.database.clone(), llm.clone())
+        } else {
+            HybridRetriever::fts_only(self.database.clone())
+        };
+        if !use_cache {
+            return retriever.retrieve(query, limit, document_ids).await;
+        }
+
+        let key = RetrievalKey::new(query, document_ids, limit, hybrid);
+        let version = self.database.chunks_version()?;
+        if let Some(chunks) = self.retrieval_cache.get(&key, version) {
+            tracing::debug!("Reusing cached retrieval for {:?}", query);
+            return Ok(chunks);
+        }
+
+        let chunks = retriever.retrieve(query, limit, document_ids).await?;
+        self.retrieval_cache.insert(key, version, chunks.clone());
+        Ok(chunks)
+    }
+
     pub async fn query(&self, request: RagQuery) -> Result<RagResponse> {
         // Clone LLM client to avoid holding lock across await
         let llm = {
@@ -69,38 +176,80 @@ impl RagEngine {
                 .clone()
         };
 
-        // Get or create conversation
-        let (conversation_id, history) = match request.conversation_id {
+        let (max_tokens, temperature) = request.generation_params()?;
+
+        let request_scope = ConversationScope {
+            document_ids: request.document_ids,
+            categories: request.categories,
+        };
+
+        // Get or create conversation. A new conversation keeps the scope it
+        // was started with; later turns without a scope inherit it.
+        let (conversation_id, messages, scope) = match request.conversation_id {
             Some(id) => {
                 // Fetch existing conversation history for context
                 let messages = self.database.get_conversation_messages(&id)?;
-                let history: Vec<ConversationMessage> = messages
-                    .into_iter()
-                    .map(|m| ConversationMessage {
-                        role: match m.role {
-                            MessageRole::User => "user".to_string(),
-                            MessageRole::Assistant => "assistant".to_string(),
-                            MessageRole::System => "system".to_string(),
-                        },
-                        content: m.content,
-                    })
-                    .collect();
-                (id, history)
+                let scope = if request_scope.is_empty() {
+                    self.database
+                        .get_conversation(&id)?
+                        .map(|c| c.scope)
+                        .unwrap_or_default()
+                } else {
+                    request_scope
+                };
+                (id, messages, scope)
             }
             None => {
                 let conv = self.database.create_conversation(Some(&request.query))?;
-                (conv.id, vec![])
+                if !request_scope.is_empty() {
+                    self.database.set_conversation_scope(&conv.id, &request_scope)?;
+                }
+                (conv.id, vec![], request_scope)
             }
         };
 
         // Retrieve relevant chunks using hybrid search
-        let max_chunks = {
+        let (max_chunks, neighbor_window, chunk_size) = {
             let settings = self.settings.read();
-            request.max_chunks.unwrap_or(settings.max_context_chunks)
+            (
+                request.max_chunks.unwrap_or(settings.max_context_chunks),
+                settings.context_neighbor_window.min(MAX_CONTEXT_NEIGHBOR_WINDOW),
+                settings.chunk_size,
+            )
         };
 
-        let retriever = HybridRetriever::new(self.database.clone(), llm.clone());
-        let chunks = retriever.retrieve(&request.query, max_chunks, request.document_ids.as_deref()).await?;
+        let document_ids = scope_to_categories(
+            &self.database,
+            scope.document_ids,
+            scope.categories.as_deref(),
+        )?;
+
+        let mut chunks = self.retrieve(&llm, &request.query, max_chunks, document_ids.as_deref()).await?;
+
+        if self.settings.read().boost_frequent_documents {
+            let mut doc_ids: Vec<String> = chunks.iter().map(|c| c.chunk.document_id.clone()).collect();
+            doc_ids.sort();
+            doc_ids.dedup();
+            let counts = self.database.get_access_counts(&doc_ids)?;
+            boost_by_access(&mut chunks, &counts);
+        }
+
+        // Room for neighbours is what max_chunks full-size chunks would take
+        if neighbor_window > 0 {
+            chunks = expand_with_neighbors(&self.database, chunks, neighbor_window, max_chunks * chunk_size)?;
+        }
+
+        // Pinned chunks always lead the context, whatever the retrieval scores
+        let pinned = self.database.get_pinned_chunks(&conversation_id)?;
+        if !pinned.is_empty() {
+            chunks.retain(|c| !pinned.iter().any(|p| p.id == c.chunk.id));
+            let pinned = pinned.into_iter().map(|chunk| ChunkWithScore {
+                chunk,
+                score: 1.0,
+                search_type: SearchType::Pinned,
+            });
+            chunks.splice(0..0, pinned);
+        }
 
         if chunks.is_empty() {
             // No relevant context found
@@ -109,28 +258,67 @@ impl RagEngine {
                 citations: vec![],
                 sources: vec![],
                 conversation_id,
+                follow_up_questions: vec![],
+                citation_style: self.settings.read().citation_style,
             });
         }
 
         // Build context for generation
         let source_chunks = self.build_source_chunks(&chunks)?;
-        let context = self.build_context(&source_chunks);
+        let mut context = self.build_context(&source_chunks);
+        let (multimodal, max_images) = {
+            let settings = self.settings.read();
+            (settings.multimodal_rag, settings.max_context_images.min(MAX_CONTEXT_IMAGES))
+        };
+        if multimodal {
+            let attached = attach_source_images(&mut context, &source_chunks, max_images);
+            if attached > 0 {
+                tracing::debug!("Attached {} source images to the question", attached);
+            }
+        }
+
+        let (history, history_summary) = self.windowed_history(&llm, &conversation_id, messages).await;
+        let mut system_prompt = self.build_system_prompt();
+        if let Some(summary) = history_summary {
+            system_prompt.push_str(&format!(
+                "\n\nSUMMARY OF EARLIER CONVERSATION (older messages are not shown):\n{}",
+                summary
+            ));
+        }
 
         // Generate response
         let gen_request = GenerateRequest {
             prompt: request.query.clone(),
-            system_prompt: Some(self.build_system_prompt()),
+            system_prompt: Some(system_prompt),
             context,
             history,
-            max_tokens: Some(2000),
-            temperature: Some(0.7),
+            max_tokens: Some(max_tokens),
+            temperature: Some(temperature),
         };
 
         let response = llm.generate(gen_request).await?;
 
+        // Never save a blank assistant turn to history
+        if response.content.trim().is_empty() {
+            return Err(RecallError::LlmApi(
+                "The model returned an empty answer. Please try again.".to_string(),
+            ));
+        }
+
         // Build citations from response
         let citations = self.build_citations(&response.citations, &source_chunks)?;
 
+        let follow_up_questions = if self.settings.read().suggest_followups {
+            suggest_followups(&llm, &request.query, &response.content, self.build_context(&source_chunks))
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to suggest follow-up questions: {}", e);
+                    vec![]
+                })
+        } else {
+            vec![]
+        };
+
         // Save to conversation history
         self.database.add_message(
             &conversation_id,
@@ -146,8 +334,17 @@ impl RagEngine {
             &citations,
         )?;
 
+        // Cited documents count as accessed for "recently viewed" and ranking
+        let cited: Vec<String> = citations.iter().map(|c| c.document_id.clone()).collect();
+        if let Err(e) = self.database.record_document_access(&cited) {
+            tracing::warn!("Failed to record document access: {}", e);
+        }
+
+        // History keeps the model's own [chunk_id] markers; the reader gets
+        // the chosen style
+        let citation_style = self.settings.read().citation_style;
         Ok(RagResponse {
-            answer: response.content,
+            answer: format_citations(&response.content, &citations, citation_style),
             citations,
             sources: if request.include_sources {
                 source_chunks
@@ -155,24 +352,100 @@ impl RagEngine {
                 vec![]
             },
             conversation_id,
+            follow_up_questions,
+            citation_style,
         })
     }
 
+    /// Keep the most recent `max_history_messages` messages. Older ones are
+    /// represented by the conversation's running summary when enabled.
+    async fn windowed_history(
+        &self,
+        llm: &LlmClient,
+        conversation_id: &str,
+        messages: Vec<Message>,
+    ) -> (Vec<ConversationMessage>, Option<String>) {
+        let (max_messages, summarize) = {
+            let settings = self.settings.read();
+            (settings.max_history_messages, settings.summarize_history)
+        };
+
+        if max_messages == 0 || messages.len() <= max_messages {
+            return (messages.into_iter().map(to_conversation_message).collect(), None);
+        }
+
+        let split = messages.len() - max_messages;
+        let summary = if summarize {
+            self.running_summary(llm, conversation_id, &messages[..split]).await
+        } else {
+            None
+        };
+
+        let recent = messages
+            .into_iter()
+            .skip(split)
+            .map(to_conversation_message)
+            .collect();
+        (recent, summary)
+    }
+
+    /// Bring the cached summary up to date with `older`, summarizing only the
+    /// messages it doesn't cover yet. Falls back to the stale summary if the
+    /// LLM call fails, so a summary error never blocks answering.
+    async fn running_summary(&self, llm: &LlmClient, conversation_id: &str, older: &[Message]) -> Option<String> {
+        let cached = self
+            .database
+            .get_conversation_summary(conversation_id)
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load conversation summary: {}", e);
+                None
+            });
+
+        // A summary covering more than `older` overlaps the window (the window
+        // was shrunk then grown again), so start over rather than repeat turns
+        let (previous, covered) = match cached {
+            Some((summary, count)) if count <= older.len() => (Some(summary), count),
+            _ => (None, 0),
+        };
+
+        if covered == older.len() {
+            return previous;
+        }
+
+        match summarize_turns(llm, previous.as_deref(), &older[covered..]).await {
+            Ok(summary) => {
+                if let Err(e) = self
+                    .database
+                    .set_conversation_summary(conversation_id, &summary, older.len())
+                {
+                    tracing::warn!("Failed to cache conversation summary: {}", e);
+                }
+                Some(summary)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to summarize conversation history: {}", e);
+                previous
+            }
+        }
+    }
+
     fn build_source_chunks(&self, chunks: &[ChunkWithScore]) -> Result<Vec<SourceChunk>> {
         let mut sources = Vec::new();
-        let mut doc_cache: HashMap<String, String> = HashMap::new();
+        let mut doc_cache: HashMap<String, (String, Option<String>)> = HashMap::new();
 
         for cws in chunks {
-            let doc_title = if let Some(title) = doc_cache.get(&cws.chunk.document_id) {
-                title.clone()
+            let (doc_title, thumbnail_path) = if let Some(cached) = doc_cache.get(&cws.chunk.document_id) {
+                cached.clone()
             } else {
-                let title = self
-                    .database
-                    .get_document(&cws.chunk.document_id)?
-                    .map(|d| d.title)
-                    .unwrap_or_else(|| "Unknown".to_string());
-                doc_cache.insert(cws.chunk.document_id.clone(), title.clone());
-                title
+                let entry = match self.database.get_document(&cws.chunk.document_id)? {
+                    Some(doc) => {
+                        let thumbnail = thumbnail_path(&doc);
+                        (doc.title, thumbnail)
+                    }
+                    None => ("Unknown".to_string(), None),
+                };
+                doc_cache.insert(cws.chunk.document_id.clone(), entry.clone());
+                entry
             };
 
             sources.push(SourceChunk {
@@ -184,6 +457,9 @@ impl RagEngine {
                 timestamp: cws.chunk.timestamp_start,
                 relevance_score: cws.score,
                 search_type: cws.search_type,
+                thumbnail_path,
+                start_offset: cws.chunk.start_offset,
+                end_offset: cws.chunk.end_offset,
             });
         }
 
@@ -199,12 +475,14 @@ impl RagEngine {
                 source: s.document_title.clone(),
                 page: s.page_number,
                 timestamp: s.timestamp,
+                image: None,
             })
             .collect()
     }
 
     fn build_system_prompt(&self) -> String {
-        r#"You are RECALL.OS, an AI assistant that answers questions based on the user's personal knowledge base.
+        let citation_style = self.settings.read().citation_style;
+        let prompt = r#"You are RECALL.OS, an AI assistant that answers questions based on the user's personal knowledge base.
 
 ## Instructions
 
@@ -231,7 +509,8 @@ Provide clear, well-organized answers. Use markdown formatting when appropriate:
 - Use headers for long answers with multiple sections
 - Use code blocks for code or technical content
 
-When citing sources, naturally integrate citations into your response."#.to_string()
+When citing sources, naturally integrate citations into your response."#;
+        format!("{} {}", prompt, citation_style.prompt_instructions())
     }
 
     fn build_citations(
@@ -253,6 +532,9 @@ When citing sources, naturally integrate citations into your response."#.to_stri
                     page_number: source.page_number,
                     timestamp: source.timestamp,
                     relevance_score: source.relevance_score,
+                    thumbnail_path: source.thumbnail_path.clone(),
+                    start_offset: source.start_offset,
+                    end_offset: source.end_offset,
                 })
             })
             .collect();
@@ -261,6 +543,256 @@ When citing sources, naturally integrate citations into your response."#.to_stri
     }
 }
 
+/// The image file itself serves as the thumbnail for image and screenshot
+/// documents, as long as it is still on disk
+fn thumbnail_path(doc: &Document) -> Option<String> {
+    matches!(doc.file_type, FileType::Image | FileType::Screenshot)
+        .then(|| doc.file_path.clone())
+        .filter(|path| Path::new(path).exists())
+}
+
+/// Attach the image behind up to `limit` image or screenshot sources, once
+/// per document, to the first context chunk from it. Unreadable or oversized
+/// images are skipped. Returns how many were attached.
+fn attach_source_images(context: &mut [ContextChunk], sources: &[SourceChunk], limit: usize) -> usize {
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut attached = 0;
+
+    for (chunk, source) in context.iter_mut().zip(sources) {
+        if attached >= limit {
+            break;
+        }
+        let Some(path) = source.thumbnail_path.as_deref() else {
+            continue;
+        };
+        if !seen.insert(source.document_id.as_str()) {
+            continue;
+        }
+
+        let path = Path::new(path);
+        let too_large = std::fs::metadata(path).map_or(true, |m| m.len() > MAX_CONTEXT_IMAGE_BYTES);
+        let Some(mime_type) = detect_mime(path).filter(|m| m.starts_with("image/")) else {
+            continue;
+        };
+        if too_large {
+            tracing::debug!("Not attaching {:?}: missing or too large", path);
+            continue;
+        }
+        match std::fs::read(path) {
+            Ok(data) => {
+                chunk.image = Some(ContextImage { mime_type, data });
+                attached += 1;
+            }
+            Err(e) => tracing::warn!("Failed to read source image {:?}: {}", path, e),
+        }
+    }
+
+    attached
+}
+
+fn to_conversation_message(message: Message) -> ConversationMessage {
+    ConversationMessage {
+        role: match message.role {
+            MessageRole::User => "user".to_string(),
+            MessageRole::Assistant => "assistant".to_string(),
+            MessageRole::System => "system".to_string(),
+        },
+        content: message.content,
+    }
+}
+
+/// Fold `turns` into `previous` (if any) to produce an updated summary
+async fn summarize_turns(llm: &LlmClient, previous: Option<&str>, turns: &[Message]) -> Result<String> {
+    let transcript = turns
+        .iter()
+        .map(|m| {
+            let speaker = match m.role {
+                MessageRole::User => "User",
+                MessageRole::Assistant => "Assistant",
+                MessageRole::System => "System",
+            };
+            format!("{}: {}", speaker, m.content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let prompt = match previous {
+        Some(previous) => format!(
+            "Existing summary:\n{}\n\nNew messages:\n{}\n\nWrite the updated summary.",
+            previous, transcript
+        ),
+        None => format!("Messages:\n{}\n\nWrite the summary.", transcript),
+    };
+
+    let response = llm
+        .generate(GenerateRequest {
+            prompt,
+            system_prompt: Some(
+                "You maintain a running summary of a conversation between a user and an assistant \
+                 about the user's documents. Merge the new messages into the existing summary. Keep \
+                 names, numbers, decisions, and open questions the user may refer back to. Reply \
+                 with the summary only, in under 250 words."
+                    .to_string(),
+            ),
+            context: vec![],
+            history: vec![],
+            max_tokens: Some(500),
+            temperature: Some(0.2),
+        })
+        .await?;
+
+    Ok(response.content.trim().to_string())
+}
+
+/// Follow-up questions suggested after each answer
+const FOLLOWUP_COUNT: usize = 3;
+
+/// Ask for follow-up questions the retrieved context can answer
+async fn suggest_followups(
+    llm: &LlmClient,
+    question: &str,
+    answer: &str,
+    context: Vec<ContextChunk>,
+) -> Result<Vec<String>> {
+    let response = llm
+        .generate(GenerateRequest {
+            prompt: format!(
+                "Question:\n{}\n\nAnswer:\n{}\n\nSuggest {} follow-up questions.",
+                question, answer, FOLLOWUP_COUNT
+            ),
+            system_prompt: Some(format!(
+                "You suggest follow-up questions for a user exploring their personal knowledge base. \
+                 Each question must be answerable from the provided context and must not repeat the \
+                 original question. Keep each under 15 words. Reply with a JSON array of exactly {} \
+                 strings and nothing else.",
+                FOLLOWUP_COUNT
+            )),
+            context,
+            history: vec![],
+            max_tokens: Some(200),
+            temperature: Some(0.4),
+        })
+        .await?;
+
+    Ok(parse_followups(&response.content))
+}
+
+/// Read questions from a JSON array, falling back to one question per line
+fn parse_followups(content: &str) -> Vec<String> {
+    let trimmed = content
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+
+    let questions = serde_json::from_str::<Vec<String>>(trimmed).unwrap_or_else(|_| {
+        trimmed
+            .lines()
+            .map(|line| line.trim_start_matches(|c: char| c.is_ascii_digit() || "-*.) ".contains(c)))
+            .map(str::to_string)
+            .collect()
+    });
+
+    questions
+        .into_iter()
+        .map(|q| q.trim().to_string())
+        .filter(|q| !q.is_empty())
+        .take(FOLLOWUP_COUNT)
+        .collect()
+}
+
+/// Narrow a document filter to documents carrying any of the given categories
+pub fn scope_to_categories(
+    database: &Database,
+    document_ids: Option<Vec<String>>,
+    categories: Option<&[String]>,
+) -> Result<Option<Vec<String>>> {
+    let categories = match categories {
+        Some(c) if !c.is_empty() => c,
+        _ => return Ok(document_ids),
+    };
+
+    let category_ids = database.get_document_ids_by_categories(categories)?;
+
+    Ok(Some(match document_ids {
+        Some(ids) => ids.into_iter().filter(|id| category_ids.contains(id)).collect(),
+        None => category_ids,
+    }))
+}
+
+/// Surround each retrieved chunk with up to `window` chunks on either side
+/// from the same document, in document order. Chunks already in the context
+/// aren't repeated, and the nearest neighbours are added first until the
+/// context would exceed `token_budget` tokens.
+fn expand_with_neighbors(
+    database: &Database,
+    chunks: Vec<ChunkWithScore>,
+    window: usize,
+    token_budget: usize,
+) -> Result<Vec<ChunkWithScore>> {
+    let mut seen: HashSet<i64> = chunks.iter().map(|c| c.chunk.id).collect();
+    let mut tokens: usize = chunks.iter().map(|c| c.chunk.token_count.max(0) as usize).sum();
+    let mut expanded = Vec::with_capacity(chunks.len());
+
+    for cws in chunks {
+        let anchor = cws.chunk.chunk_index;
+        let mut neighbors: Vec<Chunk> = if tokens < token_budget {
+            database
+                .get_neighbor_chunks(&cws.chunk.document_id, anchor, window as i32)?
+                .into_iter()
+                .filter(|c| !seen.contains(&c.id))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        neighbors.sort_by_key(|c| ((c.chunk_index - anchor).abs(), c.chunk_index));
+
+        let mut added = Vec::new();
+        for chunk in neighbors {
+            let chunk_tokens = chunk.token_count.max(0) as usize;
+            if tokens + chunk_tokens > token_budget {
+                break;
+            }
+            tokens += chunk_tokens;
+            seen.insert(chunk.id);
+            added.push(ChunkWithScore {
+                chunk,
+                score: cws.score,
+                search_type: SearchType::Neighbor,
+            });
+        }
+        added.sort_by_key(|c| c.chunk.chunk_index);
+
+        let split = added.partition_point(|c| c.chunk.chunk_index < anchor);
+        let after = added.split_off(split);
+        expanded.extend(added);
+        expanded.push(cws);
+        expanded.extend(after);
+    }
+
+    Ok(expanded)
+}
+
+/// Narrow a document filter to documents that arrived through any of the given sources
+pub fn scope_to_sources(
+    database: &Database,
+    document_ids: Option<Vec<String>>,
+    sources: Option<&[IngestionSource]>,
+) -> Result<Option<Vec<String>>> {
+    let sources = match sources {
+        Some(s) if !s.is_empty() => s,
+        _ => return Ok(document_ids),
+    };
+
+    let source_ids = database.get_document_ids_by_sources(sources)?;
+
+    Ok(Some(match document_ids {
+        Some(ids) => ids.into_iter().filter(|id| source_ids.contains(id)).collect(),
+        None => source_ids,
+    }))
+}
+
 fn truncate_snippet(text: &str, max_len: usize) -> String {
     if text.len() <= max_len {
         text.to_string()
@@ -268,3 +800,398 @@ fn truncate_snippet(text: &str, max_len: usize) -> String {
         format!("{}...", &text[..max_len])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{Chunk, Document, DocumentStatus, FileType};
+    use crate::llm::MockLlmProvider;
+    use chrono::Utc;
+    use std::path::Path;
+    use std::sync::atomic::Ordering;
+    use tempfile::tempdir;
+
+    fn insert_document_with_chunk(database: &Database, content: &str) -> i64 {
+        insert_document_with_chunks(database, &[content])[0]
+    }
+
+    fn insert_document_with_chunks(database: &Database, contents: &[&str]) -> Vec<i64> {
+        let doc = Document {
+            id: "doc-1".to_string(),
+            title: "Transfers".to_string(),
+            file_path: "/tmp/transfers.md".to_string(),
+            file_type: FileType::Markdown,
+            file_size: contents.iter().map(|c| c.len() as i64).sum(),
+            file_hash: "hash".to_string(),
+            mime_type: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            ingested_at: None,
+            status: DocumentStatus::Completed,
+            error_message: None,
+            metadata: serde_json::json!({}),
+            ingestion_source: Default::default(),
+            chunk_count: 0,
+            total_tokens: 0,
+            last_accessed: None,
+            access_count: 0,
+        };
+        database.insert_document(&doc).unwrap();
+
+        let chunks: Vec<Chunk> = contents
+            .iter()
+            .enumerate()
+            .map(|(i, content)| Chunk {
+                id: 0,
+                document_id: doc.id.clone(),
+                chunk_index: i as i32,
+                content: content.to_string(),
+                token_count: 20,
+                start_offset: None,
+                end_offset: None,
+                page_number: None,
+                timestamp_start: None,
+                timestamp_end: None,
+                metadata: serde_json::json!({}),
+                created_at: Utc::now(),
+            })
+            .collect();
+        database.insert_chunks(&chunks).unwrap()
+    }
+
+    #[test]
+    fn test_parse_followups() {
+        let json = "```json\n[\"What changed in v2?\", \"Who approved it?\", \"When?\", \"Extra\"]\n```";
+        assert_eq!(
+            parse_followups(json),
+            vec!["What changed in v2?", "Who approved it?", "When?"]
+        );
+
+        let lines = "1. What changed in v2?\n- Who approved it?\n\n";
+        assert_eq!(parse_followups(lines), vec!["What changed in v2?", "Who approved it?"]);
+    }
+
+    #[tokio::test]
+    async fn test_query_cites_context_and_windows_history() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let database = Arc::new(
+            Database::new(&temp_dir.path().join("test.db"), Path::new("."))
+                .expect("Failed to create database"),
+        );
+        let chunk_id = insert_document_with_chunk(
+            &database,
+            "A Hohmann transfer uses two engine burns to move between circular orbits.",
+        );
+
+        let conversation = database.create_conversation(Some("Orbits")).unwrap();
+        for i in 0..3 {
+            database
+                .add_message(&conversation.id, MessageRole::User, &format!("question {}", i), &[])
+                .unwrap();
+            database
+                .add_message(&conversation.id, MessageRole::Assistant, &format!("answer {}", i), &[])
+                .unwrap();
+        }
+
+        let settings = Settings {
+            max_history_messages: 2,
+            summarize_history: true,
+            ..Settings::default()
+        };
+        let mock = Arc::new(MockLlmProvider::default());
+        let engine = RagEngine::new(
+            database.clone(),
+            Arc::new(RwLock::new(Some(LlmClient::mock(mock.clone())))),
+            Arc::new(RwLock::new(settings)),
+        );
+
+        let response = engine
+            .query(RagQuery {
+                query: "How does a Hohmann transfer work?".to_string(),
+                conversation_id: Some(conversation.id.clone()),
+                max_chunks: Some(5),
+                include_sources: true,
+                document_ids: None,
+                categories: None,
+                max_tokens: None,
+                temperature: None,
+            })
+            .await
+            .expect("Query failed");
+
+        assert_eq!(response.citations.len(), 1);
+        assert_eq!(response.citations[0].chunk_id, chunk_id);
+        assert_eq!(response.sources[0].document_title, "Transfers");
+
+        // One call to summarize the four older messages, one to answer
+        assert_eq!(mock.generate_calls.load(Ordering::SeqCst), 2);
+        let request = mock.last_request.lock().clone().unwrap();
+        assert_eq!(request.history.len(), 2);
+        assert!(request.system_prompt.unwrap().contains("SUMMARY OF EARLIER CONVERSATION"));
+        assert_eq!(
+            database.get_conversation_summary(&conversation.id).unwrap().map(|(_, n)| n),
+            Some(4)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conversation_scope_applies_to_later_turns() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let database = Arc::new(
+            Database::new(&temp_dir.path().join("test.db"), Path::new("."))
+                .expect("Failed to create database"),
+        );
+        insert_document_with_chunk(
+            &database,
+            "A Hohmann transfer uses two engine burns to move between circular orbits.",
+        );
+
+        let engine = RagEngine::new(
+            database.clone(),
+            Arc::new(RwLock::new(Some(LlmClient::mock(Arc::new(MockLlmProvider::default()))))),
+            Arc::new(RwLock::new(Settings::default())),
+        );
+        let ask = |conversation_id: Option<String>, document_ids: Option<Vec<String>>| RagQuery {
+            query: "Hohmann transfer".to_string(),
+            conversation_id,
+            max_chunks: Some(5),
+            include_sources: true,
+            document_ids,
+            categories: None,
+            max_tokens: None,
+            temperature: None,
+        };
+
+        let first = engine
+            .query(ask(None, Some(vec!["elsewhere".to_string()])))
+            .await
+            .expect("Query failed");
+        assert!(first.sources.is_empty());
+        let id = first.conversation_id;
+        let conversation = database.get_conversation(&id).unwrap().unwrap();
+        assert_eq!(conversation.scope.document_ids, Some(vec!["elsewhere".to_string()]));
+
+        // A later turn without its own scope inherits the conversation's
+        let second = engine.query(ask(Some(id.clone()), None)).await.expect("Query failed");
+        assert!(second.sources.is_empty());
+
+        database.set_conversation_scope(&id, &ConversationScope::default()).unwrap();
+        let third = engine.query(ask(Some(id), None)).await.expect("Query failed");
+        assert_eq!(third.sources.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_chunks_lead_the_context() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let database = Arc::new(
+            Database::new(&temp_dir.path().join("test.db"), Path::new("."))
+                .expect("Failed to create database"),
+        );
+        let chunk_id = insert_document_with_chunk(&database, "The launch window opens in March.");
+        let conversation = database.create_conversation(Some("Launch")).unwrap();
+        database.pin_chunk(&conversation.id, chunk_id).unwrap();
+        database.pin_chunk(&conversation.id, chunk_id).unwrap();
+        assert!(database.pin_chunk(&conversation.id, chunk_id + 100).is_err());
+
+        let engine = RagEngine::new(
+            database.clone(),
+            Arc::new(RwLock::new(Some(LlmClient::mock(Arc::new(MockLlmProvider::default()))))),
+            Arc::new(RwLock::new(Settings::default())),
+        );
+        // Nothing in the query matches the pinned chunk, and retrieval can't
+        // add it a second time when it does
+        for query in ["Hohmann transfer", "launch window"] {
+            let response = engine
+                .query(RagQuery {
+                    query: query.to_string(),
+                    conversation_id: Some(conversation.id.clone()),
+                    max_chunks: Some(5),
+                    include_sources: true,
+                    document_ids: None,
+                    categories: None,
+                    max_tokens: None,
+                    temperature: None,
+                })
+                .await
+                .expect("Query failed");
+            assert_eq!(response.sources.len(), 1);
+            assert_eq!(response.sources[0].chunk_id, chunk_id);
+        }
+
+        database.unpin_chunk(&conversation.id, chunk_id).unwrap();
+        assert!(database.get_pinned_chunks(&conversation.id).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_empty_answer_is_an_error_and_not_saved() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let database = Arc::new(
+            Database::new(&temp_dir.path().join("test.db"), Path::new("."))
+                .expect("Failed to create database"),
+        );
+        insert_document_with_chunk(&database, "A Hohmann transfer uses two engine burns.");
+        let conversation = database.create_conversation(Some("Orbits")).unwrap();
+
+        let mock = MockLlmProvider {
+            answer: String::new(),
+            ..MockLlmProvider::default()
+        };
+        let engine = RagEngine::new(
+            database.clone(),
+            Arc::new(RwLock::new(Some(LlmClient::mock(Arc::new(mock))))),
+            Arc::new(RwLock::new(Settings::default())),
+        );
+
+        let result = engine
+            .query(RagQuery {
+                query: "Hohmann transfer".to_string(),
+                conversation_id: Some(conversation.id.clone()),
+                max_chunks: Some(5),
+                include_sources: false,
+                document_ids: None,
+                categories: None,
+                max_tokens: None,
+                temperature: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(RecallError::LlmApi(_))));
+        assert!(database.get_conversation_messages(&conversation.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_neighbors_surround_matches_within_budget() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let database = Database::new(&temp_dir.path().join("test.db"), Path::new("."))
+            .expect("Failed to create database");
+        let ids = insert_document_with_chunks(&database, &["zero", "one", "two", "three", "four", "five", "six"]);
+        let retrieved = |indexes: &[usize]| -> Vec<ChunkWithScore> {
+            let chunks = database.get_chunks_by_ids(&indexes.iter().map(|&i| ids[i]).collect::<Vec<_>>()).unwrap();
+            indexes
+                .iter()
+                .map(|&i| ChunkWithScore {
+                    chunk: chunks.iter().find(|c| c.id == ids[i]).unwrap().clone(),
+                    score: 0.5,
+                    search_type: SearchType::Hybrid,
+                })
+                .collect()
+        };
+        let order = |chunks: &[ChunkWithScore]| chunks.iter().map(|c| c.chunk.chunk_index).collect::<Vec<_>>();
+
+        // Overlapping windows around 2 and 4 share chunk 3 once
+        let expanded = expand_with_neighbors(&database, retrieved(&[4, 2]), 1, 1000).unwrap();
+        assert_eq!(order(&expanded), vec![3, 4, 5, 1, 2]);
+        assert!(matches!(expanded[0].search_type, SearchType::Neighbor));
+        assert!(matches!(expanded[1].search_type, SearchType::Hybrid));
+
+        // 20 tokens a chunk: room for the match and its two nearest neighbours
+        let expanded = expand_with_neighbors(&database, retrieved(&[3]), 2, 60).unwrap();
+        assert_eq!(order(&expanded), vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_search_within_document_highlights_body_matches() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let database = Arc::new(
+            Database::new(&temp_dir.path().join("test.db"), Path::new(".")).expect("Failed to create database"),
+        );
+        let ids = insert_document_with_chunks(
+            &database,
+            &["Wire transfers settle overnight.", "Nothing relevant here.", "A transfer fee applies abroad."],
+        );
+
+        // The title "Transfers" alone doesn't make every chunk match
+        let retriever = HybridRetriever::fts_only(database.clone());
+        let matches = retriever.retrieve_in_document("transfer", "doc-1", 10).await.unwrap();
+        let mut found: Vec<i64> = matches.iter().map(|m| m.chunk.id).collect();
+        found.sort();
+        assert_eq!(found, vec![ids[0], ids[2]]);
+
+        assert!(retriever.retrieve_in_document("transfer", "doc-2", 10).await.unwrap().is_empty());
+
+        let segments = highlight_snippet("Wire transfers settle overnight.", "transfer", 200);
+        assert_eq!(
+            segments,
+            vec![
+                SnippetSegment { text: "Wire ".to_string(), highlighted: false },
+                SnippetSegment { text: "transfers".to_string(), highlighted: true },
+                SnippetSegment { text: " settle overnight.".to_string(), highlighted: false },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generation_params_override_defaults_within_bounds() {
+        let query = |max_tokens, temperature| RagQuery {
+            query: "Hohmann transfer".to_string(),
+            conversation_id: None,
+            max_chunks: None,
+            include_sources: false,
+            document_ids: None,
+            categories: None,
+            max_tokens,
+            temperature,
+        };
+
+        assert_eq!(
+            query(None, None).generation_params().unwrap(),
+            (DEFAULT_ANSWER_MAX_TOKENS, DEFAULT_ANSWER_TEMPERATURE)
+        );
+        assert_eq!(query(Some(300), Some(0.0)).generation_params().unwrap(), (300, 0.0));
+        assert!(query(Some(0), None).generation_params().is_err());
+        assert!(query(Some(MAX_ANSWER_TOKENS + 1), None).generation_params().is_err());
+        assert!(query(None, Some(2.5)).generation_params().is_err());
+        assert!(query(None, Some(f32::NAN)).generation_params().is_err());
+    }
+
+    #[test]
+    fn test_source_images_attached_once_per_document_up_to_limit() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let png = temp_dir.path().join("screen.png");
+        image::RgbaImage::new(4, 4).save(&png).unwrap();
+        let png = png.to_string_lossy().to_string();
+
+        let source = |chunk_id, document_id: &str, thumbnail_path: Option<&str>| SourceChunk {
+            chunk_id,
+            document_id: document_id.to_string(),
+            document_title: document_id.to_string(),
+            content: "Quarterly revenue chart".to_string(),
+            page_number: None,
+            timestamp: None,
+            relevance_score: 1.0,
+            search_type: SearchType::Hybrid,
+            thumbnail_path: thumbnail_path.map(str::to_string),
+            start_offset: None,
+            end_offset: None,
+        };
+        let sources = vec![
+            source(1, "notes", None),
+            source(2, "screen-a", Some(&png)),
+            source(3, "screen-a", Some(&png)),
+            source(4, "screen-b", Some(&png)),
+        ];
+        let to_context = |sources: &[SourceChunk]| -> Vec<ContextChunk> {
+            sources
+                .iter()
+                .map(|s| ContextChunk {
+                    id: s.chunk_id,
+                    content: s.content.clone(),
+                    source: s.document_title.clone(),
+                    page: None,
+                    timestamp: None,
+                    image: None,
+                })
+                .collect()
+        };
+
+        let mut context = to_context(&sources);
+        assert_eq!(attach_source_images(&mut context, &sources, 5), 2);
+        let with_image: Vec<i64> = context.iter().filter(|c| c.image.is_some()).map(|c| c.id).collect();
+        assert_eq!(with_image, vec![2, 4]);
+        assert_eq!(context[1].image.as_ref().unwrap().mime_type, "image/png");
+
+        let mut context = to_context(&sources);
+        assert_eq!(attach_source_images(&mut context, &sources, 1), 1);
+        assert!(context[3].image.is_none());
+    }
+}
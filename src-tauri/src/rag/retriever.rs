@@ -1,12 +1,13 @@
 use crate::database::{Chunk, ChunkWithScore, Database, SearchType};
 use crate::error::Result;
-use crate::llm::{LlmClient, LlmProvider};
+use crate::llm::LlmClient;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct HybridRetriever {
     database: Arc<Database>,
-    llm: LlmClient,
+    /// None for keyword-only retrieval
+    llm: Option<LlmClient>,
 }
 
 /// Related document found through similarity search
@@ -19,7 +20,15 @@ pub struct RelatedDocument {
 
 impl HybridRetriever {
     pub fn new(database: Arc<Database>, llm: LlmClient) -> Self {
-        Self { database, llm }
+        Self {
+            database,
+            llm: Some(llm),
+        }
+    }
+
+    /// Retriever that skips vector search, for libraries ingested without embeddings
+    pub fn fts_only(database: Arc<Database>) -> Self {
+        Self { database, llm: None }
     }
 
     /// Find documents similar to the given document
@@ -38,6 +47,8 @@ impl HybridRetriever {
             return Ok(vec![]);
         }
 
+        let metric = self.database.get_similarity_metric()?;
+
         // Get embeddings for source chunks and search for similar ones
         let mut doc_scores: HashMap<String, (String, f64)> = HashMap::new();
 
@@ -51,7 +62,8 @@ impl HybridRetriever {
                 Ok(results) => {
                     tracing::debug!("Vector search returned {} results for chunk {}", results.len(), chunk.id);
                     for (chunk_id, distance) in results {
-                        tracing::debug!("  Result: chunk_id={}, distance={}, similarity={}", chunk_id, distance, 1.0 / (1.0 + distance));
+                        let similarity = metric.similarity(distance);
+                        tracing::debug!("  Result: chunk_id={}, distance={}, similarity={}", chunk_id, distance, similarity);
                         // Get the chunk to find its document
                         if let Ok(Some(related_chunk)) = self.database.get_chunk(chunk_id) {
                             // Skip chunks from the same document
@@ -59,7 +71,6 @@ impl HybridRetriever {
                                 continue;
                             }
 
-                            let similarity = 1.0 / (1.0 + distance);
                             if similarity >= min_similarity {
                                 // Get document info
                                 if let Ok(Some(doc)) = self.database.get_document(&related_chunk.document_id) {
@@ -107,10 +118,49 @@ impl HybridRetriever {
         Ok(merged)
     }
 
+    /// Search within one document's chunks, for an in-document find
+    pub async fn retrieve_in_document(&self, query: &str, document_id: &str, limit: usize) -> Result<Vec<ChunkWithScore>> {
+        let fts_query = prepare_fts_query(query);
+        let fts_results = if fts_query.is_empty() {
+            vec![]
+        } else {
+            self.database
+                .fts_search_in_document(&fts_query, document_id, limit * 2)?
+                .into_iter()
+                .map(|(chunk_id, score)| (chunk_id, score, SearchType::Fts))
+                .collect()
+        };
+
+        let vector_results = match &self.llm {
+            Some(llm) => match llm.embed_query(query).await {
+                Ok(embedding) if !embedding.is_empty() => {
+                    let metric = self.database.get_similarity_metric()?;
+                    self.database
+                        .vector_search_in_document(&embedding, document_id, limit * 2)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|(chunk_id, distance)| (chunk_id, metric.similarity(distance), SearchType::Vector))
+                        .collect()
+                }
+                Ok(_) => vec![],
+                Err(e) => {
+                    tracing::warn!("In-document vector search failed, using keywords only: {}", e);
+                    vec![]
+                }
+            },
+            _ => vec![],
+        };
+
+        self.reciprocal_rank_fusion(vector_results, fts_results, limit, Some(&[document_id.to_string()]))
+    }
+
     async fn vector_search(&self, query: &str, limit: usize) -> Result<Vec<(i64, f64, SearchType)>> {
+        let Some(llm) = &self.llm else {
+            return Ok(vec![]);
+        };
+
         // Generate query embedding
-        let embeddings = self.llm.embed(&[query.to_string()]).await?;
-        let query_embedding = embeddings.into_iter().next().unwrap_or_default();
+        let query_embedding = llm.embed_query(query).await?;
 
         if query_embedding.is_empty() {
             return Ok(vec![]);
@@ -118,13 +168,14 @@ impl HybridRetriever {
 
         // Search vectors
         let results = self.database.vector_search(&query_embedding, limit)?;
+        let metric = self.database.get_similarity_metric()?;
 
-        // Convert distance to similarity score (assuming cosine distance)
+        // Convert distance to similarity score under the index's metric
         // Lower distance = higher similarity
         let scored: Vec<_> = results
             .into_iter()
             .map(|(chunk_id, distance)| {
-                let similarity = 1.0 / (1.0 + distance);
+                let similarity = metric.similarity(distance);
                 (chunk_id, similarity, SearchType::Vector)
             })
             .collect();
@@ -273,6 +324,105 @@ fn prepare_fts_query(query: &str) -> String {
     }
 }
 
+/// A run of snippet text, marked when it matches a query term
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SnippetSegment {
+    pub text: String,
+    pub highlighted: bool,
+}
+
+/// Cut a snippet of about `max_chars` around the first word of `text` that
+/// matches a query term, split into segments with the matching words
+/// highlighted. Terms match word prefixes, as the FTS query does.
+pub fn highlight_snippet(text: &str, query: &str, max_chars: usize) -> Vec<SnippetSegment> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 1)
+        .map(str::to_lowercase)
+        .collect();
+    let is_match = |word: &str| {
+        let word = word.to_lowercase();
+        terms.iter().any(|term| word.starts_with(term.as_str()))
+    };
+
+    // Words as (byte start, byte end), split on anything not alphanumeric
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                words.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    // Open the window a little before the first match
+    let first = words.iter().find(|&&(s, e)| is_match(&text[s..e])).map_or(0, |&(s, _)| s);
+    let lead = max_chars / 4;
+    let window_start = text[..first]
+        .char_indices()
+        .rev()
+        .nth(lead.saturating_sub(1))
+        .map_or(0, |(i, _)| i);
+    let window_start = if window_start == 0 {
+        0
+    } else {
+        // Start on a word boundary
+        words.iter().map(|&(s, _)| s).find(|&s| s >= window_start).unwrap_or(window_start)
+    };
+    let window_end = text[window_start..]
+        .char_indices()
+        .nth(max_chars)
+        .map_or(text.len(), |(i, _)| window_start + i);
+
+    let mut segments: Vec<SnippetSegment> = Vec::new();
+    let mut push = |text: &str, highlighted: bool| {
+        if text.is_empty() {
+            return;
+        }
+        match segments.last_mut() {
+            Some(last) if last.highlighted == highlighted => last.text.push_str(text),
+            _ => segments.push(SnippetSegment { text: text.to_string(), highlighted }),
+        }
+    };
+
+    if window_start > 0 {
+        push("...", false);
+    }
+    let mut cursor = window_start;
+    for &(s, e) in words.iter().filter(|&&(s, e)| s >= window_start && e <= window_end) {
+        if is_match(&text[s..e]) {
+            push(&text[cursor..s], false);
+            push(&text[s..e], true);
+            cursor = e;
+        }
+    }
+    push(&text[cursor..window_end], false);
+    if window_end < text.len() {
+        push("...", false);
+    }
+
+    segments
+}
+
+/// Weight of the access boost: a document opened or cited `n` times scores
+/// `1 + ACCESS_BOOST_WEIGHT * ln(1 + n)` times its retrieval score
+const ACCESS_BOOST_WEIGHT: f64 = 0.1;
+
+/// Re-rank chunks so frequently accessed documents edge ahead of equally
+/// relevant ones. The log keeps a heavily used document from crowding out
+/// better matches.
+pub fn boost_by_access(chunks: &mut [ChunkWithScore], access_counts: &HashMap<String, i64>) {
+    for cws in chunks.iter_mut() {
+        let count = access_counts.get(&cws.chunk.document_id).copied().unwrap_or(0).max(0);
+        cws.score *= 1.0 + ACCESS_BOOST_WEIGHT * (count as f64).ln_1p();
+    }
+    chunks.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+}
+
 pub struct TieredRetriever {
     database: Arc<Database>,
     llm: LlmClient,
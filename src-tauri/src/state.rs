@@ -1,13 +1,17 @@
-use crate::capture::CaptureManager;
-use crate::database::Database;
+use crate::capture::{resolve_captures_dir, CaptureManager};
+use crate::database::{Database, FileType, FtsTokenizer, SimilarityMetric};
 use crate::error::{RecallError, Result};
-use crate::ingestion::{IngestionEngine, WatcherManager};
+use crate::ingestion::{default_exclude_patterns, FFmpeg, IngestionEngine, WatcherManager};
 use crate::llm::LlmClient;
-use crate::rag::RagEngine;
+use crate::local_api::LocalApiServer;
+use crate::rag::{CitationStyle, RagEngine};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Manager, Runtime};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,13 +22,67 @@ pub struct Settings {
     pub reasoning_model: String,
     pub chunk_size: usize,
     pub chunk_overlap: usize,
+    // Drop exact-duplicate chunks within a document (repeated headers/footers)
+    #[serde(default)]
+    pub dedupe_chunks: bool,
+    // Chunks shorter than this (stray page numbers, single words) are merged into a neighbour
+    #[serde(default = "default_min_chunk_chars")]
+    pub min_chunk_chars: usize,
+    // Keep repeated page headers/footers and figure captions out of embeddings and keyword search
+    #[serde(default)]
+    pub index_body_chunks_only: bool,
     pub max_context_chunks: usize,
+    // Adjacent chunks added on each side of every retrieved chunk (0 = off)
+    #[serde(default)]
+    pub context_neighbor_window: usize,
+    // Most recent conversation messages sent with each question (0 = all)
+    #[serde(default = "default_max_history_messages")]
+    pub max_history_messages: usize,
+    // Condense messages outside the window into a running summary
+    #[serde(default = "default_summarize_history")]
+    pub summarize_history: bool,
+    // Where answers show their citations: "inline" markers, "footnotes"
+    // (markers plus a source list) or an "end_list" of sources only
+    #[serde(default)]
+    pub citation_style: CitationStyle,
+    // Suggest follow-up questions after each answer (one extra LLM call)
+    #[serde(default)]
+    pub suggest_followups: bool,
+    // Nudge retrieval toward documents that are opened or cited often
+    #[serde(default)]
+    pub boost_frequent_documents: bool,
+    // Send the image itself along with the OCR text of retrieved image and
+    // screenshot chunks, so answers can use layout and diagrams
+    #[serde(default)]
+    pub multimodal_rag: bool,
+    // Most images attached to one question when multimodal_rag is on
+    #[serde(default = "default_max_context_images")]
+    pub max_context_images: usize,
+    // Reuse retrieval results for a repeated query and scope for a few
+    // minutes, until the library changes
+    #[serde(default = "default_cache_retrieval")]
+    pub cache_retrieval: bool,
+    // Check the vector index, refresh planner statistics and open the
+    // embedding connection in the background at launch
+    #[serde(default = "default_warmup_on_startup")]
+    pub warmup_on_startup: bool,
     pub video_segment_duration: u64,
     pub keyframe_interval: f64,
+    // Frames sent to the vision model per video segment, evenly spaced
+    #[serde(default = "default_max_video_frames_per_segment")]
+    pub max_video_frames_per_segment: usize,
     #[serde(default)]
     pub watched_folders: Vec<String>,
+    // Exclude patterns per watched folder (see ingestion/exclude.rs); folders
+    // without an entry use the built-in defaults
+    #[serde(default)]
+    pub watched_folder_excludes: HashMap<String, Vec<String>>,
     #[serde(default = "default_auto_ingest")]
     pub auto_ingest_enabled: bool,
+    // File types ingested from disk; files of other types are skipped by
+    // manual, directory and watched-folder ingestion
+    #[serde(default = "default_enabled_file_types")]
+    pub enabled_file_types: Vec<FileType>,
     // Screen capture settings
     #[serde(default)]
     pub screen_capture_enabled: bool,
@@ -38,8 +96,111 @@ pub struct Settings {
     pub capture_app_list: Vec<String>,
     #[serde(default = "default_capture_retention")]
     pub capture_retention_days: u32,
+    // Most screenshots kept regardless of age (0 = no cap)
+    #[serde(default)]
+    pub capture_retention_max_count: u32,
     #[serde(default = "default_capture_hotkey")]
     pub capture_hotkey: String,
+    #[serde(default = "default_capture_trigger_mode")]
+    pub capture_trigger_mode: String,
+    #[serde(default = "default_capture_min_interval")]
+    pub capture_min_interval_secs: u64,
+    // Per-app capture intervals in seconds, keyed by process name
+    #[serde(default)]
+    pub capture_app_intervals: HashMap<String, u64>,
+    // Skip scheduled captures after this many seconds without input (0 = off)
+    #[serde(default)]
+    pub capture_idle_threshold_secs: u64,
+    // Pixelate card numbers, keys and passwords in screenshots before ingesting them
+    #[serde(default)]
+    pub capture_redact_sensitive_regions: bool,
+    // Where new captures are saved (defaults to app data/captures)
+    #[serde(default)]
+    pub captures_directory: Option<String>,
+    // Embedding settings
+    // Off = keyword-only library: no embedding calls during ingestion or search
+    #[serde(default = "default_embeddings_enabled")]
+    pub embeddings_enabled: bool,
+    #[serde(default = "default_embedding_batch_size")]
+    pub embedding_batch_size: usize,
+    // Request timeouts
+    #[serde(default = "default_generation_timeout")]
+    pub generation_timeout_secs: u64,
+    #[serde(default = "default_upload_timeout")]
+    pub upload_timeout_secs: u64,
+    #[serde(default = "default_embedding_timeout")]
+    pub embedding_timeout_secs: u64,
+    // Rate limiting (requests per minute for the Gemini tier in use)
+    #[serde(default = "default_gemini_requests_per_minute")]
+    pub gemini_requests_per_minute: u64,
+    // Language detection
+    #[serde(default = "default_detect_language")]
+    pub detect_language_on_ingest: bool,
+    // Full-text search tokenizer: "porter", "unicode61" or "trigram"
+    #[serde(default = "default_fts_tokenizer")]
+    pub fts_tokenizer: String,
+    // Vector similarity metric: "cosine", "l2" or "dot". Match the embedding
+    // model; changing it rebuilds the vector index.
+    #[serde(default = "default_similarity_metric")]
+    pub similarity_metric: String,
+    // Where a file with the same content as an existing document counts as
+    // that document moved: "global" (anywhere) or "folder" (within the same
+    // watched folder, or the same directory outside watched folders)
+    #[serde(default = "default_dedupe_scope")]
+    pub dedupe_scope: String,
+    // Store new chunk text zstd-compressed, trading CPU for database size.
+    // Existing chunks are converted with convert_chunk_storage.
+    #[serde(default)]
+    pub compress_chunk_text: bool,
+    // Abort an ingestion after this many seconds without progress (0 disables)
+    #[serde(default = "default_ingestion_timeout")]
+    pub ingestion_timeout_secs: u64,
+    // Pause between queued files to stay under provider rate limits; skipped
+    // while the rate limiter has plenty of headroom (0 disables)
+    #[serde(default = "default_ingestion_cooldown_ms")]
+    pub ingestion_cooldown_ms: u64,
+    // Retries for an extraction or embedding stage that fails with a
+    // transient error (rate limit, network, provider error); 0 disables
+    #[serde(default = "default_ingestion_max_retries")]
+    pub ingestion_max_retries: u32,
+    // Categorize documents as part of ingestion (one extra LLM call per
+    // document; screenshots with little text are skipped)
+    #[serde(default)]
+    pub auto_categorize: bool,
+    // Prior versions kept per file when a changed file is re-ingested, each
+    // with an LLM summary of what changed (0 disables)
+    #[serde(default)]
+    pub document_versions_kept: usize,
+    // PDF page render scales for Windows OCR and vision-model OCR (1.0 = 72
+    // DPI); clamped to 0.5-8.0 and lowered per page to stay under pixel caps
+    #[serde(default = "default_ocr_render_scale")]
+    pub ocr_render_scale: f64,
+    #[serde(default = "default_vision_ocr_render_scale")]
+    pub vision_ocr_render_scale: f64,
+    // Scanned PDF pages sent to vision-model OCR per document; later pages
+    // are skipped and the document marked partial (0 = no limit)
+    #[serde(default = "default_max_ocr_pages")]
+    pub max_ocr_pages: u32,
+    // Path to ffmpeg, overriding the bundled binary and PATH lookup
+    #[serde(default)]
+    pub ffmpeg_path: Option<String>,
+    // Local HTTP API (see local_api.rs); enabled and token are managed by the
+    // local API commands. Binds to 127.0.0.1 unless allow_lan is set.
+    #[serde(default)]
+    pub enable_local_api: bool,
+    #[serde(default = "default_local_api_port")]
+    pub local_api_port: u16,
+    #[serde(default)]
+    pub local_api_allow_lan: bool,
+    #[serde(default)]
+    pub local_api_token: Option<String>,
+    // URL sent a JSON POST whenever a document finishes ingesting (see
+    // ingestion/webhook.rs)
+    #[serde(default)]
+    pub ingestion_webhook_url: Option<String>,
+    // Level for the log file in app_data_dir/logs (error, warn, info, debug, trace)
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
     // License settings
     #[serde(default)]
     pub license_key: Option<String>,
@@ -53,6 +214,30 @@ pub struct Settings {
     pub license_instance_id: Option<String>,
 }
 
+fn default_min_chunk_chars() -> usize {
+    20
+}
+
+fn default_max_history_messages() -> usize {
+    20
+}
+
+fn default_summarize_history() -> bool {
+    true
+}
+
+fn default_max_context_images() -> usize {
+    3
+}
+
+fn default_cache_retrieval() -> bool {
+    true
+}
+
+fn default_warmup_on_startup() -> bool {
+    true
+}
+
 fn default_auto_ingest() -> bool {
     false
 }
@@ -77,6 +262,139 @@ fn default_capture_hotkey() -> String {
     "Ctrl+Shift+S".to_string()
 }
 
+fn default_capture_trigger_mode() -> String {
+    "interval".to_string()
+}
+
+fn default_capture_min_interval() -> u64 {
+    10
+}
+
+fn default_embeddings_enabled() -> bool {
+    true
+}
+
+fn default_embedding_batch_size() -> usize {
+    crate::llm::MAX_EMBEDDING_BATCH_SIZE
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_gemini_requests_per_minute() -> u64 {
+    crate::llm::DEFAULT_REQUESTS_PER_MINUTE
+}
+
+fn default_generation_timeout() -> u64 {
+    crate::llm::DEFAULT_REQUEST_TIMEOUT.as_secs()
+}
+
+fn default_upload_timeout() -> u64 {
+    crate::llm::UPLOAD_TIMEOUT.as_secs()
+}
+
+fn default_embedding_timeout() -> u64 {
+    crate::llm::EMBEDDING_TIMEOUT.as_secs()
+}
+
+fn default_detect_language() -> bool {
+    true
+}
+
+fn default_local_api_port() -> u16 {
+    7749
+}
+
+fn default_fts_tokenizer() -> String {
+    "porter".to_string()
+}
+
+fn default_similarity_metric() -> String {
+    "cosine".to_string()
+}
+
+fn default_dedupe_scope() -> String {
+    "global".to_string()
+}
+
+fn default_max_video_frames_per_segment() -> usize {
+    20
+}
+
+fn default_ingestion_timeout() -> u64 {
+    900
+}
+
+fn default_ingestion_max_retries() -> u32 {
+    2
+}
+
+fn default_ocr_render_scale() -> f64 {
+    crate::ingestion::WINDOWS_OCR_RENDER_SCALE
+}
+
+fn default_vision_ocr_render_scale() -> f64 {
+    crate::ingestion::GEMINI_RENDER_SCALE
+}
+
+fn default_max_ocr_pages() -> u32 {
+    200
+}
+
+fn default_ingestion_cooldown_ms() -> u64 {
+    2000
+}
+
+fn default_enabled_file_types() -> Vec<FileType> {
+    FileType::INGESTIBLE.to_vec()
+}
+
+impl Settings {
+    /// Whether files of this type are ingested. Screen captures are always
+    /// allowed since they aren't read from disk.
+    pub fn file_type_enabled(&self, file_type: FileType) -> bool {
+        file_type == FileType::Screenshot || self.enabled_file_types.contains(&file_type)
+    }
+
+    /// Whether a file at `a` and a document at `b` with the same content are
+    /// the same document, under `dedupe_scope`
+    pub fn in_dedupe_scope(&self, a: &Path, b: &Path) -> bool {
+        self.dedupe_scope != "folder" || self.dedupe_folder(a) == self.dedupe_folder(b)
+    }
+
+    /// The innermost watched folder containing `path`, else its directory
+    fn dedupe_folder(&self, path: &Path) -> Option<PathBuf> {
+        self.containing_watched_folder(path)
+            .map(|(_, folder)| folder)
+            .or_else(|| path.parent().map(Path::to_path_buf))
+    }
+
+    /// Position in `watched_folders` of the innermost watched folder
+    /// containing `path`; lower ranks are ingested first
+    pub fn watched_folder_rank(&self, path: &Path) -> Option<usize> {
+        self.containing_watched_folder(path).map(|(rank, _)| rank)
+    }
+
+    fn containing_watched_folder(&self, path: &Path) -> Option<(usize, PathBuf)> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.watched_folders
+            .iter()
+            .map(|folder| Path::new(folder).canonicalize().unwrap_or_else(|_| PathBuf::from(folder)))
+            .enumerate()
+            .filter(|(_, folder)| path.starts_with(folder))
+            .max_by_key(|(_, folder)| folder.components().count())
+    }
+
+    /// Exclude patterns for a watched folder, falling back to the defaults
+    pub fn exclude_patterns_for(&self, folder: &str) -> Vec<String> {
+        self.watched_folder_excludes
+            .get(folder)
+            .cloned()
+            .unwrap_or_else(default_exclude_patterns)
+    }
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -86,18 +404,67 @@ impl Default for Settings {
             reasoning_model: "gemini-2.0-flash".to_string(),
             chunk_size: 512,
             chunk_overlap: 50,
+            dedupe_chunks: false,
+            min_chunk_chars: default_min_chunk_chars(),
+            index_body_chunks_only: false,
             max_context_chunks: 20,
+            context_neighbor_window: 0,
+            max_history_messages: default_max_history_messages(),
+            summarize_history: default_summarize_history(),
+            citation_style: CitationStyle::default(),
+            suggest_followups: false,
+            boost_frequent_documents: false,
+            multimodal_rag: false,
+            max_context_images: 3,
+            cache_retrieval: true,
+            warmup_on_startup: true,
             video_segment_duration: 300,
             keyframe_interval: 0.2,
+            max_video_frames_per_segment: default_max_video_frames_per_segment(),
             watched_folders: Vec::new(),
+            watched_folder_excludes: HashMap::new(),
             auto_ingest_enabled: false,
+            enabled_file_types: default_enabled_file_types(),
             screen_capture_enabled: false,
             capture_interval_secs: 60,
             capture_mode: "active_window".to_string(),
             capture_app_filter: "none".to_string(),
             capture_app_list: Vec::new(),
             capture_retention_days: 7,
+            capture_retention_max_count: 0,
             capture_hotkey: "Ctrl+Shift+S".to_string(),
+            capture_trigger_mode: "interval".to_string(),
+            capture_min_interval_secs: 10,
+            capture_app_intervals: HashMap::new(),
+            capture_idle_threshold_secs: 0,
+            capture_redact_sensitive_regions: false,
+            captures_directory: None,
+            embeddings_enabled: default_embeddings_enabled(),
+            embedding_batch_size: default_embedding_batch_size(),
+            generation_timeout_secs: default_generation_timeout(),
+            upload_timeout_secs: default_upload_timeout(),
+            embedding_timeout_secs: default_embedding_timeout(),
+            gemini_requests_per_minute: default_gemini_requests_per_minute(),
+            detect_language_on_ingest: true,
+            fts_tokenizer: "porter".to_string(),
+            similarity_metric: default_similarity_metric(),
+            dedupe_scope: default_dedupe_scope(),
+            compress_chunk_text: false,
+            ingestion_timeout_secs: 900,
+            ingestion_cooldown_ms: default_ingestion_cooldown_ms(),
+            ingestion_max_retries: default_ingestion_max_retries(),
+            auto_categorize: false,
+            document_versions_kept: 0,
+            ocr_render_scale: default_ocr_render_scale(),
+            vision_ocr_render_scale: default_vision_ocr_render_scale(),
+            max_ocr_pages: default_max_ocr_pages(),
+            ffmpeg_path: None,
+            enable_local_api: false,
+            local_api_port: default_local_api_port(),
+            local_api_allow_lan: false,
+            local_api_token: None,
+            ingestion_webhook_url: None,
+            log_level: default_log_level(),
             license_key: None,
             license_activated_at: None,
             license_customer_name: None,
@@ -116,6 +483,9 @@ pub struct AppState {
     pub watcher_manager: Arc<WatcherManager>,
     pub capture_manager: Arc<CaptureManager>,
     pub app_data_dir: PathBuf,
+    /// Set to stop a running `categorize_all_documents` after its current batch
+    pub categorization_cancelled: AtomicBool,
+    pub local_api: LocalApiServer,
 }
 
 impl AppState {
@@ -136,6 +506,22 @@ impl AppState {
         let database = Arc::new(Database::new(&db_path, &resources_dir)?);
         let settings = Arc::new(RwLock::new(Self::load_settings(&app_data_dir)));
 
+        if let Err(e) = crate::logging::attach_file_logging(
+            &app_data_dir.join("logs"),
+            &settings.read().log_level,
+        ) {
+            tracing::warn!("File logging unavailable: {}", e);
+        }
+
+        // Rebuild the FTS index if the configured tokenizer changed
+        if let Err(e) = Self::sync_fts_tokenizer(&database, &settings.read()) {
+            tracing::warn!("Failed to apply FTS tokenizer setting: {}", e);
+        }
+        if let Err(e) = Self::sync_similarity_metric(&database, &settings.read()) {
+            tracing::warn!("Failed to apply similarity metric setting: {}", e);
+        }
+        database.set_chunk_compression(settings.read().compress_chunk_text);
+
         let llm_client = Arc::new(RwLock::new(None));
 
         let ingestion_engine = Arc::new(IngestionEngine::new(
@@ -153,18 +539,30 @@ impl AppState {
         let watcher_manager = Arc::new(WatcherManager::new());
 
         // Initialize capture manager
+        let captures_dir = resolve_captures_dir(&app_data_dir, settings.read().captures_directory.as_deref());
         let capture_manager = Arc::new(CaptureManager::new(
             app_data_dir.clone(),
+            captures_dir,
             database.clone(),
             llm_client.clone(),
             ingestion_engine.clone(),
         )?);
 
+        // Warn early if video/audio ingestion won't work
+        let ffmpeg_status = FFmpeg::check(settings.read().ffmpeg_path.as_deref());
+        match ffmpeg_status.ffmpeg_version {
+            Some(ref version) => tracing::info!("ffmpeg available: {}", version),
+            None => tracing::warn!(
+                "ffmpeg unavailable, video/audio ingestion disabled: {}",
+                ffmpeg_status.error.unwrap_or_default()
+            ),
+        }
+
         // Initialize LLM client if API key exists
         {
             let settings_guard = settings.read();
             if let Some(ref api_key) = settings_guard.gemini_api_key {
-                let client = LlmClient::new(api_key.clone());
+                let client = Self::build_llm_client(api_key.clone(), &settings_guard);
                 *llm_client.write() = Some(client);
             }
         }
@@ -178,6 +576,8 @@ impl AppState {
             watcher_manager,
             capture_manager,
             app_data_dir,
+            categorization_cancelled: AtomicBool::new(false),
+            local_api: LocalApiServer::new(),
         })
     }
 
@@ -218,10 +618,78 @@ impl AppState {
     }
 
     pub fn update_llm_client(&self, api_key: String) {
-        let client = LlmClient::new(api_key);
+        let mut client = Self::build_llm_client(api_key, &self.settings.read());
+        // Keep cached query embeddings; the cache clears itself on a model change
+        if let Some(cache) = self.llm_client.read().as_ref().map(|c| c.query_cache()) {
+            client = client.with_query_cache(cache);
+        }
         *self.llm_client.write() = Some(client);
     }
 
+    /// Make sure the FTS index uses the tokenizer selected in settings
+    pub fn sync_fts_tokenizer(database: &Database, settings: &Settings) -> Result<()> {
+        let tokenizer: FtsTokenizer = settings.fts_tokenizer.parse().map_err(|_| {
+            RecallError::Config(format!("Unknown FTS tokenizer: {}", settings.fts_tokenizer))
+        })?;
+
+        if database.ensure_fts_tokenizer(tokenizer)? {
+            tracing::info!("FTS index rebuilt with tokenizer: {}", tokenizer.as_str());
+        }
+        Ok(())
+    }
+
+    /// Make sure the vector index ranks by the metric selected in settings
+    pub fn sync_similarity_metric(database: &Database, settings: &Settings) -> Result<()> {
+        let metric: SimilarityMetric = settings.similarity_metric.parse().map_err(|_| {
+            RecallError::Config(format!("Unknown similarity metric: {}", settings.similarity_metric))
+        })?;
+
+        if database.ensure_similarity_metric(metric)? {
+            tracing::info!("Vector index rebuilt with metric: {}", metric.as_str());
+        }
+        Ok(())
+    }
+
+    /// Create an LLM client configured from the current settings
+    fn build_llm_client(api_key: String, settings: &Settings) -> LlmClient {
+        LlmClient::new(api_key)
+            .with_rate_limit(settings.gemini_requests_per_minute)
+            .with_embedding_batch_size(settings.embedding_batch_size)
+            .with_timeouts(
+                Duration::from_secs(settings.generation_timeout_secs),
+                Duration::from_secs(settings.upload_timeout_secs),
+                Duration::from_secs(settings.embedding_timeout_secs),
+            )
+    }
+
+    /// Take the cold start off the first question of the session: check
+    /// sqlite-vec, prime the query planner and index pages, and make one
+    /// embedding call so the HTTP connection is open. Failures only log.
+    pub async fn warm_up(&self) {
+        let started = std::time::Instant::now();
+
+        let database = self.database.clone();
+        match tokio::task::spawn_blocking(move || database.warm_up()).await {
+            Ok(Ok(vec_available)) => {
+                if !vec_available {
+                    tracing::warn!("Warmup: sqlite-vec unavailable, questions will use keyword search only");
+                }
+            }
+            Ok(Err(e)) => tracing::warn!("Warmup: database step failed: {}", e),
+            Err(e) => tracing::warn!("Warmup: database task failed: {}", e),
+        }
+
+        let llm = self.llm_client.read().clone();
+        let embeddings_enabled = self.settings.read().embeddings_enabled;
+        if let (Some(llm), true) = (llm, embeddings_enabled) {
+            if let Err(e) = llm.embed_query("warmup").await {
+                tracing::warn!("Warmup: embedding call failed: {}", e);
+            }
+        }
+
+        tracing::info!("Startup warmup finished in {}ms", started.elapsed().as_millis());
+    }
+
     /// Start the file watcher with configured folders
     pub fn start_watcher<R: Runtime + 'static>(&self, app_handle: AppHandle<R>) -> Result<()> {
         let settings = self.settings.read();
@@ -242,18 +710,22 @@ impl AppState {
             return Ok(());
         }
 
-        let folders = settings.watched_folders.clone();
+        let folders: Vec<(String, Vec<String>)> = settings
+            .watched_folders
+            .iter()
+            .map(|f| (f.clone(), settings.exclude_patterns_for(f)))
+            .collect();
         drop(settings);
 
         // Start the watcher
         self.watcher_manager.start()?;
 
         // Add all configured folders
-        for folder in &folders {
+        for (folder, excludes) in folders {
             let path = PathBuf::from(folder);
             if path.exists() && path.is_dir() {
                 tracing::info!("Adding watch for folder: {:?}", path);
-                if let Err(e) = self.watcher_manager.add_folder(path.clone()) {
+                if let Err(e) = self.watcher_manager.add_folder(path.clone(), excludes) {
                     tracing::warn!("Failed to watch folder {:?}: {}", path, e);
                 } else {
                     tracing::info!("Successfully watching folder: {:?}", path);
@@ -10,7 +10,7 @@ import HelpModal from "./components/HelpModal";
 import LicenseModal from "./components/LicenseModal";
 import { useSettings } from "./hooks/useSettings";
 import { useLicenseStatus } from "./hooks/useLicense";
-import type { Citation, SourceChunk, IngestionProgress, Document } from "./types";
+import type { Citation, SourceChunk, IngestionProgress, Document, IngestionBatchSummary } from "./types";
 
 interface Toast {
   id: string;
@@ -136,9 +136,23 @@ function App() {
       addToast("warning", "Trial limit reached (25 documents). Upgrade for unlimited.");
     });
 
+    const unlistenBatchComplete = listen<IngestionBatchSummary>("ingestion-batch-complete", (event) => {
+      const { succeeded, failed, skipped, failures } = event.payload;
+      queryClient.invalidateQueries({ queryKey: ["documents"] });
+      queryClient.invalidateQueries({ queryKey: ["stats"] });
+      const message = `Import finished: ${succeeded} ingested, ${failed} failed, ${skipped} skipped`;
+      if (failed > 0) {
+        const names = failures.map((f) => f.path.split(/[\\/]/).pop()).join(", ");
+        addToast("warning", `${message}. Failed: ${names}`);
+      } else {
+        addToast("success", message);
+      }
+    });
+
     return () => {
       unlistenComplete.then((fn) => fn());
       unlistenDeleted.then((fn) => fn());
+      unlistenBatchComplete.then((fn) => fn());
       unlistenCaptureComplete.then((fn) => fn());
       unlistenCaptureStarted.then((fn) => fn());
       unlistenCaptureError.then((fn) => fn());
@@ -223,6 +237,9 @@ function App() {
       timestamp: citation.timestamp,
       relevance_score: citation.relevance_score,
       search_type: "hybrid",
+      thumbnail_path: citation.thumbnail_path ?? null,
+      start_offset: citation.start_offset ?? null,
+      end_offset: citation.end_offset ?? null,
     });
   };
 
@@ -236,6 +253,9 @@ function App() {
       timestamp: null,
       relevance_score: 1.0,
       search_type: "hybrid",
+      thumbnail_path: null,
+      start_offset: null,
+      end_offset: null,
     });
   };
 
@@ -4,8 +4,9 @@ import ReactMarkdown from "react-markdown";
 import rehypeSanitize from "rehype-sanitize";
 import { useQueryWithSources } from "../hooks/useRag";
 import { useConversationMessages } from "../hooks/useConversations";
+import { useSettings } from "../hooks/useSettings";
 import CitationChip from "./CitationChip";
-import type { Citation, SourceChunk } from "../types";
+import { isRecallError, type Citation, type CitationStyle, type SourceChunk } from "../types";
 import clsx from "clsx";
 
 interface Message {
@@ -14,6 +15,20 @@ interface Message {
   content: string;
   citations: Citation[];
   sources: SourceChunk[];
+  followUps?: string[];
+  citationStyle?: CitationStyle;
+}
+
+const CITATION_LINK_PREFIX = "#cite-";
+
+/** Turn [n] markers for known citations into links the renderer makes clickable */
+function linkCitationMarkers(content: string, citationCount: number): string {
+  return content.replace(/\[(\d+)\](?!\()/g, (marker, n: string) => {
+    const number = parseInt(n);
+    return number >= 1 && number <= citationCount
+      ? `[[${number}]](${CITATION_LINK_PREFIX}${number})`
+      : marker;
+  });
 }
 
 interface ChatPanelProps {
@@ -42,6 +57,7 @@ export default function ChatPanel({
   const lastLoadedConversationIdRef = useRef<string | null>(null);
 
   const queryMutation = useQueryWithSources();
+  const { data: settings } = useSettings();
   const { data: conversationMessages } = useConversationMessages(conversationId);
 
   // Load messages when conversation changes
@@ -112,6 +128,8 @@ export default function ChatPanel({
         content: response.answer,
         citations: response.citations,
         sources: response.sources,
+        followUps: response.follow_up_questions,
+        citationStyle: response.citation_style,
       };
 
       setMessages((prev) => [...prev, assistantMessage]);
@@ -119,7 +137,7 @@ export default function ChatPanel({
       const errorMessage: Message = {
         id: crypto.randomUUID(),
         role: "assistant",
-        content: `Error: ${error instanceof Error ? error.message : "Failed to get response"}`,
+        content: `Error: ${isRecallError(error) || error instanceof Error ? error.message : "Failed to get response"}`,
         citations: [],
         sources: [],
       };
@@ -154,20 +172,45 @@ export default function ChatPanel({
       );
     }
 
+    const citationStyle = message.citationStyle ?? settings?.citation_style ?? "inline";
+    const content =
+      citationStyle === "end_list"
+        ? message.content
+        : linkCitationMarkers(message.content, message.citations.length);
+
     return (
       <div className="mb-4 animate-fade-in">
         <div className="max-w-[80%] glass rounded-2xl rounded-bl-md px-4 py-3">
           <div className="prose prose-invert max-w-none">
             <ReactMarkdown
               rehypePlugins={[rehypeSanitize]}
-              components={markdownComponents}
+              components={{
+                ...markdownComponents,
+                a: ({ href, children }: { href?: string; children?: React.ReactNode }) => {
+                  // The sanitizer may prefix in-page links, so match on the end
+                  const match = href?.match(/cite-(\d+)$/);
+                  const citation = match ? message.citations[parseInt(match[1]) - 1] : undefined;
+                  if (citation) {
+                    return (
+                      <button
+                        onClick={() => onCitationClick(citation)}
+                        title={citation.document_title}
+                        className="text-cyan-400 hover:text-cyan-300 font-semibold text-xs align-super"
+                      >
+                        {children}
+                      </button>
+                    );
+                  }
+                  return <a href={href}>{children}</a>;
+                },
+              }}
             >
-              {message.content}
+              {content}
             </ReactMarkdown>
           </div>
 
           {/* Citations */}
-          {message.citations.length > 0 && (
+          {message.citations.length > 0 && citationStyle !== "inline" && (
             <div className="mt-3 pt-3 border-t border-slate-600/50">
               <div className="text-xs text-cyan-400 mb-2 font-medium">Sources:</div>
               <div className="flex flex-wrap gap-2">
@@ -176,12 +219,34 @@ export default function ChatPanel({
                     key={index}
                     citation={citation}
                     onClick={() => onCitationClick(citation)}
+                    number={citationStyle === "footnotes" ? index + 1 : undefined}
                   />
                 ))}
               </div>
             </div>
           )}
 
+          {/* Suggested follow-up questions */}
+          {message.followUps && message.followUps.length > 0 && (
+            <div className="mt-3 pt-3 border-t border-slate-600/50">
+              <div className="text-xs text-cyan-400 mb-2 font-medium">Follow up:</div>
+              <div className="flex flex-col items-start gap-1.5">
+                {message.followUps.map((question, index) => (
+                  <button
+                    key={index}
+                    onClick={() => {
+                      setInput(question);
+                      inputRef.current?.focus();
+                    }}
+                    className="text-left text-sm text-slate-300 hover:text-white px-2 py-1 rounded-lg bg-slate-700/40 hover:bg-slate-700/70 transition-colors"
+                  >
+                    {question}
+                  </button>
+                ))}
+              </div>
+            </div>
+          )}
+
           {/* Copy with attribution */}
           <div className="mt-2 pt-2 border-t border-slate-700/30 flex justify-end">
             <button
@@ -295,9 +360,11 @@ export default function ChatPanel({
           <div className="flex items-center gap-2 text-red-400 text-sm mb-3 bg-red-500/10 border border-red-500/20 rounded-lg px-3 py-2">
             <AlertCircle className="w-4 h-4 flex-shrink-0" />
             <span className="break-words">
-              {queryMutation.error instanceof Error
-                ? queryMutation.error.message
-                : "Failed to get response. Please try again."}
+              {isRecallError(queryMutation.error) && queryMutation.error.code === "invalid_api_key"
+                ? "Your API key was rejected. Re-enter it in Settings."
+                : isRecallError(queryMutation.error) || queryMutation.error instanceof Error
+                  ? queryMutation.error.message
+                  : "Failed to get response. Please try again."}
             </span>
           </div>
         )}
@@ -1,13 +1,16 @@
 import { FileText, Film, BookOpen } from "lucide-react";
+import { convertFileSrc } from "@tauri-apps/api/core";
 import type { Citation } from "../types";
 import clsx from "clsx";
 
 interface CitationChipProps {
   citation: Citation;
   onClick: () => void;
+  /** Footnote number matching the [n] marker in the answer */
+  number?: number;
 }
 
-export default function CitationChip({ citation, onClick }: CitationChipProps) {
+export default function CitationChip({ citation, onClick, number }: CitationChipProps) {
   const formatTimestamp = (seconds: number) => {
     const mins = Math.floor(seconds / 60);
     const secs = Math.floor(seconds % 60);
@@ -24,6 +27,9 @@ export default function CitationChip({ citation, onClick }: CitationChipProps) {
         "hover:shadow-lg hover:shadow-cyan-500/10"
       )}
     >
+      {number !== undefined && (
+        <span className="text-cyan-400 font-semibold">{number}.</span>
+      )}
       {citation.timestamp !== null ? (
         <>
           <Film className="w-3.5 h-3.5 text-cyan-400" />
@@ -37,6 +43,15 @@ export default function CitationChip({ citation, onClick }: CitationChipProps) {
           <span className="text-slate-400 mx-1">—</span>
           <span className="text-cyan-400 font-semibold">Page {citation.page_number}</span>
         </>
+      ) : citation.thumbnail_path ? (
+        <>
+          <img
+            src={convertFileSrc(citation.thumbnail_path)}
+            alt=""
+            className="h-8 w-12 rounded object-cover border border-cyan-500/20"
+          />
+          <span className="max-w-[120px] truncate">{citation.document_title}</span>
+        </>
       ) : (
         <>
           <FileText className="w-3.5 h-3.5 text-cyan-400" />
@@ -106,6 +106,7 @@ function GettingStartedContent() {
           <li>Images (.png, .jpg)</li>
           <li>Videos (.mp4, .mkv, .avi)</li>
           <li>Audio (.mp3, .wav, .flac)</li>
+          <li>Subtitles (.srt, .vtt)</li>
         </ul>
       </div>
 
@@ -11,16 +11,19 @@ import {
   RefreshCw,
   Clock,
   Shield,
+  Stethoscope,
 } from "lucide-react";
 import {
   useCaptureStatus,
   useStartCapture,
   useStopCapture,
   useCaptureNow,
+  useTestCapture,
   usePauseCapture,
   useResumeCapture,
   useRunningApps,
   useUpdateCaptureSettings,
+  useSetCaptureAppIntervals,
   useCleanupCaptures,
 } from "../hooks/useCapture";
 import { useSettings } from "../hooks/useSettings";
@@ -34,9 +37,11 @@ export default function ScreenCaptureSettings() {
   const startCapture = useStartCapture();
   const stopCapture = useStopCapture();
   const captureNow = useCaptureNow();
+  const testCapture = useTestCapture();
   const pauseCapture = usePauseCapture();
   const resumeCapture = useResumeCapture();
   const updateSettings = useUpdateCaptureSettings();
+  const setCaptureAppIntervals = useSetCaptureAppIntervals();
   const cleanupCaptures = useCleanupCaptures();
 
   // Local state for form
@@ -45,7 +50,11 @@ export default function ScreenCaptureSettings() {
   const [mode, setMode] = useState<"active_window" | "full_screen">("active_window");
   const [filterMode, setFilterMode] = useState<"none" | "whitelist" | "blacklist">("none");
   const [appList, setAppList] = useState<string[]>([]);
+  const [appIntervals, setAppIntervals] = useState<Record<string, number>>({});
   const [retentionDays, setRetentionDays] = useState(7);
+  const [retentionMaxCount, setRetentionMaxCount] = useState(0);
+  const [idleThresholdSecs, setIdleThresholdSecs] = useState(0);
+  const [redactSensitive, setRedactSensitive] = useState(false);
   const [hotkey, setHotkey] = useState("Ctrl+Shift+S");
   const [showAppPicker, setShowAppPicker] = useState(false);
 
@@ -57,7 +66,11 @@ export default function ScreenCaptureSettings() {
       setMode(settings.capture_mode);
       setFilterMode(settings.capture_app_filter);
       setAppList(settings.capture_app_list);
+      setAppIntervals(settings.capture_app_intervals ?? {});
       setRetentionDays(settings.capture_retention_days);
+      setRetentionMaxCount(settings.capture_retention_max_count);
+      setIdleThresholdSecs(settings.capture_idle_threshold_secs ?? 0);
+      setRedactSensitive(settings.capture_redact_sensitive_regions ?? false);
       setHotkey(settings.capture_hotkey);
     }
   }, [settings]);
@@ -80,8 +93,12 @@ export default function ScreenCaptureSettings() {
       filter_mode: filterMode,
       app_list: appList,
       retention_days: retentionDays,
+      retention_max_count: retentionMaxCount,
+      idle_threshold_secs: idleThresholdSecs,
+      redact_sensitive_regions: redactSensitive,
       hotkey,
     });
+    await setCaptureAppIntervals.mutateAsync(appIntervals);
   };
 
   const handleCaptureNow = async () => {
@@ -103,6 +120,16 @@ export default function ScreenCaptureSettings() {
     setAppList(appList.filter((a) => a !== appName));
   };
 
+  const handleSetAppInterval = (appName: string, secs: number) => {
+    setAppIntervals({ ...appIntervals, [appName]: secs });
+  };
+
+  const handleRemoveAppInterval = (appName: string) => {
+    const next = { ...appIntervals };
+    delete next[appName];
+    setAppIntervals(next);
+  };
+
   const formatLastCapture = (dateStr: string | null) => {
     if (!dateStr) return "Never";
     const date = new Date(dateStr);
@@ -117,6 +144,15 @@ export default function ScreenCaptureSettings() {
     { value: 300, label: "5 minutes" },
   ];
 
+  const appIntervalOptions = [
+    { value: 10, label: "10 seconds" },
+    { value: 30, label: "30 seconds" },
+    { value: 60, label: "1 minute" },
+    { value: 300, label: "5 minutes" },
+    { value: 900, label: "15 minutes" },
+    { value: 3600, label: "1 hour" },
+  ];
+
   const retentionOptions = [
     { value: 1, label: "1 day" },
     { value: 3, label: "3 days" },
@@ -125,6 +161,22 @@ export default function ScreenCaptureSettings() {
     { value: 30, label: "1 month" },
   ];
 
+  const retentionCountOptions = [
+    { value: 0, label: "No limit" },
+    { value: 100, label: "100 screenshots" },
+    { value: 500, label: "500 screenshots" },
+    { value: 1000, label: "1,000 screenshots" },
+    { value: 5000, label: "5,000 screenshots" },
+  ];
+
+  const idleOptions = [
+    { value: 0, label: "Always capture" },
+    { value: 120, label: "Pause after 2 minutes idle" },
+    { value: 300, label: "Pause after 5 minutes idle" },
+    { value: 900, label: "Pause after 15 minutes idle" },
+    { value: 1800, label: "Pause after 30 minutes idle" },
+  ];
+
   return (
     <div className="space-y-6">
       {/* Status Card */}
@@ -146,11 +198,16 @@ export default function ScreenCaptureSettings() {
                 {captureStatus?.scheduler_running
                   ? captureStatus?.paused
                     ? "Paused"
-                    : "Capturing"
+                    : captureStatus?.idle
+                      ? "Idle"
+                      : "Capturing"
                   : "Stopped"}
               </p>
               <p className="text-xs text-slate-400">
                 {captureStatus?.capture_count || 0} captures this session
+                {!!captureStatus?.idle_skipped_count && (
+                  <>, {captureStatus.idle_skipped_count} skipped while idle</>
+                )}
                 {captureStatus?.last_capture && (
                   <> - Last: {formatLastCapture(captureStatus.last_capture)}</>
                 )}
@@ -191,8 +248,43 @@ export default function ScreenCaptureSettings() {
               )}
               <span>Capture Now</span>
             </button>
+            <button
+              onClick={() => testCapture.mutate()}
+              disabled={testCapture.isPending}
+              className="p-2 hover:bg-slate-600 rounded-lg transition-colors disabled:opacity-50"
+              title="Test capture and OCR without saving"
+            >
+              {testCapture.isPending ? (
+                <Loader2 className="w-4 h-4 animate-spin" />
+              ) : (
+                <Stethoscope className="w-4 h-4" />
+              )}
+            </button>
           </div>
         </div>
+
+        {/* Test capture results */}
+        {testCapture.isError && (
+          <p className="mt-3 text-xs text-red-400 break-words">
+            Capture failed: {(testCapture.error as { message?: string })?.message ?? String(testCapture.error)}
+          </p>
+        )}
+        {testCapture.data && (
+          <div className="mt-3 text-xs text-slate-400 space-y-1">
+            <p>
+              {testCapture.data.monitors_detected} monitor(s) detected, captured{" "}
+              {testCapture.data.width}x{testCapture.data.height} in {testCapture.data.capture_ms} ms
+              {testCapture.data.source_app && <> from {testCapture.data.source_app}</>}
+            </p>
+            {testCapture.data.ocr_error ? (
+              <p className="text-red-400 break-words">OCR failed: {testCapture.data.ocr_error}</p>
+            ) : (
+              <p className="text-slate-300 whitespace-pre-wrap line-clamp-4">
+                OCR ({testCapture.data.text_length} chars): {testCapture.data.text_preview || "(no text)"}
+              </p>
+            )}
+          </div>
+        )}
       </div>
 
       {/* Enable Toggle */}
@@ -276,6 +368,53 @@ export default function ScreenCaptureSettings() {
             </option>
           ))}
         </select>
+
+        {/* Per-app overrides */}
+        <div className="mt-3 space-y-1">
+          {Object.entries(appIntervals).map(([app, secs]) => (
+            <div
+              key={app}
+              className="flex items-center gap-2 bg-slate-700 rounded px-3 py-2"
+            >
+              <span className="flex-1 text-sm truncate">{app}</span>
+              <select
+                value={secs}
+                onChange={(e) => handleSetAppInterval(app, Number(e.target.value))}
+                className="bg-slate-600 border border-slate-500 rounded px-2 py-1 text-sm focus:outline-none focus:border-blue-500"
+              >
+                {appIntervalOptions.map((opt) => (
+                  <option key={opt.value} value={opt.value}>
+                    {opt.label}
+                  </option>
+                ))}
+              </select>
+              <button
+                onClick={() => handleRemoveAppInterval(app)}
+                className="p-1 hover:bg-slate-600 rounded"
+              >
+                <Trash2 className="w-3 h-3 text-red-400" />
+              </button>
+            </div>
+          ))}
+          <select
+            value=""
+            onFocus={() => refetchApps()}
+            onChange={(e) => e.target.value && handleSetAppInterval(e.target.value, intervalSecs)}
+            className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 text-sm text-slate-400 focus:outline-none focus:border-blue-500"
+          >
+            <option value="">Add a per-app interval...</option>
+            {runningApps
+              ?.filter((app) => !(app.process_name in appIntervals))
+              .map((app) => (
+                <option key={app.process_name} value={app.process_name}>
+                  {app.process_name}
+                </option>
+              ))}
+          </select>
+          <p className="text-xs text-slate-500">
+            Listed apps are captured on their own schedule; others use the interval above
+          </p>
+        </div>
       </div>
 
       {/* App Filter */}
@@ -373,6 +512,43 @@ export default function ScreenCaptureSettings() {
         )}
       </div>
 
+      {/* Idle detection */}
+      <div>
+        <label className="block text-sm font-medium mb-2">
+          When Idle
+        </label>
+        <select
+          value={idleThresholdSecs}
+          onChange={(e) => setIdleThresholdSecs(Number(e.target.value))}
+          className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+        >
+          {idleOptions.map((opt) => (
+            <option key={opt.value} value={opt.value}>
+              {opt.label}
+            </option>
+          ))}
+        </select>
+        <p className="text-xs text-slate-500 mt-1">
+          Skips scheduled captures when there has been no keyboard or mouse input, and resumes on activity.
+        </p>
+      </div>
+
+      {/* Redaction */}
+      <div>
+        <label className="flex items-center gap-2 text-sm font-medium">
+          <input
+            type="checkbox"
+            checked={redactSensitive}
+            onChange={(e) => setRedactSensitive(e.target.checked)}
+            className="rounded border-slate-600 bg-slate-700"
+          />
+          Redact Sensitive Text
+        </label>
+        <p className="text-xs text-slate-500 mt-1">
+          Pixelates card numbers, API keys and passwords in each screenshot before it is saved, and masks them in the extracted text. Uses one extra OCR request per capture; screenshots that can't be checked are discarded.
+        </p>
+      </div>
+
       {/* Retention */}
       <div>
         <label className="block text-sm font-medium mb-2">
@@ -390,6 +566,17 @@ export default function ScreenCaptureSettings() {
               </option>
             ))}
           </select>
+          <select
+            value={retentionMaxCount}
+            onChange={(e) => setRetentionMaxCount(Number(e.target.value))}
+            className="flex-1 bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+          >
+            {retentionCountOptions.map((opt) => (
+              <option key={opt.value} value={opt.value}>
+                {opt.label}
+              </option>
+            ))}
+          </select>
           <button
             onClick={() => cleanupCaptures.mutate()}
             disabled={cleanupCaptures.isPending}
@@ -405,7 +592,7 @@ export default function ScreenCaptureSettings() {
           </button>
         </div>
         <p className="text-xs text-slate-500 mt-1">
-          Screenshots older than this will be automatically deleted
+          Screenshots older than this, or beyond the count limit, are deleted along with their search entries
         </p>
       </div>
 
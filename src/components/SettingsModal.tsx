@@ -1,24 +1,44 @@
 import { useState, useEffect } from "react";
-import { X, Key, Loader2, CheckCircle, AlertCircle, Settings2, FolderOpen, Trash2, Plus, Eye, EyeOff, RefreshCw, Clock, Camera } from "lucide-react";
-import { useSettings, useUpdateSettings, useValidateApiKey, useClearApiKey, useGetApiKeyUnmasked } from "../hooks/useSettings";
+import { X, Key, Loader2, CheckCircle, AlertCircle, Settings2, FolderOpen, Trash2, Plus, Eye, EyeOff, RefreshCw, Clock, Camera, ChevronUp, ChevronDown } from "lucide-react";
+import { useSettings, useUpdateSettings, useValidateApiKey, useClearApiKey, useGetApiKeyUnmasked, useNotificationSnooze, useSnoozeNotifications, useFFmpegStatus } from "../hooks/useSettings";
 import { useResetDatabase } from "../hooks/useDocuments";
-import { useWatcherStatus, useAddWatchedFolder, useRemoveWatchedFolder, useToggleAutoIngest } from "../hooks/useWatcher";
+import { useWatcherStatus, useAddWatchedFolder, useRemoveWatchedFolder, useSetWatchedFolderExcludes, useReorderWatchedFolders, useToggleAutoIngest, usePauseWatcher, useResumeWatcher } from "../hooks/useWatcher";
+import { useLocalApiStatus, useStartLocalApi, useStopLocalApi, useRegenerateLocalApiToken } from "../hooks/useLocalApi";
 import { open } from "@tauri-apps/plugin-dialog";
-import type { Settings } from "../types";
+import { isRecallError, type CitationStyle, type FileType, type Settings, type SimilarityMetric } from "../types";
 import clsx from "clsx";
 import ScreenCaptureSettings from "./ScreenCaptureSettings";
 
+const INGESTIBLE_FILE_TYPES: { type: FileType; label: string }[] = [
+  { type: "pdf", label: "PDF" },
+  { type: "text", label: "Text" },
+  { type: "markdown", label: "Markdown" },
+  { type: "image", label: "Images" },
+  { type: "video", label: "Video" },
+  { type: "audio", label: "Audio" },
+  { type: "subtitle", label: "Subtitles" },
+];
+
+// Types that can't be ingested without ffmpeg
+const FFMPEG_FILE_TYPES: FileType[] = ["video", "audio"];
+
 interface SettingsModalProps {
   onClose: () => void;
 }
 
 export default function SettingsModal({ onClose }: SettingsModalProps) {
   const { data: settings, isLoading } = useSettings();
+  const { data: ffmpegStatus } = useFFmpegStatus();
+  const ffmpegMissing = ffmpegStatus?.available === false;
   const { data: watcherStatus } = useWatcherStatus();
+  const pauseWatcher = usePauseWatcher();
+  const resumeWatcher = useResumeWatcher();
   const updateSettings = useUpdateSettings();
   const validateApiKey = useValidateApiKey();
   const addWatchedFolder = useAddWatchedFolder();
   const removeWatchedFolder = useRemoveWatchedFolder();
+  const setWatchedFolderExcludes = useSetWatchedFolderExcludes();
+  const reorderWatchedFolders = useReorderWatchedFolders();
   const toggleAutoIngest = useToggleAutoIngest();
 
   const [apiKey, setApiKey] = useState("");
@@ -32,6 +52,12 @@ export default function SettingsModal({ onClose }: SettingsModalProps) {
   const clearApiKey = useClearApiKey();
   const getApiKeyUnmasked = useGetApiKeyUnmasked();
   const resetDatabase = useResetDatabase();
+  const { data: localApiStatus } = useLocalApiStatus();
+  const startLocalApi = useStartLocalApi();
+  const stopLocalApi = useStopLocalApi();
+  const regenerateLocalApiToken = useRegenerateLocalApiToken();
+  const { data: snoozedUntil } = useNotificationSnooze();
+  const snoozeNotifications = useSnoozeNotifications();
 
   useEffect(() => {
     if (settings) {
@@ -128,7 +154,7 @@ export default function SettingsModal({ onClose }: SettingsModalProps) {
       setShowResetConfirm(false);
     } catch (error) {
       console.error("Failed to reset database:", error);
-      alert(`Failed to reset database: ${error}`);
+      alert(`Failed to reset database: ${isRecallError(error) ? error.message : String(error)}`);
     }
   };
 
@@ -263,7 +289,7 @@ export default function SettingsModal({ onClose }: SettingsModalProps) {
                   <div className="flex items-start gap-2 mt-2 text-red-400 text-sm bg-red-500/10 border border-red-500/20 rounded-lg p-2">
                     <AlertCircle className="w-4 h-4 flex-shrink-0 mt-0.5" />
                     <span className="break-words">
-                      {validateApiKey.error instanceof Error
+                      {isRecallError(validateApiKey.error) || validateApiKey.error instanceof Error
                         ? validateApiKey.error.message
                         : "API key validation failed. Please check your key and try again."}
                     </span>
@@ -367,14 +393,30 @@ export default function SettingsModal({ onClose }: SettingsModalProps) {
                 <span
                   className={clsx(
                     "w-2 h-2 rounded-full",
-                    watcherStatus?.is_running ? "bg-green-500" : "bg-slate-500"
+                    watcherStatus?.state === "running"
+                      ? "bg-green-500"
+                      : watcherStatus?.state === "paused"
+                        ? "bg-amber-500"
+                        : "bg-slate-500"
                   )}
                 />
                 <span className="text-slate-400">
-                  {watcherStatus?.is_running
+                  {watcherStatus?.state === "running"
                     ? `Syncing ${watcherStatus.watched_folders.length} folder(s)`
-                    : "Sync paused"}
+                    : watcherStatus?.state === "paused"
+                      ? "Sync paused; changes will be picked up on resume"
+                      : "Sync stopped"}
                 </span>
+                {watcherStatus?.is_running && (
+                  <button
+                    onClick={() =>
+                      watcherStatus.state === "paused" ? resumeWatcher.mutate() : pauseWatcher.mutate()
+                    }
+                    className="ml-auto px-3 py-1 text-xs bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors"
+                  >
+                    {watcherStatus.state === "paused" ? "Resume" : "Pause"}
+                  </button>
+                )}
               </div>
 
               {/* Add folder button */}
@@ -423,30 +465,117 @@ export default function SettingsModal({ onClose }: SettingsModalProps) {
                       </div>
                     ))}
                     {/* Already synced folders */}
-                    {watcherStatus?.watched_folders.map((folder) => (
-                      <div
-                        key={folder}
-                        className="flex items-center justify-between bg-slate-700 rounded-lg px-3 py-2"
-                      >
-                        <div className="flex items-center gap-2 min-w-0">
-                          <FolderOpen className="w-4 h-4 text-blue-400 flex-shrink-0" />
-                          <span className="text-sm truncate" title={folder}>
-                            {folder}
-                          </span>
+                    {watcherStatus?.watched_folders.map((folder, index, folders) => {
+                      const excludes = (watcherStatus.watched_folder_excludes[folder] ?? []).join(", ");
+                      // Earlier folders are ingested first when several have files waiting
+                      const moveFolder = (offset: number) => {
+                        const order = [...folders];
+                        [order[index], order[index + offset]] = [order[index + offset], order[index]];
+                        reorderWatchedFolders.mutate(order);
+                      };
+                      return (
+                        <div key={folder} className="bg-slate-700 rounded-lg px-3 py-2 space-y-2">
+                          <div className="flex items-center justify-between">
+                            <div className="flex items-center gap-2 min-w-0">
+                              <FolderOpen className="w-4 h-4 text-blue-400 flex-shrink-0" />
+                              <span className="text-sm truncate" title={folder}>
+                                {folder}
+                              </span>
+                            </div>
+                            <div className="flex items-center flex-shrink-0">
+                              <button
+                                onClick={() => moveFolder(-1)}
+                                disabled={index === 0 || reorderWatchedFolders.isPending}
+                                className="p-1 hover:bg-slate-600 rounded transition-colors disabled:opacity-30"
+                                title="Ingest before the folder above"
+                              >
+                                <ChevronUp className="w-4 h-4" />
+                              </button>
+                              <button
+                                onClick={() => moveFolder(1)}
+                                disabled={index === folders.length - 1 || reorderWatchedFolders.isPending}
+                                className="p-1 hover:bg-slate-600 rounded transition-colors disabled:opacity-30"
+                                title="Ingest after the folder below"
+                              >
+                                <ChevronDown className="w-4 h-4" />
+                              </button>
+                              <button
+                                onClick={() => removeWatchedFolder.mutate(folder)}
+                                className="p-1 hover:bg-slate-600 rounded transition-colors"
+                                title="Remove folder"
+                              >
+                                <Trash2 className="w-4 h-4 text-red-400" />
+                              </button>
+                            </div>
+                          </div>
+                          <input
+                            key={excludes}
+                            type="text"
+                            defaultValue={excludes}
+                            placeholder="Exclude (names, globs or paths, comma separated)"
+                            title="Subfolders and files matching these patterns are skipped"
+                            onBlur={(e) => {
+                              const patterns = e.target.value.split(",").map((p) => p.trim()).filter(Boolean);
+                              if (patterns.join(", ") !== excludes) {
+                                setWatchedFolderExcludes.mutate({ folderPath: folder, excludePatterns: patterns });
+                              }
+                            }}
+                            className="w-full bg-slate-800 border border-slate-600 rounded px-2 py-1 text-xs focus:outline-none focus:border-blue-500"
+                          />
                         </div>
-                        <button
-                          onClick={() => removeWatchedFolder.mutate(folder)}
-                          className="p-1 hover:bg-slate-600 rounded transition-colors flex-shrink-0"
-                          title="Remove folder"
-                        >
-                          <Trash2 className="w-4 h-4 text-red-400" />
-                        </button>
-                      </div>
-                    ))}
+                      );
+                    })}
                   </div>
                 )}
               </div>
 
+              {/* File types */}
+              <div>
+                <label className="block text-sm font-medium mb-2">
+                  File Types to Import
+                </label>
+                <div className="grid grid-cols-4 gap-2">
+                  {INGESTIBLE_FILE_TYPES.map(({ type, label }) => {
+                    const enabled = (localSettings.enabled_file_types ?? INGESTIBLE_FILE_TYPES.map((t) => t.type)).includes(type);
+                    const needsFFmpeg = ffmpegMissing && FFMPEG_FILE_TYPES.includes(type);
+                    return (
+                      <label
+                        key={type}
+                        className={clsx("flex items-center gap-2 text-sm", needsFFmpeg && "text-slate-500")}
+                        title={needsFFmpeg ? "Requires FFmpeg" : undefined}
+                      >
+                        <input
+                          type="checkbox"
+                          checked={enabled && !needsFFmpeg}
+                          disabled={needsFFmpeg}
+                          onChange={(e) =>
+                            setLocalSettings((prev) => {
+                              const current = prev.enabled_file_types ?? INGESTIBLE_FILE_TYPES.map((t) => t.type);
+                              return {
+                                ...prev,
+                                enabled_file_types: e.target.checked
+                                  ? [...current, type]
+                                  : current.filter((t) => t !== type),
+                              };
+                            })
+                          }
+                          className="rounded border-slate-600 bg-slate-700"
+                        />
+                        {label}
+                      </label>
+                    );
+                  })}
+                </div>
+                <p className="text-xs text-slate-500 mt-1">
+                  Unchecked types are skipped everywhere: synced folders, folder imports and single files.
+                </p>
+                {ffmpegMissing && (
+                  <p className="text-xs text-amber-400 mt-1">
+                    Video and audio need FFmpeg. {ffmpegStatus?.error}
+                  </p>
+                )}
+              </div>
+
               <div className="bg-slate-700/50 rounded-lg p-4 text-sm">
                 <h4 className="font-medium mb-2">How it works</h4>
                 <p className="text-slate-400">
@@ -497,6 +626,112 @@ export default function SettingsModal({ onClose }: SettingsModalProps) {
                 />
               </div>
 
+              <div>
+                <label className="block text-sm font-medium mb-2">
+                  Minimum Chunk Length (characters)
+                </label>
+                <input
+                  type="number"
+                  min={0}
+                  value={localSettings.min_chunk_chars ?? 20}
+                  onChange={(e) =>
+                    setLocalSettings((prev) => ({
+                      ...prev,
+                      min_chunk_chars: Math.max(0, parseInt(e.target.value) || 0),
+                    }))
+                  }
+                  className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                />
+                <p className="text-xs text-slate-500 mt-1">
+                  Shorter chunks (stray page numbers, single words) are merged into a neighbour. 0 keeps every chunk.
+                </p>
+              </div>
+
+              <div>
+                <label className="flex items-center gap-2 text-sm font-medium">
+                  <input
+                    type="checkbox"
+                    checked={localSettings.index_body_chunks_only ?? false}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        index_body_chunks_only: e.target.checked,
+                      }))
+                    }
+                    className="rounded border-slate-600 bg-slate-700"
+                  />
+                  Index body text only
+                </label>
+                <p className="text-xs text-slate-500 mt-1">
+                  Repeated page headers, footers and figure captions are kept with the document but left out of search. Applies to newly ingested documents.
+                </p>
+              </div>
+
+              <div>
+                <label className="block text-sm font-medium mb-2">
+                  Similarity Metric
+                </label>
+                <select
+                  value={localSettings.similarity_metric ?? "cosine"}
+                  onChange={(e) =>
+                    setLocalSettings((prev) => ({
+                      ...prev,
+                      similarity_metric: e.target.value as SimilarityMetric,
+                    }))
+                  }
+                  className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                >
+                  <option value="cosine">Cosine (recommended for Gemini)</option>
+                  <option value="l2">Euclidean (L2)</option>
+                  <option value="dot">Dot product</option>
+                </select>
+                <p className="text-xs text-slate-500 mt-1">
+                  Match the metric your embedding model was trained for. Changing it rebuilds the vector index.
+                </p>
+              </div>
+
+              <div>
+                <label className="block text-sm font-medium mb-2">
+                  Duplicate Detection Scope
+                </label>
+                <select
+                  value={localSettings.dedupe_scope ?? "global"}
+                  onChange={(e) =>
+                    setLocalSettings((prev) => ({
+                      ...prev,
+                      dedupe_scope: e.target.value as "global" | "folder",
+                    }))
+                  }
+                  className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                >
+                  <option value="global">Anywhere (follow moves between folders)</option>
+                  <option value="folder">Within each folder</option>
+                </select>
+                <p className="text-xs text-slate-500 mt-1">
+                  With "Within each folder", identical files in different watched folders are kept as separate documents.
+                </p>
+              </div>
+
+              <div>
+                <label className="flex items-center gap-2 text-sm font-medium">
+                  <input
+                    type="checkbox"
+                    checked={localSettings.compress_chunk_text ?? false}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        compress_chunk_text: e.target.checked,
+                      }))
+                    }
+                    className="rounded border-slate-600 bg-slate-700"
+                  />
+                  Compress stored chunk text
+                </label>
+                <p className="text-xs text-slate-500 mt-1">
+                  Shrinks large libraries at a small CPU cost. Applies to newly ingested documents.
+                </p>
+              </div>
+
               <div>
                 <label className="block text-sm font-medium mb-2">
                   Max Context Chunks
@@ -514,6 +749,172 @@ export default function SettingsModal({ onClose }: SettingsModalProps) {
                 />
               </div>
 
+              <div>
+                <label className="block text-sm font-medium mb-2">
+                  Neighboring Chunks
+                </label>
+                <input
+                  type="number"
+                  min={0}
+                  max={5}
+                  value={localSettings.context_neighbor_window ?? 0}
+                  onChange={(e) =>
+                    setLocalSettings((prev) => ({
+                      ...prev,
+                      context_neighbor_window: Math.min(5, Math.max(0, parseInt(e.target.value) || 0)),
+                    }))
+                  }
+                  className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                />
+                <p className="text-xs text-slate-500 mt-1">
+                  Adds the chunks just before and after each match, for answers that straddle a chunk boundary. 0 turns this off.
+                </p>
+              </div>
+
+              <div>
+                <label className="block text-sm font-medium mb-2">
+                  Citation Style
+                </label>
+                <select
+                  value={localSettings.citation_style ?? "inline"}
+                  onChange={(e) =>
+                    setLocalSettings((prev) => ({
+                      ...prev,
+                      citation_style: e.target.value as CitationStyle,
+                    }))
+                  }
+                  className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                >
+                  <option value="inline">Inline markers</option>
+                  <option value="footnotes">Markers and a numbered source list</option>
+                  <option value="end_list">Source list at the end</option>
+                </select>
+                <p className="text-xs text-slate-500 mt-1">
+                  How answers point to their sources. Click a marker or source to open it.
+                </p>
+              </div>
+
+              <div>
+                <label className="flex items-center gap-2 text-sm font-medium">
+                  <input
+                    type="checkbox"
+                    checked={localSettings.suggest_followups ?? false}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        suggest_followups: e.target.checked,
+                      }))
+                    }
+                    className="rounded border-slate-600 bg-slate-700"
+                  />
+                  Suggest follow-up questions
+                </label>
+                <p className="text-xs text-slate-500 mt-1">
+                  Offers 3 follow-up questions after each answer. Uses one extra API call per answer.
+                </p>
+              </div>
+
+              <div>
+                <label className="flex items-center gap-2 text-sm font-medium">
+                  <input
+                    type="checkbox"
+                    checked={localSettings.boost_frequent_documents ?? false}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        boost_frequent_documents: e.target.checked,
+                      }))
+                    }
+                    className="rounded border-slate-600 bg-slate-700"
+                  />
+                  Favor frequently used documents
+                </label>
+                <p className="text-xs text-slate-500 mt-1">
+                  Ranks documents you open or get cited often slightly higher in answers.
+                </p>
+              </div>
+
+              <div>
+                <label className="flex items-center gap-2 text-sm font-medium">
+                  <input
+                    type="checkbox"
+                    checked={localSettings.multimodal_rag ?? false}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        multimodal_rag: e.target.checked,
+                      }))
+                    }
+                    className="rounded border-slate-600 bg-slate-700"
+                  />
+                  Show source images to the model
+                </label>
+                <p className="text-xs text-slate-500 mt-1">
+                  Sends matching screenshots and images with the question, not just their text, so answers can cover diagrams and layout. Costs more per question.
+                </p>
+              </div>
+
+              {localSettings.multimodal_rag && (
+                <div>
+                  <label className="block text-sm font-medium mb-2">
+                    Max Images per Question
+                  </label>
+                  <input
+                    type="number"
+                    min={1}
+                    max={10}
+                    value={localSettings.max_context_images ?? 3}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        max_context_images: Math.min(10, Math.max(1, parseInt(e.target.value) || 3)),
+                      }))
+                    }
+                    className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                  />
+                </div>
+              )}
+
+              <div>
+                <label className="flex items-center gap-2 text-sm font-medium">
+                  <input
+                    type="checkbox"
+                    checked={localSettings.cache_retrieval ?? true}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        cache_retrieval: e.target.checked,
+                      }))
+                    }
+                    className="rounded border-slate-600 bg-slate-700"
+                  />
+                  Cache search results for follow-up questions
+                </label>
+                <p className="text-xs text-slate-500 mt-1">
+                  Repeating a question within a few minutes reuses its sources instead of searching again. Ingesting or deleting documents clears the cache.
+                </p>
+              </div>
+
+              <div>
+                <label className="flex items-center gap-2 text-sm font-medium">
+                  <input
+                    type="checkbox"
+                    checked={localSettings.warmup_on_startup ?? true}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        warmup_on_startup: e.target.checked,
+                      }))
+                    }
+                    className="rounded border-slate-600 bg-slate-700"
+                  />
+                  Warm up search at launch
+                </label>
+                <p className="text-xs text-slate-500 mt-1">
+                  Prepares the search index and API connection in the background so the first question is answered faster. Uses one embedding request per launch.
+                </p>
+              </div>
+
               <div>
                 <label className="block text-sm font-medium mb-2">
                   Video Segment Duration (seconds)
@@ -534,6 +935,283 @@ export default function SettingsModal({ onClose }: SettingsModalProps) {
                 </p>
               </div>
 
+              <div>
+                <label className="block text-sm font-medium mb-2">
+                  Video Frames per Segment
+                </label>
+                <input
+                  type="number"
+                  min={1}
+                  value={localSettings.max_video_frames_per_segment || 20}
+                  onChange={(e) =>
+                    setLocalSettings((prev) => ({
+                      ...prev,
+                      max_video_frames_per_segment: Math.max(1, parseInt(e.target.value) || 20),
+                    }))
+                  }
+                  className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                />
+                <p className="text-xs text-slate-500 mt-1">
+                  Evenly spaced frames sent to the vision model. More frames capture more detail at higher cost.
+                </p>
+              </div>
+
+              <div>
+                <label className="block text-sm font-medium mb-2">
+                  Ingestion Retries
+                </label>
+                <input
+                  type="number"
+                  min={0}
+                  max={10}
+                  value={localSettings.ingestion_max_retries ?? 2}
+                  onChange={(e) =>
+                    setLocalSettings((prev) => ({
+                      ...prev,
+                      ingestion_max_retries: Math.min(10, Math.max(0, parseInt(e.target.value) || 0)),
+                    }))
+                  }
+                  className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                />
+                <p className="text-xs text-slate-500 mt-1">
+                  Retries OCR, transcription and embeddings after rate limits or network errors before marking a file failed.
+                </p>
+              </div>
+
+              <div>
+                <label className="flex items-center gap-2 text-sm font-medium">
+                  <input
+                    type="checkbox"
+                    checked={localSettings.auto_categorize ?? false}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        auto_categorize: e.target.checked,
+                      }))
+                    }
+                    className="rounded border-slate-600 bg-slate-700"
+                  />
+                  Categorize documents on import
+                </label>
+                <p className="text-xs text-slate-500 mt-1">
+                  Assigns content categories while importing instead of in a separate pass (one extra API call per document).
+                </p>
+              </div>
+
+              <div>
+                <label className="block text-sm font-medium mb-2">
+                  Document Versions Kept
+                </label>
+                <input
+                  type="number"
+                  min={0}
+                  max={20}
+                  value={localSettings.document_versions_kept ?? 0}
+                  onChange={(e) =>
+                    setLocalSettings((prev) => ({
+                      ...prev,
+                      document_versions_kept: Math.min(20, Math.max(0, parseInt(e.target.value) || 0)),
+                    }))
+                  }
+                  className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                />
+                <p className="text-xs text-slate-500 mt-1">
+                  When a changed file is re-imported, keep this many earlier versions and summarize what changed (one extra API call). 0 turns this off.
+                </p>
+              </div>
+
+              <div className="grid grid-cols-2 gap-4">
+                <div>
+                  <label className="block text-sm font-medium mb-2">
+                    OCR Render Scale
+                  </label>
+                  <input
+                    type="number"
+                    min={0.5}
+                    max={8}
+                    step={0.5}
+                    value={localSettings.ocr_render_scale ?? 3}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        ocr_render_scale: Math.min(8, Math.max(0.5, parseFloat(e.target.value) || 3)),
+                      }))
+                    }
+                    className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                  />
+                </div>
+                <div>
+                  <label className="block text-sm font-medium mb-2">
+                    Vision OCR Render Scale
+                  </label>
+                  <input
+                    type="number"
+                    min={0.5}
+                    max={8}
+                    step={0.5}
+                    value={localSettings.vision_ocr_render_scale ?? 2}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        vision_ocr_render_scale: Math.min(8, Math.max(0.5, parseFloat(e.target.value) || 2)),
+                      }))
+                    }
+                    className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                  />
+                </div>
+                <p className="col-span-2 text-xs text-slate-500 -mt-2">
+                  Resolution scanned PDF pages are rendered at for Windows OCR and for vision-model OCR (1 = 72 DPI). Higher reads small print better but is slower; very large pages are scaled down automatically.
+                </p>
+              </div>
+
+              <div>
+                <label className="block text-sm font-medium mb-2">
+                  Max Vision OCR Pages
+                </label>
+                <input
+                  type="number"
+                  min={0}
+                  value={localSettings.max_ocr_pages ?? 200}
+                  onChange={(e) =>
+                    setLocalSettings((prev) => ({
+                      ...prev,
+                      max_ocr_pages: Math.max(0, parseInt(e.target.value) || 0),
+                    }))
+                  }
+                  className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                />
+                <p className="text-xs text-slate-500 mt-1">
+                  Scanned PDFs longer than this only have their first pages read, and are marked partial. Protects your API quota from huge scans. 0 = no limit.
+                </p>
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="text-sm font-medium">Notifications</p>
+                  <p className="text-xs text-slate-500">
+                    {snoozedUntil
+                      ? `Snoozed until ${new Date(snoozedUntil).toLocaleTimeString()}`
+                      : "Related-content and screenshot pop-ups are on"}
+                  </p>
+                </div>
+                <select
+                  value=""
+                  onChange={(e) => snoozeNotifications.mutate(parseInt(e.target.value))}
+                  disabled={snoozeNotifications.isPending}
+                  className="bg-slate-700 border border-slate-600 rounded-lg px-3 py-1.5 text-sm focus:outline-none focus:border-blue-500"
+                >
+                  <option value="" disabled>
+                    {snoozedUntil ? "Change snooze" : "Snooze"}
+                  </option>
+                  <option value="30">30 minutes</option>
+                  <option value="60">1 hour</option>
+                  <option value="240">4 hours</option>
+                  {snoozedUntil && <option value="0">Resume now</option>}
+                </select>
+              </div>
+
+              {/* Local API */}
+              <div className="border-t border-slate-600 pt-4 mt-4 space-y-3">
+                <div className="flex items-center justify-between">
+                  <div>
+                    <h4 className="text-sm font-medium">Local API</h4>
+                    <p className="text-xs text-slate-500">
+                      {localApiStatus?.running
+                        ? `Listening on ${localApiStatus.address}`
+                        : "Query your library over HTTP from scripts"}
+                    </p>
+                  </div>
+                  <button
+                    onClick={() => (localApiStatus?.running ? stopLocalApi.mutate() : startLocalApi.mutate())}
+                    disabled={startLocalApi.isPending || stopLocalApi.isPending}
+                    className="px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors"
+                  >
+                    {localApiStatus?.running ? "Stop" : "Start"}
+                  </button>
+                </div>
+                {startLocalApi.isError && (
+                  <p className="text-xs text-red-400">
+                    {isRecallError(startLocalApi.error) ? startLocalApi.error.message : String(startLocalApi.error)}
+                  </p>
+                )}
+                <div>
+                  <label className="block text-sm font-medium mb-2">Port</label>
+                  <input
+                    type="number"
+                    min={1024}
+                    max={65535}
+                    value={localSettings.local_api_port ?? 7749}
+                    onChange={(e) =>
+                      setLocalSettings((prev) => ({
+                        ...prev,
+                        local_api_port: parseInt(e.target.value) || 7749,
+                      }))
+                    }
+                    className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                  />
+                </div>
+                <div>
+                  <label className="flex items-center gap-2 text-sm font-medium">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.local_api_allow_lan ?? false}
+                      onChange={(e) =>
+                        setLocalSettings((prev) => ({
+                          ...prev,
+                          local_api_allow_lan: e.target.checked,
+                        }))
+                      }
+                      className="rounded border-slate-600 bg-slate-700"
+                    />
+                    Allow access from other devices on my network
+                  </label>
+                  <p className="text-xs text-slate-500 mt-1">
+                    Off keeps the API on this computer only. Anyone with the token can read your library.
+                  </p>
+                </div>
+                {localApiStatus?.token && (
+                  <div>
+                    <label className="block text-sm font-medium mb-2">Bearer Token</label>
+                    <div className="flex gap-2">
+                      <input
+                        type="text"
+                        readOnly
+                        value={localApiStatus.token}
+                        className="flex-1 bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 font-mono text-xs"
+                      />
+                      <button
+                        onClick={() => regenerateLocalApiToken.mutate()}
+                        disabled={regenerateLocalApiToken.isPending}
+                        title="Generate a new token"
+                        className="px-3 py-2 bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors"
+                      >
+                        <RefreshCw className="w-4 h-4" />
+                      </button>
+                    </div>
+                  </div>
+                )}
+              </div>
+
+              {/* Ingestion webhook */}
+              <div className="border-t border-slate-600 pt-4 mt-4">
+                <label className="block text-sm font-medium mb-2">Ingestion Webhook URL</label>
+                <input
+                  type="url"
+                  placeholder="https://example.com/hooks/recall"
+                  value={localSettings.ingestion_webhook_url ?? ""}
+                  onChange={(e) =>
+                    setLocalSettings((prev) => ({
+                      ...prev,
+                      ingestion_webhook_url: e.target.value.trim() || null,
+                    }))
+                  }
+                  className="w-full bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 focus:outline-none focus:border-blue-500"
+                />
+                <p className="text-xs text-slate-500 mt-1">
+                  Receives a JSON POST with the document id, title, type and chunk count after each ingestion.
+                </p>
+              </div>
+
               {/* Danger Zone */}
               <div className="border-t border-slate-600 pt-4 mt-4">
                 <h4 className="text-sm font-medium text-red-400 mb-3">Danger Zone</h4>
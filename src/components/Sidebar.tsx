@@ -13,6 +13,8 @@ import {
   Loader2,
   CheckCircle,
   XCircle,
+  ShieldAlert,
+  FileX,
   ChevronRight,
   ChevronDown,
   Trash2,
@@ -20,6 +22,7 @@ import {
   HelpCircle,
   ArrowUpDown,
   Clock,
+  History,
   SortAsc,
   FileType as FileTypeIcon,
   HardDrive,
@@ -32,6 +35,7 @@ import {
   Sparkles,
   FolderOpen,
   Shield,
+  Lock,
 } from "lucide-react";
 
 // Custom icon component matching the app icon
@@ -41,11 +45,16 @@ const RecallIcon = ({ className }: { className?: string }) => (
     <circle cx="12" cy="12" r="3.5" fill="currentColor"/>
   </svg>
 );
-import { useDocuments, useIngestFile, useIngestDirectory, useDeleteDocument, useReingestDocument, useIngestionStats, useCategorizeAllDocuments } from "../hooks/useDocuments";
+import { useDocuments, useIngestFile, useIngestFileWithPassword, useIngestDirectory, useDeleteDocument, useReingestDocument, useIngestionStats, useCategorizeAllDocuments } from "../hooks/useDocuments";
 import { useConversations, useDeleteConversation } from "../hooks/useConversations";
+import { useFFmpegStatus } from "../hooks/useSettings";
 import type { Document, IngestionProgress, FileType, Conversation } from "../types";
 import clsx from "clsx";
 
+const DOCUMENT_EXTENSIONS = ["pdf", "txt", "md", "png", "jpg", "jpeg", "srt", "vtt"];
+// Video and audio; only offered when ffmpeg is available
+const MEDIA_EXTENSIONS = ["mp4", "mkv", "avi", "mov", "webm", "mp3", "wav", "flac", "m4a"];
+
 interface SidebarProps {
   onSettingsClick: () => void;
   onHelpClick: () => void;
@@ -63,17 +72,18 @@ interface SidebarProps {
   trialDocsLimit?: number;
 }
 
-type SortOption = "recent" | "alphabetical" | "type" | "size";
+type SortOption = "recent" | "opened" | "alphabetical" | "type" | "size";
 type ViewMode = "flat" | "grouped" | "content";
 
 const sortOptions: { value: SortOption; label: string; icon: React.ReactNode }[] = [
   { value: "recent", label: "Recent", icon: <Clock className="w-3.5 h-3.5" /> },
+  { value: "opened", label: "Last Opened", icon: <History className="w-3.5 h-3.5" /> },
   { value: "alphabetical", label: "A-Z", icon: <SortAsc className="w-3.5 h-3.5" /> },
   { value: "type", label: "Type", icon: <FileTypeIcon className="w-3.5 h-3.5" /> },
   { value: "size", label: "Size", icon: <HardDrive className="w-3.5 h-3.5" /> },
 ];
 
-const categoryOrder: FileType[] = ["pdf", "text", "markdown", "video", "audio", "image", "screenshot", "unknown"];
+const categoryOrder: FileType[] = ["pdf", "text", "markdown", "video", "audio", "image", "screenshot", "subtitle", "unknown"];
 
 const categoryLabels: Record<FileType, string> = {
   pdf: "PDFs",
@@ -83,6 +93,7 @@ const categoryLabels: Record<FileType, string> = {
   audio: "Audio",
   image: "Images",
   screenshot: "Screenshots",
+  subtitle: "Subtitles",
   unknown: "Other",
 };
 
@@ -145,9 +156,12 @@ export default function Sidebar({
 
   const { data: stats } = useIngestionStats();
   const ingestFile = useIngestFile();
+  const ingestFileWithPassword = useIngestFileWithPassword();
   const ingestDirectory = useIngestDirectory();
   const deleteDocument = useDeleteDocument();
   const reingestDocument = useReingestDocument();
+  const { data: ffmpegStatus } = useFFmpegStatus();
+  const ffmpegMissing = ffmpegStatus?.available === false;
 
   const handleAddFile = async () => {
     const selected = await open({
@@ -155,7 +169,7 @@ export default function Sidebar({
       filters: [
         {
           name: "Documents",
-          extensions: ["pdf", "txt", "md", "mp4", "mkv", "avi", "mov", "webm", "mp3", "wav", "flac", "m4a", "png", "jpg", "jpeg"],
+          extensions: ffmpegMissing ? DOCUMENT_EXTENSIONS : [...DOCUMENT_EXTENSIONS, ...MEDIA_EXTENSIONS],
         },
       ],
     });
@@ -209,9 +223,46 @@ export default function Sidebar({
       case "processing":
         return <Loader2 className="w-4 h-4 animate-spin text-blue-400" />;
       case "completed":
+        if (doc.metadata?.source_missing === true) {
+          return (
+            <span title={`Source file missing: ${doc.file_path}`}>
+              <FileX className="w-4 h-4 text-amber-400" />
+            </span>
+          );
+        }
+        if (doc.metadata?.ocr_truncated) {
+          return (
+            <span title={doc.error_message ?? "Partially ingested"}>
+              <CheckCircle className="w-4 h-4 text-amber-400" />
+            </span>
+          );
+        }
         return <CheckCircle className="w-4 h-4 text-green-400" />;
       case "failed":
+        if (doc.metadata?.password_protected === true) {
+          return (
+            <button
+              onClick={(e) => {
+                e.stopPropagation();
+                const password = window.prompt(`Password for ${doc.title}`);
+                if (password) {
+                  ingestFileWithPassword.mutate({ path: doc.file_path, password });
+                }
+              }}
+              className="p-0.5 hover:bg-slate-600 rounded"
+              title={`${doc.error_message ?? "Password-protected PDF"} - click to enter the password`}
+            >
+              <Lock className="w-4 h-4 text-amber-400" />
+            </button>
+          );
+        }
         return <XCircle className="w-4 h-4 text-red-400" />;
+      case "blocked":
+        return (
+          <span title={doc.error_message ?? "Blocked by provider safety filter"}>
+            <ShieldAlert className="w-4 h-4 text-amber-400" />
+          </span>
+        );
       default:
         return null;
     }
@@ -237,6 +288,9 @@ export default function Sidebar({
               // Sort by file type, then alphabetically within type
               const typeCompare = a.file_type.localeCompare(b.file_type);
               return typeCompare !== 0 ? typeCompare : a.title.toLowerCase().localeCompare(b.title.toLowerCase());
+            case "opened":
+              // Most recently opened or cited first; never-opened documents last
+              return (b.last_accessed ?? "").localeCompare(a.last_accessed ?? "");
             case "size":
               // Sort by size descending (largest first)
               return b.file_size - a.file_size;
@@ -694,7 +748,12 @@ export default function Sidebar({
                       </span>
                       {isExpanded && (
                         <>
-                          <span className="flex-1 truncate text-sm">{doc.title}</span>
+                          <span
+                            className="flex-1 truncate text-sm"
+                            title={`${doc.chunk_count} chunks · ${doc.total_tokens.toLocaleString()} tokens`}
+                          >
+                            {doc.title}
+                          </span>
                           {getStatusIcon(doc)}
                           {filterMode === "all" && (
                             <>
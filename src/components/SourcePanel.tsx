@@ -1,8 +1,9 @@
 import { useState, useEffect } from "react";
 import { X, FileText, Film, Music, Image, Camera, ExternalLink, ChevronLeft, ChevronRight, Eye, List, BookOpen } from "lucide-react";
+import { useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
 import { convertFileSrc } from "@tauri-apps/api/core";
-import { useDocument, useDocumentChunks } from "../hooks/useDocuments";
+import { useDocument, useDocumentChunks, useRecordDocumentAccess } from "../hooks/useDocuments";
 import type { SourceChunk } from "../types";
 import PdfViewer from "./PdfViewer";
 import VideoPlayer from "./VideoPlayer";
@@ -141,6 +142,14 @@ function getChunkPreview(content: string): string {
 
 export default function SourcePanel({ source, onClose }: SourcePanelProps) {
   const { data: document, isError: isDocError, error: docError } = useDocument(source.document_id);
+  const queryClient = useQueryClient();
+  const recordAccess = useRecordDocumentAccess();
+
+  // Viewing a source counts as accessing its document
+  useEffect(() => {
+    recordAccess.mutate(source.document_id);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [source.document_id]);
   const { data: chunks, isError: isChunksError, error: chunksError } = useDocumentChunks(source.document_id);
   const [currentChunkIndex, setCurrentChunkIndex] = useState(0);
   const [viewMode, setViewMode] = useState<ViewMode>("preview");
@@ -181,6 +190,21 @@ export default function SourcePanel({ source, onClose }: SourcePanelProps) {
     }
   }, [chunks, source.chunk_id]);
 
+  // Step through chunks with the arrow keys, unless the user is typing
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      const target = e.target as HTMLElement | null;
+      if (!chunks || target?.closest("input, textarea, [contenteditable='true']")) return;
+      if (e.key === "ArrowLeft") {
+        setCurrentChunkIndex((i) => Math.max(0, i - 1));
+      } else if (e.key === "ArrowRight") {
+        setCurrentChunkIndex((i) => Math.min(chunks.length - 1, i + 1));
+      }
+    };
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, [chunks]);
+
   const currentChunk = chunks?.[currentChunkIndex];
   const isPdf = document?.file_type === "pdf";
   const isVideo = document?.file_type === "video";
@@ -217,8 +241,11 @@ export default function SourcePanel({ source, onClose }: SourcePanelProps) {
       try {
         // Use Tauri shell command to open file in default app
         await invoke("open_file_in_default_app", { path: document.file_path });
+        queryClient.invalidateQueries({ queryKey: ["recent-documents"] });
       } catch (err) {
         console.error("Failed to open file:", err);
+        // The backend flags documents whose file is gone; refresh the badge
+        queryClient.invalidateQueries({ queryKey: ["documents"] });
       }
     }
   };
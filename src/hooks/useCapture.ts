@@ -1,6 +1,12 @@
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
-import type { CaptureStatus, AppInfo } from "../types";
+import type {
+  CaptureStatus,
+  CaptureCleanupResult,
+  CaptureTestResult,
+  AppInfo,
+  HotkeyValidation,
+} from "../types";
 
 /**
  * Hook to get the current capture status
@@ -58,6 +64,30 @@ export function useCaptureNow() {
   });
 }
 
+/**
+ * Hook to capture a screenshot with a note on why it was taken
+ */
+export function useCaptureWithNote() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (note?: string) => invoke<string>("capture_with_note", { note }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["captureStatus"] });
+      queryClient.invalidateQueries({ queryKey: ["documents"] });
+    },
+  });
+}
+
+/**
+ * Hook to test capture and OCR without saving a document
+ */
+export function useTestCapture() {
+  return useMutation({
+    mutationFn: () => invoke<CaptureTestResult>("test_capture"),
+  });
+}
+
 /**
  * Hook to pause screen capture
  */
@@ -112,6 +142,11 @@ export function useUpdateCaptureSettings() {
       app_list: string[];
       retention_days: number;
       hotkey: string;
+      trigger_mode?: "interval" | "on_window_change";
+      min_interval_secs?: number;
+      retention_max_count?: number;
+      idle_threshold_secs?: number;
+      redact_sensitive_regions?: boolean;
     }) =>
       invoke("update_capture_settings", {
         enabled: settings.enabled,
@@ -121,6 +156,11 @@ export function useUpdateCaptureSettings() {
         appList: settings.app_list,
         retentionDays: settings.retention_days,
         hotkey: settings.hotkey,
+        triggerMode: settings.trigger_mode,
+        minIntervalSecs: settings.min_interval_secs,
+        retentionMaxCount: settings.retention_max_count,
+        idleThresholdSecs: settings.idle_threshold_secs,
+        redactSensitiveRegions: settings.redact_sensitive_regions,
       }),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ["captureStatus"] });
@@ -129,6 +169,32 @@ export function useUpdateCaptureSettings() {
   });
 }
 
+/**
+ * Hook to set per-app capture intervals (seconds, keyed by process name)
+ */
+export function useSetCaptureAppIntervals() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (intervals: Record<string, number>) =>
+      invoke("set_capture_app_intervals", { intervals }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["captureStatus"] });
+      queryClient.invalidateQueries({ queryKey: ["settings"] });
+    },
+  });
+}
+
+/**
+ * Hook to check a hotkey before saving it
+ */
+export function useValidateHotkey() {
+  return useMutation({
+    mutationFn: (hotkey: string) =>
+      invoke<HotkeyValidation>("validate_hotkey", { hotkey }),
+  });
+}
+
 /**
  * Hook to clean up old captures
  */
@@ -136,9 +202,10 @@ export function useCleanupCaptures() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: () => invoke<number>("cleanup_old_captures"),
+    mutationFn: () => invoke<CaptureCleanupResult>("cleanup_old_captures"),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ["documents"] });
+      queryClient.invalidateQueries({ queryKey: ["stats"] });
     },
   });
 }
@@ -1,6 +1,6 @@
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
-import type { Conversation, Message } from "../types";
+import type { Chunk, Conversation, ConversationScope, Message } from "../types";
 
 export function useConversations() {
   return useQuery({
@@ -60,3 +60,36 @@ export function useRenameConversation() {
     },
   });
 }
+
+export function useSetConversationScope() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ id, scope }: { id: string; scope: ConversationScope }) =>
+      invoke<void>("set_conversation_scope", { id, scope }),
+    onSuccess: (_, { id }) => {
+      queryClient.invalidateQueries({ queryKey: ["conversations"] });
+      queryClient.invalidateQueries({ queryKey: ["conversation", id] });
+    },
+  });
+}
+
+export function usePinnedChunks(conversationId: string | null) {
+  return useQuery({
+    queryKey: ["pinned-chunks", conversationId],
+    queryFn: () => invoke<Chunk[]>("get_pinned_chunks", { conversationId }),
+    enabled: !!conversationId,
+  });
+}
+
+export function usePinChunk() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ conversationId, chunkId, pinned }: { conversationId: string; chunkId: number; pinned: boolean }) =>
+      invoke<void>(pinned ? "pin_chunk" : "unpin_chunk", { conversationId, chunkId }),
+    onSuccess: (_, { conversationId }) => {
+      queryClient.invalidateQueries({ queryKey: ["pinned-chunks", conversationId] });
+    },
+  });
+}
@@ -1,11 +1,39 @@
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
-import type { Document, Chunk, IngestionStats } from "../types";
+import type {
+  Document,
+  FileType,
+  IngestionSource,
+  Chunk,
+  ChunkContext,
+  DocumentPreview,
+  IngestionStats,
+  ChunkingPreview,
+  IngestionCostEstimate,
+  PriceTable,
+  IngestionTimelineEntry,
+  TimelineBucket,
+  CompactionResult,
+  DocumentValidation,
+  DocumentRepairSummary,
+  DirectorySyncSummary,
+  MissingSourceAction,
+  DuplicateContentAction,
+  ReingestFilter,
+  ReingestSummary,
+  DocumentRelation,
+  RelationRebuildSummary,
+  DocumentCluster,
+  DocumentGraph,
+  DocumentGraphRequest,
+  DocumentLogLine,
+  DocumentVersion,
+} from "../types";
 
-export function useDocuments() {
+export function useDocuments(ingestionSource?: IngestionSource) {
   return useQuery({
-    queryKey: ["documents"],
-    queryFn: () => invoke<Document[]>("get_documents"),
+    queryKey: ["documents", ingestionSource ?? "all"],
+    queryFn: () => invoke<Document[]>("get_documents", { ingestionSource }),
     // Reduced from 5s to 30s - auto-ingest events update UI via Tauri events
     refetchInterval: 30000,
     // Only refetch when window is focused
@@ -21,6 +49,65 @@ export function useDocument(id: string | null) {
   });
 }
 
+export function useRecentDocuments(limit = 10) {
+  return useQuery({
+    queryKey: ["recent-documents", limit],
+    queryFn: () => invoke<Document[]>("get_recent_documents", { limit }),
+  });
+}
+
+export function useRecordDocumentAccess() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (documentId: string) => invoke("record_document_access", { documentId }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["recent-documents"] });
+    },
+  });
+}
+
+export function useDocumentHistory(documentId: string | null) {
+  return useQuery({
+    queryKey: ["document-history", documentId],
+    queryFn: () => invoke<DocumentVersion[]>("get_document_history", { documentId }),
+    enabled: !!documentId,
+  });
+}
+
+export function useDocumentIngestionLog(documentId: string | null) {
+  return useQuery({
+    queryKey: ["document-ingestion-log", documentId],
+    queryFn: () => invoke<DocumentLogLine[]>("get_document_ingestion_log", { documentId }),
+    enabled: !!documentId,
+  });
+}
+
+export function useDocumentMetadata(documentId: string | null) {
+  return useQuery({
+    queryKey: ["document-metadata", documentId],
+    queryFn: () => invoke<Record<string, unknown>>("get_document_metadata", { documentId }),
+    enabled: !!documentId,
+  });
+}
+
+export function useSetDocumentMetadataField() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ documentId, key, value }: { documentId: string; key: string; value: unknown }) =>
+      invoke<Record<string, unknown>>("set_document_metadata_field", { documentId, key, value }),
+    onSuccess: (_data, { documentId }) => {
+      queryClient.invalidateQueries({ queryKey: ["document-metadata", documentId] });
+      queryClient.invalidateQueries({ queryKey: ["document", documentId] });
+      queryClient.invalidateQueries({ queryKey: ["documents"] });
+    },
+    onError: (error) => {
+      console.error("Failed to set document metadata field:", error);
+    },
+  });
+}
+
 export function useDocumentChunks(documentId: string | null) {
   return useQuery({
     queryKey: ["chunks", documentId],
@@ -29,6 +116,23 @@ export function useDocumentChunks(documentId: string | null) {
   });
 }
 
+export function useChunkContext(chunkId: number | null, radius?: number) {
+  return useQuery({
+    queryKey: ["chunkContext", chunkId, radius],
+    queryFn: () => invoke<ChunkContext>("get_chunk_context", { chunkId, radius }),
+    enabled: chunkId !== null && chunkId > 0,
+  });
+}
+
+export function useDocumentPreview(documentId: string | null, maxLines?: number) {
+  return useQuery({
+    queryKey: ["documentPreview", documentId, maxLines],
+    queryFn: () => invoke<DocumentPreview>("render_document_preview", { documentId, maxLines }),
+    enabled: !!documentId,
+    staleTime: Infinity,
+  });
+}
+
 export function useDeleteDocument() {
   const queryClient = useQueryClient();
 
@@ -55,6 +159,22 @@ export function useIngestionStats() {
   });
 }
 
+export function useIngestionTimeline(options: {
+  startDate?: string;
+  endDate?: string;
+  bucket?: TimelineBucket;
+} = {}) {
+  return useQuery({
+    queryKey: ["stats", "timeline", options],
+    queryFn: () =>
+      invoke<IngestionTimelineEntry[]>("get_ingestion_timeline", {
+        startDate: options.startDate,
+        endDate: options.endDate,
+        bucket: options.bucket,
+      }),
+  });
+}
+
 export function useIngestFile() {
   const queryClient = useQueryClient();
 
@@ -71,6 +191,23 @@ export function useIngestFile() {
   });
 }
 
+export function useIngestFileWithPassword() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ path, password }: { path: string; password: string }) =>
+      invoke<Document>("ingest_file_with_password", { path, password }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["documents"] });
+      queryClient.invalidateQueries({ queryKey: ["stats"] });
+      queryClient.invalidateQueries({ queryKey: ["license-status"] });
+    },
+    onError: (error) => {
+      console.error("Failed to ingest protected file:", error);
+    },
+  });
+}
+
 export function useIngestDirectory() {
   const queryClient = useQueryClient();
 
@@ -88,6 +225,107 @@ export function useIngestDirectory() {
   });
 }
 
+export function useSyncDirectory() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      path,
+      recursive,
+      onMissing,
+    }: {
+      path: string;
+      recursive?: boolean;
+      onMissing?: MissingSourceAction;
+    }) => invoke<DirectorySyncSummary>("sync_directory", { path, recursive, onMissing }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["documents"] });
+      queryClient.invalidateQueries({ queryKey: ["stats"] });
+      queryClient.invalidateQueries({ queryKey: ["license-status"] });
+    },
+    onError: (error) => {
+      console.error("Failed to sync directory:", error);
+    },
+  });
+}
+
+export function useRelinkDocument() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ id, newPath }: { id: string; newPath: string }) =>
+      invoke<Document>("relink_document", { id, newPath }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["documents"] });
+    },
+    onError: (error) => {
+      console.error("Failed to relink document:", error);
+    },
+  });
+}
+
+export function useResolveDuplicateContent() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      documentId,
+      duplicateOfId,
+      action,
+    }: {
+      documentId: string;
+      duplicateOfId: string;
+      action: DuplicateContentAction;
+    }) => invoke("resolve_duplicate_content", { documentId, duplicateOfId, action }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["documents"] });
+      queryClient.invalidateQueries({ queryKey: ["stats"] });
+      queryClient.invalidateQueries({ queryKey: ["license-status"] });
+    },
+    onError: (error) => {
+      console.error("Failed to resolve duplicate content:", error);
+    },
+  });
+}
+
+export function useCheckMissingSources() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => invoke<string[]>("check_missing_sources"),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["documents"] });
+    },
+  });
+}
+
+export function useBackfillEmbeddings() {
+  return useMutation({
+    mutationFn: () => invoke<number>("backfill_embeddings"),
+    onError: (error) => {
+      console.error("Failed to backfill embeddings:", error);
+    },
+  });
+}
+
+export function usePreviewChunking() {
+  return useMutation({
+    mutationFn: (request: {
+      text?: string;
+      documentId?: string;
+      chunkSize?: number;
+      chunkOverlap?: number;
+    }) => invoke<ChunkingPreview>("preview_chunking", request),
+  });
+}
+
+export function useEstimateIngestionCost() {
+  return useMutation({
+    mutationFn: (request: { paths: string[]; recursive?: boolean; prices?: PriceTable }) =>
+      invoke<IngestionCostEstimate>("estimate_ingestion_cost", request),
+  });
+}
+
 export function useReingestDocument() {
   const queryClient = useQueryClient();
 
@@ -104,6 +342,38 @@ export function useReingestDocument() {
   });
 }
 
+export function useReingestAs() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ id, fileType }: { id: string; fileType: FileType }) =>
+      invoke<Document>("reingest_as", { id, fileType }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["documents"] });
+      queryClient.invalidateQueries({ queryKey: ["stats"] });
+    },
+    onError: (error) => {
+      console.error("Failed to reingest document with file type override:", error);
+    },
+  });
+}
+
+export function useReingestDocuments() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (filter: ReingestFilter) =>
+      invoke<ReingestSummary>("reingest_documents", { filter }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["documents"] });
+      queryClient.invalidateQueries({ queryKey: ["stats"] });
+    },
+    onError: (error) => {
+      console.error("Failed to reingest documents:", error);
+    },
+  });
+}
+
 export function useResetDatabase() {
   const queryClient = useQueryClient();
 
@@ -123,11 +393,68 @@ export function useResetDatabase() {
   });
 }
 
+export function useCompactDatabase() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (analyze?: boolean) =>
+      invoke<CompactionResult>("compact_database", { analyze }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["stats"] });
+    },
+    onError: (error) => {
+      console.error("Failed to compact database:", error);
+    },
+  });
+}
+
+export function useValidateDocuments() {
+  return useMutation({
+    mutationFn: () => invoke<DocumentValidation[]>("validate_documents"),
+    onError: (error) => {
+      console.error("Failed to validate documents:", error);
+    },
+  });
+}
+
+export function useRepairDocuments() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => invoke<DocumentRepairSummary>("repair_documents"),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["documents"] });
+      queryClient.invalidateQueries({ queryKey: ["stats"] });
+    },
+    onError: (error) => {
+      console.error("Failed to repair documents:", error);
+    },
+  });
+}
+
+export function useConvertChunkStorage() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => invoke<number>("convert_chunk_storage"),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["stats"] });
+    },
+    onError: (error) => {
+      console.error("Failed to convert chunk storage:", error);
+    },
+  });
+}
+
 export interface ContentCategory {
   category: string;
   confidence: number;
 }
 
+export interface DocumentCategorization extends ContentCategory {
+  labels: ContentCategory[];
+}
+
 export function useContentCategories() {
   return useQuery({
     queryKey: ["contentCategories"],
@@ -140,7 +467,7 @@ export function useCategorizeDocument() {
 
   return useMutation({
     mutationFn: (documentId: string) =>
-      invoke<ContentCategory>("categorize_document", { documentId }),
+      invoke<DocumentCategorization>("categorize_document", { documentId }),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ["documents"] });
     },
@@ -163,3 +490,49 @@ export function useCategorizeAllDocuments() {
     },
   });
 }
+
+export function useCancelCategorization() {
+  return useMutation({
+    mutationFn: () => invoke<void>("cancel_categorization"),
+  });
+}
+
+export function useRelatedDocuments(documentId: string | null, limit?: number) {
+  return useQuery({
+    queryKey: ["relatedDocuments", documentId, limit],
+    queryFn: () =>
+      invoke<DocumentRelation[]>("get_related_documents", { documentId, limit }),
+    enabled: !!documentId,
+  });
+}
+
+export function useRebuildDocumentRelations() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => invoke<RelationRebuildSummary>("rebuild_document_relations"),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["relatedDocuments"] });
+      queryClient.invalidateQueries({ queryKey: ["documentGraph"] });
+    },
+    onError: (error) => {
+      console.error("Failed to rebuild document relations:", error);
+    },
+  });
+}
+
+export function useDocumentGraph(request?: DocumentGraphRequest) {
+  return useQuery({
+    queryKey: ["documentGraph", request],
+    queryFn: () => invoke<DocumentGraph>("get_document_graph", { request }),
+  });
+}
+
+export function useDocumentClusters(k?: number, enabled = true) {
+  return useQuery({
+    queryKey: ["documentClusters", k],
+    queryFn: () => invoke<DocumentCluster[]>("cluster_documents", { k }),
+    enabled,
+    staleTime: Infinity,
+  });
+}
@@ -0,0 +1,51 @@
+import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+
+export interface LocalApiStatus {
+  running: boolean;
+  address: string | null;
+  token: string | null;
+  allow_lan: boolean;
+}
+
+export function useLocalApiStatus() {
+  return useQuery({
+    queryKey: ["local-api-status"],
+    queryFn: () => invoke<LocalApiStatus>("get_local_api_status"),
+  });
+}
+
+export function useStartLocalApi() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => invoke<LocalApiStatus>("start_local_api"),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["local-api-status"] });
+      queryClient.invalidateQueries({ queryKey: ["settings"] });
+    },
+  });
+}
+
+export function useStopLocalApi() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => invoke("stop_local_api"),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["local-api-status"] });
+      queryClient.invalidateQueries({ queryKey: ["settings"] });
+    },
+  });
+}
+
+export function useRegenerateLocalApiToken() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => invoke<string>("regenerate_local_api_token"),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["local-api-status"] });
+    },
+  });
+}
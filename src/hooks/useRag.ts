@@ -1,6 +1,15 @@
 import { useMutation } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
-import type { RagResponse, ChunkWithScore, SearchRequest, SearchResult } from "../types";
+import type {
+  RagResponse,
+  ChunkWithScore,
+  DocumentMatch,
+  DocumentSearchRequest,
+  SearchRequest,
+  SearchResult,
+  SearchJsonResponse,
+  SimilarChunk,
+} from "../types";
 
 export function useQuery() {
   return useMutation({
@@ -21,17 +30,24 @@ export function useQueryWithSources() {
       conversationId,
       maxChunks,
       documentIds,
+      maxTokens,
+      temperature,
     }: {
       query: string;
       conversationId?: string;
       maxChunks?: number;
       documentIds?: string[];
+      // Answer length (1-8192 tokens) and sampling temperature (0-2)
+      maxTokens?: number;
+      temperature?: number;
     }) =>
       invoke<RagResponse>("query_with_sources", {
         query,
         conversationId,
         maxChunks,
         documentIds,
+        maxTokens,
+        temperature,
       }),
   });
 }
@@ -43,9 +59,30 @@ export function useSearch() {
   });
 }
 
+export function useSearchWithinDocument() {
+  return useMutation({
+    mutationFn: (request: DocumentSearchRequest) =>
+      invoke<DocumentMatch[]>("search_within_document", { request }),
+  });
+}
+
+export function useSearchJson() {
+  return useMutation({
+    mutationFn: (request: SearchRequest) =>
+      invoke<SearchJsonResponse>("search_json", { request }),
+  });
+}
+
 export function useHybridSearch() {
   return useMutation({
     mutationFn: ({ query, limit }: { query: string; limit?: number }) =>
       invoke<ChunkWithScore[]>("hybrid_search", { query, limit }),
   });
 }
+
+export function useFindSimilarChunks() {
+  return useMutation({
+    mutationFn: ({ chunkId, limit }: { chunkId: number; limit?: number }) =>
+      invoke<SimilarChunk[]>("find_similar_chunks", { chunkId, limit }),
+  });
+}
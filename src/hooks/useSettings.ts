@@ -1,6 +1,6 @@
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
-import type { Settings } from "../types";
+import type { FFmpegStatus, LlmStatus, Settings } from "../types";
 
 export function useSettings() {
   return useQuery({
@@ -16,10 +16,21 @@ export function useUpdateSettings() {
     mutationFn: (settings: Settings) => invoke("update_settings", { newSettings: settings }),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ["settings"] });
+      // ffmpeg_path may have changed
+      queryClient.invalidateQueries({ queryKey: ["ffmpegStatus"] });
     },
   });
 }
 
+export function useLlmStatus() {
+  return useQuery({
+    queryKey: ["llmStatus"],
+    queryFn: () => invoke<LlmStatus>("get_llm_status"),
+    // Reads tracked outcomes only, so polling is cheap
+    refetchInterval: 10000,
+  });
+}
+
 export function useValidateApiKey() {
   const queryClient = useQueryClient();
 
@@ -27,6 +38,7 @@ export function useValidateApiKey() {
     mutationFn: (apiKey: string) => invoke<boolean>("validate_api_key", { apiKey }),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ["settings"] });
+      queryClient.invalidateQueries({ queryKey: ["llmStatus"] });
     },
   });
 }
@@ -47,3 +59,44 @@ export function useClearApiKey() {
     },
   });
 }
+
+export function useFFmpegStatus() {
+  return useQuery({
+    queryKey: ["ffmpegStatus"],
+    queryFn: () => invoke<FFmpegStatus>("get_ffmpeg_status"),
+  });
+}
+
+export function useLogFilePath() {
+  return useQuery({
+    queryKey: ["logFilePath"],
+    queryFn: () => invoke<string | null>("get_log_file_path"),
+  });
+}
+
+export function useExportLogs() {
+  return useMutation({
+    mutationFn: ({ destination, days }: { destination: string; days?: number }) =>
+      invoke<string>("export_logs", { destination, days }),
+  });
+}
+
+export function useNotificationSnooze() {
+  return useQuery({
+    queryKey: ["notification-snooze"],
+    queryFn: () => invoke<string | null>("get_notification_snooze"),
+    refetchInterval: 60000,
+  });
+}
+
+export function useSnoozeNotifications() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    // 0 minutes ends the snooze; resolves to the end time (RFC 3339) or null
+    mutationFn: (minutes: number) => invoke<string | null>("snooze_notifications", { minutes }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["notification-snooze"] });
+    },
+  });
+}
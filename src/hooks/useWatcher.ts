@@ -1,9 +1,14 @@
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
 
+export type WatcherState = "running" | "paused" | "stopped";
+
 export interface WatcherStatus {
+  // Also true while paused
   is_running: boolean;
+  state: WatcherState;
   watched_folders: string[];
+  watched_folder_excludes: Record<string, string[]>;
   auto_ingest_enabled: boolean;
 }
 
@@ -37,6 +42,29 @@ export function useStopWatcher() {
   });
 }
 
+// Hold file changes without touching watched folders or auto-ingest
+export function usePauseWatcher() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => invoke("pause_watcher"),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["watcher-status"] });
+    },
+  });
+}
+
+export function useResumeWatcher() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => invoke("resume_watcher"),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["watcher-status"] });
+    },
+  });
+}
+
 export function useAddWatchedFolder() {
   const queryClient = useQueryClient();
 
@@ -49,6 +77,31 @@ export function useAddWatchedFolder() {
   });
 }
 
+export function useSetWatchedFolderExcludes() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ folderPath, excludePatterns }: { folderPath: string; excludePatterns: string[] }) =>
+      invoke("set_watched_folder_excludes", { folderPath, excludePatterns }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["watcher-status"] });
+      queryClient.invalidateQueries({ queryKey: ["settings"] });
+    },
+  });
+}
+
+export function useReorderWatchedFolders() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (folderPaths: string[]) => invoke<string[]>("reorder_watched_folders", { folderPaths }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["watcher-status"] });
+      queryClient.invalidateQueries({ queryKey: ["settings"] });
+    },
+  });
+}
+
 export function useRemoveWatchedFolder() {
   const queryClient = useQueryClient();
 
@@ -12,11 +12,20 @@ export interface Document {
   status: DocumentStatus;
   error_message: string | null;
   metadata: Record<string, unknown>;
+  ingestion_source: IngestionSource;
+  chunk_count: number;
+  total_tokens: number;
+  last_accessed: string | null;
+  access_count: number;
 }
 
-export type FileType = "pdf" | "text" | "markdown" | "video" | "audio" | "image" | "screenshot" | "unknown";
+export type IngestionSource = "manual" | "watched" | "capture" | "url";
 
-export type DocumentStatus = "pending" | "processing" | "completed" | "failed";
+export type FileType = "pdf" | "text" | "markdown" | "video" | "audio" | "image" | "screenshot" | "subtitle" | "unknown";
+
+export type SimilarityMetric = "cosine" | "l2" | "dot";
+
+export type DocumentStatus = "pending" | "processing" | "completed" | "failed" | "blocked";
 
 export interface Chunk {
   id: number;
@@ -33,13 +42,53 @@ export interface Chunk {
   created_at: string;
 }
 
+export interface ChunkContext {
+  chunk: Chunk;
+  before: Chunk[];
+  after: Chunk[];
+}
+
+export type DocumentPreview =
+  | { kind: "image"; path: string }
+  | { kind: "text"; lines: string[]; truncated: boolean }
+  | { kind: "none" };
+
 export interface ChunkWithScore {
   chunk: Chunk;
   score: number;
   search_type: SearchType;
 }
 
-export type SearchType = "vector" | "fts" | "hybrid";
+export type SearchType = "vector" | "fts" | "hybrid" | "pinned" | "neighbor";
+
+export interface SimilarChunk {
+  chunk: Chunk;
+  document_title: string;
+  score: number;
+}
+
+export interface DocumentSearchRequest {
+  document_id: string;
+  query: string;
+  limit?: number;
+  // Also rank chunks by meaning; needs embeddings and an API key
+  include_vector?: boolean;
+}
+
+export interface SnippetSegment {
+  text: string;
+  highlighted: boolean;
+}
+
+export interface DocumentMatch {
+  chunk_id: number;
+  chunk_index: number;
+  score: number;
+  search_type: SearchType;
+  page_number: number | null;
+  timestamp: number | null;
+  snippet: SnippetSegment[];
+}
 
 export interface Citation {
   chunk_id: number;
@@ -49,6 +98,9 @@ export interface Citation {
   page_number: number | null;
   timestamp: number | null;
   relevance_score: number;
+  thumbnail_path?: string | null;
+  start_offset?: number | null;
+  end_offset?: number | null;
 }
 
 export interface SourceChunk {
@@ -60,13 +112,20 @@ export interface SourceChunk {
   timestamp: number | null;
   relevance_score: number;
   search_type: SearchType;
+  thumbnail_path: string | null;
+  start_offset: number | null;
+  end_offset: number | null;
 }
 
+export type CitationStyle = "inline" | "footnotes" | "end_list";
+
 export interface RagResponse {
   answer: string;
   citations: Citation[];
   sources: SourceChunk[];
   conversation_id: string;
+  follow_up_questions?: string[];
+  citation_style?: CitationStyle;
 }
 
 export interface Message {
@@ -82,6 +141,13 @@ export interface Conversation {
   title: string | null;
   created_at: string;
   updated_at: string;
+  // Applied to queries that don't pass their own document_ids/categories
+  scope: ConversationScope;
+}
+
+export interface ConversationScope {
+  document_ids?: string[] | null;
+  categories?: string[] | null;
 }
 
 export interface Settings {
@@ -91,11 +157,29 @@ export interface Settings {
   reasoning_model: string;
   chunk_size: number;
   chunk_overlap: number;
+  dedupe_chunks: boolean;
+  min_chunk_chars: number;
+  index_body_chunks_only: boolean;
   max_context_chunks: number;
+  context_neighbor_window: number;
+  max_history_messages: number;
+  summarize_history: boolean;
+  citation_style: CitationStyle;
+  suggest_followups: boolean;
+  boost_frequent_documents: boolean;
+  multimodal_rag: boolean;
+  max_context_images: number;
+  // Reuse recent retrieval results for a repeated query until the library changes
+  cache_retrieval: boolean;
+  warmup_on_startup: boolean;
   video_segment_duration: number;
   keyframe_interval: number;
+  max_video_frames_per_segment: number;
   watched_folders: string[];
+  watched_folder_excludes: Record<string, string[]>;
   auto_ingest_enabled: boolean;
+  // File types ingested from disk; others are skipped
+  enabled_file_types: FileType[];
   // Screen capture settings
   screen_capture_enabled: boolean;
   capture_interval_secs: number;
@@ -103,12 +187,97 @@ export interface Settings {
   capture_app_filter: "none" | "whitelist" | "blacklist";
   capture_app_list: string[];
   capture_retention_days: number;
+  capture_retention_max_count: number;
   capture_hotkey: string;
+  capture_trigger_mode: "interval" | "on_window_change";
+  capture_min_interval_secs: number;
+  capture_app_intervals: Record<string, number>;
+  // Skip scheduled captures after this many seconds without input (0 = off)
+  capture_idle_threshold_secs: number;
+  capture_redact_sensitive_regions: boolean;
+  captures_directory: string | null;
+  // Embedding settings
+  embeddings_enabled: boolean;
+  embedding_batch_size: number;
+  // Request timeouts (seconds)
+  generation_timeout_secs: number;
+  upload_timeout_secs: number;
+  embedding_timeout_secs: number;
+  // Rate limiting
+  gemini_requests_per_minute: number;
+  // Language detection
+  detect_language_on_ingest: boolean;
+  // Full-text search tokenizer
+  fts_tokenizer: "porter" | "unicode61" | "trigram";
+  // Vector similarity metric; changing it rebuilds the vector index
+  similarity_metric: SimilarityMetric;
+  // Where an identical file counts as the same document: "global" follows
+  // moves anywhere, "folder" only within the same watched folder
+  dedupe_scope: "global" | "folder";
+  compress_chunk_text: boolean;
+  // Abort an ingestion after this many seconds without progress (0 disables)
+  ingestion_timeout_secs: number;
+  ingestion_cooldown_ms: number;
+  // Retries for transient extraction/embedding failures (0 disables)
+  ingestion_max_retries: number;
+  // Categorize documents during ingestion (one extra LLM call each)
+  auto_categorize: boolean;
+  document_versions_kept: number;
+  // PDF render scales for Windows OCR and vision-model OCR (1.0 = 72 DPI)
+  ocr_render_scale: number;
+  vision_ocr_render_scale: number;
+  // Scanned PDF pages sent to vision OCR per document (0 = no limit)
+  max_ocr_pages: number;
+  // Path to ffmpeg (overrides bundled binary / PATH)
+  ffmpeg_path: string | null;
+  // Local HTTP API; enabled and token are managed by the local API commands
+  enable_local_api: boolean;
+  local_api_port: number;
+  // Listen on all interfaces instead of 127.0.0.1
+  local_api_allow_lan: boolean;
+  local_api_token: string | null;
+  // POSTed a JSON payload whenever a document finishes ingesting
+  ingestion_webhook_url: string | null;
+  // Log file level
+  log_level: "error" | "warn" | "info" | "debug" | "trace";
   // License settings
   license_key: string | null;
   license_activated_at: string | null;
 }
 
+export type ProviderState =
+  | "unknown"
+  | "healthy"
+  | "rate_limited"
+  | "invalid_key"
+  | "offline"
+  | "degraded"
+  | "not_configured";
+
+export interface LlmStatus {
+  configured: boolean;
+  provider: string;
+  embedding_model: string;
+  ingestion_model: string;
+  reasoning_model: string;
+  state: ProviderState;
+  last_success_at: string | null;
+  last_failure_at: string | null;
+  last_error: string | null;
+  recent_successes: number;
+  recent_failures: number;
+  rate_limited_until: string | null;
+  requests_per_minute: number | null;
+}
+
+export interface FFmpegStatus {
+  available: boolean;
+  ffmpeg_path: string;
+  ffmpeg_version: string | null;
+  ffprobe_version: string | null;
+  error: string | null;
+}
+
 export interface LicenseStatus {
   is_valid: boolean;
   license_key: string | null;
@@ -118,6 +287,11 @@ export interface LicenseStatus {
   documents_limit: number | null;
 }
 
+export interface CaptureCleanupResult {
+  files_removed: number;
+  documents_removed: number;
+}
+
 export interface CaptureStatus {
   enabled: boolean;
   scheduler_running: boolean;
@@ -127,6 +301,16 @@ export interface CaptureStatus {
   capture_count: number;
   last_capture: string | null;
   hotkey: string;
+  trigger_mode: "interval" | "on_window_change";
+  idle: boolean;
+  idle_skipped_count: number;
+}
+
+export interface HotkeyValidation {
+  hotkey: string;
+  valid: boolean;
+  problem: "invalid_format" | "conflict" | null;
+  message: string | null;
 }
 
 export interface AppInfo {
@@ -135,6 +319,21 @@ export interface AppInfo {
   is_foreground: boolean;
 }
 
+export interface CaptureTestResult {
+  mode: string;
+  monitors_detected: number;
+  width: number;
+  height: number;
+  foreground_app: AppInfo | null;
+  source_app: string | null;
+  window_title: string | null;
+  capture_ms: number;
+  text_preview: string | null;
+  text_length: number;
+  ocr_error: string | null;
+  ocr_ms: number | null;
+}
+
 export interface IngestionStats {
   total_documents: number;
   completed_documents: number;
@@ -145,12 +344,185 @@ export interface IngestionStats {
   total_size_bytes: number;
 }
 
+export type TimelineBucket = "day" | "week" | "month";
+
+export interface IngestionTimelineEntry {
+  period: string;
+  file_type: FileType;
+  document_count: number;
+  total_size_bytes: number;
+}
+
+export interface EmbeddingBackfillProgress {
+  embedded: number;
+  total: number;
+}
+
+export interface PreviewChunk {
+  chunk_index: number;
+  content: string;
+  token_count: number;
+  start_offset: number | null;
+  end_offset: number | null;
+  page_number: number | null;
+  timestamp_start: number | null;
+  timestamp_end: number | null;
+}
+
+export interface ChunkingPreview {
+  chunk_size: number;
+  chunk_overlap: number;
+  total_tokens: number;
+  chunks: PreviewChunk[];
+}
+
+// Prices for estimate_ingestion_cost, in any one currency
+export interface PriceTable {
+  embedding_per_million_tokens?: number;
+  ocr_per_page?: number;
+  vision_per_frame?: number;
+  transcription_per_minute?: number;
+}
+
+export interface FileCostEstimate {
+  path: string;
+  file_type: FileType;
+  embedding_tokens: number;
+  ocr_pages: number;
+  vision_frames: number;
+  audio_minutes: number;
+}
+
+export interface IngestionCostEstimate {
+  files: FileCostEstimate[];
+  embedding_tokens: number;
+  ocr_pages: number;
+  vision_frames: number;
+  audio_minutes: number;
+  estimated_cost: number | null;
+  errors: string[];
+}
+
+export type MissingSourceAction = "flag" | "delete";
+
+export interface DirectorySyncSummary {
+  added: number;
+  updated: number;
+  moved: number;
+  unchanged: number;
+  removed: number;
+  flagged_missing: number;
+  failed: number;
+  errors: string[];
+}
+
+// Payload of the "duplicate-content-found" event
+export interface DuplicateContentNotification {
+  document_id: string;
+  document_title: string;
+  file_type: FileType;
+  duplicate_of_id: string;
+  duplicate_of_title: string;
+  duplicate_of_file_type: FileType;
+}
+
+export type DuplicateContentAction = "link" | "skip";
+
+export interface BatchFailure {
+  path: string;
+  reason: string;
+}
+
+// Payload of "ingestion-batch-complete", emitted when a directory import finishes
+export interface IngestionBatchSummary {
+  batch_id: string;
+  succeeded: number;
+  failed: number;
+  // Already ingested and unchanged, or of a disabled file type
+  skipped: number;
+  total_bytes: number;
+  total_chunks: number;
+  elapsed_ms: number;
+  failures: BatchFailure[];
+}
+
+export interface ReingestFilter {
+  status?: DocumentStatus;
+  file_type?: FileType;
+  category?: string;
+  created_after?: string;
+  created_before?: string;
+}
+
+export interface ReingestSummary {
+  enqueued: number;
+  skipped_missing: number;
+  failures: BatchFailure[];
+}
+
+export interface CategorizationProgress {
+  processed: number;
+  total: number;
+  categorized: number;
+  failed: number;
+}
+
+export interface CompactionResult {
+  bytes_before: number;
+  bytes_after: number;
+  bytes_reclaimed: number;
+  analyzed: boolean;
+  duration_ms: number;
+}
+
+export type DocumentIssue =
+  | { kind: "source_missing" }
+  | { kind: "source_changed" }
+  | { kind: "missing_file_hash" }
+  | { kind: "missing_mime_type" }
+  | { kind: "wrong_file_type"; stored: FileType; detected: FileType }
+  | { kind: "missing_ingested_at" }
+  | { kind: "no_chunks" }
+  | { kind: "chunk_count_drift"; stored: number; actual: number }
+  | { kind: "interrupted"; status: DocumentStatus };
+
+export interface DocumentValidation {
+  document_id: string;
+  title: string;
+  file_path: string;
+  issues: DocumentIssue[];
+}
+
+export interface DocumentRepairSummary {
+  checked: number;
+  repaired: number;
+  remaining: DocumentValidation[];
+}
+
+export interface DocumentVersion {
+  id: number;
+  document_id: string;
+  file_path: string;
+  file_hash: string;
+  content: string;
+  change_summary: string | null;
+  created_at: string;
+}
+
+export interface DocumentLogLine {
+  timestamp: string;
+  level: string;
+  target: string;
+  message: string;
+}
+
 export interface IngestionProgress {
   document_id: string;
   file_path: string;
   stage: IngestionStage;
   progress: number;
   message: string;
+  updated_at: string;
 }
 
 export type IngestionStage =
@@ -166,9 +538,130 @@ export interface SearchRequest {
   query: string;
   limit?: number;
   document_ids?: string[];
+  categories?: string[];
+  ingestion_sources?: IngestionSource[];
+  // ISO 639-3 codes, e.g. "fra"
+  languages?: string[];
 }
 
 export interface SearchResult {
   chunks: ChunkWithScore[];
   total: number;
 }
+
+// Stable, versioned shape returned by search_json
+export interface SearchJsonResponse {
+  version: number;
+  query: string;
+  documents: SearchJsonDocument[];
+  results: SearchJsonResult[];
+}
+
+export interface SearchJsonDocument {
+  id: string;
+  title: string;
+  path: string;
+  file_type: string;
+}
+
+export interface SearchJsonResult {
+  citation: number;
+  document_id: string;
+  chunk_id: number;
+  text: string;
+  score: number;
+  match: "vector" | "fts" | "hybrid";
+  page: number | null;
+  timestamp: number | null;
+  start_offset: number | null;
+  end_offset: number | null;
+}
+
+export interface DocumentRelation {
+  document_id: string;
+  related_id: string;
+  related_title: string;
+  similarity: number;
+  updated_at: string;
+}
+
+export interface RelationRebuildSummary {
+  documents_processed: number;
+  relations_found: number;
+  failed: number;
+}
+
+export interface GraphNode {
+  id: string;
+  title: string;
+  file_type: FileType;
+  category: string | null;
+  created_at: string;
+  degree: number;
+}
+
+export interface GraphEdge {
+  source: string;
+  target: string;
+  weight: number;
+}
+
+export interface DocumentGraph {
+  nodes: GraphNode[];
+  edges: GraphEdge[];
+}
+
+export interface DocumentGraphRequest {
+  min_similarity?: number;
+  categories?: string[];
+  file_types?: FileType[];
+  created_after?: string;
+  created_before?: string;
+  include_isolated?: boolean;
+}
+
+export interface ClusterMember {
+  id: string;
+  title: string;
+  similarity: number;
+}
+
+export interface DocumentCluster {
+  label: string;
+  documents: ClusterMember[];
+}
+
+export type ErrorCode =
+  | "database"
+  | "io"
+  | "json"
+  | "offline"
+  | "http"
+  | "pdf_extract"
+  | "llm_api"
+  | "rate_limit"
+  | "invalid_api_key"
+  | "embedding"
+  | "ingestion"
+  | "ffmpeg"
+  | "ocr"
+  | "vector_search"
+  | "extension_load"
+  | "config"
+  | "capture"
+  | "content_blocked"
+  | "trial_limit_reached"
+  | "not_found"
+  | "tauri"
+  | "other";
+
+/** Error rejected by a Tauri command */
+export interface RecallError {
+  code: ErrorCode;
+  message: string;
+  retry_after_secs?: number;
+}
+
+export function isRecallError(error: unknown): error is RecallError {
+  return typeof error === "object" && error !== null && "code" in error && "message" in error;
+}